@@ -382,7 +382,7 @@ impl Into<Span> for Location {
 
 /// A span of locations within a source file, expressed as a half-open interval
 /// of bytes `[begin,end)`.
-#[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+#[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
 pub struct Span {
 	pub source: Source,
 	pub begin: usize,
@@ -50,6 +50,25 @@ fn main() {
 			.arg(Arg::with_name("ignore_duplicate_defs")
 				.long("ignore-duplicate-defs")
 				.help("Ignore multiple module/entity definitions")))
+		.subcommand(SubCommand::with_name("dpi-header")
+			.about("Generates a C header for the DPI imports and exports")
+			.arg(Arg::with_name("inc")
+				.short("I")
+				.value_name("DIR")
+				.help("Adds a search path for SystemVerilog includes")
+				.multiple(true)
+				.takes_value(true)
+				.number_of_values(1))
+			.arg(Arg::with_name("output")
+				.short("o")
+				.value_name("FILE")
+				.help("Writes the header to FILE instead of stdout")
+				.takes_value(true))
+			.arg(Arg::with_name("INPUT")
+				.help("The input files to use")
+				.required(true)
+				.multiple(true)
+				.index(1)))
 		.get_matches();
 
 	let mut session = Session {
@@ -63,6 +82,8 @@ fn main() {
 	} else if let Some(m) = matches.subcommand_matches("elaborate") {
 		session.opts.ignore_duplicate_defs = m.is_present("ignore_duplicate_defs");
 		elaborate(m, &session);
+	} else if let Some(m) = matches.subcommand_matches("dpi-header") {
+		dpi_header(m, &session);
 	}
 }
 
@@ -134,7 +155,7 @@ fn compile(matches: &ArgMatches) {
 
 
 fn elaborate(matches: &ArgMatches, session: &Session) {
-	use moore_common::errors::DiagBuilder2;
+	use moore_common::errors::{DiagBuilder2, Severity};
 
 	// Load the syntax trees previously parsed and stored into the library.
 	let mut asts = svlog::store::load_items(".moore").unwrap();
@@ -173,7 +194,7 @@ fn elaborate(matches: &ArgMatches, session: &Session) {
 	};
 
 	// Lower to HIR.
-	let hir = match svlog::hir::lower(session, &nameres, top, asts) {
+	let mut hir = match svlog::hir::lower(session, &nameres, top, asts) {
 		Ok(x) => x,
 		Err(_) => {
 			println!("{}", DiagBuilder2::fatal("lowering to HIR failed"));
@@ -181,4 +202,80 @@ fn elaborate(matches: &ArgMatches, session: &Session) {
 		},
 	};
 	println!("lowered {} modules", hir.mods.len());
+
+	// Assign types to expressions.
+	if svlog::hir::typeck(session, &nameres, &mut hir).is_err() {
+		println!("{}", DiagBuilder2::fatal("type checking failed"));
+		std::process::exit(1);
+	}
+
+	// Check the drivers and loads of variables and nets.
+	let diags = svlog::hir::check_drivers(session, &nameres, &hir);
+	for diag in &diags {
+		println!("{}", diag);
+	}
+	if diags.iter().any(|d| d.get_severity() >= Severity::Error) {
+		println!("{}", DiagBuilder2::fatal("driver analysis failed"));
+		std::process::exit(1);
+	}
+}
+
+
+fn dpi_header(matches: &ArgMatches, session: &Session) {
+	use std::io::Write;
+	use moore_common::errors::DiagBuilder2;
+
+	// Prepare a list of include paths.
+	let include_paths: Vec<_> = match matches.values_of("inc") {
+		Some(args) => args.map(|x| std::path::Path::new(x)).collect(),
+		None => Vec::new()
+	};
+
+	// Parse the input files.
+	let sm = source::get_source_manager();
+	let mut asts = Vec::new();
+	for filename in matches.values_of("INPUT").unwrap() {
+		let source = match sm.open(&filename) {
+			Some(s) => s,
+			None => panic!("Unable to open input file '{}'", filename),
+		};
+		let preproc = svlog::preproc::Preprocessor::new(source, &include_paths);
+		let lexer = svlog::lexer::Lexer::new(preproc);
+		match svlog::parser::parse(lexer) {
+			Ok(x) => asts.push(x),
+			Err(()) => std::process::exit(1),
+		}
+	}
+
+	// Resolve names, such that typedefs are looked up through imports.
+	svlog::renumber::renumber(&mut asts);
+	let nameres = match svlog::resolve::resolve(session, &asts) {
+		Ok(x) => x,
+		Err(_) => {
+			println!("{}", DiagBuilder2::fatal("name resolution failed"));
+			std::process::exit(1);
+		}
+	};
+
+	// Derive the include guard from the output file name.
+	let output = matches.value_of("output");
+	let guard: String = match output.and_then(|o| Path::new(o).file_name()).and_then(|s| s.to_str()) {
+		Some(name) => name.to_uppercase().chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect(),
+		None => String::from("DPI_H"),
+	};
+
+	// Generate the header and write it to the requested destination.
+	let header = match svlog::dpi::generate_header(&asts, &nameres, &guard) {
+		Ok(x) => x,
+		Err(()) => std::process::exit(1),
+	};
+	match output {
+		Some(path) => {
+			if let Err(e) = std::fs::File::create(path).and_then(|mut f| f.write_all(header.as_bytes())) {
+				println!("{}", DiagBuilder2::fatal(format!("unable to write `{}`: {}", path, e)));
+				std::process::exit(1);
+			}
+		}
+		None => print!("{}", header),
+	}
 }
@@ -0,0 +1,625 @@
+// Copyright (c) 2017 Fabian Schuiki
+
+//! This module implements the analysis of the drivers and loads of the
+//! variables and nets of a design. Variables that are driven by more than one
+//! process in a way the standard forbids are reported as errors. Outputs that
+//! are never driven, inputs that are never read, and declarations that are
+//! never used are reported as warnings.
+//! IEEE 1800-2009 6.5, 9.2.2.2, 9.2.2.4
+
+use std;
+use std::collections::{HashMap, HashSet};
+use moore_common::errors::*;
+use moore_common::Session;
+use moore_svlog_syntax::ast;
+use moore_svlog_syntax::resolve::NameResolution;
+use nodes::*;
+use lower::consteval::{const_eval_hir, ConstEnv};
+use lower::expr::{lower_expr, lower_lvalue};
+use lower::stmt::{Accesses, Write};
+
+
+/// Analyze the drivers and loads of the variables and nets of an elaborated
+/// design. The diagnostics are returned rather than emitted, such that the
+/// caller can decide how to treat the warnings.
+pub fn check_drivers(session: &Session, nameres: &NameResolution, root: &Root) -> Vec<DiagBuilder2> {
+	// Count how often each declaration is referred to, be it lexically or
+	// through a hierarchical name.
+	let mut refs = HashMap::new();
+	for &def in nameres.defs.values() {
+		*refs.entry(def).or_insert(0) += 1;
+	}
+	count_hier_refs(&root.design.scope, &mut refs);
+
+	let mut c = DriverChecker {
+		session: session,
+		nameres: nameres,
+		root: root,
+		refs: refs,
+		diags: Vec::new(),
+		reported: HashSet::new(),
+		next_scope: 0,
+	};
+	c.check_instance(&root.design);
+
+	// Undriven, unread, and unused declarations are a property of the source
+	// text, so check each module and interface only once.
+	let mut elements: Vec<(Name, Span, &[Port], &HierarchyBody)> = root.mods.values()
+		.map(|m| (m.name, m.span, &m.ports[..], &m.body))
+		.chain(root.intfs.values().map(|i| (i.name, i.span, &i.ports[..], &i.body)))
+		.collect();
+	elements.sort_by_key(|&(_, span, _, _)| (span.source.0, span.begin));
+	for (name, _, ports, body) in elements {
+		c.check_element(name, ports, body);
+	}
+	c.diags
+}
+
+
+#[allow(dead_code)]
+struct DriverChecker<'a> {
+	session: &'a Session,
+	nameres: &'a NameResolution,
+	root: &'a Root,
+	/// The number of references to each declaration.
+	refs: HashMap<NodeId, usize>,
+	diags: Vec<DiagBuilder2>,
+	/// The variables for which multiple drivers have been reported.
+	reported: HashSet<NodeId>,
+	/// Used to tell apart the scopes a declaration is elaborated into.
+	next_scope: usize,
+}
+
+/// A write to a variable by a process.
+struct Driver {
+	/// The process that performs the write, unique within an instance.
+	process: usize,
+	kind: DriverKind,
+	part: Part,
+	span: Span,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DriverKind {
+	Procedure(ast::ProcedureKind),
+	ContAssign,
+	/// An output port of an instance, given by the name of the instance and
+	/// the port.
+	Port(Name, Name),
+}
+
+impl DriverKind {
+	/// Check whether a process of this kind must be the only one that writes
+	/// to a variable.
+	fn is_exclusive(&self) -> bool {
+		match *self {
+			DriverKind::Procedure(ast::ProcedureKind::AlwaysComb) |
+			DriverKind::Procedure(ast::ProcedureKind::AlwaysLatch) |
+			DriverKind::Procedure(ast::ProcedureKind::AlwaysFf) |
+			DriverKind::ContAssign |
+			DriverKind::Port(..) => true,
+			DriverKind::Procedure(_) => false,
+		}
+	}
+
+	fn describe(&self) -> String {
+		match *self {
+			DriverKind::Procedure(kind) => format!("driven by `{}` procedure", procedure_keyword(kind)),
+			DriverKind::ContAssign => format!("driven by continuous assignment"),
+			DriverKind::Port(inst, port) => format!("driven by port `{}` of instance `{}`", port, inst),
+		}
+	}
+}
+
+/// The part of a variable that is written. Writes with a select that is not
+/// constant are considered to write the entire variable, which is the longest
+/// static prefix of the lvalue.
+/// IEEE 1800-2009 11.5.3
+#[derive(Clone, Copy, PartialEq)]
+enum Part {
+	Whole,
+	Range(i64, i64),
+	Member(Name),
+}
+
+impl Part {
+	fn overlaps(&self, other: &Part) -> bool {
+		match (*self, *other) {
+			(Part::Range(a0, a1), Part::Range(b0, b1)) => a0 <= b1 && b0 <= a1,
+			(Part::Member(a), Part::Member(b)) => a == b,
+			_ => true,
+		}
+	}
+}
+
+/// A signal connected to a port of an instance.
+enum Conn<'b> {
+	Expr(&'b ast::Expr),
+	/// A connection `.name` or `.*`, which connects to the signal of the same
+	/// name.
+	Implicit(Name, Span),
+}
+
+/// The declarations of a scope elaborated into an instance, used to tell
+/// apart the copies of a declaration in the iterations of a generate loop.
+struct ScopeDecls {
+	id: usize,
+	vars: HashSet<NodeId>,
+}
+
+impl<'a> DriverChecker<'a> {
+	/// Check that the variables of an instance and the instances below it
+	/// are not driven by conflicting processes.
+	fn check_instance(&mut self, inst: &Instance) {
+		let ports = self.ports_of(inst.target);
+		let mut names = HashMap::new();
+		let mut outer = HashSet::new();
+		for slice in ports.iter().flat_map(|p| p.slices.iter()) {
+			if slice.intf.is_none() && slice.kind == ast::PortKind::Var {
+				let decl = self.nameres.get(&slice.id).cloned().unwrap_or(slice.id);
+				names.insert(decl, slice.name);
+				outer.insert(decl);
+			}
+		}
+		let mut drivers = HashMap::new();
+		let mut stack = Vec::new();
+		let mut process = 0;
+		self.collect_drivers(&inst.scope, ConstEnv::default(), outer, &mut names, &mut stack, &mut drivers, &mut process);
+
+		let mut drivers: Vec<_> = drivers.into_iter().collect();
+		drivers.sort_by_key(|&(_, ref ds): &(_, Vec<Driver>)| (ds[0].span.source.0, ds[0].span.begin));
+		for ((_, def), ds) in drivers {
+			let name = names[&def];
+			self.check_conflicts(name, def, &ds);
+		}
+		self.visit_instances(&inst.scope);
+	}
+
+	fn visit_instances(&mut self, scope: &InstScope) {
+		for sub in &scope.scopes {
+			self.visit_instances(sub);
+		}
+		for inst in &scope.insts {
+			self.check_instance(inst);
+		}
+	}
+
+	/// Gather the writes to the variables of an instance, keyed by the scope
+	/// the variable is declared in and its declaration.
+	fn collect_drivers(
+		&mut self,
+		scope: &InstScope,
+		mut env: ConstEnv,
+		mut vars: HashSet<NodeId>,
+		names: &mut HashMap<NodeId, Name>,
+		stack: &mut Vec<ScopeDecls>,
+		drivers: &mut HashMap<(usize, NodeId), Vec<Driver>>,
+		process: &mut usize,
+	) {
+		env.values.extend(scope.values.iter().map(|(&k, &v)| (k, v)));
+		for decl in &scope.body.vars {
+			for name in &decl.names {
+				vars.insert(name.id);
+				names.insert(name.id, name.name);
+			}
+		}
+		stack.push(ScopeDecls { id: self.next_scope, vars: vars });
+		self.next_scope += 1;
+
+		for prok in &scope.body.procs {
+			let mut acc = Accesses::default();
+			acc.stmt(&prok.stmt);
+			add_drivers(&acc.writes, DriverKind::Procedure(prok.origin), *process, &env, stack, drivers);
+			*process += 1;
+		}
+		for assign in &scope.body.assigns {
+			let mut acc = Accesses::default();
+			acc.lvalue(&assign.lhs);
+			add_drivers(&acc.writes, DriverKind::ContAssign, *process, &env, stack, drivers);
+			*process += 1;
+		}
+		for (inst, port, dir, conn) in self.connections(&scope.body.insts) {
+			if dir != ast::PortDir::Output {
+				continue;
+			}
+			let kind = DriverKind::Port(inst, port);
+			match conn {
+				Conn::Expr(expr) => if let Ok(lv) = lower_lvalue(self.nameres, expr) {
+					let mut acc = Accesses::default();
+					acc.lvalue(&lv);
+					add_drivers(&acc.writes, kind, *process, &env, stack, drivers);
+				},
+				Conn::Implicit(name, span) => {
+					let found = stack.iter().rev()
+						.flat_map(|s| s.vars.iter().map(move |&v| (s.id, v)))
+						.find(|&(_, v)| names.get(&v) == Some(&name));
+					if let Some(key) = found {
+						drivers.entry(key).or_insert_with(Vec::new).push(Driver {
+							process: *process,
+							kind: kind,
+							part: Part::Whole,
+							span: span,
+						});
+					}
+				}
+			}
+			*process += 1;
+		}
+
+		for sub in &scope.scopes {
+			self.collect_drivers(sub, env.clone(), HashSet::new(), names, stack, drivers, process);
+		}
+		stack.pop();
+	}
+
+	/// Report a variable that is written by processes that conflict with each
+	/// other, pointing at each of them.
+	fn check_conflicts(&mut self, name: Name, def: NodeId, drivers: &[Driver]) {
+		let mut involved = vec![false; drivers.len()];
+		for (i, a) in drivers.iter().enumerate() {
+			for (j, b) in drivers.iter().enumerate().skip(i + 1) {
+				if a.process != b.process && (a.kind.is_exclusive() || b.kind.is_exclusive()) && a.part.overlaps(&b.part) {
+					involved[i] = true;
+					involved[j] = true;
+				}
+			}
+		}
+		if !involved.iter().any(|&x| x) || !self.reported.insert(def) {
+			return;
+		}
+
+		let mut diag = DiagBuilder2::error(format!("`{}` is driven by more than one process", name));
+		let mut seen = HashSet::new();
+		let mut exclusive = None;
+		for (driver, _) in drivers.iter().zip(involved.iter()).filter(|&(_, &x)| x) {
+			if !seen.insert(driver.process) {
+				continue;
+			}
+			if let DriverKind::Procedure(kind) = driver.kind {
+				if driver.kind.is_exclusive() && exclusive.is_none() {
+					exclusive = Some(kind);
+				}
+			}
+			diag = diag.span(driver.span).add_note(driver.kind.describe());
+		}
+		let reason = match exclusive {
+			Some(kind) => format!("variables written by an `{}` procedure cannot be written by any other process", procedure_keyword(kind)),
+			None => format!("variables driven by a continuous assignment or output port cannot have any other driver"),
+		};
+		self.diags.push(diag.add_note(reason));
+	}
+
+	/// Check that the outputs of a module or interface are driven, that its
+	/// inputs are read, and that its variables and nets are used.
+	fn check_element(&mut self, element: Name, ports: &[Port], body: &HierarchyBody) {
+		let mut decls = Vec::new();
+		collect_decls(body, &mut decls);
+
+		let mut names: HashMap<Name, Vec<NodeId>> = HashMap::new();
+		for &(id, name, _, _) in &decls {
+			names.entry(name).or_insert_with(Vec::new).push(id);
+		}
+		let mut port_names = HashSet::new();
+		let mut self_refs = HashMap::new();
+		for slice in ports.iter().flat_map(|p| p.slices.iter()) {
+			port_names.insert(slice.name);
+			names.entry(slice.name).or_insert_with(Vec::new).push(slice.id);
+			// The names in a non-ANSI port list refer to the port declarations
+			// in the body.
+			if let Some(&decl) = self.nameres.get(&slice.id) {
+				*self_refs.entry(decl).or_insert(0) += 1;
+				names.entry(slice.name).or_insert_with(Vec::new).push(decl);
+			}
+		}
+
+		let mut counts = Counts::default();
+		for &(id, _, _, init) in &decls {
+			if init {
+				counts.inits.insert(id);
+			}
+		}
+		self.count_accesses(body, &names, &mut counts);
+
+		// Declarations may also be referred to by items that are not analyzed
+		// here, e.g. assertions and gate instances. Assume that these both
+		// read and drive the declaration.
+		let opaque = |id: NodeId| {
+			let total = self.refs.get(&id).cloned().unwrap_or(0);
+			let known = counts.reads.get(&id).cloned().unwrap_or(0)
+				+ counts.writes.get(&id).cloned().unwrap_or(0)
+				+ self_refs.get(&id).cloned().unwrap_or(0);
+			total > known
+		};
+		let mut warnings = Vec::new();
+		for slice in ports.iter().flat_map(|p| p.slices.iter()) {
+			if slice.intf.is_some() {
+				continue;
+			}
+			let ids = &names[&slice.name];
+			match slice.dir {
+				ast::PortDir::Input => {
+					if !ids.iter().any(|&id| counts.reads.contains_key(&id) || opaque(id)) {
+						warnings.push(DiagBuilder2::warning(format!("input `{}` of `{}` is never read", slice.name, element))
+							.span(slice.span));
+					}
+				}
+				ast::PortDir::Output => {
+					if !ids.iter().any(|&id| counts.writes.contains_key(&id) || counts.inits.contains(&id) || opaque(id)) {
+						warnings.push(DiagBuilder2::warning(format!("output `{}` of `{}` is never driven", slice.name, element))
+							.span(slice.span));
+					}
+				}
+				ast::PortDir::Inout | ast::PortDir::Ref => (),
+			}
+		}
+		for &(id, name, span, _) in &decls {
+			if port_names.contains(&name) {
+				continue;
+			}
+			if !counts.reads.contains_key(&id) && !counts.writes.contains_key(&id) && !opaque(id) {
+				warnings.push(DiagBuilder2::warning(format!("`{}` is declared but never used", name))
+					.span(span));
+			}
+		}
+		self.diags.extend(warnings);
+	}
+
+	/// Count the reads and writes of the declarations in a body, including
+	/// the ones in generate constructs.
+	fn count_accesses(&mut self, body: &HierarchyBody, names: &HashMap<Name, Vec<NodeId>>, counts: &mut Counts) {
+		for prok in &body.procs {
+			let mut acc = Accesses::default();
+			acc.stmt(&prok.stmt);
+			counts.add(&acc);
+		}
+		for assign in &body.assigns {
+			let mut acc = Accesses::default();
+			acc.lvalue(&assign.lhs);
+			acc.expr(&assign.rhs);
+			counts.add(&acc);
+		}
+		for sub in &body.subroutines {
+			let mut acc = Accesses::default();
+			for arg in &sub.args {
+				if let Some(ref e) = arg.default {
+					acc.expr(e);
+				}
+			}
+			for stmt in &sub.stmts {
+				acc.stmt(stmt);
+			}
+			counts.add(&acc);
+		}
+		let inits = body.vars.iter().flat_map(|d| d.names.iter())
+			.chain(body.nets.iter().flat_map(|d| d.names.iter()))
+			.filter_map(|n| n.init.as_ref());
+		for init in inits {
+			if let Ok(e) = lower_expr(self.nameres, init) {
+				let mut acc = Accesses::default();
+				acc.expr(&e);
+				counts.add(&acc);
+			}
+		}
+		for (_, _, dir, conn) in self.connections(&body.insts) {
+			let (read, write) = match dir {
+				ast::PortDir::Input => (true, false),
+				ast::PortDir::Output => (false, true),
+				ast::PortDir::Inout | ast::PortDir::Ref => (true, true),
+			};
+			match conn {
+				Conn::Expr(expr) => {
+					let lv = if write { lower_lvalue(self.nameres, expr).ok() } else { None };
+					let e = if read || lv.is_none() { lower_expr(self.nameres, expr).ok() } else { None };
+					let mut acc = Accesses::default();
+					if let Some(ref lv) = lv {
+						acc.lvalue(lv);
+					}
+					if let Some(ref e) = e {
+						acc.expr(e);
+					}
+					counts.add(&acc);
+				}
+				Conn::Implicit(name, _) => for &id in names.get(&name).map(|v| &v[..]).unwrap_or(&[]) {
+					if read {
+						*counts.reads.entry(id).or_insert(0) += 1;
+					}
+					if write {
+						*counts.writes.entry(id).or_insert(0) += 1;
+					}
+				},
+			}
+		}
+
+		for region in &body.genreg {
+			self.count_accesses(region, names, counts);
+		}
+		let blocks = body.genfors.iter().map(|gf| &gf.block)
+			.chain(body.genifs.iter().flat_map(|gi| std::iter::once(&gi.main_block).chain(gi.else_block.iter())))
+			.chain(body.gencases.iter().flat_map(|gc| gc.items.iter().map(|i| &i.block)));
+		for block in blocks {
+			self.count_accesses(&block.body, names, counts);
+		}
+	}
+
+	/// Determine the signals connected to the ports of the instances in a
+	/// body, together with the instance name, port name, and port direction.
+	/// Interface ports are skipped.
+	fn connections<'b>(&self, insts: &'b [ast::Inst]) -> Vec<(Name, Name, ast::PortDir, Conn<'b>)> {
+		let mut conns = Vec::new();
+		for inst in insts {
+			let ports = match self.nameres.get(&inst.target.id) {
+				Some(&id) => self.ports_of(id),
+				None => continue,
+			};
+			let port_dir = |port: &Port| match port.slices.first() {
+				Some(slice) if slice.intf.is_none() => Some(slice.dir),
+				_ => None,
+			};
+			for inst_name in &inst.names {
+				let name = inst_name.name.name;
+				let mut connected = HashSet::new();
+				let mut wildcard = None;
+				for (index, conn) in inst_name.conns.iter().enumerate() {
+					let (port, c) = match conn.kind {
+						ast::PortConnKind::Auto => {
+							wildcard = Some(conn.span);
+							continue;
+						}
+						ast::PortConnKind::Positional(ref expr) => (ports.get(index), Conn::Expr(expr)),
+						ast::PortConnKind::Named(ref n, ref mode) => {
+							connected.insert(n.name);
+							let port = ports.iter().find(|p| p.name == Some(n.name));
+							match *mode {
+								ast::PortConnMode::Connected(ref expr) => (port, Conn::Expr(expr)),
+								ast::PortConnMode::Auto => (port, Conn::Implicit(n.name, n.span)),
+								ast::PortConnMode::Unconnected => continue,
+							}
+						}
+					};
+					if let Some(port) = port {
+						if let (Some(port_name), Some(dir)) = (port.name, port_dir(port)) {
+							conns.push((name, port_name, dir, c));
+						}
+					}
+				}
+				if let Some(span) = wildcard {
+					for port in ports {
+						if let (Some(port_name), Some(dir)) = (port.name, port_dir(port)) {
+							if !connected.contains(&port_name) {
+								conns.push((name, port_name, dir, Conn::Implicit(port_name, span)));
+							}
+						}
+					}
+				}
+			}
+		}
+		conns
+	}
+
+	/// The ports of a module or interface.
+	fn ports_of(&self, id: NodeId) -> &'a [Port] {
+		if let Some(m) = self.root.mods.get(&id) {
+			&m.ports
+		} else if let Some(i) = self.root.intfs.get(&id) {
+			&i.ports
+		} else {
+			&[]
+		}
+	}
+}
+
+
+/// The reads and writes of declarations.
+#[derive(Default)]
+struct Counts {
+	reads: HashMap<NodeId, usize>,
+	writes: HashMap<NodeId, usize>,
+	/// The declarations with an initial value.
+	inits: HashSet<NodeId>,
+}
+
+impl Counts {
+	fn add(&mut self, acc: &Accesses) {
+		for &(_, _, ident) in &acc.reads {
+			if let Some(def) = ident.def {
+				*self.reads.entry(def).or_insert(0) += 1;
+			}
+		}
+		for write in &acc.writes {
+			if let Some(def) = write.ident.def {
+				*self.writes.entry(def).or_insert(0) += 1;
+			}
+		}
+	}
+}
+
+/// Record the writes to the variables declared in the scopes on the stack.
+fn add_drivers(
+	writes: &[Write],
+	kind: DriverKind,
+	process: usize,
+	env: &ConstEnv,
+	stack: &[ScopeDecls],
+	drivers: &mut HashMap<(usize, NodeId), Vec<Driver>>,
+) {
+	for write in writes {
+		let def = match write.ident.def {
+			Some(def) => def,
+			None => continue,
+		};
+		let scope = match stack.iter().rev().find(|s| s.vars.contains(&def)) {
+			Some(s) => s.id,
+			None => continue,
+		};
+		drivers.entry((scope, def)).or_insert_with(Vec::new).push(Driver {
+			process: process,
+			kind: kind,
+			part: written_part(write, env),
+			span: write.span,
+		});
+	}
+}
+
+/// Determine which part of a variable a write assigns to.
+fn written_part(write: &Write, env: &ConstEnv) -> Part {
+	if let Some(member) = write.member {
+		return Part::Member(member);
+	}
+	let eval = |e: &Expr| const_eval_hir(e, env).ok().map(|v| v.as_int());
+	let part = match write.select {
+		Some(&Select::Bit(ref e)) => eval(e).map(|i| Part::Range(i, i)),
+		Some(&Select::Range(mode, ref a, ref b)) => match (eval(a), eval(b)) {
+			(Some(a), Some(b)) => Some(match mode {
+				ast::RangeMode::Absolute => Part::Range(std::cmp::min(a, b), std::cmp::max(a, b)),
+				ast::RangeMode::RelativeUp => Part::Range(a, a + b - 1),
+				ast::RangeMode::RelativeDown => Part::Range(a - b + 1, a),
+			}),
+			_ => None,
+		},
+		None => None,
+	};
+	part.unwrap_or(Part::Whole)
+}
+
+/// Collect the variables and nets declared in a body, including the ones in
+/// generate constructs, together with whether they have an initial value.
+fn collect_decls(body: &HierarchyBody, into: &mut Vec<(NodeId, Name, Span, bool)>) {
+	let names = body.vars.iter().flat_map(|d| d.names.iter())
+		.chain(body.nets.iter().flat_map(|d| d.names.iter()));
+	for name in names {
+		into.push((name.id, name.name, name.name_span, name.init.is_some()));
+	}
+	for region in &body.genreg {
+		collect_decls(region, into);
+	}
+	let blocks = body.genfors.iter().map(|gf| &gf.block)
+		.chain(body.genifs.iter().flat_map(|gi| std::iter::once(&gi.main_block).chain(gi.else_block.iter())))
+		.chain(body.gencases.iter().flat_map(|gc| gc.items.iter().map(|i| &i.block)));
+	for block in blocks {
+		collect_decls(&block.body, into);
+	}
+}
+
+/// Count the declarations referred to by hierarchical names.
+fn count_hier_refs(scope: &InstScope, refs: &mut HashMap<NodeId, usize>) {
+	for r in scope.hier_refs.values() {
+		*refs.entry(r.decl).or_insert(0) += 1;
+	}
+	for sub in &scope.scopes {
+		count_hier_refs(sub, refs);
+	}
+	for inst in &scope.insts {
+		count_hier_refs(&inst.scope, refs);
+	}
+}
+
+fn procedure_keyword(kind: ast::ProcedureKind) -> &'static str {
+	match kind {
+		ast::ProcedureKind::Initial => "initial",
+		ast::ProcedureKind::Always => "always",
+		ast::ProcedureKind::AlwaysComb => "always_comb",
+		ast::ProcedureKind::AlwaysLatch => "always_latch",
+		ast::ProcedureKind::AlwaysFf => "always_ff",
+		ast::ProcedureKind::Final => "final",
+	}
+}
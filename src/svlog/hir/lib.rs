@@ -10,6 +10,12 @@ extern crate moore_svlog_syntax;
 
 mod nodes;
 mod lower;
+mod ty;
+mod typeck;
+mod drivers;
 
 pub use self::nodes::*;
 pub use self::lower::*;
+pub use self::ty::*;
+pub use self::typeck::*;
+pub use self::drivers::*;
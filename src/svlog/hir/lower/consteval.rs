@@ -0,0 +1,256 @@
+// Copyright (c) 2017 Fabian Schuiki
+
+//! This module implements the evaluation of constant expressions, as they
+//! appear in parameter values, generate constructs, and the ranges of selects.
+
+use std;
+use std::collections::{HashMap, HashSet};
+use moore_common::errors::*;
+use moore_svlog_syntax::ast;
+use moore_svlog_syntax::resolve::NameResolution;
+use moore_svlog_syntax::token::{Lit, Op};
+use nodes::*;
+
+
+/// The constants visible at a point in the design hierarchy.
+#[derive(Clone, Default)]
+pub struct ConstEnv {
+	/// The values of the visible parameters and genvars.
+	pub values: HashMap<NodeId, ConstValue>,
+	/// The genvars declared in the enclosing scopes.
+	pub genvars: HashSet<NodeId>,
+	/// The genvars that act as the index of an enclosing generate loop.
+	pub active: HashSet<NodeId>,
+}
+
+
+/// Evaluate a constant expression. Identifiers must refer to parameters or
+/// genvars whose value is known in the environment.
+pub fn const_eval(nameres: &NameResolution, expr: &ast::Expr, env: &ConstEnv) -> DiagResult2<ConstValue> {
+	match expr.data {
+		ast::LiteralExpr(ref lit) => const_eval_literal(lit, expr.span),
+		ast::IdentExpr(ref ident) |
+		ast::ScopeExpr(_, ref ident) => const_eval_ident(nameres, ident, env),
+		ast::UnaryExpr { op, ref expr, postfix: false } => {
+			let arg = const_eval(nameres, expr, env)?.as_int();
+			const_unary_op(op, arg, expr.span).map(ConstValue::Int)
+		}
+		ast::BinaryExpr { op, ref lhs, ref rhs } => {
+			let lhs = const_eval(nameres, lhs, env)?.as_int();
+			let rhs = const_eval(nameres, rhs, env)?.as_int();
+			const_binary_op(op, lhs, rhs, expr.span).map(ConstValue::Int)
+		}
+		ast::TernaryExpr { ref cond, ref true_expr, ref false_expr } => {
+			if const_eval(nameres, cond, env)?.is_true() {
+				const_eval(nameres, true_expr, env)
+			} else {
+				const_eval(nameres, false_expr, env)
+			}
+		}
+		ast::CallExpr(ref callee, ref args) => {
+			let name = match callee.data {
+				ast::SysIdentExpr(ref ident) => ident.name,
+				_ => {
+					return Err(DiagBuilder2::error("function calls cannot be used in a constant expression")
+						.span(expr.span));
+				}
+			};
+			match (&*name.as_str(), args.as_slice()) {
+				("clog2", &[ast::CallArg { expr: Some(ref arg), .. }]) => {
+					let arg = const_eval(nameres, arg, env)?.as_int();
+					Ok(ConstValue::Int(const_clog2(arg)))
+				}
+				_ => Err(DiagBuilder2::error(format!("`${}` cannot be used in a constant expression", name))
+					.span(expr.span)),
+			}
+		}
+		_ => Err(DiagBuilder2::error("expression is not constant").span(expr.span)),
+	}
+}
+
+/// Evaluate a constant expression that has been lowered to HIR.
+pub fn const_eval_hir(expr: &Expr, env: &ConstEnv) -> DiagResult2<ConstValue> {
+	match expr.kind {
+		ExprKind::Lit(ref lit) => const_eval_literal(lit, expr.span),
+		ExprKind::Ident(ref ident) => const_eval_def(ident.def, ident.name, ident.span, env),
+		ExprKind::Unary(op, ref arg) => {
+			let arg = const_eval_hir(arg, env)?.as_int();
+			const_unary_op(op, arg, expr.span).map(ConstValue::Int)
+		}
+		ExprKind::Binary(op, ref lhs, ref rhs) => {
+			let lhs = const_eval_hir(lhs, env)?.as_int();
+			let rhs = const_eval_hir(rhs, env)?.as_int();
+			const_binary_op(op, lhs, rhs, expr.span).map(ConstValue::Int)
+		}
+		ExprKind::Ternary(ref cond, ref true_expr, ref false_expr) => {
+			if const_eval_hir(cond, env)?.is_true() {
+				const_eval_hir(true_expr, env)
+			} else {
+				const_eval_hir(false_expr, env)
+			}
+		}
+		ExprKind::SysCall(ref name, ref args) => {
+			match (&*name.name.as_str(), args.as_slice()) {
+				("clog2", &[CallArg { expr: Some(ref arg), .. }]) => {
+					let arg = const_eval_hir(arg, env)?.as_int();
+					Ok(ConstValue::Int(const_clog2(arg)))
+				}
+				_ => Err(DiagBuilder2::error(format!("`${}` cannot be used in a constant expression", name.name))
+					.span(expr.span)),
+			}
+		}
+		ExprKind::Call(..) => {
+			Err(DiagBuilder2::error("function calls cannot be used in a constant expression").span(expr.span))
+		}
+		_ => Err(DiagBuilder2::error("expression is not constant").span(expr.span)),
+	}
+}
+
+/// Evaluate a type that has been parsed in place of an expression. This
+/// happens for parameter assignments such as `#(N)`, where the parser
+/// cannot tell whether `N` refers to a type or a value.
+pub fn const_eval_type_as_expr(nameres: &NameResolution, ty: &ast::Type, env: &ConstEnv) -> DiagResult2<ConstValue> {
+	match ty.data {
+		ast::NamedType(ref ident) if ty.dims.is_empty() && ty.sign == ast::TypeSign::None => {
+			const_eval_ident(nameres, ident, env)
+		}
+		_ => Err(DiagBuilder2::error("expected a value, found a type").span(ty.span)),
+	}
+}
+
+/// Apply the operator of a compound assignment, such as `+=`.
+pub fn const_assign_op(op: &ast::AssignOp, lhs: ConstValue, rhs: ConstValue, span: Span) -> DiagResult2<ConstValue> {
+	let op = match *op {
+		ast::AssignOp::Identity => return Ok(rhs),
+		ast::AssignOp::Add => Op::Add,
+		ast::AssignOp::Sub => Op::Sub,
+		ast::AssignOp::Mul => Op::Mul,
+		ast::AssignOp::Div => Op::Div,
+		ast::AssignOp::Mod => Op::Mod,
+		ast::AssignOp::BitAnd => Op::BitAnd,
+		ast::AssignOp::BitOr => Op::BitOr,
+		ast::AssignOp::BitXor => Op::BitXor,
+		ast::AssignOp::LogicShL => Op::LogicShL,
+		ast::AssignOp::LogicShR => Op::LogicShR,
+		ast::AssignOp::ArithShL => Op::ArithShL,
+		ast::AssignOp::ArithShR => Op::ArithShR,
+	};
+	const_binary_op(op, lhs.as_int(), rhs.as_int(), span).map(ConstValue::Int)
+}
+
+fn const_eval_ident(nameres: &NameResolution, ident: &ast::Identifier, env: &ConstEnv) -> DiagResult2<ConstValue> {
+	const_eval_def(nameres.get(&ident.id).cloned(), ident.name, ident.span, env)
+}
+
+fn const_eval_def(def: Option<NodeId>, name: Name, span: Span, env: &ConstEnv) -> DiagResult2<ConstValue> {
+	if let Some(&v) = def.and_then(|d| env.values.get(&d)) {
+		return Ok(v);
+	}
+	if def.map(|d| env.genvars.contains(&d)).unwrap_or(false) {
+		Err(DiagBuilder2::error(format!("genvar `{}` can only be used within a generate loop", name))
+			.span(span))
+	} else {
+		Err(DiagBuilder2::error(format!("`{}` is not a constant", name))
+			.span(span)
+			.add_note("only parameters and genvars may be used in a constant expression"))
+	}
+}
+
+fn const_eval_literal(lit: &Lit, span: Span) -> DiagResult2<ConstValue> {
+	let parsed = match *lit {
+		Lit::UnsignedInteger(v) => i64::from_str_radix(&v.as_str(), 10).ok(),
+		Lit::BasedInteger(_, _, base, v) => {
+			let radix = match base {
+				'b' => 2,
+				'o' => 8,
+				'd' => 10,
+				'h' => 16,
+				_ => unreachable!(),
+			};
+			i64::from_str_radix(&v.as_str(), radix).ok()
+		}
+		Lit::UnbasedUnsized('0') => Some(0),
+		_ => None,
+	};
+	match parsed {
+		Some(v) => Ok(ConstValue::Int(v)),
+		None => Err(DiagBuilder2::error("literal cannot be evaluated to an integer").span(span)),
+	}
+}
+
+fn const_unary_op(op: Op, arg: i64, span: Span) -> DiagResult2<i64> {
+	Ok(match op {
+		Op::Add => arg,
+		Op::Sub => arg.wrapping_neg(),
+		Op::LogicNot => (arg == 0) as i64,
+		Op::BitNot => !arg,
+		_ => {
+			return Err(DiagBuilder2::error(format!("operator `{}` cannot be used in a constant expression", op))
+				.span(span));
+		}
+	})
+}
+
+fn const_clog2(arg: i64) -> i64 {
+	let mut value = 0;
+	while value < 64 && (1i64 << value) < arg {
+		value += 1;
+	}
+	value
+}
+
+fn const_binary_op(op: Op, lhs: i64, rhs: i64, span: Span) -> DiagResult2<i64> {
+	Ok(match op {
+		Op::Add => lhs.wrapping_add(rhs),
+		Op::Sub => lhs.wrapping_sub(rhs),
+		Op::Mul => lhs.wrapping_mul(rhs),
+		Op::Div | Op::Mod if rhs == 0 => {
+			return Err(DiagBuilder2::error("division by zero in constant expression").span(span));
+		}
+		Op::Div => lhs.wrapping_div(rhs),
+		Op::Mod => lhs.wrapping_rem(rhs),
+		// Negative exponents yield 0 except for bases 1 and -1, and are
+		// undefined for base 0. IEEE 1800-2009 table 11-4
+		Op::Pow if rhs < 0 => match lhs {
+			0 => {
+				return Err(DiagBuilder2::error("zero raised to a negative power is undefined in constant expression")
+					.span(span));
+			}
+			1 => 1,
+			-1 => if rhs % 2 == 0 { 1 } else { -1 },
+			_ => 0,
+		},
+		Op::Pow => lhs.wrapping_pow(rhs as u32),
+		Op::LogicEq | Op::CaseEq => (lhs == rhs) as i64,
+		Op::LogicNeq | Op::CaseNeq => (lhs != rhs) as i64,
+		Op::Lt => (lhs < rhs) as i64,
+		Op::Leq => (lhs <= rhs) as i64,
+		Op::Gt => (lhs > rhs) as i64,
+		Op::Geq => (lhs >= rhs) as i64,
+		Op::LogicAnd => (lhs != 0 && rhs != 0) as i64,
+		Op::LogicOr => (lhs != 0 || rhs != 0) as i64,
+		Op::BitAnd => lhs & rhs,
+		Op::BitOr => lhs | rhs,
+		Op::BitXor => lhs ^ rhs,
+		Op::BitXnor | Op::BitNxor => !(lhs ^ rhs),
+		// Shifting by the full width or more shifts out all bits. The shift
+		// amount is unsigned, such that negative amounts are huge.
+		Op::LogicShL | Op::ArithShL => lhs.checked_shl(shift_amount(rhs)).unwrap_or(0),
+		Op::LogicShR => (lhs as u64).checked_shr(shift_amount(rhs)).unwrap_or(0) as i64,
+		Op::ArithShR => lhs.checked_shr(shift_amount(rhs)).unwrap_or(if lhs < 0 { -1 } else { 0 }),
+		_ => {
+			return Err(DiagBuilder2::error(format!("operator `{}` cannot be used in a constant expression", op))
+				.span(span));
+		}
+	})
+}
+
+/// Convert the right-hand side of a shift to a shift amount, saturating
+/// amounts that do not fit.
+fn shift_amount(rhs: i64) -> u32 {
+	if rhs < 0 || rhs > std::u32::MAX as i64 {
+		std::u32::MAX
+	} else {
+		rhs as u32
+	}
+}
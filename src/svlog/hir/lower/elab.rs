@@ -0,0 +1,911 @@
+// Copyright (c) 2017 Fabian Schuiki
+
+//! This module implements the elaboration of the design hierarchy. Starting at
+//! the top module, parameters are assigned their final values and generate
+//! constructs are unrolled, which yields a tree of instances and scopes.
+
+use std;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use moore_common::errors::*;
+use moore_common::name::get_name_table;
+use moore_common::Session;
+use moore_svlog_syntax::ast;
+use moore_svlog_syntax::token::Op;
+use moore_svlog_syntax::resolve::NameResolution;
+use nodes::*;
+use lower::Result;
+use lower::consteval::{self, ConstEnv};
+
+/// The maximum number of iterations a generate loop may go through before it
+/// is considered to not terminate.
+const MAX_GENERATE_ITERATIONS: usize = 1 << 16;
+
+/// The maximum depth of the instance hierarchy. Guards against modules that
+/// instantiate themselves.
+const MAX_INSTANCE_DEPTH: usize = 256;
+
+/// A net, or a range of its bits, connected by an alias statement.
+type AliasTarget = (NodeId, Option<(i64, i64)>);
+
+
+/// Elaborate the design hierarchy below the module `top`. The bind directives
+/// add instances to the scopes they target. The parameters declared in
+/// packages are visible throughout the design.
+pub fn elaborate(
+	session: &Session,
+	nameres: &NameResolution,
+	top: NodeId,
+	pkgs: &HashMap<NodeId, Package>,
+	mods: &HashMap<NodeId, Module>,
+	intfs: &HashMap<NodeId, Interface>,
+	checkers: &HashMap<NodeId, Checker>,
+	binds: &[ast::BindDirective],
+) -> Result<Instance> {
+	let mut e = Elaborator {
+		session: session,
+		nameres: nameres,
+		severity: Severity::Note,
+		mods: mods,
+		intfs: intfs,
+		checkers: checkers,
+		binds: binds,
+		bound: vec![false; binds.len()],
+		path: Vec::new(),
+		nets: HashMap::new(),
+		aliased: HashMap::new(),
+		depth: 0,
+		globals: ConstEnv::default(),
+	};
+
+	// Packages may only refer to the packages declared before them, so
+	// evaluate their parameters in source order.
+	let mut pkgs: Vec<_> = pkgs.values().collect();
+	pkgs.sort_by_key(|p| (p.span.source.0, p.span.begin));
+	let mut globals = ConstEnv::default();
+	for pkg in pkgs {
+		let mut scope = InstScope {
+			name: pkg.name,
+			span: pkg.span,
+			values: HashMap::new(),
+			body: empty_hierarchy_body(),
+			scopes: Vec::new(),
+			insts: Vec::new(),
+			types: BTreeMap::new(),
+			hier_refs: HashMap::new(),
+		};
+		if e.elaborate_params(&pkg.body.params, &mut globals, &mut scope).is_err() {
+			return Err(());
+		}
+	}
+	e.globals = globals;
+
+	let (name, span) = match mods.get(&top) {
+		Some(m) => (m.name, m.span),
+		None => panic!("top module {} has not been lowered", top),
+	};
+	let globals = e.globals.clone();
+	let inst = e.elaborate_instance(name, span, top, &[], &globals);
+
+	// Bind directives that name specific instances must have found them.
+	let unmatched: Vec<_> = binds.iter()
+		.zip(e.bound.iter())
+		.filter(|&(bind, &bound)| !bound && (bind.target.len() > 1 || !bind.insts.is_empty()))
+		.map(|(bind, _)| bind)
+		.collect();
+	for bind in unmatched {
+		let span = bind.target.iter().fold(bind.target[0].span, |sp, n| Span::union(sp, n.span));
+		e.add_diag(DiagBuilder2::error("Bind directive does not match any instance in the design").span(span));
+	}
+
+	if e.is_error() {
+		Err(())
+	} else {
+		inst
+	}
+}
+
+
+#[allow(dead_code)]
+struct Elaborator<'a> {
+	session: &'a Session,
+	nameres: &'a NameResolution,
+	severity: Severity,
+	mods: &'a HashMap<NodeId, Module>,
+	intfs: &'a HashMap<NodeId, Interface>,
+	checkers: &'a HashMap<NodeId, Checker>,
+	binds: &'a [ast::BindDirective],
+	/// Which of the bind directives have been applied to an instance.
+	bound: Vec<bool>,
+	/// The hierarchical name of the scope being elaborated.
+	path: Vec<Name>,
+	/// The dimensions of the nets visible in the instance being elaborated,
+	/// outermost first and followed by the width of the element type. `None`
+	/// if the dimensions are not known, e.g. for user-defined nettypes.
+	nets: HashMap<NodeId, Option<Vec<usize>>>,
+	/// The pairs of nets aliased to each other in the instance being
+	/// elaborated, together with the alias statement that connects them.
+	aliased: HashMap<(AliasTarget, AliasTarget), Span>,
+	depth: usize,
+	/// The values of the parameters declared in packages.
+	globals: ConstEnv,
+}
+
+/// A generate construct, used to process loops and conditionals in the order
+/// in which they appear in the source text.
+enum GenerateItem<'b> {
+	For(&'b GenerateFor),
+	If(&'b GenerateIf),
+	Case(&'b GenerateCase),
+}
+
+impl<'b> GenerateItem<'b> {
+	fn span(&self) -> Span {
+		match *self {
+			GenerateItem::For(x) => x.span,
+			GenerateItem::If(x) => x.span,
+			GenerateItem::Case(x) => x.span,
+		}
+	}
+}
+
+impl<'a> Elaborator<'a> {
+	fn add_diag(&mut self, diag: DiagBuilder2) {
+		self.severity = std::cmp::max(self.severity, diag.severity);
+		println!("{}", diag);
+	}
+
+	fn is_error(&self) -> bool {
+		self.severity >= Severity::Error
+	}
+
+	/// Emit the diagnostic of a failed operation.
+	fn check<T>(&mut self, result: DiagResult2<T>) -> Result<T> {
+		result.map_err(|diag| self.add_diag(diag))
+	}
+
+	fn const_eval(&mut self, expr: &ast::Expr, env: &ConstEnv) -> Result<ConstValue> {
+		let result = consteval::const_eval(self.nameres, expr, env);
+		self.check(result)
+	}
+
+	/// Elaborate an instance of a module, interface, or checker. The parameter
+	/// assignments are evaluated in the environment of the parent scope.
+	fn elaborate_instance(
+		&mut self,
+		name: Name,
+		span: Span,
+		target: NodeId,
+		assignments: &[ast::ParamAssignment],
+		parent_env: &ConstEnv,
+	) -> Result<Instance> {
+		let (params, body) = if let Some(m) = self.mods.get(&target) {
+			(&m.params[..], &m.body)
+		} else if let Some(i) = self.intfs.get(&target) {
+			(&i.params[..], &i.body)
+		} else if let Some(c) = self.checkers.get(&target) {
+			(&[][..], &c.body)
+		} else {
+			panic!("instance `{}` refers to node {}, which is neither a module, an interface, nor a checker", name, target);
+		};
+
+		if self.depth >= MAX_INSTANCE_DEPTH {
+			self.add_diag(DiagBuilder2::error(format!("instance hierarchy is deeper than {} levels", MAX_INSTANCE_DEPTH))
+				.span(span)
+				.add_note("is a module instantiating itself?"));
+			return Err(());
+		}
+		self.depth += 1;
+		self.path.push(name);
+
+		// Parameters may be overridden if they are declared in the parameter
+		// port list, or in the body if the parameter port list is omitted.
+		let overridable: Vec<&ast::ParamDecl> = if params.is_empty() {
+			body.params.iter().filter(|p| !p.local).collect()
+		} else {
+			params.iter().collect()
+		};
+		let overrides = self.map_param_assignments(name, &overridable, assignments, parent_env);
+
+		let mut env = self.globals.clone();
+		env.values.extend(overrides?);
+		let mut scope = InstScope {
+			name: name,
+			span: span,
+			values: HashMap::new(),
+			body: empty_hierarchy_body(),
+			scopes: Vec::new(),
+			insts: Vec::new(),
+			types: BTreeMap::new(),
+			hier_refs: HashMap::new(),
+		};
+		let outer_nets = std::mem::replace(&mut self.nets, HashMap::new());
+		let outer_aliased = std::mem::replace(&mut self.aliased, HashMap::new());
+		let result = self.elaborate_params(params, &mut env, &mut scope)
+			.map(|_| self.declare_ports(target, &env))
+			.and_then(|_| self.elaborate_body(body, &mut env, &mut scope))
+			.and_then(|_| self.elaborate_binds(target, &env, &mut scope));
+
+		self.nets = outer_nets;
+		self.aliased = outer_aliased;
+		self.path.pop();
+		self.depth -= 1;
+		result?;
+		Ok(Instance {
+			name: name,
+			span: span,
+			target: target,
+			scope: scope,
+			intf_ports: HashMap::new(),
+		})
+	}
+
+	/// Evaluate the parameter assignments of an instantiation and associate
+	/// them with the parameters they override.
+	fn map_param_assignments(
+		&mut self,
+		name: Name,
+		params: &[&ast::ParamDecl],
+		assignments: &[ast::ParamAssignment],
+		env: &ConstEnv,
+	) -> Result<HashMap<NodeId, ConstValue>> {
+		// Flatten the parameter declarations into a list of names, keeping
+		// track of which ones are value parameters.
+		let mut decls = Vec::new();
+		for param in params {
+			match param.kind {
+				ast::ParamKind::Type(ref ds) => decls.extend(ds.iter().map(|d| (d.name, false))),
+				ast::ParamKind::Value(ref ds) => decls.extend(ds.iter().map(|d| (d.name, true))),
+			}
+		}
+
+		let mut values = HashMap::new();
+		let mut failed = false;
+		for (index, assignment) in assignments.iter().enumerate() {
+			let decl = match assignment.name {
+				Some(ref n) => decls.iter().find(|&&(ref d, _)| d.name == n.name),
+				None => decls.get(index),
+			};
+			let (decl, is_value) = match decl {
+				Some(&x) => x,
+				None => {
+					let msg = match assignment.name {
+						Some(ref n) => format!("`{}` has no parameter `{}`", name, n.name),
+						None => format!("`{}` has only {} parameters", name, decls.len()),
+					};
+					self.add_diag(DiagBuilder2::error(msg).span(assignment.span));
+					failed = true;
+					continue;
+				}
+			};
+
+			// Type parameters are not yet tracked during elaboration.
+			if !is_value {
+				continue;
+			}
+			let value = match assignment.expr {
+				ast::TypeOrExpr::Expr(ref expr) => self.const_eval(expr, env),
+				ast::TypeOrExpr::Type(ref ty) => {
+					let result = consteval::const_eval_type_as_expr(self.nameres, ty, env);
+					self.check(result)
+				}
+			};
+			match value {
+				Ok(v) => { values.insert(decl.id, v); }
+				Err(()) => failed = true,
+			}
+		}
+
+		if failed {
+			Err(())
+		} else {
+			Ok(values)
+		}
+	}
+
+	/// Assign values to a list of parameter declarations. Parameters that have
+	/// been overridden already carry a value in the environment, all others
+	/// are assigned their default value.
+	fn elaborate_params(&mut self, params: &[ast::ParamDecl], env: &mut ConstEnv, scope: &mut InstScope) -> Result<()> {
+		for param in params {
+			let decls = match param.kind {
+				ast::ParamKind::Value(ref decls) => decls,
+				ast::ParamKind::Type(_) => continue,
+			};
+			for decl in decls {
+				let value = match env.values.get(&decl.name.id) {
+					Some(&v) => v,
+					None => match decl.expr {
+						Some(ref expr) => self.const_eval(expr, env)?,
+						None => {
+							self.add_diag(DiagBuilder2::error(format!("parameter `{}` has no value", decl.name.name))
+								.span(decl.name.span)
+								.add_note("the parameter must either have a default value or be assigned one upon instantiation"));
+							return Err(());
+						}
+					},
+				};
+				env.values.insert(decl.name.id, value);
+				scope.values.insert(decl.name.id, value);
+			}
+		}
+		Ok(())
+	}
+
+	/// Elaborate a hierarchy body into a scope. Generate regions are merged
+	/// into the scope, while generate loops and conditionals produce nested
+	/// scopes.
+	fn elaborate_body(&mut self, body: &HierarchyBody, env: &mut ConstEnv, scope: &mut InstScope) -> Result<()> {
+		// Gather the generate constructs in this scope, including the ones in
+		// generate regions, and order them as they appear in the source text.
+		// Their position determines the name of unnamed generate blocks.
+		let mut gens = Vec::new();
+		self.elaborate_body_items(body, env, scope, &mut gens)?;
+		gens.sort_by_key(|g| g.span().begin);
+		for alias in &scope.body.aliases {
+			self.check_alias(alias, env);
+		}
+
+		for (index, gen) in gens.into_iter().enumerate() {
+			match gen {
+				GenerateItem::For(gf) => self.elaborate_generate_for(gf, index + 1, env, scope)?,
+				GenerateItem::If(gi) => self.elaborate_generate_if(gi, index + 1, env, scope)?,
+				GenerateItem::Case(gc) => self.elaborate_generate_case(gc, index + 1, env, scope)?,
+			}
+		}
+		Ok(())
+	}
+
+	/// Copy the non-generate items of a body into a scope and collect the
+	/// generate constructs.
+	fn elaborate_body_items<'b>(
+		&mut self,
+		body: &'b HierarchyBody,
+		env: &mut ConstEnv,
+		scope: &mut InstScope,
+		gens: &mut Vec<GenerateItem<'b>>,
+	) -> Result<()> {
+		self.elaborate_params(&body.params, env, scope)?;
+		for genvar in &body.genvars {
+			env.genvars.insert(genvar.id);
+		}
+
+		for net in &body.nets {
+			for name in &net.names {
+				let dims = match net.net_type {
+					ast::NetType::User | ast::NetType::Interconnect => None,
+					_ => self.net_dims(Some(&net.ty), &name.dims, env),
+				};
+				self.nets.insert(name.id, dims);
+			}
+		}
+
+		let b = &mut scope.body;
+		b.procs.extend(body.procs.iter().cloned());
+		b.nets.extend(body.nets.iter().cloned());
+		b.vars.extend(body.vars.iter().cloned());
+		b.assigns.extend(body.assigns.iter().cloned());
+		b.params.extend(body.params.iter().cloned());
+		b.genvars.extend(body.genvars.iter().cloned());
+		b.classes.extend(body.classes.iter().cloned());
+		b.subroutines.extend(body.subroutines.iter().cloned());
+		b.asserts.extend(body.asserts.iter().cloned());
+		b.typedefs.extend(body.typedefs.iter().cloned());
+		b.covergroups.extend(body.covergroups.iter().cloned());
+		b.dpi_decls.extend(body.dpi_decls.iter().cloned());
+		b.gates.extend(body.gates.iter().cloned());
+		b.specparams.extend(body.specparams.iter().cloned());
+		b.specify_blocks.extend(body.specify_blocks.iter().cloned());
+		b.clockings.extend(body.clockings.iter().cloned());
+		b.default_clockings.extend(body.default_clockings.iter().cloned());
+		b.sequences.extend(body.sequences.iter().cloned());
+		b.properties.extend(body.properties.iter().cloned());
+		b.nettypes.extend(body.nettypes.iter().cloned());
+		b.aliases.extend(body.aliases.iter().cloned());
+		b.modports.extend(body.modports.iter().cloned());
+
+		for inst in &body.insts {
+			self.elaborate_inst(inst, env, scope)?;
+		}
+
+		for region in &body.genreg {
+			self.elaborate_body_items(region, env, scope, gens)?;
+		}
+		gens.extend(body.genfors.iter().map(|x| GenerateItem::For(x)));
+		gens.extend(body.genifs.iter().map(|x| GenerateItem::If(x)));
+		gens.extend(body.gencases.iter().map(|x| GenerateItem::Case(x)));
+		Ok(())
+	}
+
+	/// Record the dimensions of the ports of a module or interface that are
+	/// nets.
+	fn declare_ports(&mut self, target: NodeId, env: &ConstEnv) {
+		let ports = match self.mods.get(&target) {
+			Some(m) => &m.ports,
+			None => match self.intfs.get(&target) {
+				Some(i) => &i.ports,
+				None => return,
+			},
+		};
+		for slice in ports.iter().flat_map(|p| p.slices.iter()) {
+			let is_net = match slice.kind {
+				ast::PortKind::Net(_) => true,
+				ast::PortKind::Var => false,
+			};
+			if is_net {
+				let dims = self.net_dims(slice.ty.as_ref(), &slice.dims, env);
+				self.nets.insert(slice.id, dims);
+			}
+		}
+	}
+
+	/// Determine the dimensions of a net, given its data type and unpacked
+	/// dimensions.
+	fn net_dims(&mut self, ty: Option<&ast::Type>, unpacked: &[ast::TypeDim], env: &ConstEnv) -> Option<Vec<usize>> {
+		let elem = match ty.map(|ty| &ty.data) {
+			None |
+			Some(&ast::ImplicitType) |
+			Some(&ast::BitType) |
+			Some(&ast::LogicType) |
+			Some(&ast::RegType) => 1,
+			Some(&ast::ByteType) => 8,
+			Some(&ast::ShortIntType) => 16,
+			Some(&ast::IntType) => 32,
+			Some(&ast::LongIntType) |
+			Some(&ast::TimeType) => 64,
+			_ => return None,
+		};
+		let packed = ty.map(|ty| &ty.dims[..]).unwrap_or(&[]);
+		let mut dims = Vec::new();
+		for dim in unpacked.iter().chain(packed.iter()) {
+			let size = match *dim {
+				ast::TypeDim::Expr(ref size) => consteval::const_eval(self.nameres, size, env).ok()?.as_int(),
+				ast::TypeDim::Range(ref lhs, ref rhs) => {
+					let lhs = consteval::const_eval(self.nameres, lhs, env).ok()?.as_int();
+					let rhs = consteval::const_eval(self.nameres, rhs, env).ok()?.as_int();
+					(lhs - rhs).abs() + 1
+				}
+				_ => return None,
+			};
+			dims.push(size as usize);
+		}
+		dims.push(elem);
+		Some(dims)
+	}
+
+	/// Check that the nets connected by an alias statement have the same
+	/// width, and that no net is aliased to itself or to the same net twice.
+	/// IEEE 1800-2009 10.11
+	fn check_alias(&mut self, alias: &ast::Alias, env: &ConstEnv) {
+		let mut first: Option<(Span, usize)> = None;
+		let mut targets: Vec<(AliasTarget, Span)> = Vec::new();
+		for net in &alias.nets {
+			let width = match self.alias_dims(net, env) {
+				Ok(Some(dims)) => dims.iter().product::<usize>(),
+				Ok(None) | Err(()) => continue,
+			};
+			if let Some(target) = self.alias_target(net, env) {
+				targets.push((target, net.span));
+			}
+			match first {
+				None => first = Some((net.span, width)),
+				Some((span, w)) if w != width => {
+					self.add_diag(DiagBuilder2::error(format!("Net in alias statement is {} bits wide, but previous net is {} bits wide", width, w))
+						.span(net.span)
+						.add_note("previous net was here:")
+						.span(span));
+				}
+				Some(_) => (),
+			}
+		}
+
+		for (i, &(a, a_span)) in targets.iter().enumerate() {
+			for &(b, b_span) in &targets[i+1..] {
+				if a == b {
+					self.add_diag(DiagBuilder2::error(format!("`{}` is aliased to itself", b_span.extract()))
+						.span(b_span)
+						.add_note("previously listed here:")
+						.span(a_span));
+					continue;
+				}
+				let pair = if a < b { (a, b) } else { (b, a) };
+				if let Some(&prev) = self.aliased.get(&pair) {
+					self.add_diag(DiagBuilder2::error(format!("`{}` and `{}` are already aliased to each other", a_span.extract(), b_span.extract()))
+						.span(alias.span)
+						.add_note("previous alias statement was here:")
+						.span(prev));
+				} else {
+					self.aliased.insert(pair, alias.span);
+				}
+			}
+		}
+	}
+
+	/// Determine the net and bits an operand of an alias statement refers to,
+	/// as in `a` or `a[3:0]`. Yields `None` for concatenations and selects
+	/// whose bounds are not constant.
+	fn alias_target(&self, expr: &ast::Expr, env: &ConstEnv) -> Option<AliasTarget> {
+		let eval = |e: &ast::Expr| consteval::const_eval(self.nameres, e, env).ok().map(|v| v.as_int());
+		match expr.data {
+			ast::IdentExpr(ref ident) => self.nameres.get(&ident.id).map(|&id| (id, None)),
+			ast::IndexExpr { ref indexee, ref index } => {
+				let id = match indexee.data {
+					ast::IdentExpr(ref ident) => *self.nameres.get(&ident.id)?,
+					_ => return None,
+				};
+				let bits = match index.data {
+					ast::RangeExpr { mode: ast::RangeMode::Absolute, ref lhs, ref rhs } => (eval(lhs)?, eval(rhs)?),
+					ast::RangeExpr { mode: ast::RangeMode::RelativeUp, ref lhs, ref rhs } => {
+						let (base, width) = (eval(lhs)?, eval(rhs)?);
+						(base + width - 1, base)
+					}
+					ast::RangeExpr { mode: ast::RangeMode::RelativeDown, ref lhs, ref rhs } => {
+						let (base, width) = (eval(lhs)?, eval(rhs)?);
+						(base, base - width + 1)
+					}
+					_ => {
+						let i = eval(index)?;
+						(i, i)
+					}
+				};
+				Some((id, Some(bits)))
+			}
+			_ => None,
+		}
+	}
+
+	/// Determine the dimensions of an operand of an alias statement. Yields
+	/// `None` if they cannot be determined.
+	fn alias_dims(&mut self, expr: &ast::Expr, env: &ConstEnv) -> Result<Option<Vec<usize>>> {
+		match expr.data {
+			ast::IdentExpr(ref ident) => {
+				match self.nameres.get(&ident.id).and_then(|id| self.nets.get(id)) {
+					Some(dims) => return Ok(dims.clone()),
+					None => (),
+				}
+				self.add_diag(DiagBuilder2::error(format!("`{}` is not a net; only nets can be aliased", ident.name)).span(ident.span));
+				Err(())
+			}
+			ast::IndexExpr { ref indexee, ref index } => {
+				let dims = match self.alias_dims(indexee, env)? {
+					Some(ref dims) if dims.len() > 1 => dims[1..].to_vec(),
+					_ => return Ok(None),
+				};
+				let size = match index.data {
+					ast::RangeExpr { mode: ast::RangeMode::Absolute, ref lhs, ref rhs } => {
+						let lhs = self.const_eval(lhs, env)?.as_int();
+						let rhs = self.const_eval(rhs, env)?.as_int();
+						(lhs - rhs).abs() as usize + 1
+					}
+					ast::RangeExpr { ref rhs, .. } => self.const_eval(rhs, env)?.as_int() as usize,
+					_ => return Ok(Some(dims)),
+				};
+				Ok(Some(std::iter::once(size).chain(dims.into_iter()).collect()))
+			}
+			ast::ConcatExpr { ref repeat, ref exprs } => {
+				let mut width = 0;
+				for expr in exprs {
+					match self.alias_dims(expr, env)? {
+						Some(dims) => width += dims.iter().product::<usize>(),
+						None => return Ok(None),
+					}
+				}
+				if let Some(ref repeat) = *repeat {
+					width *= self.const_eval(repeat, env)?.as_int() as usize;
+				}
+				Ok(Some(vec![width]))
+			}
+			_ => {
+				self.add_diag(DiagBuilder2::error("Only nets, selects of nets, and concatenations thereof can be aliased").span(expr.span));
+				Err(())
+			}
+		}
+	}
+
+	/// Elaborate the instances of an instantiation into a scope.
+	fn elaborate_inst(&mut self, inst: &ast::Inst, env: &ConstEnv, scope: &mut InstScope) -> Result<()> {
+		scope.body.insts.push(inst.clone());
+		let target = match self.nameres.get(&inst.target.id) {
+			Some(&id) => id,
+			None => return Ok(()),
+		};
+		// Instances of primitives are leaf cells and have no body to
+		// elaborate.
+		if !self.mods.contains_key(&target) && !self.intfs.contains_key(&target) && !self.checkers.contains_key(&target) {
+			return Ok(());
+		}
+		if let Some(ref delay) = inst.delay {
+			self.add_diag(DiagBuilder2::error(format!("`{}` is not a primitive; its instances cannot have a delay", inst.target.name))
+				.span(delay.span)
+				.add_note("parameter values are assigned with `#(...)`"));
+			return Err(());
+		}
+		for name in &inst.names {
+			let child = self.elaborate_instance(name.name.name, name.span, target, &inst.params, env)?;
+			scope.insts.push(child);
+		}
+		Ok(())
+	}
+
+	/// Add the instantiations of the bind directives that target an instance
+	/// of the module or interface `target` to its scope.
+	fn elaborate_binds(&mut self, target: NodeId, env: &ConstEnv, scope: &mut InstScope) -> Result<()> {
+		let binds = self.binds;
+		for (index, bind) in binds.iter().enumerate() {
+			if self.is_bind_target(bind, target) {
+				self.bound[index] = true;
+				self.elaborate_inst(&bind.inst, env, scope)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Check whether a bind directive targets the instance being elaborated.
+	/// A bind directive either names a single instance by its hierarchical
+	/// name, or a module or interface and optionally a list of its instances.
+	fn is_bind_target(&self, bind: &ast::BindDirective, target: NodeId) -> bool {
+		let matches = |names: &[ast::Identifier]| {
+			names.len() <= self.path.len()
+				&& self.path[self.path.len() - names.len()..].iter().zip(names).all(|(a, b)| *a == b.name)
+		};
+		if bind.target.len() > 1 {
+			return bind.target.len() == self.path.len() && matches(&bind.target);
+		}
+		match self.nameres.get(&bind.target[0].id) {
+			Some(&id) if id == target => (),
+			_ => return false,
+		}
+		bind.insts.is_empty() || bind.insts.iter().any(|names| matches(names))
+	}
+
+	/// Unroll a generate loop. Each iteration produces a scope named after the
+	/// generate block and the value of the genvar, e.g. `gen_blk[3]`.
+	fn elaborate_generate_for(&mut self, gf: &GenerateFor, index: usize, env: &ConstEnv, scope: &mut InstScope) -> Result<()> {
+		let mut env = env.clone();
+		let (genvar, genvar_name, mut value) = self.elaborate_genvar_init(&gf.init, &mut env)?;
+		if env.active.contains(&genvar) {
+			self.add_diag(DiagBuilder2::error(format!("genvar `{}` is already the index of an enclosing generate loop", genvar_name))
+				.span(gf.init.span));
+			return Err(());
+		}
+		env.active.insert(genvar);
+
+		let base = match gf.block.label {
+			Some(l) => format!("{}", l),
+			None => format!("genblk{}", index),
+		};
+		let mut seen = HashSet::new();
+		loop {
+			env.values.insert(genvar, value);
+			if !self.const_eval(&gf.cond, &env)?.is_true() {
+				break;
+			}
+
+			// Each value of the genvar may only occur once, which also catches
+			// loops that never advance. Loops that run for too long are
+			// stopped as well.
+			if !seen.insert(value) {
+				self.add_diag(DiagBuilder2::error(format!("genvar `{}` takes on the value {} more than once", genvar_name, value))
+					.span(gf.span)
+					.add_note("the generate loop does not terminate"));
+				return Err(());
+			}
+			if seen.len() > MAX_GENERATE_ITERATIONS {
+				self.add_diag(DiagBuilder2::error(format!("generate loop exceeds {} iterations", MAX_GENERATE_ITERATIONS))
+					.span(gf.span));
+				return Err(());
+			}
+
+			let mut sub = InstScope {
+				name: get_name_table().intern(&format!("{}[{}]", base, value), true),
+				span: gf.block.span,
+				values: HashMap::new(),
+				body: empty_hierarchy_body(),
+				scopes: Vec::new(),
+				insts: Vec::new(),
+				types: BTreeMap::new(),
+				hier_refs: HashMap::new(),
+			};
+			sub.values.insert(genvar, value);
+			self.path.push(sub.name);
+			self.elaborate_body(&gf.block.body, &mut env.clone(), &mut sub)?;
+			self.path.pop();
+			scope.scopes.push(sub);
+
+			value = self.elaborate_genvar_step(&gf.step, genvar, genvar_name, &env)?;
+		}
+		Ok(())
+	}
+
+	/// Determine the genvar and initial value of a generate loop.
+	fn elaborate_genvar_init(&mut self, init: &ast::Stmt, env: &mut ConstEnv) -> Result<(NodeId, Name, ConstValue)> {
+		let (lhs, rhs) = match init.data {
+			ast::GenvarDeclStmt(ref decls) if decls.len() == 1 && decls[0].init.is_some() => {
+				let decl = &decls[0];
+				let value = self.const_eval(decl.init.as_ref().unwrap(), env)?;
+				env.genvars.insert(decl.id);
+				return Ok((decl.id, decl.name, value));
+			}
+			ast::BlockingAssignStmt { ref lhs, ref rhs, op: ast::AssignOp::Identity } => (lhs, rhs),
+			ast::ExprStmt(ast::Expr { data: ast::AssignExpr { op: ast::AssignOp::Identity, ref lhs, ref rhs }, .. }) => (&**lhs, &**rhs),
+			_ => {
+				self.add_diag(DiagBuilder2::error("generate loop must be initialized with an assignment to a genvar")
+					.span(init.span));
+				return Err(());
+			}
+		};
+		let (genvar, name) = self.resolve_genvar(lhs, env)?;
+		let value = self.const_eval(rhs, env)?;
+		Ok((genvar, name, value))
+	}
+
+	/// Compute the next value of the genvar of a generate loop.
+	fn elaborate_genvar_step(&mut self, step: &ast::Expr, genvar: NodeId, genvar_name: Name, env: &ConstEnv) -> Result<ConstValue> {
+		let (target, value) = match step.data {
+			ast::AssignExpr { ref op, ref lhs, ref rhs } => {
+				let rhs = self.const_eval(rhs, env)?;
+				(lhs, self.check(consteval::const_assign_op(op, env.values[&genvar], rhs, step.span)))
+			}
+			ast::UnaryExpr { op: Op::Inc, ref expr, .. } => (expr, Ok(ConstValue::Int(env.values[&genvar].as_int().wrapping_add(1)))),
+			ast::UnaryExpr { op: Op::Dec, ref expr, .. } => (expr, Ok(ConstValue::Int(env.values[&genvar].as_int().wrapping_sub(1)))),
+			_ => {
+				self.add_diag(DiagBuilder2::error("generate loop step must be an assignment to the genvar")
+					.span(step.span));
+				return Err(());
+			}
+		};
+		let (id, _) = self.resolve_genvar(target, env)?;
+		if id != genvar {
+			self.add_diag(DiagBuilder2::error(format!("generate loop step must assign to genvar `{}`", genvar_name))
+				.span(target.span));
+			return Err(());
+		}
+		value
+	}
+
+	/// Resolve the target of a genvar assignment, making sure it is a genvar.
+	fn resolve_genvar(&mut self, expr: &ast::Expr, env: &ConstEnv) -> Result<(NodeId, Name)> {
+		let ident = match expr.data {
+			ast::IdentExpr(ref ident) => ident,
+			_ => {
+				self.add_diag(DiagBuilder2::error("expected a genvar").span(expr.span));
+				return Err(());
+			}
+		};
+		match self.nameres.get(&ident.id) {
+			Some(&id) if env.genvars.contains(&id) => Ok((id, ident.name)),
+			_ => {
+				self.add_diag(DiagBuilder2::error(format!("`{}` is not a genvar", ident.name))
+					.span(ident.span)
+					.add_note("the index of a generate loop must be declared with `genvar`"));
+				Err(())
+			}
+		}
+	}
+
+	/// Evaluate a generate conditional and elaborate the selected block, if
+	/// any, into a nested scope.
+	fn elaborate_generate_if(&mut self, gi: &GenerateIf, index: usize, env: &ConstEnv, scope: &mut InstScope) -> Result<()> {
+		let block = if self.const_eval(&gi.cond, env)?.is_true() {
+			&gi.main_block
+		} else {
+			match gi.else_block {
+				Some(ref b) => b,
+				None => return Ok(()),
+			}
+		};
+		self.elaborate_generate_block(block, index, env, scope)
+	}
+
+	/// Evaluate a case-generate construct and elaborate the block of the first
+	/// matching item, or the `default` item if none matches, into a nested
+	/// scope.
+	/// IEEE 1800-2009 27.5
+	fn elaborate_generate_case(&mut self, gc: &GenerateCase, index: usize, env: &ConstEnv, scope: &mut InstScope) -> Result<()> {
+		let value = self.const_eval(&gc.expr, env)?;
+		let mut default = None;
+		for item in &gc.items {
+			if item.exprs.is_empty() {
+				default = Some(&item.block);
+				continue;
+			}
+			for expr in &item.exprs {
+				if self.const_eval(expr, env)? == value {
+					return self.elaborate_generate_block(&item.block, index, env, scope);
+				}
+			}
+		}
+		match default {
+			Some(block) => self.elaborate_generate_block(block, index, env, scope),
+			None => Ok(()),
+		}
+	}
+
+	/// Elaborate the block selected by a generate conditional into a nested
+	/// scope.
+	fn elaborate_generate_block(&mut self, block: &GenerateBlock, index: usize, env: &ConstEnv, scope: &mut InstScope) -> Result<()> {
+		// An unnamed block that consists of nothing but another generate
+		// conditional does not form a scope of its own. This covers the
+		// `else if` chains.
+		if block.label.is_none() && is_single_generate_cond(&block.body) {
+			return match block.body.genifs.first() {
+				Some(gi) => self.elaborate_generate_if(gi, index, env, scope),
+				None => self.elaborate_generate_case(&block.body.gencases[0], index, env, scope),
+			};
+		}
+
+		let name = match block.label {
+			Some(l) => l,
+			None => get_name_table().intern(&format!("genblk{}", index), true),
+		};
+		let mut sub = InstScope {
+			name: name,
+			span: block.span,
+			values: HashMap::new(),
+			body: empty_hierarchy_body(),
+			scopes: Vec::new(),
+			insts: Vec::new(),
+			types: BTreeMap::new(),
+			hier_refs: HashMap::new(),
+		};
+		self.path.push(name);
+		self.elaborate_body(&block.body, &mut env.clone(), &mut sub)?;
+		self.path.pop();
+		scope.scopes.push(sub);
+		Ok(())
+	}
+}
+
+
+/// Create a hierarchy body without any items.
+pub fn empty_hierarchy_body() -> HierarchyBody {
+	HierarchyBody {
+		procs: vec![],
+		nets: vec![],
+		vars: vec![],
+		assigns: vec![],
+		params: vec![],
+		insts: vec![],
+		gates: vec![],
+		genreg: vec![],
+		genvars: vec![],
+		genfors: vec![],
+		genifs: vec![],
+		gencases: vec![],
+		classes: vec![],
+		subroutines: vec![],
+		asserts: vec![],
+		typedefs: vec![],
+		covergroups: vec![],
+		dpi_decls: vec![],
+		specparams: vec![],
+		specify_blocks: vec![],
+		clockings: vec![],
+		default_clockings: vec![],
+		sequences: vec![],
+		properties: vec![],
+		nettypes: vec![],
+		aliases: vec![],
+		modports: vec![],
+	}
+}
+
+/// Check if a hierarchy body consists of a single generate conditional.
+fn is_single_generate_cond(b: &HierarchyBody) -> bool {
+	b.genifs.len() + b.gencases.len() == 1
+		&& b.procs.is_empty() && b.nets.is_empty() && b.vars.is_empty()
+		&& b.assigns.is_empty() && b.params.is_empty() && b.insts.is_empty()
+		&& b.gates.is_empty()
+		&& b.genreg.is_empty() && b.genvars.is_empty() && b.genfors.is_empty()
+		&& b.classes.is_empty() && b.subroutines.is_empty()
+		&& b.asserts.is_empty() && b.typedefs.is_empty() && b.covergroups.is_empty()
+		&& b.dpi_decls.is_empty()
+		&& b.specparams.is_empty()
+		&& b.specify_blocks.is_empty()
+		&& b.clockings.is_empty()
+		&& b.default_clockings.is_empty()
+		&& b.sequences.is_empty()
+		&& b.properties.is_empty()
+		&& b.nettypes.is_empty()
+		&& b.aliases.is_empty()
+		&& b.modports.is_empty()
+}
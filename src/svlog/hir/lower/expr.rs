@@ -0,0 +1,257 @@
+// Copyright (c) 2017 Fabian Schuiki
+
+//! This module implements the lowering of AST expressions to HIR. Expressions
+//! that are assigned to are lowered to lvalues, which only admit the forms
+//! that can be assigned to, and compound assignments are expanded.
+
+use moore_common::errors::*;
+use moore_svlog_syntax::ast;
+use moore_svlog_syntax::resolve::NameResolution;
+use moore_svlog_syntax::token::Op;
+use nodes::*;
+
+
+/// Lower an expression that yields a value.
+pub fn lower_expr(nameres: &NameResolution, expr: &ast::Expr) -> DiagResult2<Expr> {
+	let kind = match expr.data {
+		ast::DummyExpr => {
+			return Err(DiagBuilder2::error("invalid expression").span(expr.span));
+		}
+		ast::LiteralExpr(ref lit) => ExprKind::Lit(lit.clone()),
+		ast::IdentExpr(ref ident) |
+		ast::ScopeExpr(_, ref ident) => ExprKind::Ident(lower_ident(nameres, ident)),
+		ast::SysIdentExpr(ref ident) => ExprKind::SysCall(*ident, Vec::new()),
+		ast::CallExpr(ref callee, ref args) => {
+			let args = lower_call_args(nameres, args)?;
+			match callee.data {
+				ast::SysIdentExpr(ref ident) => ExprKind::SysCall(*ident, args),
+				_ => ExprKind::Call(Box::new(lower_expr(nameres, callee)?), args),
+			}
+		}
+		ast::IndexExpr { ref indexee, ref index } => {
+			ExprKind::Select(Box::new(lower_expr(nameres, indexee)?), lower_select(nameres, index)?)
+		}
+		ast::UnaryExpr { op: op @ Op::Inc, ref expr, postfix } |
+		ast::UnaryExpr { op: op @ Op::Dec, ref expr, postfix } => {
+			ExprKind::IncDec {
+				op: op,
+				postfix: postfix,
+				target: Box::new(lower_lvalue(nameres, expr)?),
+			}
+		}
+		ast::UnaryExpr { op, ref expr, .. } => ExprKind::Unary(op, Box::new(lower_expr(nameres, expr)?)),
+		ast::BinaryExpr { op, ref lhs, ref rhs } => {
+			ExprKind::Binary(op, Box::new(lower_expr(nameres, lhs)?), Box::new(lower_expr(nameres, rhs)?))
+		}
+		ast::TernaryExpr { ref cond, ref true_expr, ref false_expr } => {
+			ExprKind::Ternary(
+				Box::new(lower_expr(nameres, cond)?),
+				Box::new(lower_expr(nameres, true_expr)?),
+				Box::new(lower_expr(nameres, false_expr)?),
+			)
+		}
+		ast::AssignExpr { ref op, ref lhs, ref rhs } => {
+			let (lhs, rhs) = lower_assign(nameres, op, lhs, rhs, expr.id, expr.span)?;
+			ExprKind::Assign(Box::new(lhs), Box::new(rhs))
+		}
+		ast::TypeExpr(ref ty) => ExprKind::Type((**ty).clone()),
+		ast::ConstructorCallExpr(ref args) => ExprKind::ConstructorCall(lower_call_args(nameres, args)?),
+		ast::ClassNewExpr(ref expr) => ExprKind::New(lower_opt_expr(nameres, expr)?),
+		ast::ArrayNewExpr(ref size, ref init) => {
+			ExprKind::ArrayNew(Box::new(lower_expr(nameres, size)?), lower_opt_expr(nameres, init)?)
+		}
+		ast::EmptyQueueExpr => ExprKind::EmptyQueue,
+		ast::InsideExpr(ref expr, ref ranges) => {
+			ExprKind::Inside(Box::new(lower_expr(nameres, expr)?), lower_exprs(nameres, ranges)?)
+		}
+		ast::StreamConcatExpr { ref slice, ref exprs } => {
+			let mut lowered = Vec::new();
+			for e in exprs {
+				lowered.push(StreamExpr {
+					expr: lower_expr(nameres, &e.expr)?,
+					range: match e.range {
+						Some(ref r) => Some(lower_expr(nameres, r)?),
+						None => None,
+					},
+				});
+			}
+			ExprKind::Stream(lower_stream_slice(nameres, slice)?, lowered)
+		}
+		ast::ConcatExpr { ref repeat, ref exprs } => {
+			ExprKind::Concat(lower_opt_expr(nameres, repeat)?, lower_exprs(nameres, exprs)?)
+		}
+		ast::MinTypMaxExpr { ref min, ref typ, ref max } => {
+			ExprKind::MinTypMax(
+				Box::new(lower_expr(nameres, min)?),
+				Box::new(lower_expr(nameres, typ)?),
+				Box::new(lower_expr(nameres, max)?),
+			)
+		}
+		ast::RangeExpr { mode, ref lhs, ref rhs } => {
+			ExprKind::Range(mode, Box::new(lower_expr(nameres, lhs)?), Box::new(lower_expr(nameres, rhs)?))
+		}
+		ast::MemberExpr { ref expr, ref name } => ExprKind::Member(Box::new(lower_expr(nameres, expr)?), *name),
+		ast::PatternExpr(ref fields) => {
+			let mut lowered = Vec::new();
+			for field in fields {
+				lowered.push(match field.data {
+					ast::PatternFieldData::Default(ref e) => PatternField::Default(lower_expr(nameres, e)?),
+					ast::PatternFieldData::Member(ref m, ref e) => {
+						PatternField::Member(lower_expr(nameres, m)?, lower_expr(nameres, e)?)
+					}
+					ast::PatternFieldData::Type(ref ty, ref e) => PatternField::Type(ty.clone(), lower_expr(nameres, e)?),
+					ast::PatternFieldData::Expr(ref e) => PatternField::Expr(lower_expr(nameres, e)?),
+					ast::PatternFieldData::Repeat(ref n, ref es) => {
+						PatternField::Repeat(lower_expr(nameres, n)?, lower_exprs(nameres, es)?)
+					}
+				});
+			}
+			ExprKind::Pattern(lowered)
+		}
+		ast::RandomizeWithExpr { ref call, ref names, ref items } => {
+			ExprKind::Randomize {
+				call: Box::new(lower_expr(nameres, call)?),
+				names: names.clone(),
+				items: items.clone(),
+			}
+		}
+	};
+	Ok(Expr {
+		id: expr.id,
+		span: expr.span,
+		kind: kind,
+	})
+}
+
+/// Lower an expression that is assigned to. Expressions other than names,
+/// selects, member accesses, concatenations, streaming concatenations, and
+/// assignment patterns cannot be assigned to.
+pub fn lower_lvalue(nameres: &NameResolution, expr: &ast::Expr) -> DiagResult2<LValue> {
+	let kind = match expr.data {
+		ast::IdentExpr(ref ident) |
+		ast::ScopeExpr(_, ref ident) => LValueKind::Ident(lower_ident(nameres, ident)),
+		ast::IndexExpr { ref indexee, ref index } => {
+			LValueKind::Select(Box::new(lower_lvalue(nameres, indexee)?), lower_select(nameres, index)?)
+		}
+		ast::MemberExpr { ref expr, ref name } => LValueKind::Member(Box::new(lower_expr(nameres, expr)?), *name),
+		ast::ConcatExpr { repeat: None, ref exprs } => {
+			let mut lowered = Vec::new();
+			for e in exprs {
+				lowered.push(lower_lvalue(nameres, e)?);
+			}
+			LValueKind::Concat(lowered)
+		}
+		ast::StreamConcatExpr { ref slice, ref exprs } => {
+			let mut lowered = Vec::new();
+			for e in exprs {
+				lowered.push(LValueStreamExpr {
+					target: lower_lvalue(nameres, &e.expr)?,
+					range: match e.range {
+						Some(ref r) => Some(lower_expr(nameres, r)?),
+						None => None,
+					},
+				});
+			}
+			LValueKind::Stream(lower_stream_slice(nameres, slice)?, lowered)
+		}
+		ast::PatternExpr(ref fields) => {
+			let mut lowered = Vec::new();
+			for field in fields {
+				match field.data {
+					ast::PatternFieldData::Expr(ref e) => lowered.push(lower_lvalue(nameres, e)?),
+					_ => {
+						return Err(DiagBuilder2::error("only positional assignment patterns can be assigned to")
+							.span(field.span));
+					}
+				}
+			}
+			LValueKind::Pattern(lowered)
+		}
+		_ => return Err(DiagBuilder2::error("expression cannot be assigned to").span(expr.span)),
+	};
+	Ok(LValue {
+		id: expr.id,
+		span: expr.span,
+		kind: kind,
+	})
+}
+
+/// Lower an assignment `lhs op= rhs`. Compound assignments are expanded into
+/// `lhs = lhs op rhs`, where the operation is assigned the given ID and span.
+pub fn lower_assign(nameres: &NameResolution, op: &ast::AssignOp, lhs: &ast::Expr, rhs: &ast::Expr, id: NodeId, span: Span) -> DiagResult2<(LValue, Expr)> {
+	let target = lower_lvalue(nameres, lhs)?;
+	let value = lower_expr(nameres, rhs)?;
+	let op = match *op {
+		ast::AssignOp::Identity => return Ok((target, value)),
+		ast::AssignOp::Add => Op::Add,
+		ast::AssignOp::Sub => Op::Sub,
+		ast::AssignOp::Mul => Op::Mul,
+		ast::AssignOp::Div => Op::Div,
+		ast::AssignOp::Mod => Op::Mod,
+		ast::AssignOp::BitAnd => Op::BitAnd,
+		ast::AssignOp::BitOr => Op::BitOr,
+		ast::AssignOp::BitXor => Op::BitXor,
+		ast::AssignOp::LogicShL => Op::LogicShL,
+		ast::AssignOp::LogicShR => Op::LogicShR,
+		ast::AssignOp::ArithShL => Op::ArithShL,
+		ast::AssignOp::ArithShR => Op::ArithShR,
+	};
+	let current = lower_expr(nameres, lhs)?;
+	Ok((target, Expr {
+		id: id,
+		span: span,
+		kind: ExprKind::Binary(op, Box::new(current), Box::new(value)),
+	}))
+}
+
+pub fn lower_ident(nameres: &NameResolution, ident: &ast::Identifier) -> Ident {
+	Ident {
+		id: ident.id,
+		name: ident.name,
+		span: ident.span,
+		def: nameres.get(&ident.id).cloned(),
+	}
+}
+
+fn lower_select(nameres: &NameResolution, index: &ast::Expr) -> DiagResult2<Select> {
+	Ok(match index.data {
+		ast::RangeExpr { mode, ref lhs, ref rhs } => {
+			Select::Range(mode, Box::new(lower_expr(nameres, lhs)?), Box::new(lower_expr(nameres, rhs)?))
+		}
+		_ => Select::Bit(Box::new(lower_expr(nameres, index)?)),
+	})
+}
+
+fn lower_exprs(nameres: &NameResolution, exprs: &[ast::Expr]) -> DiagResult2<Vec<Expr>> {
+	exprs.iter().map(|e| lower_expr(nameres, e)).collect()
+}
+
+fn lower_opt_expr(nameres: &NameResolution, expr: &Option<Box<ast::Expr>>) -> DiagResult2<Option<Box<Expr>>> {
+	match *expr {
+		Some(ref e) => Ok(Some(Box::new(lower_expr(nameres, e)?))),
+		None => Ok(None),
+	}
+}
+
+fn lower_call_args(nameres: &NameResolution, args: &[ast::CallArg]) -> DiagResult2<Vec<CallArg>> {
+	let mut lowered = Vec::new();
+	for arg in args {
+		lowered.push(CallArg {
+			span: arg.span,
+			name: arg.name,
+			expr: match arg.expr {
+				Some(ref e) => Some(lower_expr(nameres, e)?),
+				None => None,
+			},
+		});
+	}
+	Ok(lowered)
+}
+
+fn lower_stream_slice(nameres: &NameResolution, slice: &Option<ast::StreamConcatSlice>) -> DiagResult2<Option<StreamSlice>> {
+	Ok(match *slice {
+		Some(ast::StreamConcatSlice::Expr(ref e)) => Some(StreamSlice::Expr(Box::new(lower_expr(nameres, e)?))),
+		Some(ast::StreamConcatSlice::Type(ref ty)) => Some(StreamSlice::Type(ty.clone())),
+		None => None,
+	})
+}
@@ -0,0 +1,679 @@
+// Copyright (c) 2017 Fabian Schuiki
+
+//! This module resolves the hierarchical names in an elaborated design, e.g.
+//! `top.u_core.result`. The first name is searched downwards among the
+//! instances, generate scopes, and named blocks visible where the name is
+//! used, and then upwards through the enclosing instances. The remaining names
+//! select nested instances, generate scopes, interface ports, and named blocks
+//! until a declaration is reached. The interface ports of each instance are
+//! bound to the interface instances connected to them along the way.
+//! IEEE 1800-2009 23.6, 23.8, 25.3
+
+use std;
+use std::collections::HashMap;
+use moore_common::errors::*;
+use moore_common::name::get_name_table;
+use moore_common::Session;
+use moore_svlog_syntax::ast;
+use moore_svlog_syntax::resolve::{HierName, NameResolution};
+use nodes::*;
+use lower::Result;
+use lower::consteval::{self, ConstEnv};
+
+
+/// Resolve the hierarchical names used within an elaborated design, and
+/// record their targets in the scopes they are used in. Also binds the
+/// interface ports of the instances.
+pub fn resolve_hier_names(
+	session: &Session,
+	nameres: &NameResolution,
+	mods: &HashMap<NodeId, Module>,
+	intfs: &HashMap<NodeId, Interface>,
+	checkers: &HashMap<NodeId, Checker>,
+	design: &mut Instance,
+) -> Result<()> {
+	let (found, bindings) = {
+		let mut r = HierResolver {
+			session: session,
+			nameres: nameres,
+			severity: Severity::Note,
+			mods: mods,
+			intfs: intfs,
+			checkers: checkers,
+			found: Vec::new(),
+			bindings: Vec::new(),
+		};
+		r.visit_instance(&mut vec![Level::Inst(design)], &mut Vec::new());
+		if r.is_error() {
+			return Err(());
+		}
+		(r.found, r.bindings)
+	};
+	for (steps, id, target) in found {
+		scope_mut(&mut design.scope, &steps).hier_refs.insert(id, target);
+	}
+	for (steps, id, binding) in bindings {
+		let inst = match steps.split_last() {
+			Some((&Step::Inst(i), outer)) => &mut scope_mut(&mut design.scope, outer).insts[i],
+			_ => &mut *design,
+		};
+		inst.intf_ports.insert(id, binding);
+	}
+	Ok(())
+}
+
+
+#[allow(dead_code)]
+struct HierResolver<'a> {
+	session: &'a Session,
+	nameres: &'a NameResolution,
+	severity: Severity,
+	mods: &'a HashMap<NodeId, Module>,
+	intfs: &'a HashMap<NodeId, Interface>,
+	checkers: &'a HashMap<NodeId, Checker>,
+	/// The resolved names, together with the way from the top instance to the
+	/// scope they are used in.
+	found: Vec<(Vec<Step>, NodeId, HierRef)>,
+	/// The interface ports bound, together with the way from the top instance
+	/// to the instance they belong to.
+	bindings: Vec<(Vec<Step>, NodeId, IntfBinding)>,
+}
+
+/// A level of the instance tree on the way from the top instance to a scope.
+#[derive(Clone, Copy)]
+enum Level<'a> {
+	/// The outermost scope of an instance.
+	Inst(&'a Instance),
+	/// A generate scope.
+	Scope(&'a InstScope),
+}
+
+impl<'a> Level<'a> {
+	fn scope(&self) -> &'a InstScope {
+		match *self {
+			Level::Inst(inst) => &inst.scope,
+			Level::Scope(scope) => scope,
+		}
+	}
+
+	fn name(&self) -> Name {
+		match *self {
+			Level::Inst(inst) => inst.name,
+			Level::Scope(scope) => scope.name,
+		}
+	}
+}
+
+/// A step from a scope into one of its nested scopes or instances.
+#[derive(Clone, Copy)]
+enum Step {
+	Scope(usize),
+	Inst(usize),
+}
+
+/// A position in the instance tree reached while following a hierarchical
+/// name.
+#[derive(Clone)]
+struct Cursor<'a> {
+	/// The levels from the top instance down to the reached scope.
+	levels: Vec<Level<'a>>,
+	/// The named blocks entered within that scope.
+	blocks: Vec<Name>,
+}
+
+/// One element of a hierarchical name, e.g. `gen_blk[2]`.
+struct Segment<'a> {
+	ident: &'a ast::Identifier,
+	index: Option<&'a ast::Expr>,
+}
+
+impl<'a> HierResolver<'a> {
+	fn add_diag(&mut self, diag: DiagBuilder2) {
+		self.severity = std::cmp::max(self.severity, diag.severity);
+		println!("{}", diag);
+	}
+
+	fn is_error(&self) -> bool {
+		self.severity >= Severity::Error
+	}
+
+	/// Resolve the names used within an instance and the instances below it.
+	/// The last level is the instance itself.
+	fn visit_instance(&mut self, levels: &mut Vec<Level<'a>>, steps: &mut Vec<Step>) {
+		let target = match *levels.last().unwrap() {
+			Level::Inst(inst) => inst.target,
+			Level::Scope(_) => unreachable!(),
+		};
+
+		// Names used within a generate block are resolved in each scope the
+		// block has been elaborated into. Names in blocks that have not been
+		// selected are skipped.
+		let mut blocks = Vec::new();
+		if let Some(body) = self.body_of(target) {
+			collect_generate_blocks(body, &mut blocks);
+		}
+		if levels.len() > 1 {
+			self.bind_intf_ports(levels, steps);
+		}
+		let names: Vec<_> = self.nameres.hier_names.iter()
+			.filter(|n| n.parent == target)
+			.map(|n| (n, innermost_block(&blocks, n.expr.span)))
+			.collect();
+		self.visit_scope(levels, steps, None, &names);
+	}
+
+	fn visit_scope(&mut self, levels: &mut Vec<Level<'a>>, steps: &mut Vec<Step>, block: Option<Span>, names: &[(&'a HierName, Option<Span>)]) {
+		for &(name, b) in names {
+			if b == block {
+				self.resolve_name(levels, steps, name);
+			}
+		}
+		let scope = levels.last().unwrap().scope();
+		for (i, sub) in scope.scopes.iter().enumerate() {
+			levels.push(Level::Scope(sub));
+			steps.push(Step::Scope(i));
+			self.visit_scope(levels, steps, Some(sub.span), names);
+			levels.pop();
+			steps.pop();
+		}
+		for (i, inst) in scope.insts.iter().enumerate() {
+			levels.push(Level::Inst(inst));
+			steps.push(Step::Inst(i));
+			self.visit_instance(levels, steps);
+			levels.pop();
+			steps.pop();
+		}
+	}
+
+	/// Bind the interface ports of the instance at the end of `levels` to the
+	/// interface instances connected to them, and check that these match the
+	/// interface and modport the ports ask for.
+	/// IEEE 1800-2009 25.3, 25.5
+	fn bind_intf_ports(&mut self, levels: &[Level<'a>], steps: &[Step]) {
+		let (_, ports) = self.header_of(match *levels.last().unwrap() {
+			Level::Inst(inst) => inst.target,
+			Level::Scope(_) => unreachable!(),
+		});
+		let cursor = Cursor { levels: levels.to_vec(), blocks: vec![] };
+		for slice in ports.iter().flat_map(|p| p.slices.iter()) {
+			let port = match slice.intf {
+				Some(ref p) => p,
+				None => continue,
+			};
+			let name = ast::Identifier { id: slice.id, span: slice.span, name: slice.name };
+			let found = match self.follow_intf_port(&cursor, &name) {
+				Ok(Some(c)) => c,
+				Ok(None) | Err(()) => continue,
+			};
+			let inst = match *found.levels.last().unwrap() {
+				Level::Inst(inst) => inst,
+				Level::Scope(_) => unreachable!(),
+			};
+			let path = found.levels.iter().map(|l| l.name()).collect();
+			let intf_name = self.element_name(inst.target).unwrap();
+			if let Some(expected) = port.intf {
+				if expected != inst.target {
+					self.add_diag(DiagBuilder2::error(format!("interface port `{}` of `{}` expects an instance of `{}`, but `{}` is an instance of `{}`", slice.name, path_to_string(&cursor), self.element_name(expected).unwrap(), path_to_string(&found), intf_name))
+						.span(slice.span));
+					continue;
+				}
+			}
+			let modport = match port.modport {
+				Some(ref mp) => match inst.scope.body.modports.iter().find(|m| m.name == mp.name) {
+					Some(m) => Some(m.id),
+					None => {
+						self.add_diag(DiagBuilder2::error(format!("interface `{}` has no modport `{}`", intf_name, mp.name))
+							.span(mp.span)
+							.add_note(format!("interface port `{}` of `{}` is connected to `{}`", slice.name, path_to_string(&cursor), path_to_string(&found))));
+						continue;
+					}
+				},
+				None => None,
+			};
+			self.bindings.push((steps.to_vec(), slice.id, IntfBinding {
+				path: path,
+				intf: inst.target,
+				modport: modport,
+			}));
+		}
+	}
+
+	/// Resolve a hierarchical name used in the scope at the end of `levels`.
+	fn resolve_name(&mut self, levels: &[Level<'a>], steps: &[Step], name: &'a HierName) {
+		let mut segs = Vec::new();
+		if !collect_segments(&name.expr, &mut segs) || self.is_data_port(levels, segs[0].ident) {
+			return;
+		}
+		let env = const_env(levels);
+		let mut cursor = match self.find_start(levels, &env, &segs[0]) {
+			Ok(Some(c)) => c,
+			Ok(None) | Err(()) => return,
+		};
+		for seg in &segs[1..] {
+			let mut next = match self.find_nested(&cursor, &env, seg) {
+				Ok(next) => next,
+				Err(()) => return,
+			};
+			if next.is_none() {
+				next = match self.follow_intf_port(&cursor, seg.ident) {
+					Ok(next) => next,
+					Err(()) => return,
+				};
+			}
+			if let Some(next) = next {
+				cursor = next;
+				continue;
+			}
+
+			// Any index or remaining names select from the declaration.
+			match self.find_decl(&cursor, seg.ident.name) {
+				Some(decl) => {
+					let mut path: Vec<_> = cursor.levels.iter().map(|l| l.name()).collect();
+					path.extend(cursor.blocks.iter().cloned());
+					path.push(seg.ident.name);
+					self.found.push((steps.to_vec(), seg.ident.id, HierRef {
+						path: path,
+						decl: decl,
+					}));
+				}
+				None => {
+					self.add_diag(DiagBuilder2::error(format!("`{}` has no member `{}`", path_to_string(&cursor), seg.ident.name))
+						.span(seg.ident.span)
+						.add_note(format!("in hierarchical name `{}`", name.expr.span.extract())));
+				}
+			}
+			return;
+		}
+	}
+
+	/// Find the scope the first name of a hierarchical name refers to. The
+	/// enclosing scopes are searched from the innermost outwards, which covers
+	/// downward references and upward references to the instances and scopes
+	/// above. An enclosing instance may also be referred to by its own name or
+	/// the name of its module.
+	/// IEEE 1800-2009 23.8
+	fn find_start(&mut self, levels: &[Level<'a>], env: &ConstEnv, seg: &Segment<'a>) -> Result<Option<Cursor<'a>>> {
+		let def = self.nameres.get(&seg.ident.id).cloned();
+		let inst = levels.iter().rposition(is_inst).unwrap();
+		if let Some(def) = def {
+			if self.find_port(levels[inst], def).is_some() {
+				let cursor = Cursor { levels: levels[..inst+1].to_vec(), blocks: vec![] };
+				return self.follow_intf_port(&cursor, seg.ident);
+			}
+		}
+
+		for i in (0..levels.len()).rev() {
+			let cursor = Cursor { levels: levels[..i+1].to_vec(), blocks: vec![] };
+			if let Some(found) = self.find_nested(&cursor, env, seg)? {
+				return Ok(Some(found));
+			}
+			if let Level::Inst(inst) = levels[i] {
+				if seg.index.is_none() && (inst.name == seg.ident.name || self.element_name(inst.target) == Some(seg.ident.name)) {
+					return Ok(Some(cursor));
+				}
+			}
+		}
+
+		// Names that refer to a declaration lexically but do not lead to an
+		// instance, e.g. members of a primitive instance, are left alone.
+		if def.is_none() {
+			self.add_diag(DiagBuilder2::error(format!("`{}` has not been defined", seg.ident.name))
+				.span(seg.ident.span)
+				.add_note("no instance or scope of this name is visible from here"));
+		}
+		Ok(None)
+	}
+
+	/// Find the generate scope, instance, or named block a name refers to
+	/// within the scope of a cursor. The index of a name is only evaluated if
+	/// it selects one of the scopes of a generate loop.
+	fn find_nested(&mut self, cursor: &Cursor<'a>, env: &ConstEnv, seg: &Segment<'a>) -> Result<Option<Cursor<'a>>> {
+		let scope = cursor.levels.last().unwrap().scope();
+		let name = match seg.index {
+			None => seg.ident.name,
+			Some(_) if !cursor.blocks.is_empty() => return Ok(None),
+			Some(index) => {
+				let prefix = format!("{}[", seg.ident.name);
+				if !scope.scopes.iter().any(|s| s.name.as_str().starts_with(&prefix)) {
+					return Ok(None);
+				}
+				match consteval::const_eval(self.nameres, index, env) {
+					Ok(v) => get_name_table().intern(&format!("{}{}]", prefix, v), true),
+					Err(diag) => {
+						self.add_diag(diag);
+						return Err(());
+					}
+				}
+			}
+		};
+
+		let mut found = Vec::new();
+		if seg.index.is_none() {
+			let mut blocks = cursor.blocks.clone();
+			blocks.push(name);
+			if self.nameres.scopes.contains_key(&lexical_path(self, &cursor.levels, &blocks)) {
+				found.push(Cursor { levels: cursor.levels.clone(), blocks: blocks });
+			}
+		}
+		if cursor.blocks.is_empty() {
+			let nested = scope.scopes.iter().filter(|s| s.name == name).map(Level::Scope)
+				.chain(scope.insts.iter().filter(|i| i.name == name).map(Level::Inst));
+			for level in nested {
+				let mut levels = cursor.levels.clone();
+				levels.push(level);
+				found.push(Cursor { levels: levels, blocks: vec![] });
+			}
+		}
+		if found.len() > 1 {
+			self.add_diag(DiagBuilder2::error(format!("`{}` is ambiguous; `{}` contains multiple scopes of this name", name, path_to_string(cursor)))
+				.span(seg.ident.span));
+			return Err(());
+		}
+		Ok(found.pop())
+	}
+
+	/// Find the declaration a name refers to within the scope of a cursor.
+	fn find_decl(&self, cursor: &Cursor<'a>, name: Name) -> Option<NodeId> {
+		if !cursor.blocks.is_empty() {
+			let path = lexical_path(self, &cursor.levels, &cursor.blocks);
+			return self.nameres.scopes.get(&path).and_then(|defs| defs.get(&name)).cloned();
+		}
+		let level = *cursor.levels.last().unwrap();
+		let body = &level.scope().body;
+		let names = body.vars.iter().flat_map(|d| d.names.iter())
+			.chain(body.nets.iter().flat_map(|d| d.names.iter()));
+		for n in names {
+			if n.name == name {
+				return Some(n.id);
+			}
+		}
+		for sub in &body.subroutines {
+			if sub.name == name {
+				return Some(sub.id);
+			}
+		}
+		if let Some(id) = find_param(&body.params, name) {
+			return Some(id);
+		}
+		if let Level::Inst(inst) = level {
+			let (params, ports) = self.header_of(inst.target);
+			if let Some(id) = find_param(params, name) {
+				return Some(id);
+			}
+			for slice in ports.iter().flat_map(|p| p.slices.iter()) {
+				if slice.name == name {
+					return Some(slice.id);
+				}
+			}
+		}
+		None
+	}
+
+	/// Follow an interface port of the instance a cursor points at to the
+	/// interface instance connected to it. Yields `None` if the name is not an
+	/// interface port.
+	fn follow_intf_port(&mut self, cursor: &Cursor<'a>, name: &ast::Identifier) -> Result<Option<Cursor<'a>>> {
+		let inst = match (cursor.blocks.is_empty(), cursor.levels.last()) {
+			(true, Some(&Level::Inst(inst))) => inst,
+			_ => return Ok(None),
+		};
+		let (_, ports) = self.header_of(inst.target);
+		let index = match ports.iter().position(|p| p.name == Some(name.name)) {
+			Some(i) => i,
+			None => return Ok(None),
+		};
+		if !ports[index].slices.iter().any(|s| self.is_intf_port(s)) {
+			return Ok(None);
+		}
+
+		// Find the port connection in the instantiation within the parent
+		// scope.
+		let parent = cursor.levels.len() - 1;
+		if parent == 0 {
+			self.add_diag(DiagBuilder2::error(format!("Interface port `{}` of the top module is not connected", name.name))
+				.span(name.span));
+			return Err(());
+		}
+		let inst_name = cursor.levels[parent-1].scope().body.insts.iter()
+			.flat_map(|i| i.names.iter())
+			.find(|n| n.name.name == inst.name);
+		let conn = inst_name.and_then(|n| n.conns.iter().enumerate().filter_map(|(i, conn)| match conn.kind {
+			ast::PortConnKind::Named(ref n, ast::PortConnMode::Connected(ref expr)) if n.name == name.name => Some(Ok(expr)),
+			ast::PortConnKind::Named(ref n, ast::PortConnMode::Unconnected) if n.name == name.name => Some(Err(())),
+			ast::PortConnKind::Named(ref n, ast::PortConnMode::Auto) if n.name == name.name => Some(Err(())),
+			ast::PortConnKind::Positional(ref expr) if i == index => Some(Ok(expr)),
+			_ => None,
+		}).next());
+		let target = match conn {
+			// Named connections of the form `.name` and `.*` connect to the
+			// interface of the same name in the parent scope.
+			Some(Err(())) if !is_unconnected(inst_name.unwrap(), name.name) => name,
+			None if inst_name.map(|n| n.conns.iter().any(|c| c.kind == ast::PortConnKind::Auto)).unwrap_or(false) => name,
+			Some(Ok(&ast::Expr { data: ast::IdentExpr(ref ident), .. })) => ident,
+			Some(Ok(expr)) => {
+				self.add_diag(DiagBuilder2::error(format!("Interface port `{}` must be connected to an interface instance or port", name.name))
+					.span(expr.span));
+				return Err(());
+			}
+			_ => {
+				self.add_diag(DiagBuilder2::error(format!("Interface port `{}` of `{}` is not connected", name.name, path_to_string(cursor)))
+					.span(name.span));
+				return Err(());
+			}
+		};
+
+		// The connected interface is either an instance visible in the parent
+		// scope, or an interface port of the parent instance.
+		let outer = cursor.levels[..parent].iter().rposition(is_inst).unwrap();
+		for i in (outer..parent).rev() {
+			let scope = cursor.levels[i].scope();
+			if let Some(intf) = scope.insts.iter().find(|x| x.name == target.name && self.intfs.contains_key(&x.target)) {
+				let mut levels = cursor.levels[..i+1].to_vec();
+				levels.push(Level::Inst(intf));
+				return Ok(Some(Cursor { levels: levels, blocks: vec![] }));
+			}
+		}
+		let outer = Cursor { levels: cursor.levels[..outer+1].to_vec(), blocks: vec![] };
+		match self.follow_intf_port(&outer, target)? {
+			Some(c) => Ok(Some(c)),
+			None => {
+				self.add_diag(DiagBuilder2::error(format!("`{}` is not an interface instance", target.name))
+					.span(target.span)
+					.add_note(format!("connected to interface port `{}` of `{}`", name.name, path_to_string(cursor))));
+				Err(())
+			}
+		}
+	}
+
+	/// Find the port slice of an instance that declares a node.
+	fn find_port(&self, level: Level<'a>, id: NodeId) -> Option<&'a PortSlice> {
+		let target = match level {
+			Level::Inst(inst) => inst.target,
+			Level::Scope(_) => return None,
+		};
+		let (_, ports) = self.header_of(target);
+		ports.iter().flat_map(|p| p.slices.iter()).find(|s| s.id == id)
+	}
+
+	/// Check whether a name refers to a port of the enclosing instance that is
+	/// not an interface port. Members of such ports, e.g. of a struct type,
+	/// are not hierarchical names.
+	fn is_data_port(&self, levels: &[Level<'a>], ident: &ast::Identifier) -> bool {
+		let inst = levels.iter().rposition(is_inst).unwrap();
+		match self.nameres.get(&ident.id).and_then(|&def| self.find_port(levels[inst], def)) {
+			Some(port) => !self.is_intf_port(port),
+			None => false,
+		}
+	}
+
+	/// Check whether a port is of an interface type.
+	fn is_intf_port(&self, slice: &PortSlice) -> bool {
+		slice.intf.is_some()
+	}
+
+	/// The name of a module, interface, or checker.
+	fn element_name(&self, id: NodeId) -> Option<Name> {
+		self.mods.get(&id).map(|m| m.name)
+			.or_else(|| self.intfs.get(&id).map(|i| i.name))
+			.or_else(|| self.checkers.get(&id).map(|c| c.name))
+	}
+
+	/// The body of a module, interface, or checker.
+	fn body_of(&self, id: NodeId) -> Option<&'a HierarchyBody> {
+		self.mods.get(&id).map(|m| &m.body)
+			.or_else(|| self.intfs.get(&id).map(|i| &i.body))
+			.or_else(|| self.checkers.get(&id).map(|c| &c.body))
+	}
+
+	/// The parameters and ports of a module or interface.
+	fn header_of(&self, id: NodeId) -> (&'a [ast::ParamDecl], &'a [Port]) {
+		if let Some(m) = self.mods.get(&id) {
+			(&m.params, &m.ports)
+		} else if let Some(i) = self.intfs.get(&id) {
+			(&i.params, &i.ports)
+		} else {
+			(&[], &[])
+		}
+	}
+}
+
+
+/// Follow a way from the top instance to a scope.
+fn scope_mut<'a>(mut scope: &'a mut InstScope, steps: &[Step]) -> &'a mut InstScope {
+	for &step in steps {
+		scope = match step {
+			Step::Scope(i) => &mut {scope}.scopes[i],
+			Step::Inst(i) => &mut {scope}.insts[i].scope,
+		};
+	}
+	scope
+}
+
+fn is_inst(level: &Level) -> bool {
+	match *level {
+		Level::Inst(_) => true,
+		Level::Scope(_) => false,
+	}
+}
+
+fn is_unconnected(inst: &ast::InstName, name: Name) -> bool {
+	inst.conns.iter().any(|c| match c.kind {
+		ast::PortConnKind::Named(ref n, ast::PortConnMode::Unconnected) => n.name == name,
+		_ => false,
+	})
+}
+
+/// Break a hierarchical name up into its elements. Fails for names that
+/// cannot be followed through the hierarchy, e.g. `a[1][2].b`.
+fn collect_segments<'a>(expr: &'a ast::Expr, segs: &mut Vec<Segment<'a>>) -> bool {
+	let (ident, index) = match expr.data {
+		ast::IdentExpr(ref ident) => (ident, None),
+		ast::MemberExpr { ref expr, ref name } => {
+			if !collect_segments(expr, segs) {
+				return false;
+			}
+			(name, None)
+		}
+		ast::IndexExpr { ref index, .. } if is_range(index) => return false,
+		ast::IndexExpr { ref indexee, ref index } => match indexee.data {
+			ast::IdentExpr(ref ident) => (ident, Some(&**index)),
+			ast::MemberExpr { ref expr, ref name } => {
+				if !collect_segments(expr, segs) {
+					return false;
+				}
+				(name, Some(&**index))
+			}
+			_ => return false,
+		},
+		_ => return false,
+	};
+	segs.push(Segment { ident: ident, index: index });
+	true
+}
+
+fn is_range(expr: &ast::Expr) -> bool {
+	match expr.data {
+		ast::RangeExpr { .. } => true,
+		_ => false,
+	}
+}
+
+/// Gather the values of the parameters and genvars visible in the scope at
+/// the end of `levels`.
+fn const_env(levels: &[Level]) -> ConstEnv {
+	let mut env = ConstEnv::default();
+	let inst = levels.iter().rposition(is_inst).unwrap();
+	for level in &levels[inst..] {
+		env.values.extend(level.scope().values.iter().map(|(&k, &v)| (k, v)));
+	}
+	env
+}
+
+/// Determine the name under which the resolver recorded the named blocks of a
+/// scope, which consists of the name of the module and the labels of the
+/// generate blocks and named blocks, e.g. `[top, gen_blk, blk]`.
+fn lexical_path(r: &HierResolver, levels: &[Level], blocks: &[Name]) -> Vec<Name> {
+	let inst = levels.iter().rposition(is_inst).unwrap();
+	let mut path = Vec::new();
+	if let Level::Inst(inst) = levels[inst] {
+		path.extend(r.element_name(inst.target));
+	}
+	for level in &levels[inst+1..] {
+		let name = level.name().as_str();
+		let label = match name.find('[') {
+			Some(i) => get_name_table().intern(&name[..i], true),
+			None => level.name(),
+		};
+		path.push(label);
+	}
+	path.extend(blocks.iter().cloned());
+	path
+}
+
+/// Format the hierarchical name of a cursor, e.g. `top.u_core.gen_blk[1]`.
+fn path_to_string(cursor: &Cursor) -> String {
+	let names: Vec<String> = cursor.levels.iter().map(|l| format!("{}", l.name()))
+		.chain(cursor.blocks.iter().map(|b| format!("{}", b)))
+		.collect();
+	names.join(".")
+}
+
+fn find_param(params: &[ast::ParamDecl], name: Name) -> Option<NodeId> {
+	for param in params {
+		match param.kind {
+			ast::ParamKind::Type(ref decls) => for decl in decls {
+				if decl.name.name == name {
+					return Some(decl.name.id);
+				}
+			},
+			ast::ParamKind::Value(ref decls) => for decl in decls {
+				if decl.name.name == name {
+					return Some(decl.name.id);
+				}
+			},
+		}
+	}
+	None
+}
+
+/// Collect the spans of the generate blocks in a body.
+fn collect_generate_blocks(body: &HierarchyBody, into: &mut Vec<Span>) {
+	for region in &body.genreg {
+		collect_generate_blocks(region, into);
+	}
+	let blocks = body.genfors.iter().map(|gf| &gf.block)
+		.chain(body.genifs.iter().flat_map(|gi| std::iter::once(&gi.main_block).chain(gi.else_block.iter())))
+		.chain(body.gencases.iter().flat_map(|gc| gc.items.iter().map(|i| &i.block)));
+	for block in blocks {
+		into.push(block.span);
+		collect_generate_blocks(&block.body, into);
+	}
+}
+
+/// Find the innermost generate block that contains a span.
+fn innermost_block(blocks: &[Span], span: Span) -> Option<Span> {
+	blocks.iter()
+		.filter(|b| b.source == span.source && b.begin <= span.begin && span.end <= b.end)
+		.min_by_key(|b| b.end - b.begin)
+		.cloned()
+}
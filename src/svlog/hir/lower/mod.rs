@@ -3,14 +3,21 @@
 //! This module implements the process of lowering the AST to HIR.
 
 mod port;
+mod elab;
+mod hier;
+pub mod stmt;
+pub mod consteval;
+pub mod expr;
 
 use std;
 use moore_common::errors::*;
 use moore_common::Session;
 use moore_svlog_syntax::ast;
-use moore_svlog_syntax::resolve::NameResolution;
+use moore_svlog_syntax::resolve::{self, NameResolution};
 use nodes::*;
-use std::collections::HashMap;
+use ty::TyInterner;
+use lower::expr::{lower_expr, lower_ident};
+use std::collections::{HashMap, HashSet};
 
 /// General result of lowering a node.
 type Result<T> = std::result::Result<T, ()>;
@@ -24,6 +31,14 @@ pub fn lower(session: &Session, nameres: &NameResolution, top: NodeId, asts: Vec
 		mods: HashMap::new(),
 		intfs: HashMap::new(),
 		pkgs: HashMap::new(),
+		udps: HashMap::new(),
+		checkers: HashMap::new(),
+		binds: Vec::new(),
+		nettypes: HashSet::new(),
+		intf_decls: HashSet::new(),
+		mod_protos: HashMap::new(),
+		intf_protos: HashMap::new(),
+		unit_timeunits: ast::Timeunit::default(),
 	};
 	l.map_asts(asts);
 	l.finish()
@@ -38,6 +53,19 @@ struct Lowerer<'a> {
 	mods: HashMap<NodeId, Module>,
 	intfs: HashMap<NodeId, Interface>,
 	pkgs: HashMap<NodeId, Package>,
+	udps: HashMap<NodeId, ast::UdpDecl>,
+	checkers: HashMap<NodeId, Checker>,
+	binds: Vec<ast::BindDirective>,
+	/// The user-defined nettypes declared anywhere in the design.
+	nettypes: HashSet<NodeId>,
+	/// The interfaces declared anywhere in the design.
+	intf_decls: HashSet<NodeId>,
+	/// The extern module declarations, by name.
+	mod_protos: HashMap<Name, ast::ModDecl>,
+	/// The extern interface declarations, by name.
+	intf_protos: HashMap<Name, ast::IntfDecl>,
+	/// The timeunits declared in the compilation unit currently being lowered.
+	unit_timeunits: ast::Timeunit,
 }
 
 impl<'a> Lowerer<'a> {
@@ -50,22 +78,63 @@ impl<'a> Lowerer<'a> {
 		self.severity >= Severity::Error
 	}
 
-	/// Consume the lowerer and wrap the lowered nodes up in a Root node.
+	/// Consume the lowerer, elaborate the design hierarchy below the top
+	/// module, and wrap the lowered nodes up in a Root node.
 	fn finish(self) -> Result<Root> {
 		if self.severity >= Severity::Error {
-			Err(())
-		} else {
-			Ok(Root {
-				top: self.top,
-				mods: self.mods,
-				intfs: self.intfs,
-				pkgs: self.pkgs,
-			})
+			return Err(());
 		}
+		let mut design = elab::elaborate(self.session, self.nameres, self.top, &self.pkgs, &self.mods, &self.intfs, &self.checkers, &self.binds)?;
+		hier::resolve_hier_names(self.session, self.nameres, &self.mods, &self.intfs, &self.checkers, &mut design)?;
+		Ok(Root {
+			top: self.top,
+			mods: self.mods,
+			intfs: self.intfs,
+			pkgs: self.pkgs,
+			udps: self.udps,
+			checkers: self.checkers,
+			binds: self.binds,
+			design: design,
+			tys: TyInterner::new(),
+		})
 	}
 
 	/// Lower multiple root nodes.
-	fn map_asts(&mut self, asts: Vec<ast::Root>) {
+	fn map_asts(&mut self, mut asts: Vec<ast::Root>) {
+		// Extern declarations need to be known up front, since definitions
+		// with a `(.*)` port list take their parameters and ports from them.
+		for ast in &mut asts {
+			let items = std::mem::replace(&mut ast.items, Vec::new());
+			for item in items {
+				match item {
+					ast::Item::ExternModule(d) => { self.mod_protos.insert(d.name, d); }
+					ast::Item::ExternInterface(d) => { self.intf_protos.insert(d.name, d); }
+					x => ast.items.push(x),
+				}
+			}
+		}
+
+		// Nettypes need to be known up front, since declarations of nets of a
+		// user-defined nettype look like variable declarations. The same holds
+		// for interfaces, which are used as the type of interface ports.
+		for ast in &asts {
+			for item in &ast.items {
+				match *item {
+					ast::Item::Module(ref decl) => {
+						collect_nettypes(&decl.items, &mut self.nettypes);
+						collect_interfaces(&decl.items, &mut self.intf_decls);
+					}
+					ast::Item::Interface(ref decl) => {
+						collect_nettypes(&decl.items, &mut self.nettypes);
+						self.intf_decls.insert(decl.id);
+						collect_interfaces(&decl.items, &mut self.intf_decls);
+					}
+					ast::Item::Package(ref decl) => collect_nettypes(&decl.items, &mut self.nettypes),
+					ast::Item::Item(ast::HierarchyItem::NettypeDecl(ref decl)) => { self.nettypes.insert(decl.name.id); }
+					_ => (),
+				}
+			}
+		}
 		for ast in asts {
 			self.map_ast(ast);
 		}
@@ -73,6 +142,7 @@ impl<'a> Lowerer<'a> {
 
 	/// Lower a root node.
 	fn map_ast(&mut self, ast: ast::Root) {
+		self.unit_timeunits = ast.timeunits;
 		for item in ast.items {
 			self.map_item(item);
 		}
@@ -85,15 +155,30 @@ impl<'a> Lowerer<'a> {
 			ast::Item::Interface(d) => self.map_interface(d),
 			ast::Item::Package(d) => self.map_package(d),
 			ast::Item::Class(d) => self.map_class(d),
+			ast::Item::Primitive(d) => { self.udps.insert(d.name.id, d); },
+			ast::Item::Checker(d) => self.map_checker(d),
+			ast::Item::Bind(d) => self.binds.push(d),
 			ast::Item::Item(ast::HierarchyItem::ImportDecl(_)) => (), // import decls irrelevant after name resolution
+			ast::Item::Item(ast::HierarchyItem::DpiDecl(_)) => (), // DPI decls only need to be visible to name resolution
+			ast::Item::Item(ast::HierarchyItem::NettypeDecl(_)) => (), // nettypes have been collected up front
+			ast::Item::Item(ast::HierarchyItem::Constraint(_)) => (), // out-of-class constraints only need name resolution
 			x => self.add_diag(DiagBuilder2::error(format!("{} cannot appear here", x.as_str())).span(x.span())),
 		}
 	}
 
 	/// Lower a module.
-	fn map_module(&mut self, node: ast::ModDecl) {
+	fn map_module(&mut self, mut node: ast::ModDecl) {
 		// println!("mapping module {}", node.name);
 
+		// A `(.*)` port list reuses the header of the extern declaration.
+		if node.wildcard_ports {
+			if let Some(proto) = self.mod_protos.remove(&node.name) {
+				node.params = proto.params;
+				node.ports = proto.ports;
+			}
+		}
+		inherit_timeunits(&node.timeunits, &mut node.items);
+
 		// If the first port has neither direction, port kind, nor type
 		// specified, non-ANSI style shall be assumed. Otherwise, the ports are
 		// assumed to be in ANSI style.
@@ -105,8 +190,8 @@ impl<'a> Lowerer<'a> {
 		// Map the items in the module.
 		let body = self.map_hierarchy_body(node.items);
 
-		// TODO: Digest timeunits
 		// TODO: Digest parameters
+		let timescale = self.map_timeunits(&node.timeunits);
 		let mut m = Module {
 			id: node.id,
 			name: node.name,
@@ -114,6 +199,7 @@ impl<'a> Lowerer<'a> {
 			lifetime: node.lifetime,
 			ports: ports,
 			params: node.params,
+			timescale: timescale,
 			body: body,
 		};
 
@@ -124,6 +210,39 @@ impl<'a> Lowerer<'a> {
 		}
 	}
 
+	/// Add a net declaration for each net implicitly declared by an item.
+	fn map_implicit_nets(&self, item: &ast::HierarchyItem, nets: &mut Vec<ast::NetDecl>) {
+		for ident in resolve::implicit_net_idents(item) {
+			let net_type = match self.nameres.implicit_nets.get(&ident.id) {
+				Some(&t) => t,
+				None => continue,
+			};
+			nets.push(ast::NetDecl {
+				span: ident.span,
+				net_type: net_type,
+				strength: None,
+				kind: ast::NetKind::None,
+				ty: ast::Type {
+					id: ast::DUMMY_NODE_ID,
+					span: ident.span,
+					data: ast::ImplicitType,
+					sign: ast::TypeSign::None,
+					dims: Vec::new(),
+				},
+				delay: None,
+				names: vec![ast::VarDeclName {
+					id: ident.id,
+					span: ident.span,
+					name: ident.name,
+					name_span: ident.span,
+					dims: Vec::new(),
+					init: None,
+				}],
+				attrs: Vec::new(),
+			});
+		}
+	}
+
 	fn map_hierarchy_body(&mut self, items: Vec<ast::HierarchyItem>) -> HierarchyBody {
 		let mut b = HierarchyBody {
 			procs: vec![],
@@ -132,6 +251,7 @@ impl<'a> Lowerer<'a> {
 			assigns: vec![],
 			params: vec![],
 			insts: vec![],
+			gates: vec![],
 			genreg: vec![],
 			genvars: vec![],
 			genfors: vec![],
@@ -141,30 +261,70 @@ impl<'a> Lowerer<'a> {
 			subroutines: vec![],
 			asserts: vec![],
 			typedefs: vec![],
+			covergroups: vec![],
+			dpi_decls: vec![],
+			specparams: vec![],
+			specify_blocks: vec![],
+			clockings: vec![],
+			default_clockings: vec![],
+			sequences: vec![],
+			properties: vec![],
+			nettypes: vec![],
+			aliases: vec![],
+			modports: vec![],
 		};
 		for item in items {
+			self.map_implicit_nets(&item, &mut b.nets);
 			match item {
 				// The following items have already been handled.
 				ast::HierarchyItem::ImportDecl(_) |
+				ast::HierarchyItem::ExportDecl(_) |
 				ast::HierarchyItem::PortDecl(_) => (),
 
-				ast::HierarchyItem::Procedure(p) => b.procs.push(p),
+				ast::HierarchyItem::Procedure(p) => {
+					if let Ok(p) = self.map_procedure(&p) {
+						b.procs.push(p);
+					}
+				}
 				ast::HierarchyItem::NetDecl(d) => b.nets.push(d),
+				// A declaration whose type is a user-defined nettype, as in
+				// `mynettype x;`, declares a net.
+				ast::HierarchyItem::VarDecl(d) if self.is_nettype(&d.ty) => {
+					b.nets.push(ast::NetDecl {
+						span: d.span,
+						net_type: ast::NetType::User,
+						strength: None,
+						kind: ast::NetKind::None,
+						ty: d.ty,
+						delay: None,
+						names: d.names,
+						attrs: d.attrs,
+					});
+				}
 				ast::HierarchyItem::VarDecl(mut d) => {
 					// TODO: Maybe variables should be unrolled, such that each
 					// name of the variable gets a copy of the type definition.
 					// This would give us the chance to apply these default
 					// signs as well.
 					if d.ty.sign == ast::TypeSign::None {
-						// TODO: This is only valid for logics. Integers should
-						// be signed by default.
-						d.ty.sign = ast::TypeSign::Unsigned;
+						d.ty.sign = match d.ty.data {
+							ast::ByteType |
+							ast::ShortIntType |
+							ast::IntType |
+							ast::LongIntType => ast::TypeSign::Signed,
+							_ => ast::TypeSign::Unsigned,
+						};
 					}
 					b.vars.push(d)
 				},
 				ast::HierarchyItem::ParamDecl(d) => b.params.push(d),
-				ast::HierarchyItem::ContAssign(a) => b.assigns.push(a),
+				ast::HierarchyItem::ContAssign(a) => {
+					if let Ok(a) = self.map_cont_assign(&a) {
+						b.assigns.extend(a);
+					}
+				}
 				ast::HierarchyItem::Inst(i) => b.insts.push(i),
+				ast::HierarchyItem::GateInst(g) => b.gates.push(g),
 				ast::HierarchyItem::GenvarDecl(d) => b.genvars.extend(d),
 				ast::HierarchyItem::GenerateRegion(_, items) => b.genreg.push(self.map_hierarchy_body(items)),
 				ast::HierarchyItem::GenerateFor(gf) => {
@@ -184,13 +344,45 @@ impl<'a> Lowerer<'a> {
 						else_block: gi.else_block.map(|b| self.map_generate_block(b)),
 					});
 				}
+				ast::HierarchyItem::GenerateCase(gc) => {
+					let items = gc.items.into_iter().map(|item| GenerateCaseItem {
+						span: item.span,
+						exprs: item.exprs,
+						block: self.map_generate_block(item.block),
+					}).collect();
+					b.gencases.push(GenerateCase {
+						span: gc.span,
+						expr: gc.expr,
+						items: items,
+					});
+				}
 				ast::HierarchyItem::ClassDecl(d) => b.classes.push(d),
-				ast::HierarchyItem::SubroutineDecl(sd) => b.subroutines.push(sd),
+				ast::HierarchyItem::SubroutineDecl(sd) => {
+					if let Ok(sd) = self.map_subroutine(&sd) {
+						b.subroutines.push(sd);
+					}
+				}
 				ast::HierarchyItem::Assertion(a) => b.asserts.push(a),
 				ast::HierarchyItem::Typedef(td) => b.typedefs.push(td),
-
-				// Unimplemented for now
-				ast::HierarchyItem::ModportDecl(mp) => (),
+				ast::HierarchyItem::CovergroupDecl(cg) => b.covergroups.push(cg),
+				ast::HierarchyItem::DpiDecl(d) => b.dpi_decls.push(d),
+				ast::HierarchyItem::SpecparamDecl(d) => b.specparams.push(d),
+				ast::HierarchyItem::SpecifyBlock(d) => b.specify_blocks.push(d),
+				ast::HierarchyItem::ClockingDecl(d) => b.clockings.push(d),
+				ast::HierarchyItem::DefaultClocking(_, n) => b.default_clockings.push(n),
+				ast::HierarchyItem::SequenceDecl(d) => b.sequences.push(d),
+				ast::HierarchyItem::PropertyDecl(d) => b.properties.push(d),
+				ast::HierarchyItem::NettypeDecl(d) => b.nettypes.push(d),
+				ast::HierarchyItem::Alias(a) => b.aliases.push(a),
+				// Out-of-class constraints only need to be visible to name
+				// resolution.
+				ast::HierarchyItem::Constraint(_) => (),
+				// Nested design elements are lifted out of their parent.
+				ast::HierarchyItem::ModuleDecl(d) => self.map_module(d),
+				ast::HierarchyItem::InterfaceDecl(d) => self.map_interface(d),
+				ast::HierarchyItem::ModportDecl(d) => for item in &d.items {
+					b.modports.push(self.map_modport(item));
+				},
 
 				// TODO: Remove this once the AST has stabilized.
 				x => self.add_diag(DiagBuilder2::fatal(format!("lowering to HIR not implemented for this hierarchy item")).span(x.span()))
@@ -199,6 +391,16 @@ impl<'a> Lowerer<'a> {
 		b
 	}
 
+	/// Check whether a type refers to a user-defined nettype.
+	fn is_nettype(&self, ty: &ast::Type) -> bool {
+		let name = match ty.data {
+			ast::NamedType(ref name) => name,
+			ast::ScopedType { ref name, member: false, .. } => name,
+			_ => return false,
+		};
+		self.nameres.get(&name.id).map(|id| self.nettypes.contains(id)).unwrap_or(false)
+	}
+
 	fn map_generate_block(&mut self, block: ast::GenerateBlock) -> GenerateBlock {
 		GenerateBlock {
 			span: block.span,
@@ -208,7 +410,16 @@ impl<'a> Lowerer<'a> {
 	}
 
 	/// Lower an interface.
-	fn map_interface(&mut self, node: ast::IntfDecl) {
+	fn map_interface(&mut self, mut node: ast::IntfDecl) {
+		// A `(.*)` port list reuses the header of the extern declaration.
+		if node.wildcard_ports {
+			if let Some(proto) = self.intf_protos.remove(&node.name) {
+				node.params = proto.params;
+				node.ports = proto.ports;
+			}
+		}
+		inherit_timeunits(&node.timeunits, &mut node.items);
+
 		let ports = match self.map_ports(node.ports, &node.items) {
 			Ok(x) => x,
 			Err(()) => return,
@@ -217,6 +428,7 @@ impl<'a> Lowerer<'a> {
 		// Map the items in the interface.
 		let body = self.map_hierarchy_body(node.items);
 
+		let timescale = self.map_timeunits(&node.timeunits);
 		let mut i = Interface {
 			id: node.id,
 			name: node.name,
@@ -224,6 +436,7 @@ impl<'a> Lowerer<'a> {
 			lifetime: node.lifetime,
 			ports: ports,
 			params: node.params,
+			timescale: timescale,
 			body: body,
 		};
 
@@ -240,11 +453,13 @@ impl<'a> Lowerer<'a> {
 		// Map the items in the package.
 		let body = self.map_hierarchy_body(node.items);
 
-		// TODO: Digest timeunits
+		let timescale = self.map_timeunits(&node.timeunits);
 		let mut m = Package {
+			id: node.id,
 			name: node.name,
 			span: node.name_span,
 			lifetime: node.lifetime,
+			timescale: timescale,
 			body: body,
 		};
 
@@ -255,6 +470,101 @@ impl<'a> Lowerer<'a> {
 		}
 	}
 
+	/// Lower a checker.
+	fn map_checker(&mut self, node: ast::CheckerDecl) {
+		let body = self.map_hierarchy_body(node.items);
+		let c = Checker {
+			id: node.name.id,
+			name: node.name.name,
+			span: node.name.span,
+			ports: node.ports,
+			body: body,
+		};
+		self.checkers.insert(c.id, c);
+	}
+
+	/// Lower a modport. Ports that name an item of the interface are lowered
+	/// to an identifier expression referring to that item.
+	fn map_modport(&mut self, item: &ast::ModportItem) -> Modport {
+		let mut ports = Vec::new();
+		for port in &item.ports {
+			match *port {
+				ast::ModportPort::Simple { dir, ports: ref simple, .. } => for port in simple {
+					let expr = match port.expr {
+						Some(ref expr) => match lower_expr(self.nameres, expr) {
+							Ok(e) => Some(e),
+							Err(d) => {
+								self.add_diag(d);
+								continue;
+							}
+						},
+						None if !port.explicit => Some(Expr {
+							id: port.name.id,
+							span: port.name.span,
+							kind: ExprKind::Ident(lower_ident(self.nameres, &port.name)),
+						}),
+						None => None,
+					};
+					ports.push(ModportPort {
+						id: port.name.id,
+						name: port.name.name,
+						span: port.span,
+						kind: ModportPortKind::Simple(dir, expr),
+					});
+				},
+				ast::ModportPort::Tf { kind, ports: ref tfs, .. } => for tf in tfs {
+					let name = tf.name();
+					ports.push(ModportPort {
+						id: name.id,
+						name: name.name,
+						span: name.span,
+						kind: ModportPortKind::Subroutine(kind, self.nameres.get(&name.id).cloned()),
+					});
+				},
+				ast::ModportPort::Clocking(ref name) => {
+					ports.push(ModportPort {
+						id: name.id,
+						name: name.name,
+						span: name.span,
+						kind: ModportPortKind::Clocking(self.nameres.get(&name.id).cloned()),
+					});
+				}
+			}
+		}
+		Modport {
+			id: item.name.id,
+			name: item.name.name,
+			span: item.name.span,
+			ports: ports,
+		}
+	}
+
+	/// Determine the time unit and precision of a design element. A
+	/// `timeunit` or `timeprecision` declaration within the element takes
+	/// precedence, followed by the last "`timescale" directive, the
+	/// declarations of the compilation unit, and finally a default of 1ns. The
+	/// precision defaults to the time unit.
+	/// IEEE 1800-2009 3.14.2.3
+	fn map_timeunits(&mut self, node: &ast::Timeunit) -> Timescale {
+		let unit = node.unit
+			.or(node.timescale.map(|ts| ts.unit))
+			.or(self.unit_timeunits.unit);
+		let prec = node.prec
+			.or(node.timescale.map(|ts| ts.prec))
+			.or(self.unit_timeunits.prec);
+		let unit_exp = unit.map(|v| v.exp).unwrap_or(-9);
+		let prec_exp = prec.map(|v| v.exp).unwrap_or(unit_exp);
+		if let Some(prec) = prec {
+			if prec.exp > unit_exp {
+				self.add_diag(DiagBuilder2::error("Time precision must be at least as precise as the time unit").span(prec.span));
+			}
+		}
+		Timescale {
+			unit: pow10(unit_exp),
+			precision: pow10(prec_exp),
+		}
+	}
+
 	/// Lower a class declaration.
 	fn map_class(&mut self, node: ast::ClassDecl) {
 		// TODO: Implement this.
@@ -262,6 +572,76 @@ impl<'a> Lowerer<'a> {
 }
 
 
+/// Compute the power of ten with the given exponent, for the range of exponents
+/// that time units can have.
+fn pow10(exp: i8) -> f64 {
+	const TABLE: [f64; 18] = [
+		1e-15, 1e-14, 1e-13, 1e-12, 1e-11, 1e-10, 1e-9, 1e-8, 1e-7,
+		1e-6, 1e-5, 1e-4, 1e-3, 1e-2, 1e-1, 1e0, 1e1, 1e2,
+	];
+	TABLE[(exp + 15) as usize]
+}
+
+
+/// Collect the node IDs of the nettypes declared among a list of hierarchy
+/// items, including the ones in generate constructs.
+fn collect_nettypes(items: &[ast::HierarchyItem], into: &mut HashSet<NodeId>) {
+	for item in items {
+		match *item {
+			ast::HierarchyItem::NettypeDecl(ref decl) => { into.insert(decl.name.id); }
+			ast::HierarchyItem::ModuleDecl(ref decl) => collect_nettypes(&decl.items, into),
+			ast::HierarchyItem::InterfaceDecl(ref decl) => collect_nettypes(&decl.items, into),
+			ast::HierarchyItem::GenerateRegion(_, ref items) => collect_nettypes(items, into),
+			ast::HierarchyItem::GenerateFor(ref gf) => collect_nettypes(&gf.block.items, into),
+			ast::HierarchyItem::GenerateIf(ref gi) => {
+				collect_nettypes(&gi.main_block.items, into);
+				if let Some(ref b) = gi.else_block {
+					collect_nettypes(&b.items, into);
+				}
+			}
+			ast::HierarchyItem::GenerateCase(ref gc) => for item in &gc.items {
+				collect_nettypes(&item.block.items, into);
+			},
+			_ => (),
+		}
+	}
+}
+
+
+/// Collect the node IDs of the interfaces nested within a list of hierarchy
+/// items.
+fn collect_interfaces(items: &[ast::HierarchyItem], into: &mut HashSet<NodeId>) {
+	for item in items {
+		match *item {
+			ast::HierarchyItem::ModuleDecl(ref decl) => collect_interfaces(&decl.items, into),
+			ast::HierarchyItem::InterfaceDecl(ref decl) => {
+				into.insert(decl.id);
+				collect_interfaces(&decl.items, into);
+			}
+			_ => (),
+		}
+	}
+}
+
+
+/// Pass the time units of a module or interface on to the design elements
+/// nested within it, unless they declare their own.
+/// IEEE 1800-2009 3.14.2.3
+fn inherit_timeunits(parent: &ast::Timeunit, items: &mut [ast::HierarchyItem]) {
+	let unit = parent.unit.or(parent.timescale.map(|ts| ts.unit));
+	let prec = parent.prec.or(parent.timescale.map(|ts| ts.prec));
+	for item in items {
+		let tu = match *item {
+			ast::HierarchyItem::ModuleDecl(ref mut decl) => &mut decl.timeunits,
+			ast::HierarchyItem::InterfaceDecl(ref mut decl) => &mut decl.timeunits,
+			_ => continue,
+		};
+		tu.unit = tu.unit.or(unit);
+		tu.prec = tu.prec.or(prec);
+	}
+}
+
+
 /// Check if a type is empty, i.e. it is an implicit type with no sign or packed
 /// dimensions specified.
 pub fn is_type_empty(ty: &ast::Type) -> bool {
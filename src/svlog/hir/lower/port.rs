@@ -6,6 +6,7 @@ use moore_common::errors::*;
 use moore_svlog_syntax::ast;
 use nodes::*;
 use lower::{Lowerer, is_type_empty, Result};
+use lower::expr::lower_expr;
 
 
 #[derive(Debug)]
@@ -25,6 +26,8 @@ struct PartialPortSlice {
 	pub kind: Option<ast::PortKind>,
 	pub ty: Option<ast::Type>,
 	pub dims: Vec<ast::TypeDim>,
+	pub intf: Option<IntfPort>,
+	pub data_dims: Option<(Span, Vec<ast::TypeDim>, Vec<ast::TypeDim>)>,
 }
 
 
@@ -111,6 +114,8 @@ impl<'a> Lowerer<'a> {
 					kind: kind,
 					ty: ty,
 					dims: slice.dims,
+					intf: slice.intf,
+					data_dims: slice.data_dims,
 				});
 			}
 
@@ -171,6 +176,7 @@ impl<'a> Lowerer<'a> {
 		// has one slice and not selects, as these are only supported in
 		// non-ANSI port style.
 		let default_ty = ast::Type {
+			id: ast::DUMMY_NODE_ID,
 			span: first_span.begin().into(),
 			data: ast::LogicType,
 			sign: ast::TypeSign::None,
@@ -184,12 +190,33 @@ impl<'a> Lowerer<'a> {
 		for port in ports {
 			let mut mapped_port = match port {
 				// "interface" ["." ident] ident {dimension} ["=" expr]
-				ast::Port::Intf{ .. } => {
-					unimplemented!();
+				ast::Port::Intf{ modport, name, dims, expr, .. } => {
+					let intf = IntfPort {
+						intf: None,
+						modport: modport,
+					};
+					match self.map_intf_port(name, dims, expr, intf) {
+						Some(p) => p,
+						None => continue,
+					}
+				}
+
+				// An interface port, as in `bus_if b` or `bus_if.master b`.
+				ast::Port::Named{ span, dir, kind, ty, name, dims, expr, .. } if self.intf_port_type(&ty).is_some() => {
+					if dir.is_some() || kind.is_some() {
+						self.add_diag(DiagBuilder2::error(format!("interface port `{}` cannot have a direction or port kind", name.name))
+							.span(span));
+						continue;
+					}
+					let intf = self.intf_port_type(&ty).unwrap();
+					match self.map_intf_port(name, dims, expr, intf) {
+						Some(p) => p,
+						None => continue,
+					}
 				}
 
 				// [direction] "." ident "(" [expr] ")"
-				ast::Port::Explicit{ span: _, dir, name, expr } => {
+				ast::Port::Explicit{ span: _, dir, name, expr, .. } => {
 					// If no direction has been provided, use the one carried
 					// over from the previous port.
 					let dir = dir.unwrap_or(carry_dir);
@@ -227,13 +254,15 @@ impl<'a> Lowerer<'a> {
 								kind: kind,
 								ty: ty,
 								dims: Vec::new(),
+								intf: None,
+								data_dims: None,
 							}
 						],
 					}
 				}
 
 				// [direction] [net_type|"var"] type_or_implicit ident {dimension} ["=" expr]
-				ast::Port::Named{ span: _, dir, kind, ty, name, dims, expr } => {
+				ast::Port::Named{ span: _, dir, kind, ty, name, dims, expr, .. } => {
 					// If no direction has been provided, use the one carried
 					// over from the previous port.
 					let dir = dir.unwrap_or(carry_dir);
@@ -269,6 +298,8 @@ impl<'a> Lowerer<'a> {
 								kind: kind,
 								ty: Some(ty),
 								dims: dims,
+								intf: None,
+								data_dims: None,
 							}
 						],
 					}
@@ -316,6 +347,56 @@ impl<'a> Lowerer<'a> {
 	}
 
 
+	/// Determine the interface and modport of a port type that names an
+	/// interface, as in `bus_if` or `bus_if.master`.
+	fn intf_port_type(&self, ty: &ast::Type) -> Option<IntfPort> {
+		let (name, modport) = match ty.data {
+			ast::NamedType(ref name) => (name, None),
+			ast::ScopedType { ty: ref super_ty, member: true, ref name } => match super_ty.data {
+				ast::NamedType(ref intf) => (intf, Some(*name)),
+				_ => return None,
+			},
+			_ => return None,
+		};
+		match self.nameres.get(&name.id) {
+			Some(id) if self.intf_decls.contains(id) => Some(IntfPort {
+				intf: Some(*id),
+				modport: modport,
+			}),
+			_ => None,
+		}
+	}
+
+	/// Lower an interface port. Interface ports have no direction, port kind,
+	/// or data type, and are bound to an interface instance upon elaboration.
+	/// IEEE 1800-2009 25.3
+	fn map_intf_port(&mut self, name: ast::Identifier, dims: Vec<ast::TypeDim>, expr: Option<ast::Expr>, intf: IntfPort) -> Option<PartialPort> {
+		if let Some(expr) = expr {
+			self.add_diag(DiagBuilder2::error(format!("interface port `{}` cannot have a default value", name.name))
+				.span(expr.span));
+			return None;
+		}
+		Some(PartialPort {
+			name: Some(name.name),
+			span: name.span,
+			slices: vec![
+				PartialPortSlice {
+					id: name.id,
+					name: name.name,
+					span: name.span,
+					selects: Vec::new(),
+					dir: Some(ast::PortDir::Inout),
+					kind: Some(ast::PortKind::Net(ast::NetType::Wire)),
+					ty: None,
+					dims: dims,
+					intf: Some(intf),
+					data_dims: None,
+				}
+			],
+		})
+	}
+
+
 	/// Lower a list of ports, assuming they are presented in non-ANSI-style.
 	fn map_nonansi_ports(&mut self, ports: Vec<ast::Port>, items: &[ast::HierarchyItem], first_span: Span) -> Vec<PartialPort> {
 		let mut mapped = Vec::new();
@@ -342,7 +423,7 @@ impl<'a> Lowerer<'a> {
 				}
 
 				// [direction] "." ident "(" [expr] ")"
-				ast::Port::Explicit{ span, dir, name, expr } => {
+				ast::Port::Explicit{ span, dir, name, expr, .. } => {
 					if dir.is_some() {
 						self.add_diag(DiagBuilder2::error("port directions are only allowed in an ANSI port list")
 							.span(span));
@@ -353,7 +434,7 @@ impl<'a> Lowerer<'a> {
 				}
 
 				// [direction] [net_type|"var"] type_or_implicit ident {dimension} ["=" expr]
-				ast::Port::Named{ span, dir, kind, ty, name, dims, expr } => {
+				ast::Port::Named{ span, dir, kind, ty, name, dims, expr, .. } => {
 					if dir.is_some() || kind.is_some() || !is_type_empty(&ty) || expr.is_some() {
 						self.add_diag(DiagBuilder2::error("ANSI style port in a non-ANSI style port list")
 							.span(span));
@@ -362,6 +443,8 @@ impl<'a> Lowerer<'a> {
 					// Convert the name of the port into an expression. This
 					// will then be used as the expression of the port stub.
 					let mut expr = ast::Expr {
+						id: ast::DUMMY_NODE_ID,
+						attrs: Vec::new(),
 						span: span,
 						data: ast::IdentExpr(ast::Identifier {
 							id: name.id,
@@ -550,22 +633,33 @@ impl<'a> Lowerer<'a> {
 					};
 
 
-					// TODO: Ensure the type dimensions match. This might only
-					// become possible at a later stage when we can resolve
-					// constant expressions.
-					let res_dims = &port_decl.0.ty.dims;
-
-
-					// TODO: Ensure the data dimensions match. This might only
-					// become possible at a later stage when we can resolve
-					// constant expressions.
-					let res_data_dims = &port_decl.1.dims;
+					// Determine the type and data dimensions. Dimensions given
+					// in only one of the declarations apply to the port. If
+					// both declarations give dimensions, they must match. This
+					// is checked during type checking, since the dimensions
+					// may contain constant expressions.
+					let res_dims = if port_decl.0.ty.dims.is_empty() {
+						&data_ty.dims
+					} else {
+						&port_decl.0.ty.dims
+					};
+					let res_data_dims = if port_decl.1.dims.is_empty() {
+						data_dims
+					} else {
+						&port_decl.1.dims
+					};
+					let both_packed = !port_decl.0.ty.dims.is_empty() && !data_ty.dims.is_empty();
+					let both_unpacked = !port_decl.1.dims.is_empty() && !data_dims.is_empty();
+					if both_packed || both_unpacked {
+						slice.data_dims = Some((span, data_ty.dims.clone(), data_dims.clone()));
+					}
 
 
 					// Assemble the above information into a type that we can
 					// assign to the slice.
 					(
 						ast::Type {
+							id: ast::DUMMY_NODE_ID,
 							span: res_span,
 							data: res_data.clone(),
 							sign: res_sign,
@@ -614,10 +708,19 @@ impl<'a> Lowerer<'a> {
 						kind: None,
 						ty: None,
 						dims: Vec::new(),
+						intf: None,
+						data_dims: None,
 					});
 				},
 				ast::IndexExpr { indexee, index } => {
-					selects.push(PortSelect::Index(index.span, Expr{}));
+					let index = match lower_expr(self.nameres, &index) {
+						Ok(e) => e,
+						Err(d) => {
+							self.add_diag(d);
+							return Err(());
+						}
+					};
+					selects.push(PortSelect::Index(index.span, index));
 					expr = *indexee;
 				},
 				ast::MemberExpr { expr: e, name } => {
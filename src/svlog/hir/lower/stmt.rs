@@ -0,0 +1,626 @@
+// Copyright (c) 2017 Fabian Schuiki
+
+//! This module implements the lowering of procedures, continuous assignments,
+//! statements, and subroutines to HIR.
+
+use std::collections::HashSet;
+use moore_common::errors::*;
+use moore_svlog_syntax::ast;
+use moore_svlog_syntax::resolve::NameResolution;
+use nodes::*;
+use lower::{Lowerer, is_type_empty, Result};
+use lower::expr::{lower_expr, lower_lvalue, lower_assign};
+
+
+impl<'a> Lowerer<'a> {
+	fn lowered<T>(&mut self, result: DiagResult2<T>) -> Result<T> {
+		result.map_err(|d| self.add_diag(d))
+	}
+
+	/// Lower a procedure. The `always_comb` and `always_latch` procedures are
+	/// desugared into an `always` procedure that waits for a change of any of
+	/// the values read in its body, excluding the ones it writes itself.
+	/// IEEE 1800-2009 9.2.2
+	pub fn map_procedure(&mut self, prok: &ast::Procedure) -> Result<Procedure> {
+		let stmt = self.lowered(lower_stmt(self.nameres, &prok.stmt))?;
+		let (kind, stmt) = match prok.kind {
+			ast::ProcedureKind::Initial => (ProcedureKind::Initial, stmt),
+			ast::ProcedureKind::Final => (ProcedureKind::Final, stmt),
+			ast::ProcedureKind::Always => (ProcedureKind::Always, stmt),
+			ast::ProcedureKind::AlwaysFf => {
+				match stmt.kind {
+					StmtKind::Timed(Timing::Event(_), _) => (),
+					_ => {
+						self.add_diag(DiagBuilder2::error("`always_ff` procedure must begin with an event control")
+							.span(prok.span));
+						return Err(());
+					}
+				}
+				(ProcedureKind::Always, stmt)
+			}
+			ast::ProcedureKind::AlwaysComb |
+			ast::ProcedureKind::AlwaysLatch => {
+				let events = infer_sensitivity(&stmt, true);
+				(ProcedureKind::Always, Stmt {
+					id: ast::DUMMY_NODE_ID,
+					span: stmt.span,
+					label: None,
+					kind: StmtKind::Timed(Timing::Event(events), Box::new(stmt)),
+				})
+			}
+		};
+		Ok(Procedure {
+			span: prok.span,
+			kind: kind,
+			origin: prok.kind,
+			stmt: stmt,
+		})
+	}
+
+	/// Lower a continuous assignment into one assignment per target.
+	pub fn map_cont_assign(&mut self, assign: &ast::ContAssign) -> Result<Vec<ContAssign>> {
+		let delay = match (&assign.delay, &assign.delay_control) {
+			(&Some(ref e), _) | (&None, &Some(ast::DelayControl { expr: ref e, .. })) => {
+				Some(self.lowered(lower_expr(self.nameres, e))?)
+			}
+			(&None, &None) => None,
+		};
+		let mut lowered = Vec::new();
+		for &(ref lhs, ref rhs) in &assign.assignments {
+			lowered.push(ContAssign {
+				span: assign.span,
+				strength: assign.strength,
+				delay: delay.clone(),
+				lhs: self.lowered(lower_lvalue(self.nameres, lhs))?,
+				rhs: self.lowered(lower_expr(self.nameres, rhs))?,
+			});
+		}
+		Ok(lowered)
+	}
+
+	/// Lower a function or task. The arguments declared in the prototype and
+	/// the ones declared in the body are gathered into one list.
+	/// IEEE 1800-2009 13.3, 13.4
+	pub fn map_subroutine(&mut self, decl: &ast::SubroutineDecl) -> Result<Subroutine> {
+		let proto = &decl.prototype;
+		let mut args: Vec<SubroutineArg> = Vec::new();
+		for port in &proto.args {
+			let name = match port.name {
+				Some(ref n) => n,
+				None => continue,
+			};
+			// An argument without a direction inherits the direction of the
+			// previous one, and also its type if it has none of its own.
+			let prev = args.last().map(|a| (a.dir, a.ty.clone()));
+			let dir = port.dir.or(prev.as_ref().map(|p| p.0)).unwrap_or(ast::SubroutinePortDir::Input);
+			let ty = match prev {
+				Some((_, ty)) if port.dir.is_none() && is_type_empty(&port.ty) => ty,
+				_ => port.ty.clone(),
+			};
+			let default = match name.expr {
+				Some(ref e) => Some(self.lowered(lower_expr(self.nameres, e))?),
+				None => None,
+			};
+			args.push(SubroutineArg {
+				id: name.name.id,
+				name: name.name.name,
+				span: port.span,
+				dir: dir,
+				ty: ty,
+				dims: name.dims.clone(),
+				default: default,
+			});
+		}
+		let mut stmts = Vec::new();
+		for item in &decl.items {
+			match *item {
+				ast::SubroutineItem::PortDecl(ref pd) => {
+					for name in &pd.names {
+						let default = match name.init {
+							Some(ref e) => Some(self.lowered(lower_expr(self.nameres, e))?),
+							None => None,
+						};
+						args.push(SubroutineArg {
+							id: name.id,
+							name: name.name,
+							span: name.span,
+							dir: pd.dir,
+							ty: pd.ty.clone(),
+							dims: name.dims.clone(),
+							default: default,
+						});
+					}
+				}
+				ast::SubroutineItem::Stmt(ref s) => stmts.push(self.lowered(lower_stmt(self.nameres, s))?),
+			}
+		}
+		Ok(Subroutine {
+			id: proto.name.id,
+			name: proto.name.name,
+			span: decl.span,
+			kind: proto.kind,
+			retty: proto.retty.clone(),
+			args: args,
+			stmts: stmts,
+		})
+	}
+}
+
+
+/// Lower a statement.
+pub fn lower_stmt(nameres: &NameResolution, stmt: &ast::Stmt) -> DiagResult2<Stmt> {
+	let lower_box = |s: &ast::Stmt| lower_stmt(nameres, s).map(Box::new);
+	let kind = match stmt.data {
+		ast::NullStmt => StmtKind::Null,
+		// Genvar declarations and imports have been handled during name
+		// resolution.
+		ast::GenvarDeclStmt(_) | ast::ImportStmt(_) => StmtKind::Null,
+		ast::SequentialBlock(ref stmts) => StmtKind::Block {
+			join: None,
+			stmts: lower_stmts(nameres, stmts)?,
+		},
+		ast::ParallelBlock(ref stmts, join) => StmtKind::Block {
+			join: Some(join),
+			stmts: lower_stmts(nameres, stmts)?,
+		},
+		ast::IfStmt { up, ref cond, ref main_stmt, ref else_stmt } => StmtKind::If {
+			up: up,
+			cond: lower_expr(nameres, cond)?,
+			main_stmt: lower_box(main_stmt)?,
+			else_stmt: match *else_stmt {
+				Some(ref s) => Some(lower_box(s)?),
+				None => None,
+			},
+		},
+		ast::BlockingAssignStmt { ref lhs, ref rhs, ref op } => {
+			let (lhs, rhs) = lower_assign(nameres, op, lhs, rhs, stmt.id, Span::union(lhs.span, rhs.span))?;
+			StmtKind::Assign {
+				kind: AssignKind::Blocking,
+				lhs: lhs,
+				rhs: rhs,
+			}
+		}
+		ast::NonblockingAssignStmt { ref lhs, ref rhs, ref delay, ref cycle, .. } => StmtKind::Assign {
+			kind: AssignKind::Nonblocking {
+				delay: match *delay {
+					Some(ref d) => Some(lower_expr(nameres, &d.expr)?),
+					None => None,
+				},
+				cycle: match *cycle {
+					Some(ref c) => Some(lower_expr(nameres, &c.expr)?),
+					None => None,
+				},
+			},
+			lhs: lower_lvalue(nameres, lhs)?,
+			rhs: lower_expr(nameres, rhs)?,
+		},
+		ast::TimedStmt(ref control, ref stmt) => {
+			let stmt = lower_box(stmt)?;
+			let timing = match *control {
+				ast::TimingControl::Delay(ref d) => Timing::Delay(lower_expr(nameres, &d.expr)?),
+				ast::TimingControl::Cycle(ref c) => Timing::Cycle(lower_expr(nameres, &c.expr)?),
+				ast::TimingControl::Event(ast::EventControl { data: ast::EventControlData::Implicit, .. }) => {
+					Timing::Event(infer_sensitivity(&stmt, false))
+				}
+				ast::TimingControl::Event(ast::EventControl { data: ast::EventControlData::Expr(ref e), .. }) => {
+					let mut events = Vec::new();
+					lower_event(nameres, e, None, &mut events)?;
+					Timing::Event(events)
+				}
+			};
+			StmtKind::Timed(timing, stmt)
+		}
+		ast::CaseStmt { up, kind, ref expr, mode, ref items } => {
+			let mut lowered = Vec::new();
+			for item in items {
+				lowered.push(match *item {
+					ast::CaseItem::Default(ref s) => CaseItem {
+						exprs: Vec::new(),
+						stmt: lower_stmt(nameres, s)?,
+					},
+					ast::CaseItem::Expr(ref es, ref s) => CaseItem {
+						exprs: es.iter().map(|e| lower_expr(nameres, e)).collect::<DiagResult2<_>>()?,
+						stmt: lower_stmt(nameres, s)?,
+					},
+				});
+			}
+			StmtKind::Case {
+				up: up,
+				kind: kind,
+				mode: mode,
+				expr: lower_expr(nameres, expr)?,
+				items: lowered,
+			}
+		}
+		ast::ForeverStmt(ref s) => StmtKind::Forever(lower_box(s)?),
+		ast::RepeatStmt(ref e, ref s) => StmtKind::Repeat(lower_expr(nameres, e)?, lower_box(s)?),
+		ast::WhileStmt(ref e, ref s) => StmtKind::While(lower_expr(nameres, e)?, lower_box(s)?),
+		ast::DoStmt(ref s, ref e) => StmtKind::DoWhile(lower_box(s)?, lower_expr(nameres, e)?),
+		ast::ForStmt(ref init, ref cond, ref step, ref body) => StmtKind::For(
+			lower_box(init)?,
+			lower_expr(nameres, cond)?,
+			lower_expr(nameres, step)?,
+			lower_box(body)?,
+		),
+		ast::ForeachStmt(ref e, ref vars, ref s) => StmtKind::Foreach(lower_expr(nameres, e)?, vars.clone(), lower_box(s)?),
+		ast::ExprStmt(ref e) => StmtKind::Expr(lower_expr(nameres, e)?),
+		ast::VarDeclStmt(ref decl) => StmtKind::VarDecl(lower_var_decl(nameres, decl)?),
+		ast::ContinueStmt => StmtKind::Continue,
+		ast::BreakStmt => StmtKind::Break,
+		ast::ReturnStmt(ref e) => StmtKind::Return(match *e {
+			Some(ref e) => Some(lower_expr(nameres, e)?),
+			None => None,
+		}),
+		ast::AssertionStmt(ref a) => StmtKind::Assertion(a.clone()),
+		ast::WaitExprStmt(ref e, ref s) => StmtKind::Wait(lower_expr(nameres, e)?, lower_box(s)?),
+		ast::WaitForkStmt => StmtKind::WaitFork,
+		ast::DisableForkStmt => StmtKind::DisableFork,
+		ast::DisableStmt(name) => StmtKind::Disable(name),
+	};
+	Ok(Stmt {
+		id: stmt.id,
+		span: stmt.span,
+		label: stmt.label,
+		kind: kind,
+	})
+}
+
+fn lower_stmts(nameres: &NameResolution, stmts: &[ast::Stmt]) -> DiagResult2<Vec<Stmt>> {
+	stmts.iter().map(|s| lower_stmt(nameres, s)).collect()
+}
+
+/// Lower a variable declaration within a procedure or subroutine.
+pub fn lower_var_decl(nameres: &NameResolution, decl: &ast::VarDecl) -> DiagResult2<VarDecl> {
+	let mut names = Vec::new();
+	for name in &decl.names {
+		names.push(VarDeclName {
+			id: name.id,
+			name: name.name,
+			span: name.span,
+			dims: name.dims.clone(),
+			init: match name.init {
+				Some(ref e) => Some(lower_expr(nameres, e)?),
+				None => None,
+			},
+		});
+	}
+	Ok(VarDecl {
+		span: decl.span,
+		konst: decl.konst,
+		ty: decl.ty.clone(),
+		names: names,
+	})
+}
+
+/// Flatten an event expression such as `posedge a or b iff en` into a list of
+/// events.
+fn lower_event(nameres: &NameResolution, event: &ast::EventExpr, iff: Option<&ast::Expr>, into: &mut Vec<Event>) -> DiagResult2<()> {
+	match *event {
+		ast::EventExpr::Edge { span, edge, ref value } => {
+			into.push(Event {
+				span: span,
+				edge: edge,
+				expr: lower_expr(nameres, value)?,
+				iff: match iff {
+					Some(e) => Some(lower_expr(nameres, e)?),
+					None => None,
+				},
+			});
+		}
+		ast::EventExpr::Iff { ref expr, ref cond, .. } => lower_event(nameres, expr, Some(cond), into)?,
+		ast::EventExpr::Or { ref lhs, ref rhs, .. } => {
+			lower_event(nameres, lhs, iff, into)?;
+			lower_event(nameres, rhs, iff, into)?;
+		}
+	}
+	Ok(())
+}
+
+
+/// Determine the events an implicit event control `@*` or an `always_comb`
+/// procedure waits for. These are the names read within the statement,
+/// excluding variables declared within it. If `exclude_written` is set, names
+/// that are assigned to within the statement are excluded as well.
+/// IEEE 1800-2009 9.4.2.2, 9.2.2.2.1
+pub fn infer_sensitivity(stmt: &Stmt, exclude_written: bool) -> Vec<Event> {
+	let mut acc = Accesses::default();
+	acc.stmt(stmt);
+	let mut excluded: HashSet<NodeId> = acc.locals.into_iter().collect();
+	if exclude_written {
+		excluded.extend(acc.writes.iter().filter_map(|w| w.ident.def));
+	}
+	let mut seen = HashSet::new();
+	let mut events = Vec::new();
+	for (id, span, ident) in acc.reads {
+		if ident.def.map(|d| excluded.contains(&d)).unwrap_or(false) || !seen.insert((ident.def, ident.name)) {
+			continue;
+		}
+		events.push(Event {
+			span: span,
+			edge: ast::EdgeIdent::Implicit,
+			expr: Expr {
+				id: id,
+				span: span,
+				kind: ExprKind::Ident(*ident),
+			},
+			iff: None,
+		});
+	}
+	events
+}
+
+/// The names read and written within statements and expressions, in the order
+/// they appear.
+#[derive(Default)]
+pub struct Accesses<'a> {
+	/// The names whose value is read, together with the ID and span of the
+	/// expression they appear in.
+	pub reads: Vec<(NodeId, Span, &'a Ident)>,
+	/// The names that are assigned to.
+	pub writes: Vec<Write<'a>>,
+	/// The variables and loop indices declared within the statement.
+	pub locals: Vec<NodeId>,
+}
+
+/// An assignment to a name, possibly to a part of it, as in `x[3] = 1`.
+pub struct Write<'a> {
+	pub ident: &'a Ident,
+	/// The location of the entire lvalue.
+	pub span: Span,
+	/// The select applied to the name. Only the first dimension is recorded.
+	pub select: Option<&'a Select>,
+	/// The member accessed on the name.
+	pub member: Option<Name>,
+}
+
+impl<'a> Accesses<'a> {
+	pub fn stmt(&mut self, stmt: &'a Stmt) {
+		match stmt.kind {
+			StmtKind::Null | StmtKind::Break | StmtKind::Continue | StmtKind::WaitFork |
+			StmtKind::DisableFork | StmtKind::Disable(_) | StmtKind::Assertion(_) => (),
+			StmtKind::Block { ref stmts, .. } => {
+				for s in stmts {
+					self.stmt(s);
+				}
+			}
+			StmtKind::If { ref cond, ref main_stmt, ref else_stmt, .. } => {
+				self.expr(cond);
+				self.stmt(main_stmt);
+				if let Some(ref s) = *else_stmt {
+					self.stmt(s);
+				}
+			}
+			StmtKind::Assign { ref kind, ref lhs, ref rhs } => {
+				if let AssignKind::Nonblocking { ref delay, ref cycle } = *kind {
+					for e in delay.iter().chain(cycle.iter()) {
+						self.expr(e);
+					}
+				}
+				self.lvalue(lhs);
+				self.expr(rhs);
+			}
+			StmtKind::Timed(ref timing, ref stmt) => {
+				match *timing {
+					Timing::Delay(ref e) | Timing::Cycle(ref e) => self.expr(e),
+					Timing::Event(ref events) => {
+						for ev in events {
+							self.expr(&ev.expr);
+							if let Some(ref e) = ev.iff {
+								self.expr(e);
+							}
+						}
+					}
+				}
+				self.stmt(stmt);
+			}
+			StmtKind::Case { ref expr, ref items, .. } => {
+				self.expr(expr);
+				for item in items {
+					for e in &item.exprs {
+						self.expr(e);
+					}
+					self.stmt(&item.stmt);
+				}
+			}
+			StmtKind::Forever(ref s) => self.stmt(s),
+			StmtKind::Repeat(ref e, ref s) |
+			StmtKind::While(ref e, ref s) |
+			StmtKind::DoWhile(ref s, ref e) |
+			StmtKind::Wait(ref e, ref s) => {
+				self.expr(e);
+				self.stmt(s);
+			}
+			StmtKind::For(ref init, ref cond, ref step, ref body) => {
+				self.stmt(init);
+				self.expr(cond);
+				self.expr(step);
+				self.stmt(body);
+			}
+			StmtKind::Foreach(ref e, ref vars, ref s) => {
+				self.locals.extend(vars.iter().filter_map(|v| v.map(|v| v.id)));
+				self.expr(e);
+				self.stmt(s);
+			}
+			StmtKind::Expr(ref e) => self.expr(e),
+			StmtKind::VarDecl(ref decl) => {
+				for name in &decl.names {
+					self.locals.push(name.id);
+					if let Some(ref e) = name.init {
+						self.expr(e);
+					}
+				}
+			}
+			StmtKind::Return(ref e) => {
+				if let Some(ref e) = *e {
+					self.expr(e);
+				}
+			}
+		}
+	}
+
+	pub fn expr(&mut self, expr: &'a Expr) {
+		match expr.kind {
+			ExprKind::Lit(_) | ExprKind::Type(_) | ExprKind::EmptyQueue => (),
+			ExprKind::Ident(ref i) => self.reads.push((expr.id, expr.span, i)),
+			ExprKind::SysCall(_, ref args) | ExprKind::ConstructorCall(ref args) => self.args(args),
+			ExprKind::Call(ref callee, ref args) => {
+				// The name of the called function is not read, but the object
+				// a method is called on is.
+				if let ExprKind::Member(ref base, _) = callee.kind {
+					self.expr(base);
+				}
+				self.args(args);
+			}
+			ExprKind::Unary(_, ref e) |
+			ExprKind::Member(ref e, _) |
+			ExprKind::New(Some(ref e)) |
+			ExprKind::Randomize { call: ref e, .. } => self.expr(e),
+			ExprKind::New(None) => (),
+			ExprKind::IncDec { ref target, .. } => {
+				self.lvalue(target);
+				self.lvalue_reads(target);
+			}
+			ExprKind::Binary(_, ref a, ref b) |
+			ExprKind::Range(_, ref a, ref b) |
+			ExprKind::ArrayNew(ref a, Some(ref b)) => {
+				self.expr(a);
+				self.expr(b);
+			}
+			ExprKind::ArrayNew(ref a, None) => self.expr(a),
+			ExprKind::Ternary(ref a, ref b, ref c) |
+			ExprKind::MinTypMax(ref a, ref b, ref c) => {
+				self.expr(a);
+				self.expr(b);
+				self.expr(c);
+			}
+			ExprKind::Assign(ref lhs, ref rhs) => {
+				self.lvalue(lhs);
+				self.expr(rhs);
+			}
+			ExprKind::Select(ref e, ref sel) => {
+				self.expr(e);
+				self.select(sel);
+			}
+			ExprKind::Concat(ref repeat, ref exprs) => {
+				if let Some(ref e) = *repeat {
+					self.expr(e);
+				}
+				for e in exprs {
+					self.expr(e);
+				}
+			}
+			ExprKind::Inside(ref e, ref ranges) => {
+				self.expr(e);
+				for r in ranges {
+					self.expr(r);
+				}
+			}
+			ExprKind::Stream(ref slice, ref exprs) => {
+				if let Some(StreamSlice::Expr(ref e)) = *slice {
+					self.expr(e);
+				}
+				for e in exprs {
+					self.expr(&e.expr);
+					if let Some(ref r) = e.range {
+						self.expr(r);
+					}
+				}
+			}
+			ExprKind::Pattern(ref fields) => {
+				for field in fields {
+					match *field {
+						PatternField::Default(ref e) |
+						PatternField::Member(_, ref e) |
+						PatternField::Type(_, ref e) |
+						PatternField::Expr(ref e) => self.expr(e),
+						PatternField::Repeat(ref n, ref es) => {
+							self.expr(n);
+							for e in es {
+								self.expr(e);
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	fn args(&mut self, args: &'a [CallArg]) {
+		for arg in args {
+			if let Some(ref e) = arg.expr {
+				self.expr(e);
+			}
+		}
+	}
+
+	fn select(&mut self, sel: &'a Select) {
+		match *sel {
+			Select::Bit(ref e) => self.expr(e),
+			Select::Range(_, ref a, ref b) => {
+				self.expr(a);
+				self.expr(b);
+			}
+		}
+	}
+
+	/// Record the names assigned to by an lvalue, and the values read to
+	/// determine which part of them is assigned.
+	pub fn lvalue(&mut self, lv: &'a LValue) {
+		match lv.kind {
+			LValueKind::Ident(ref i) => self.writes.push(Write {
+				ident: i,
+				span: lv.span,
+				select: None,
+				member: None,
+			}),
+			LValueKind::Select(ref base, ref sel) => {
+				match base.kind {
+					LValueKind::Ident(ref i) => self.writes.push(Write {
+						ident: i,
+						span: lv.span,
+						select: Some(sel),
+						member: None,
+					}),
+					_ => self.lvalue(base),
+				}
+				self.select(sel);
+			}
+			LValueKind::Member(ref base, ref name) => match base.kind {
+				ExprKind::Ident(ref i) => self.writes.push(Write {
+					ident: i,
+					span: lv.span,
+					select: None,
+					member: Some(name.name),
+				}),
+				_ => self.expr(base),
+			},
+			LValueKind::Concat(ref lvs) | LValueKind::Pattern(ref lvs) => {
+				for lv in lvs {
+					self.lvalue(lv);
+				}
+			}
+			LValueKind::Stream(ref slice, ref exprs) => {
+				if let Some(StreamSlice::Expr(ref e)) = *slice {
+					self.expr(e);
+				}
+				for e in exprs {
+					self.lvalue(&e.target);
+					if let Some(ref r) = e.range {
+						self.expr(r);
+					}
+				}
+			}
+		}
+	}
+
+	/// Record the names whose value is read by an increment or decrement of an
+	/// lvalue.
+	fn lvalue_reads(&mut self, lv: &'a LValue) {
+		match lv.kind {
+			LValueKind::Ident(ref i) => self.reads.push((lv.id, lv.span, i)),
+			LValueKind::Select(ref base, _) => self.lvalue_reads(base),
+			LValueKind::Member(ref base, _) => self.expr(base),
+			_ => (),
+		}
+	}
+}
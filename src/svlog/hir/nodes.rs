@@ -2,15 +2,14 @@
 
 //! This module contains the nodes of the tree structure that is the HIR.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 pub use moore_common::name::Name;
 pub use moore_common::source::Span;
 pub use moore_svlog_syntax::ast::NodeId;
 use moore_svlog_syntax::ast;
-
-
-// TODO: Take the AST expressions and split them into lvalue and rvalue
-// expressions. This should make a few things easier.
+use moore_svlog_syntax::token::{Lit, Op};
+use ty::{TyId, TyInterner};
 
 
 /// The root of the HIR tree. This represents one elaborated design.
@@ -19,6 +18,15 @@ pub struct Root {
 	pub mods: HashMap<NodeId, Module>,
 	pub intfs: HashMap<NodeId, Interface>,
 	pub pkgs: HashMap<NodeId, Package>,
+	/// The user-defined primitives, which are leaf cells of the design.
+	pub udps: HashMap<NodeId, ast::UdpDecl>,
+	pub checkers: HashMap<NodeId, Checker>,
+	/// The bind directives, which have been applied to the design.
+	pub binds: Vec<ast::BindDirective>,
+	/// The instance tree of the design, starting at the top module.
+	pub design: Instance,
+	/// The types assigned during type checking.
+	pub tys: TyInterner,
 }
 
 /// An search index of all nodes in a HIR tree.
@@ -47,6 +55,7 @@ pub struct Module {
 	pub lifetime: ast::Lifetime,
 	pub ports: Vec<Port>,
 	pub params: Vec<ast::ParamDecl>,
+	pub timescale: Timescale,
 	pub body: HierarchyBody,
 }
 
@@ -58,38 +67,72 @@ pub struct Interface {
 	pub lifetime: ast::Lifetime,
 	pub ports: Vec<Port>,
 	pub params: Vec<ast::ParamDecl>,
+	pub timescale: Timescale,
 	pub body: HierarchyBody,
 }
 
 /// A package.
 pub struct Package {
+	pub id: NodeId,
 	pub name: Name,
 	pub span: Span,
 	pub lifetime: ast::Lifetime,
+	pub timescale: Timescale,
+	pub body: HierarchyBody,
+}
+
+/// A checker.
+pub struct Checker {
+	pub id: NodeId,
+	pub name: Name,
+	pub span: Span,
+	pub ports: Vec<ast::AssertionPort>,
 	pub body: HierarchyBody,
 }
 
+/// The time unit and precision in effect within a module, interface, or
+/// package, in seconds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Timescale {
+	pub unit: f64,
+	pub precision: f64,
+}
+
 /// A hierarchy body represents the contents of a module, interface, or package.
 /// Generate regions and nested modules introduce additional bodies. The point
 /// of hierarchy bodies is to take a level of the design hierarchy and group all
 /// declarations by type, rather than having them in a single array in
 /// declaration order.
+#[derive(Clone)]
 pub struct HierarchyBody {
-	pub procs: Vec<ast::Procedure>,
+	pub procs: Vec<Procedure>,
 	pub nets: Vec<ast::NetDecl>,
 	pub vars: Vec<ast::VarDecl>,
-	pub assigns: Vec<ast::ContAssign>,
+	pub assigns: Vec<ContAssign>,
 	pub params: Vec<ast::ParamDecl>,
 	pub insts: Vec<ast::Inst>,
+	pub gates: Vec<ast::GateInst>,
 	pub genreg: Vec<HierarchyBody>,
 	pub genvars: Vec<ast::GenvarDecl>,
 	pub genfors: Vec<GenerateFor>,
 	pub genifs: Vec<GenerateIf>,
-	pub gencases: Vec<ast::GenerateCase>,
+	pub gencases: Vec<GenerateCase>,
 	pub classes: Vec<ast::ClassDecl>, // TODO: Make this an HIR node, since it contains hierarchy items
-	pub subroutines: Vec<ast::SubroutineDecl>, // TODO: Make this an HIR node
+	pub subroutines: Vec<Subroutine>,
 	pub asserts: Vec<ast::Assertion>,
 	pub typedefs: Vec<ast::Typedef>,
+	pub covergroups: Vec<ast::CovergroupDecl>,
+	pub dpi_decls: Vec<ast::DpiDecl>,
+	pub specparams: Vec<ast::SpecparamDecl>,
+	pub specify_blocks: Vec<ast::SpecifyBlock>,
+	pub clockings: Vec<ast::ClockingDecl>,
+	/// The clocking blocks named in `default clocking name;` statements.
+	pub default_clockings: Vec<ast::Identifier>,
+	pub sequences: Vec<ast::SequenceDecl>,
+	pub properties: Vec<ast::PropertyDecl>,
+	pub nettypes: Vec<ast::NettypeDecl>,
+	pub aliases: Vec<ast::Alias>,
+	pub modports: Vec<Modport>,
 }
 
 #[derive(Debug)]
@@ -112,6 +155,24 @@ pub struct PortSlice {
 	pub kind: ast::PortKind,
 	pub ty: Option<ast::Type>,
 	pub dims: Vec<ast::TypeDim>,
+	/// The interface and modport, if this is an interface port.
+	pub intf: Option<IntfPort>,
+	/// The location and the packed and unpacked dimensions of the variable or
+	/// net declaration that accompanies a non-ANSI port, if both it and the
+	/// port declaration specify dimensions. These are checked for equivalence
+	/// during type checking, once constant expressions can be evaluated.
+	pub data_dims: Option<(Span, Vec<ast::TypeDim>, Vec<ast::TypeDim>)>,
+}
+
+/// The interface of an interface port, as in `bus_if.master b` or
+/// `interface b`.
+#[derive(Debug, Clone)]
+pub struct IntfPort {
+	/// The interface, or `None` for a generic interface port that accepts any
+	/// interface.
+	pub intf: Option<NodeId>,
+	/// The modport through which the interface is accessed, if any.
+	pub modport: Option<ast::Identifier>,
 }
 
 #[derive(Debug)]
@@ -128,15 +189,332 @@ pub struct PortDecl {
 
 // }
 
-#[derive(Debug)]
-pub struct Expr;
+/// A modport declared within an interface, which restricts the access to the
+/// items of the interface through an interface port.
+#[derive(Debug, Clone)]
+pub struct Modport {
+	pub id: NodeId,
+	pub name: Name,
+	pub span: Span,
+	pub ports: Vec<ModportPort>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModportPort {
+	pub id: NodeId,
+	pub name: Name,
+	pub span: Span,
+	pub kind: ModportPortKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum ModportPortKind {
+	/// A port with a direction. The expression is either the name of an item
+	/// of the interface, or the expression given in `.name(expr)`. `None` for
+	/// a port with an empty expression.
+	Simple(ast::PortDir, Option<Expr>),
+	/// An imported or exported subroutine. Exported subroutines may not be
+	/// defined in the interface.
+	Subroutine(ast::ModportTfKind, Option<NodeId>),
+	/// A clocking block.
+	Clocking(Option<NodeId>),
+}
+
+/// A procedure. The `always_comb`, `always_latch`, and `always_ff` variants
+/// have been desugared into `always` procedures, with the sensitivity of the
+/// combinational and latch variants made explicit as an event control around
+/// the statement.
+#[derive(Debug, Clone)]
+pub struct Procedure {
+	pub span: Span,
+	pub kind: ProcedureKind,
+	/// The keyword the procedure was declared with.
+	pub origin: ast::ProcedureKind,
+	pub stmt: Stmt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcedureKind {
+	Initial,
+	Always,
+	Final,
+}
+
+/// A single continuous assignment. An `assign` with multiple assignments is
+/// split up into one of these per assignment.
+#[derive(Debug, Clone)]
+pub struct ContAssign {
+	pub span: Span,
+	pub strength: Option<(ast::DriveStrength, ast::DriveStrength)>,
+	pub delay: Option<Expr>,
+	pub lhs: LValue,
+	pub rhs: Expr,
+}
 
+/// A function or task.
+#[derive(Debug, Clone)]
+pub struct Subroutine {
+	pub id: NodeId,
+	pub name: Name,
+	pub span: Span,
+	pub kind: ast::SubroutineKind,
+	pub retty: Option<ast::Type>,
+	/// The arguments, regardless of whether they were declared in the
+	/// prototype or in the body.
+	pub args: Vec<SubroutineArg>,
+	pub stmts: Vec<Stmt>,
+}
+
+/// An argument of a function or task. Directions and types that were left
+/// out have been inherited from the previous argument.
+#[derive(Debug, Clone)]
+pub struct SubroutineArg {
+	pub id: NodeId,
+	pub name: Name,
+	pub span: Span,
+	pub dir: ast::SubroutinePortDir,
+	pub ty: ast::Type,
+	pub dims: Vec<ast::TypeDim>,
+	pub default: Option<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Stmt {
+	pub id: NodeId,
+	pub span: Span,
+	pub label: Option<Name>,
+	pub kind: StmtKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum StmtKind {
+	Null,
+	Block {
+		/// The kind of join, or `None` for a sequential block.
+		join: Option<ast::JoinKind>,
+		stmts: Vec<Stmt>,
+	},
+	If {
+		up: Option<ast::UniquePriority>,
+		cond: Expr,
+		main_stmt: Box<Stmt>,
+		else_stmt: Option<Box<Stmt>>,
+	},
+	/// An assignment. Compound assignments such as `a += b` have been
+	/// expanded into `a = a + b`.
+	Assign {
+		kind: AssignKind,
+		lhs: LValue,
+		rhs: Expr,
+	},
+	Timed(Timing, Box<Stmt>),
+	Case {
+		up: Option<ast::UniquePriority>,
+		kind: ast::CaseKind,
+		mode: ast::CaseMode,
+		expr: Expr,
+		items: Vec<CaseItem>,
+	},
+	Forever(Box<Stmt>),
+	Repeat(Expr, Box<Stmt>),
+	While(Expr, Box<Stmt>),
+	DoWhile(Box<Stmt>, Expr),
+	For(Box<Stmt>, Expr, Expr, Box<Stmt>),
+	Foreach(Expr, Vec<Option<ast::Identifier>>, Box<Stmt>),
+	Expr(Expr),
+	VarDecl(VarDecl),
+	Return(Option<Expr>),
+	Break,
+	Continue,
+	Wait(Expr, Box<Stmt>),
+	WaitFork,
+	DisableFork,
+	Disable(Name),
+	Assertion(Box<ast::Assertion>),
+}
+
+#[derive(Debug, Clone)]
+pub enum AssignKind {
+	Blocking,
+	Nonblocking {
+		delay: Option<Expr>,
+		/// The cycle delay of a clocking drive, e.g. `cb.q <= ##1 d`.
+		cycle: Option<Expr>,
+	},
+}
+
+/// An item of a case statement. The default item has no expressions.
+#[derive(Debug, Clone)]
+pub struct CaseItem {
+	pub exprs: Vec<Expr>,
+	pub stmt: Stmt,
+}
+
+#[derive(Debug, Clone)]
+pub enum Timing {
+	Delay(Expr),
+	/// An event control. Implicit event controls such as `@*` have been
+	/// replaced by the events they are sensitive to.
+	Event(Vec<Event>),
+	Cycle(Expr),
+}
+
+/// One event of an event control, e.g. `posedge clk iff en`.
+#[derive(Debug, Clone)]
+pub struct Event {
+	pub span: Span,
+	pub edge: ast::EdgeIdent,
+	pub expr: Expr,
+	pub iff: Option<Expr>,
+}
+
+/// A variable declaration within a procedure or subroutine.
+#[derive(Debug, Clone)]
+pub struct VarDecl {
+	pub span: Span,
+	pub konst: bool,
+	pub ty: ast::Type,
+	pub names: Vec<VarDeclName>,
+}
+
+#[derive(Debug, Clone)]
+pub struct VarDeclName {
+	pub id: NodeId,
+	pub name: Name,
+	pub span: Span,
+	pub dims: Vec<ast::TypeDim>,
+	pub init: Option<Expr>,
+}
+
+/// An expression that yields a value.
+#[derive(Debug, Clone)]
+pub struct Expr {
+	pub id: NodeId,
+	pub span: Span,
+	pub kind: ExprKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExprKind {
+	Lit(Lit),
+	Ident(Ident),
+	/// A system task or function, such as `$clog2(N)` or `$time`.
+	SysCall(ast::Identifier, Vec<CallArg>),
+	Call(Box<Expr>, Vec<CallArg>),
+	Unary(Op, Box<Expr>),
+	/// An increment or decrement, e.g. `i++` or `--i`.
+	IncDec {
+		op: Op,
+		postfix: bool,
+		target: Box<LValue>,
+	},
+	Binary(Op, Box<Expr>, Box<Expr>),
+	Ternary(Box<Expr>, Box<Expr>, Box<Expr>),
+	/// An assignment used as an expression. Compound assignments have been
+	/// expanded as for assignment statements.
+	Assign(Box<LValue>, Box<Expr>),
+	Select(Box<Expr>, Select),
+	Member(Box<Expr>, ast::Identifier),
+	Concat(Option<Box<Expr>>, Vec<Expr>),
+	Stream(Option<StreamSlice>, Vec<StreamExpr>),
+	MinTypMax(Box<Expr>, Box<Expr>, Box<Expr>),
+	Range(ast::RangeMode, Box<Expr>, Box<Expr>),
+	/// A set membership test, e.g. `x inside {1, [3:5]}`.
+	Inside(Box<Expr>, Vec<Expr>),
+	Pattern(Vec<PatternField>),
+	New(Option<Box<Expr>>),
+	ConstructorCall(Vec<CallArg>),
+	ArrayNew(Box<Expr>, Option<Box<Expr>>),
+	Randomize {
+		call: Box<Expr>,
+		names: Vec<ast::Identifier>,
+		items: Vec<ast::ConstraintItem>,
+	},
+	Type(ast::Type),
+	EmptyQueue,
+}
+
+/// A reference to a declaration by name. Scoped names such as `pkg::x` refer
+/// to the member directly.
+#[derive(Debug, Clone, Copy)]
+pub struct Ident {
+	pub id: NodeId,
+	pub name: Name,
+	pub span: Span,
+	/// The declaration the name resolves to, if any.
+	pub def: Option<NodeId>,
+}
+
+/// A bit select `[i]` or range select `[a:b]`, `[a+:b]`, or `[a-:b]`.
+#[derive(Debug, Clone)]
+pub enum Select {
+	Bit(Box<Expr>),
+	Range(ast::RangeMode, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone)]
+pub struct CallArg {
+	pub span: Span,
+	pub name: Option<Name>,
+	pub expr: Option<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub enum StreamSlice {
+	Expr(Box<Expr>),
+	Type(ast::Type),
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamExpr {
+	pub expr: Expr,
+	pub range: Option<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub enum PatternField {
+	Default(Expr),
+	Member(Expr, Expr),
+	Type(ast::Type, Expr),
+	Expr(Expr),
+	Repeat(Expr, Vec<Expr>),
+}
+
+/// An expression that can be assigned to.
+#[derive(Debug, Clone)]
+pub struct LValue {
+	pub id: NodeId,
+	pub span: Span,
+	pub kind: LValueKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum LValueKind {
+	Ident(Ident),
+	Select(Box<LValue>, Select),
+	/// A member of a struct, class, or interface. The accessed value may be
+	/// any expression, such as a class handle returned by a function.
+	Member(Box<Expr>, ast::Identifier),
+	Concat(Vec<LValue>),
+	Stream(Option<StreamSlice>, Vec<LValueStreamExpr>),
+	/// An assignment pattern that unpacks a value, e.g. `'{a, b} = c`.
+	Pattern(Vec<LValue>),
+}
+
+#[derive(Debug, Clone)]
+pub struct LValueStreamExpr {
+	pub target: LValue,
+	pub range: Option<Expr>,
+}
+
+#[derive(Clone)]
 pub struct GenerateBlock {
 	pub span: Span,
 	pub label: Option<Name>,
 	pub body: HierarchyBody,
 }
 
+#[derive(Clone)]
 pub struct GenerateFor {
 	pub span: Span,
 	pub init: ast::Stmt,
@@ -145,9 +523,125 @@ pub struct GenerateFor {
 	pub block: GenerateBlock,
 }
 
+#[derive(Clone)]
 pub struct GenerateIf {
 	pub span: Span,
 	pub cond: ast::Expr,
 	pub main_block: GenerateBlock,
 	pub else_block: Option<GenerateBlock>,
 }
+
+#[derive(Clone)]
+pub struct GenerateCase {
+	pub span: Span,
+	pub expr: ast::Expr,
+	pub items: Vec<GenerateCaseItem>,
+}
+
+/// An item of a case-generate construct. The `default` item has no
+/// expressions.
+#[derive(Clone)]
+pub struct GenerateCaseItem {
+	pub span: Span,
+	pub exprs: Vec<ast::Expr>,
+	pub block: GenerateBlock,
+}
+
+
+/// A constant value, as obtained by evaluating parameters and genvars during
+/// elaboration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConstValue {
+	Int(i64),
+}
+
+impl ConstValue {
+	/// Get the integer value of the constant.
+	pub fn as_int(&self) -> i64 {
+		match *self {
+			ConstValue::Int(v) => v,
+		}
+	}
+
+	/// Check whether the constant is considered true in a condition.
+	pub fn is_true(&self) -> bool {
+		self.as_int() != 0
+	}
+}
+
+impl fmt::Display for ConstValue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ConstValue::Int(v) => write!(f, "{}", v),
+		}
+	}
+}
+
+/// An elaborated instance of a module or interface. Parameters have been
+/// assigned their final values and all generate constructs have been unrolled.
+pub struct Instance {
+	/// The instance name, or the module name in case of the top instance.
+	pub name: Name,
+	pub span: Span,
+	/// The module or interface that is instantiated.
+	pub target: NodeId,
+	pub scope: InstScope,
+	/// The interface instances connected to the interface ports, keyed by the
+	/// node ID of the port slice.
+	pub intf_ports: HashMap<NodeId, IntfBinding>,
+}
+
+/// The interface instance an interface port is connected to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntfBinding {
+	/// The hierarchical name of the interface instance, e.g. `[top, bus]`.
+	pub path: Vec<Name>,
+	/// The interface that is instantiated.
+	pub intf: NodeId,
+	/// The modport through which the interface is accessed, if any.
+	pub modport: Option<NodeId>,
+}
+
+/// A scope within an elaborated instance. The instance itself forms the
+/// outermost scope, and each generate block that is selected by a generate if
+/// or produced by an iteration of a generate loop forms a nested scope. The
+/// body of a scope no longer contains any generate constructs, and generate
+/// regions have been merged into it.
+pub struct InstScope {
+	/// The name of the scope, e.g. `gen_blk[3]` or `genblk2`.
+	pub name: Name,
+	pub span: Span,
+	/// The values of the parameters and genvars declared in this scope.
+	pub values: HashMap<NodeId, ConstValue>,
+	pub body: HierarchyBody,
+	pub scopes: Vec<InstScope>,
+	pub insts: Vec<Instance>,
+	/// The types of the expressions in this scope, keyed by their node ID.
+	/// Filled in by the type checker.
+	pub types: BTreeMap<NodeId, TyId>,
+	/// The targets of the hierarchical names used in this scope, keyed by the
+	/// node ID of the identifier that names the referenced declaration.
+	pub hier_refs: HashMap<NodeId, HierRef>,
+}
+
+/// The target of a hierarchical name such as `top.u_core.result`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HierRef {
+	/// The hierarchical name of the referenced declaration, starting at the
+	/// top instance, e.g. `[top, u_core, gen_blk[1], result]`.
+	pub path: Vec<Name>,
+	/// The declaration that is referenced.
+	pub decl: NodeId,
+}
+
+impl InstScope {
+	/// Find a nested generate scope by name, e.g. `gen_blk[3]`.
+	pub fn find_scope(&self, name: &str) -> Option<&InstScope> {
+		self.scopes.iter().find(|s| &*s.name.as_str() == name)
+	}
+
+	/// Find an instance in this scope by name.
+	pub fn find_inst(&self, name: &str) -> Option<&Instance> {
+		self.insts.iter().find(|i| &*i.name.as_str() == name)
+	}
+}
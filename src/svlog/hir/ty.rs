@@ -0,0 +1,403 @@
+// Copyright (c) 2017 Fabian Schuiki
+
+//! This module defines the semantic types that are assigned to declarations
+//! and expressions during type checking. Types are interned in a `TyInterner`
+//! and referred to by their `TyId`. Typedefs are resolved and dimensions are
+//! evaluated before a type is interned, such that two types are identical if
+//! and only if their IDs are equal.
+
+use std::collections::HashMap;
+use std::fmt;
+use nodes::*;
+
+
+/// A resolved type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Ty {
+	/// The type of something that could not be typed. A diagnostic has been
+	/// emitted already, or the construct is not yet supported.
+	Error,
+	Void,
+	/// An integral type, i.e. a single bit or a packed vector of bits.
+	Int(IntTy),
+	Real,
+	ShortReal,
+	String,
+	Chandle,
+	Event,
+	Enum(EnumTy),
+	Struct(StructTy),
+	Class(ClassTy),
+	/// An unpacked array with the given element type and range.
+	Array(TyId, Range),
+}
+
+/// The ID of an interned type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TyId(u32);
+
+/// The ID of the error type, which is interned in every `TyInterner`.
+pub const ERROR_TY: TyId = TyId(0);
+
+/// The maximum depth of a class hierarchy that is followed when checking
+/// whether one class derives from another.
+const MAX_CLASS_DEPTH: usize = 256;
+
+/// An integral type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntTy {
+	pub signed: bool,
+	/// Whether the type may hold `x` and `z` values.
+	pub four_state: bool,
+	/// The packed dimensions, outermost first. Empty for a single bit.
+	pub dims: Vec<Range>,
+}
+
+/// An enumeration. Two enums are the same type if they were declared at the
+/// same location.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnumTy {
+	pub span: Span,
+	pub base: IntTy,
+}
+
+/// A struct or union. Two structs are the same type if they were declared at
+/// the same location.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructTy {
+	pub span: Span,
+	pub union: bool,
+	pub packed: bool,
+	pub signed: bool,
+	pub members: Vec<(Name, TyId)>,
+}
+
+/// A specialization of a class, identified by the node ID of the class name
+/// and the values assigned to its parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClassTy {
+	pub id: NodeId,
+	pub name: Name,
+	pub args: Vec<ClassArg>,
+	/// The specialization of the class this class extends, if any.
+	pub base: Option<TyId>,
+}
+
+/// The value assigned to a parameter of a class.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClassArg {
+	Value(ConstValue),
+	Type(TyId),
+}
+
+/// An evaluated range `[left:right]` of a packed or unpacked dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Range {
+	pub left: i64,
+	pub right: i64,
+}
+
+impl Range {
+	/// Create the range `[width-1:0]`.
+	pub fn with_width(width: usize) -> Range {
+		Range {
+			left: width as i64 - 1,
+			right: 0,
+		}
+	}
+
+	/// The number of elements covered by the range.
+	pub fn size(&self) -> usize {
+		((self.left - self.right).abs() + 1) as usize
+	}
+}
+
+impl IntTy {
+	/// Create a vector type `[width-1:0]`. A width of one yields a single bit.
+	pub fn new(width: usize, signed: bool, four_state: bool) -> IntTy {
+		IntTy {
+			signed: signed,
+			four_state: four_state,
+			dims: if width == 1 { vec![] } else { vec![Range::with_width(width)] },
+		}
+	}
+
+	/// The number of bits in the type.
+	pub fn width(&self) -> usize {
+		self.dims.iter().map(|d| d.size()).product()
+	}
+}
+
+impl Ty {
+	/// The `int` type.
+	pub fn int() -> Ty {
+		Ty::Int(IntTy::new(32, true, false))
+	}
+
+	/// A single unsigned bit, as produced by comparisons and logic operators.
+	pub fn bit(four_state: bool) -> Ty {
+		Ty::Int(IntTy::new(1, false, four_state))
+	}
+
+	pub fn is_error(&self) -> bool {
+		*self == Ty::Error
+	}
+
+	pub fn is_real(&self) -> bool {
+		match *self {
+			Ty::Real | Ty::ShortReal => true,
+			_ => false,
+		}
+	}
+}
+
+
+/// A table of interned types.
+pub struct TyInterner {
+	tys: Vec<Ty>,
+	ids: HashMap<Ty, TyId>,
+}
+
+impl TyInterner {
+	pub fn new() -> TyInterner {
+		let mut tys = TyInterner {
+			tys: Vec::new(),
+			ids: HashMap::new(),
+		};
+		let error = tys.intern(Ty::Error);
+		assert_eq!(error, ERROR_TY);
+		tys
+	}
+
+	/// Intern a type, returning the ID of an identical type if it has been
+	/// interned before.
+	pub fn intern(&mut self, ty: Ty) -> TyId {
+		if let Some(&id) = self.ids.get(&ty) {
+			return id;
+		}
+		let id = TyId(self.tys.len() as u32);
+		self.tys.push(ty.clone());
+		self.ids.insert(ty, id);
+		id
+	}
+
+	/// Look up an interned type.
+	pub fn get(&self, id: TyId) -> &Ty {
+		&self.tys[id.0 as usize]
+	}
+
+	/// The integral type that a type is equivalent to, if any. This covers
+	/// vectors, enums, and packed structs and unions.
+	pub fn as_int(&self, id: TyId) -> Option<IntTy> {
+		match *self.get(id) {
+			Ty::Int(ref t) => Some(t.clone()),
+			Ty::Enum(ref t) => Some(t.base.clone()),
+			Ty::Struct(ref t) if t.packed => {
+				let widths = t.members.iter().map(|&(_, ty)| self.bits(ty).unwrap_or(0));
+				let width = if t.union { widths.max().unwrap_or(0) } else { widths.sum() };
+				let four_state = t.members.iter().any(|&(_, ty)| self.as_int(ty).map(|t| t.four_state).unwrap_or(false));
+				Some(IntTy::new(width, t.signed, four_state))
+			}
+			_ => None,
+		}
+	}
+
+	pub fn is_integral(&self, id: TyId) -> bool {
+		self.as_int(id).is_some()
+	}
+
+	/// The number of bits in an integral type.
+	pub fn width(&self, id: TyId) -> Option<usize> {
+		self.as_int(id).map(|t| t.width())
+	}
+
+	/// The number of bits needed to represent a value of a type, as returned
+	/// by `$bits`. Yields `None` for dynamically sized types such as strings
+	/// and classes.
+	pub fn bits(&self, id: TyId) -> Option<usize> {
+		match *self.get(id) {
+			Ty::Real => Some(64),
+			Ty::ShortReal => Some(32),
+			Ty::Struct(ref t) if !t.packed => {
+				let mut bits = Vec::new();
+				for &(_, ty) in &t.members {
+					bits.push(self.bits(ty)?);
+				}
+				Some(if t.union { bits.into_iter().max().unwrap_or(0) } else { bits.into_iter().sum() })
+			}
+			Ty::Array(elem, range) => self.bits(elem).map(|b| b * range.size()),
+			_ => self.width(id),
+		}
+	}
+
+	/// Check whether two types are equivalent, as defined in IEEE 1800-2009
+	/// section 6.22.2.
+	pub fn is_equivalent(&self, a: TyId, b: TyId) -> bool {
+		if a == b {
+			return true;
+		}
+		match (self.get(a), self.get(b)) {
+			// Packed types and built-in integral types are equivalent if they
+			// have the same width, signedness, and number of states.
+			(&Ty::Int(_), &Ty::Int(_)) |
+			(&Ty::Int(_), &Ty::Struct(StructTy { packed: true, .. })) |
+			(&Ty::Struct(StructTy { packed: true, .. }), &Ty::Int(_)) |
+			(&Ty::Struct(StructTy { packed: true, .. }), &Ty::Struct(StructTy { packed: true, .. })) => {
+				let (a, b) = (self.as_int(a).unwrap(), self.as_int(b).unwrap());
+				a.width() == b.width() && a.signed == b.signed && a.four_state == b.four_state
+			}
+			// Unpacked arrays are equivalent if their elements are and they
+			// have the same number of elements.
+			(&Ty::Array(ea, ra), &Ty::Array(eb, rb)) => ra.size() == rb.size() && self.is_equivalent(ea, eb),
+			_ => false,
+		}
+	}
+
+	/// Check whether a value of type `from` may be assigned to something of
+	/// type `to` without a cast, as defined in IEEE 1800-2009 section 6.22.3.
+	pub fn is_assignment_compatible(&self, to: TyId, from: TyId) -> bool {
+		if self.is_equivalent(to, from) {
+			return true;
+		}
+		let (to_ty, from_ty) = (self.get(to), self.get(from));
+		match (to_ty, from_ty) {
+			// Enums only accept values of the same enum.
+			(&Ty::Enum(_), _) => false,
+			(&Ty::Array(..), _) | (_, &Ty::Array(..)) => false,
+			(&Ty::Struct(ref t), _) if !t.packed => false,
+			(_, &Ty::Struct(ref t)) if !t.packed => false,
+			// A handle to a class may be assigned to a variable of one of the
+			// classes it derives from. IEEE 1800-2009 8.15
+			(&Ty::Class(_), &Ty::Class(_)) => self.derives_from(from, to),
+			// Integral and real values are converted implicitly.
+			_ if (self.is_integral(to) || to_ty.is_real()) && (self.is_integral(from) || from_ty.is_real()) => true,
+			_ => false,
+		}
+	}
+
+	/// Check whether a class is derived from another class, directly or
+	/// through its base classes.
+	pub fn derives_from(&self, class: TyId, base: TyId) -> bool {
+		let mut current = class;
+		for _ in 0..MAX_CLASS_DEPTH {
+			current = match *self.get(current) {
+				Ty::Class(ClassTy { base: Some(b), .. }) => b,
+				_ => return false,
+			};
+			if current == base {
+				return true;
+			}
+		}
+		false
+	}
+
+	/// Check whether a value of type `from` may be cast to type `to`, as
+	/// defined in IEEE 1800-2009 sections 6.22.4 and 6.24.
+	pub fn is_cast_compatible(&self, to: TyId, from: TyId) -> bool {
+		if self.is_assignment_compatible(to, from) {
+			return true;
+		}
+		let (to_ty, from_ty) = (self.get(to), self.get(from));
+		match (to_ty, from_ty) {
+			// Integral and real values may be cast to enums.
+			(&Ty::Enum(_), _) => self.is_integral(from) || from_ty.is_real(),
+			// Strings convert to and from integral values.
+			(&Ty::String, _) => self.is_integral(from),
+			(_, &Ty::String) => self.is_integral(to),
+			// Bit-stream casts between types of the same size.
+			_ if self.is_bit_stream(to) && self.is_bit_stream(from) => self.bits(to) == self.bits(from),
+			_ => false,
+		}
+	}
+
+	/// Check whether a type is a bit-stream type, i.e. can be serialized into
+	/// a stream of bits for a cast.
+	fn is_bit_stream(&self, id: TyId) -> bool {
+		match *self.get(id) {
+			Ty::Int(_) | Ty::Enum(_) => true,
+			Ty::Struct(ref t) => t.members.iter().all(|&(_, ty)| self.is_bit_stream(ty)),
+			Ty::Array(elem, _) => self.is_bit_stream(elem),
+			_ => false,
+		}
+	}
+
+	/// Create an object that formats a type for display.
+	pub fn display(&self, id: TyId) -> DisplayTy {
+		DisplayTy {
+			tys: self,
+			id: id,
+		}
+	}
+}
+
+impl Default for TyInterner {
+	fn default() -> TyInterner {
+		TyInterner::new()
+	}
+}
+
+
+/// A type that can be displayed, as returned by `TyInterner::display`.
+pub struct DisplayTy<'a> {
+	tys: &'a TyInterner,
+	id: TyId,
+}
+
+impl<'a> fmt::Display for DisplayTy<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let tys = self.tys;
+		match *tys.get(self.id) {
+			Ty::Error => write!(f, "<error>"),
+			Ty::Void => write!(f, "void"),
+			Ty::Int(ref t) => write!(f, "{}", t),
+			Ty::Real => write!(f, "real"),
+			Ty::ShortReal => write!(f, "shortreal"),
+			Ty::String => write!(f, "string"),
+			Ty::Chandle => write!(f, "chandle"),
+			Ty::Event => write!(f, "event"),
+			Ty::Enum(ref t) => write!(f, "enum {}", t.base),
+			Ty::Struct(ref t) => {
+				write!(f, "{}", if t.union { "union" } else { "struct" })?;
+				if t.packed {
+					write!(f, " packed")?;
+				}
+				write!(f, " {{")?;
+				for &(name, ty) in &t.members {
+					write!(f, " {} {};", tys.display(ty), name)?;
+				}
+				write!(f, " }}")
+			}
+			Ty::Class(ref t) => {
+				write!(f, "{}", t.name)?;
+				if !t.args.is_empty() {
+					write!(f, " #(")?;
+					for (i, arg) in t.args.iter().enumerate() {
+						if i > 0 {
+							write!(f, ", ")?;
+						}
+						match *arg {
+							ClassArg::Value(v) => write!(f, "{}", v)?,
+							ClassArg::Type(ty) => write!(f, "{}", tys.display(ty))?,
+						}
+					}
+					write!(f, ")")?;
+				}
+				Ok(())
+			}
+			Ty::Array(elem, ref range) => write!(f, "unpacked array [{}:{}] of {}", range.left, range.right, tys.display(elem)),
+		}
+	}
+}
+
+impl fmt::Display for IntTy {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", if self.four_state { "logic" } else { "bit" })?;
+		if self.signed {
+			write!(f, " signed")?;
+		}
+		for d in &self.dims {
+			write!(f, " [{}:{}]", d.left, d.right)?;
+		}
+		Ok(())
+	}
+}
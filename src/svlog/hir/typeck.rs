@@ -0,0 +1,1424 @@
+// Copyright (c) 2017 Fabian Schuiki
+
+//! This module implements type checking of the elaborated design. Every
+//! expression is assigned a type, and the width and signedness of operands
+//! are propagated as described in IEEE 1800-2009 section 11.6. Assignments
+//! that lose or extend bits are reported as warnings, assignments between
+//! incompatible types and to things that cannot be assigned are errors.
+
+use std;
+use std::collections::{BTreeMap, HashMap};
+use moore_common::Session;
+use moore_common::errors::*;
+use moore_svlog_syntax::ast;
+use moore_svlog_syntax::resolve::NameResolution;
+use moore_svlog_syntax::token::{Lit, Op};
+use nodes::*;
+use ty::*;
+use lower::consteval::{self, ConstEnv};
+use lower::expr::lower_expr;
+
+
+type Result<T> = std::result::Result<T, ()>;
+
+/// The types assigned to the expressions of a scope, keyed by their node ID.
+pub type TypeTable = BTreeMap<NodeId, TyId>;
+
+
+/// Assign types to all expressions in the elaborated design and check
+/// assignments for compatibility. The types are stored in the `types` table
+/// of each instance scope, and interned in the `tys` of the root.
+pub fn typeck(session: &Session, nameres: &NameResolution, root: &mut Root) -> Result<()> {
+	let mut severity = Severity::Note;
+	for diag in check_types(session, nameres, root) {
+		severity = std::cmp::max(severity, diag.get_severity());
+		println!("{}", diag);
+	}
+	if severity >= Severity::Error {
+		Err(())
+	} else {
+		Ok(())
+	}
+}
+
+/// Assign types to all expressions in the elaborated design, like `typeck`.
+/// The diagnostics are returned rather than emitted.
+pub fn check_types(session: &Session, nameres: &NameResolution, root: &mut Root) -> Vec<DiagBuilder2> {
+	let Root { ref mods, ref intfs, ref pkgs, ref mut design, ref mut tys, .. } = *root;
+	let mut tc = TypeChecker {
+		session: session,
+		nameres: nameres,
+		diags: Vec::new(),
+		mods: mods,
+		intfs: intfs,
+		tys: tys,
+		global: TypeEnv::default(),
+		specializing: Vec::new(),
+		in_concat: false,
+		self_types: HashMap::new(),
+	};
+
+	// Declarations in packages are visible everywhere. Packages may only
+	// refer to the packages declared before them.
+	let mut pkgs: Vec<_> = pkgs.values().collect();
+	pkgs.sort_by_key(|p| (p.span.source.0, p.span.begin));
+	let mut global = TypeEnv::default();
+	for pkg in pkgs {
+		tc.declare_params(&pkg.body.params, &mut global);
+		tc.declare_body(&pkg.body, &mut global);
+	}
+	tc.global = global;
+
+	tc.check_instance(design);
+	tc.diags
+}
+
+
+#[allow(dead_code)]
+struct TypeChecker<'a> {
+	session: &'a Session,
+	nameres: &'a NameResolution,
+	diags: Vec<DiagBuilder2>,
+	mods: &'a HashMap<NodeId, Module>,
+	intfs: &'a HashMap<NodeId, Interface>,
+	tys: &'a mut TyInterner,
+	/// The declarations visible in every scope.
+	global: TypeEnv,
+	/// The classes currently being specialized, and whether their base class
+	/// is being mapped. Used to detect classes that refer to themselves.
+	specializing: Vec<(NodeId, bool)>,
+	/// Whether the expression being typed is an operand of a concatenation.
+	in_concat: bool,
+	/// The self-determined types of the expressions typed so far, before the
+	/// width of their context has been applied. Used for width warnings.
+	self_types: HashMap<NodeId, TyId>,
+}
+
+/// The declarations visible at a point in the design.
+#[derive(Clone, Default)]
+struct TypeEnv {
+	consts: ConstEnv,
+	/// The types of the visible variables, nets, ports, parameters, genvars,
+	/// and enum variants.
+	decls: HashMap<NodeId, TyId>,
+	/// The visible typedefs and type parameters.
+	types: HashMap<NodeId, TyId>,
+	/// The return types of the visible functions and tasks.
+	subroutines: HashMap<NodeId, TyId>,
+	/// The name, parameters, and base class of the visible classes.
+	classes: HashMap<NodeId, (Name, Vec<ast::ParamDecl>, Option<ast::Type>)>,
+	/// The declarations that cannot be assigned to, together with a
+	/// description of what they are.
+	constants: HashMap<NodeId, &'static str>,
+}
+
+impl<'a> TypeChecker<'a> {
+	fn add_diag(&mut self, diag: DiagBuilder2) {
+		self.diags.push(diag);
+	}
+
+	fn intern(&mut self, ty: Ty) -> TyId {
+		self.tys.intern(ty)
+	}
+
+	fn int_ty(&mut self, width: usize, signed: bool, four_state: bool) -> TyId {
+		self.tys.intern(Ty::Int(IntTy::new(width, signed, four_state)))
+	}
+
+	fn four_state(&self, ty: TyId) -> bool {
+		self.tys.as_int(ty).map(|t| t.four_state).unwrap_or(false)
+	}
+
+	fn check_instance(&mut self, inst: &mut Instance) {
+		let mut env = self.global.clone();
+		let (mods, intfs) = (self.mods, self.intfs);
+		let (ports, params) = if let Some(m) = mods.get(&inst.target) {
+			(&m.ports, &m.params)
+		} else if let Some(i) = intfs.get(&inst.target) {
+			(&i.ports, &i.params)
+		} else {
+			return;
+		};
+		for (&id, &value) in &inst.scope.values {
+			env.consts.values.insert(id, value);
+		}
+		// The ports may refer to parameters declared in the header as well
+		// as the body of the module.
+		self.declare_params(params, &mut env);
+		self.declare_params(&inst.scope.body.params, &mut env);
+		for slice in ports.iter().flat_map(|p| p.slices.iter()) {
+			let ty = match slice.ty {
+				Some(ref ty) => ty,
+				None => continue,
+			};
+			if let Some((span, ref packed, ref unpacked)) = slice.data_dims {
+				self.check_port_dims(slice, span, packed, unpacked, &env);
+			}
+			let ty = self.map_type(ty, &mut env);
+			let ty = self.map_unpacked_dims(ty, &slice.dims, &env);
+			env.decls.insert(slice.id, ty);
+		}
+		self.check_scope_body(&mut inst.scope, env);
+	}
+
+	/// Check that the dimensions of a non-ANSI port declaration match the
+	/// ones of the accompanying variable or net declaration.
+	fn check_port_dims(&mut self, slice: &PortSlice, span: Span, packed: &[ast::TypeDim], unpacked: &[ast::TypeDim], env: &TypeEnv) {
+		let port_packed = slice.ty.as_ref().and_then(|ty| self.map_packed_dims(&ty.dims, env));
+		let data_packed = self.map_packed_dims(packed, env);
+		let port_unpacked = self.map_unpacked_ranges(&slice.dims, env);
+		let data_unpacked = self.map_unpacked_ranges(unpacked, env);
+		let mismatch = |a: Option<Vec<Range>>, b: Option<Vec<Range>>| match (a, b) {
+			(Some(a), Some(b)) => !b.is_empty() && a != b,
+			_ => false,
+		};
+		if mismatch(port_packed, data_packed) || mismatch(port_unpacked, data_unpacked) {
+			self.add_diag(DiagBuilder2::error(format!("dimensions of port `{}` do not match its variable/net declaration", slice.name))
+				.span(slice.span)
+				.add_note("variable/net declaration was here:")
+				.span(span));
+		}
+	}
+
+	fn check_scope(&mut self, scope: &mut InstScope, parent: &TypeEnv) {
+		let mut env = parent.clone();
+		for (&id, &value) in &scope.values {
+			env.consts.values.insert(id, value);
+		}
+		self.declare_params(&scope.body.params, &mut env);
+		self.check_scope_body(scope, env);
+	}
+
+	fn check_scope_body(&mut self, scope: &mut InstScope, mut env: TypeEnv) {
+		let mut types = TypeTable::new();
+		self.declare_body(&scope.body, &mut env);
+		self.check_body(&scope.body, &env, &mut types);
+		scope.types = types;
+		for sub in &mut scope.scopes {
+			self.check_scope(sub, &env);
+		}
+		for inst in &mut scope.insts {
+			self.check_instance(inst);
+		}
+	}
+
+	/// Declare parameters. Type parameters are declared before value
+	/// parameters, since the latter may refer to the former.
+	fn declare_params(&mut self, params: &[ast::ParamDecl], env: &mut TypeEnv) {
+		for param in params {
+			if let ast::ParamKind::Type(ref decls) = param.kind {
+				for decl in decls {
+					let ty = match decl.ty {
+						Some(ref ty) => self.map_type(ty, env),
+						None => ERROR_TY,
+					};
+					env.types.insert(decl.name.id, ty);
+				}
+			}
+		}
+		for param in params {
+			if let ast::ParamKind::Value(ref decls) = param.kind {
+				for decl in decls {
+					if !env.consts.values.contains_key(&decl.name.id) {
+						if let Some(ref expr) = decl.expr {
+							if let Some(v) = self.const_eval(expr, env) {
+								env.consts.values.insert(decl.name.id, v);
+							}
+						}
+					}
+					let ty = self.map_param_type(&decl.ty, env);
+					let ty = self.map_unpacked_dims(ty, &decl.dims, env);
+					env.decls.insert(decl.name.id, ty);
+					env.constants.insert(decl.name.id, "parameter");
+				}
+			}
+		}
+	}
+
+	fn map_param_type(&mut self, ty: &ast::Type, env: &mut TypeEnv) -> TyId {
+		match ty.data {
+			// Parameters without a type take on the type of their value. Only
+			// integers are supported as values.
+			ast::ImplicitType if ty.dims.is_empty() => {
+				self.int_ty(32, ty.sign != ast::TypeSign::Unsigned, false)
+			}
+			_ => self.map_type(ty, env),
+		}
+	}
+
+	/// Declare the genvars, typedefs, classes, variables, and nets of a body.
+	fn declare_body(&mut self, body: &HierarchyBody, env: &mut TypeEnv) {
+		for decl in &body.genvars {
+			let ty = self.intern(Ty::int());
+			env.decls.insert(decl.id, ty);
+			env.consts.genvars.insert(decl.id);
+			env.constants.insert(decl.id, "genvar");
+		}
+		for class in &body.classes {
+			let base = class.extends.as_ref().map(|&(ref ty, _)| ty.clone());
+			env.classes.insert(class.name.id, (class.name.name, class.params.clone(), base));
+		}
+		for td in &body.typedefs {
+			let ty = self.map_type(&td.ty, env);
+			let ty = self.map_unpacked_dims(ty, &td.dims, env);
+			env.types.insert(td.name.id, ty);
+		}
+		for decl in &body.vars {
+			self.declare_var(decl, env);
+		}
+		for sub in &body.subroutines {
+			let ty = self.map_return_type(sub.kind, sub.retty.as_ref(), env);
+			env.subroutines.insert(sub.id, ty);
+		}
+		for decl in &body.dpi_decls {
+			if let ast::DpiDecl::Import { ref prototype, .. } = *decl {
+				let ty = self.map_return_type(prototype.kind, prototype.retty.as_ref(), env);
+				env.subroutines.insert(prototype.name.id, ty);
+			}
+		}
+		for decl in &body.nets {
+			let ty = self.map_type(&decl.ty, env);
+			for name in &decl.names {
+				let ty = self.map_unpacked_dims(ty, &name.dims, env);
+				env.decls.insert(name.id, ty);
+			}
+		}
+	}
+
+	/// Map the return type of a function or task. Tasks return nothing, and
+	/// functions without a return type return a single bit.
+	fn map_return_type(&mut self, kind: ast::SubroutineKind, retty: Option<&ast::Type>, env: &mut TypeEnv) -> TyId {
+		match (kind, retty) {
+			(ast::SubroutineKind::Task, _) => self.intern(Ty::Void),
+			(ast::SubroutineKind::Func, Some(ty)) => self.map_type(ty, env),
+			(ast::SubroutineKind::Func, None) => self.intern(Ty::bit(true)),
+		}
+	}
+
+	fn declare_var(&mut self, decl: &ast::VarDecl, env: &mut TypeEnv) {
+		let ty = self.map_type(&decl.ty, env);
+		for name in &decl.names {
+			let ty = self.map_unpacked_dims(ty, &name.dims, env);
+			env.decls.insert(name.id, ty);
+			if decl.konst {
+				env.constants.insert(name.id, "constant");
+			}
+		}
+	}
+
+	/// Map an AST type to a semantic type. Typedefs and type parameters are
+	/// replaced by the type they refer to, and dimensions are evaluated.
+	fn map_type(&mut self, ty: &ast::Type, env: &mut TypeEnv) -> TyId {
+		let packed = match self.map_packed_dims(&ty.dims, env) {
+			Some(d) => d,
+			None => return ERROR_TY,
+		};
+		let atom_signed = |signed_default| match ty.sign {
+			ast::TypeSign::None => signed_default,
+			s => s == ast::TypeSign::Signed,
+		};
+		let base = match ty.data {
+			ast::ImplicitType | ast::LogicType | ast::RegType | ast::BitType => {
+				return self.intern(Ty::Int(IntTy {
+					signed: ty.sign == ast::TypeSign::Signed,
+					four_state: ty.data != ast::BitType,
+					dims: packed,
+				}));
+			}
+			ast::ByteType => self.int_ty(8, atom_signed(true), false),
+			ast::ShortIntType => self.int_ty(16, atom_signed(true), false),
+			ast::IntType => self.int_ty(32, atom_signed(true), false),
+			ast::LongIntType => self.int_ty(64, atom_signed(true), false),
+			ast::TimeType => self.int_ty(64, atom_signed(false), true),
+			ast::RealType | ast::RealtimeType => self.intern(Ty::Real),
+			ast::ShortRealType => self.intern(Ty::ShortReal),
+			ast::StringType => self.intern(Ty::String),
+			ast::ChandleType => self.intern(Ty::Chandle),
+			ast::EventType => self.intern(Ty::Event),
+			ast::VoidType => self.intern(Ty::Void),
+			ast::NamedType(ref ident) => self.map_named_type(ident, &[], ty.span, env),
+			ast::ScopedType { member: false, ref name, .. } => self.map_named_type(name, &[], ty.span, env),
+			ast::SpecializedType(ref inner, ref params) => {
+				match inner.data {
+					ast::NamedType(ref ident) |
+					ast::ScopedType { member: false, name: ref ident, .. } => {
+						self.map_named_type(ident, params, ty.span, env)
+					}
+					_ => ERROR_TY,
+				}
+			}
+			ast::EnumType(ref base, ref names) => {
+				let base = match *base {
+					Some(ref base) => {
+						let base_ty = self.map_type(base, env);
+						match self.tys.as_int(base_ty) {
+							Some(t) => t,
+							None => {
+								if base_ty != ERROR_TY {
+									let msg = format!("enum base type `{}` is not integral", self.tys.display(base_ty));
+									self.add_diag(DiagBuilder2::error(msg).span(base.span));
+								}
+								return ERROR_TY;
+							}
+						}
+					}
+					None => IntTy::new(32, true, false),
+				};
+				let enum_ty = self.intern(Ty::Enum(EnumTy {
+					span: ty.span,
+					base: base,
+				}));
+				for name in names {
+					env.decls.insert(name.name.id, enum_ty);
+					env.constants.insert(name.name.id, "enum variant");
+				}
+				enum_ty
+			}
+			ast::StructType { ref kind, packed, ref signing, ref members } => {
+				let mut fields = Vec::new();
+				for member in members {
+					let ty = self.map_type(&member.ty, env);
+					for name in &member.names {
+						fields.push((name.name, self.map_unpacked_dims(ty, &name.dims, env)));
+					}
+				}
+				self.intern(Ty::Struct(StructTy {
+					span: ty.span,
+					union: *kind != ast::StructKind::Struct,
+					packed: packed,
+					signed: *signing == ast::TypeSign::Signed,
+					members: fields,
+				}))
+			}
+			// Modports, virtual interfaces, and mailboxes are not yet
+			// supported.
+			_ => ERROR_TY,
+		};
+
+		// Packed dimensions on an integral type form a vector of that type.
+		if packed.is_empty() {
+			return base;
+		}
+		match self.tys.as_int(base) {
+			Some(mut int) => {
+				int.dims = packed.into_iter().chain(int.dims.into_iter()).collect();
+				self.intern(Ty::Int(int))
+			}
+			None => {
+				if base != ERROR_TY {
+					let msg = format!("packed dimensions cannot be applied to `{}`", self.tys.display(base));
+					self.add_diag(DiagBuilder2::error(msg).span(ty.span));
+				}
+				ERROR_TY
+			}
+		}
+	}
+
+	/// Map a reference to a typedef, type parameter, or class, optionally
+	/// with a list of parameter assignments.
+	fn map_named_type(&mut self, ident: &ast::Identifier, params: &[ast::ParamAssignment], span: Span, env: &TypeEnv) -> TyId {
+		let id = match self.nameres.get(&ident.id) {
+			Some(&id) => id,
+			None => return ERROR_TY,
+		};
+		if let Some(&(name, ref decls, ref base)) = env.classes.get(&id) {
+			if let Some(&(_, in_base)) = self.specializing.iter().find(|&&(c, _)| c == id) {
+				let msg = if in_base {
+					format!("class `{}` inherits from itself", name)
+				} else {
+					format!("class `{}` refers to itself in its parameters", name)
+				};
+				self.add_diag(DiagBuilder2::error(msg).span(span));
+				return ERROR_TY;
+			}
+			self.specializing.push((id, false));
+			let ty = self.specialize_class(id, name, decls, base.as_ref(), params, env);
+			self.specializing.pop();
+			return ty;
+		}
+		if !params.is_empty() {
+			self.add_diag(DiagBuilder2::error(format!("`{}` is not a class and cannot be specialized", ident.name))
+				.span(span));
+			return ERROR_TY;
+		}
+		env.types.get(&id).cloned().unwrap_or(ERROR_TY)
+	}
+
+	/// Determine the specialization of a class, given the assignments to its
+	/// parameters. Parameters that are not assigned take on their default.
+	fn specialize_class(&mut self, id: NodeId, name: Name, params: &[ast::ParamDecl], base: Option<&ast::Type>, assignments: &[ast::ParamAssignment], env: &TypeEnv) -> TyId {
+		let mut decls = Vec::new();
+		for param in params {
+			match param.kind {
+				ast::ParamKind::Type(ref ds) => decls.extend(ds.iter().map(|d| (&d.name, Err(d)))),
+				ast::ParamKind::Value(ref ds) => decls.extend(ds.iter().map(|d| (&d.name, Ok(d)))),
+			}
+		}
+
+		// Match the assignments to the parameters.
+		let mut assigned = HashMap::new();
+		let mut failed = false;
+		for (index, assignment) in assignments.iter().enumerate() {
+			let decl = match assignment.name {
+				Some(ref n) => decls.iter().position(|&(d, _)| d.name == n.name),
+				None if index < decls.len() => Some(index),
+				None => None,
+			};
+			match decl {
+				Some(i) => { assigned.insert(i, assignment); }
+				None => {
+					let msg = match assignment.name {
+						Some(ref n) => format!("`{}` has no parameter `{}`", name, n.name),
+						None => format!("`{}` has only {} parameters", name, decls.len()),
+					};
+					self.add_diag(DiagBuilder2::error(msg).span(assignment.span));
+					failed = true;
+				}
+			}
+		}
+
+		// Determine the value of each parameter. Defaults may refer to the
+		// parameters declared before them.
+		let mut local = env.clone();
+		let mut args = Vec::new();
+		for (index, &(ident, ref decl)) in decls.iter().enumerate() {
+			let assignment = assigned.get(&index).map(|a| &a.expr);
+			match *decl {
+				Ok(decl) => {
+					let value = match (assignment, &decl.expr) {
+						(Some(&ast::TypeOrExpr::Expr(ref expr)), _) | (None, &Some(ref expr)) => {
+							self.const_eval(expr, &local)
+						}
+						(Some(&ast::TypeOrExpr::Type(ref ty)), _) => {
+							match consteval::const_eval_type_as_expr(self.nameres, ty, &local.consts) {
+								Ok(v) => Some(v),
+								Err(d) => {
+									self.add_diag(d);
+									None
+								}
+							}
+						}
+						(None, &None) => {
+							self.add_diag(DiagBuilder2::error(format!("parameter `{}` of `{}` has no value", ident.name, name))
+								.span(ident.span));
+							None
+						}
+					};
+					match value {
+						Some(v) => {
+							local.consts.values.insert(ident.id, v);
+							args.push(ClassArg::Value(v));
+						}
+						None => failed = true,
+					}
+				}
+				Err(decl) => {
+					let ty = match (assignment, &decl.ty) {
+						(Some(&ast::TypeOrExpr::Type(ref ty)), _) | (None, &Some(ref ty)) => {
+							self.map_type(ty, &mut local)
+						}
+						(Some(&ast::TypeOrExpr::Expr(ref expr)), _) => {
+							self.add_diag(DiagBuilder2::error(format!("parameter `{}` of `{}` expects a type", ident.name, name))
+								.span(expr.span));
+							ERROR_TY
+						}
+						(None, &None) => {
+							self.add_diag(DiagBuilder2::error(format!("parameter `{}` of `{}` has no type", ident.name, name))
+								.span(ident.span));
+							ERROR_TY
+						}
+					};
+					local.types.insert(ident.id, ty);
+					args.push(ClassArg::Type(ty));
+				}
+			}
+		}
+
+		if failed {
+			return ERROR_TY;
+		}
+
+		// The base class may refer to the parameters of the class.
+		self.specializing.last_mut().unwrap().1 = true;
+		let base = match base.map(|ty| self.map_type(ty, &mut local)) {
+			Some(ERROR_TY) => return ERROR_TY,
+			x => x,
+		};
+		self.intern(Ty::Class(ClassTy {
+			id: id,
+			name: name,
+			args: args,
+			base: base,
+		}))
+	}
+
+	fn map_packed_dims(&mut self, dims: &[ast::TypeDim], env: &TypeEnv) -> Option<Vec<Range>> {
+		let mut ranges = Vec::new();
+		for dim in dims {
+			match *dim {
+				ast::TypeDim::Range(ref lhs, ref rhs) => ranges.push(self.map_range(lhs, rhs, env)?),
+				_ => {
+					self.add_diag(DiagBuilder2::error("packed dimensions must be of the form `[a:b]`")
+						.span(dim_span(dim)));
+					return None;
+				}
+			}
+		}
+		Some(ranges)
+	}
+
+	/// Evaluate the ranges of unpacked dimensions. Dynamic arrays, queues,
+	/// and associative arrays are not yet supported and yield `None`.
+	fn map_unpacked_ranges(&mut self, dims: &[ast::TypeDim], env: &TypeEnv) -> Option<Vec<Range>> {
+		let mut ranges = Vec::new();
+		for dim in dims {
+			ranges.push(match *dim {
+				ast::TypeDim::Range(ref lhs, ref rhs) => self.map_range(lhs, rhs, env)?,
+				// An unpacked dimension `[N]` is equivalent to `[0:N-1]`.
+				ast::TypeDim::Expr(ref size) => Range {
+					left: 0,
+					right: self.const_eval(size, env)?.as_int() - 1,
+				},
+				_ => return None,
+			});
+		}
+		Some(ranges)
+	}
+
+	/// Wrap a type in unpacked array dimensions.
+	fn map_unpacked_dims(&mut self, ty: TyId, dims: &[ast::TypeDim], env: &TypeEnv) -> TyId {
+		match self.map_unpacked_ranges(dims, env) {
+			Some(ranges) => ranges.into_iter().rev().fold(ty, |ty, r| self.tys.intern(Ty::Array(ty, r))),
+			None => ERROR_TY,
+		}
+	}
+
+	fn map_range(&mut self, lhs: &ast::Expr, rhs: &ast::Expr, env: &TypeEnv) -> Option<Range> {
+		let left = self.const_eval(lhs, env)?;
+		let right = self.const_eval(rhs, env)?;
+		Some(Range {
+			left: left.as_int(),
+			right: right.as_int(),
+		})
+	}
+
+	fn const_eval(&mut self, expr: &ast::Expr, env: &TypeEnv) -> Option<ConstValue> {
+		match consteval::const_eval(self.nameres, expr, &env.consts) {
+			Ok(v) => Some(v),
+			Err(d) => {
+				self.add_diag(d);
+				None
+			}
+		}
+	}
+
+
+	fn check_body(&mut self, body: &HierarchyBody, env: &TypeEnv, types: &mut TypeTable) {
+		for decl in &body.vars {
+			self.check_var_init(decl, env, types);
+		}
+		for decl in &body.nets {
+			for name in &decl.names {
+				if let Some(init) = name.init.as_ref().and_then(|e| self.lower_expr(e)) {
+					let ty = env.decls.get(&name.id).cloned().unwrap_or(ERROR_TY);
+					self.check_assign_to(ty, &init, env, types);
+				}
+			}
+		}
+		for assign in &body.assigns {
+			self.check_assign(&assign.lhs, &assign.rhs, env, types);
+		}
+		for prok in &body.procs {
+			self.check_stmt(&prok.stmt, env, types);
+		}
+		for sub in &body.subroutines {
+			self.check_subroutine(sub, env, types);
+		}
+	}
+
+	/// Lower the initial value of a declaration that is kept in its AST form.
+	fn lower_expr(&mut self, expr: &ast::Expr) -> Option<Expr> {
+		match lower_expr(self.nameres, expr) {
+			Ok(e) => Some(e),
+			Err(d) => {
+				self.add_diag(d);
+				None
+			}
+		}
+	}
+
+	fn check_var_init(&mut self, decl: &ast::VarDecl, env: &TypeEnv, types: &mut TypeTable) {
+		for name in &decl.names {
+			if let Some(init) = name.init.as_ref().and_then(|e| self.lower_expr(e)) {
+				let ty = env.decls.get(&name.id).cloned().unwrap_or(ERROR_TY);
+				self.check_assign_to(ty, &init, env, types);
+			}
+		}
+	}
+
+	/// Declare a variable within a procedure or subroutine and check its
+	/// initial value.
+	fn check_local_var(&mut self, decl: &VarDecl, env: &mut TypeEnv, types: &mut TypeTable) {
+		let ty = self.map_type(&decl.ty, env);
+		for name in &decl.names {
+			let ty = self.map_unpacked_dims(ty, &name.dims, env);
+			env.decls.insert(name.id, ty);
+			if decl.konst {
+				env.constants.insert(name.id, "constant");
+			}
+		}
+		for name in &decl.names {
+			if let Some(ref init) = name.init {
+				let ty = env.decls.get(&name.id).cloned().unwrap_or(ERROR_TY);
+				self.check_assign_to(ty, init, env, types);
+			}
+		}
+	}
+
+	/// Check the body of a function or task. The arguments, and the return
+	/// value under the name of the function, are visible within.
+	fn check_subroutine(&mut self, sub: &Subroutine, env: &TypeEnv, types: &mut TypeTable) {
+		let mut env = env.clone();
+		if let Some(ref retty) = sub.retty {
+			let ty = self.map_type(retty, &mut env);
+			env.decls.insert(sub.id, ty);
+		}
+		for arg in &sub.args {
+			let ty = self.map_type(&arg.ty, &mut env);
+			let ty = self.map_unpacked_dims(ty, &arg.dims, &env);
+			if let Some(ref default) = arg.default {
+				self.check_assign_to(ty, default, &env, types);
+			}
+			env.decls.insert(arg.id, ty);
+		}
+		self.check_stmts(&sub.stmts, &env, types);
+	}
+
+	fn check_stmt(&mut self, stmt: &Stmt, env: &TypeEnv, types: &mut TypeTable) {
+		match stmt.kind {
+			StmtKind::Block { ref stmts, .. } => {
+				self.check_stmts(stmts, env, types);
+			}
+			StmtKind::If { ref cond, ref main_stmt, ref else_stmt, .. } => {
+				self.type_expr(cond, env, types);
+				self.check_stmt(main_stmt, env, types);
+				if let Some(ref s) = *else_stmt {
+					self.check_stmt(s, env, types);
+				}
+			}
+			StmtKind::Assign { ref kind, ref lhs, ref rhs } => {
+				if let AssignKind::Nonblocking { ref delay, ref cycle } = *kind {
+					for e in delay.iter().chain(cycle.iter()) {
+						self.type_expr(e, env, types);
+					}
+				}
+				self.check_assign(lhs, rhs, env, types);
+			}
+			StmtKind::Timed(ref timing, ref stmt) => {
+				match *timing {
+					Timing::Delay(ref e) | Timing::Cycle(ref e) => {
+						self.type_expr(e, env, types);
+					}
+					Timing::Event(ref events) => {
+						for event in events {
+							self.type_expr(&event.expr, env, types);
+							if let Some(ref e) = event.iff {
+								self.type_expr(e, env, types);
+							}
+						}
+					}
+				}
+				self.check_stmt(stmt, env, types);
+			}
+			StmtKind::Forever(ref stmt) |
+			StmtKind::Wait(_, ref stmt) => {
+				self.check_stmt(stmt, env, types);
+			}
+			StmtKind::Case { ref expr, ref items, .. } => {
+				// The case expression and all item expressions are sized to
+				// the largest among them.
+				let mut exprs = vec![expr];
+				for item in items {
+					exprs.extend(item.exprs.iter());
+				}
+				let tys: Vec<_> = exprs.iter().map(|e| self.type_expr(e, env, types)).collect();
+				let width = tys.iter().filter_map(|&t| self.tys.width(t)).max();
+				let signed = tys.iter().all(|&t| self.tys.as_int(t).map(|t| t.signed).unwrap_or(false));
+				if let Some(width) = width {
+					for e in exprs {
+						self.apply_context(e, width, signed, types);
+					}
+				}
+				for item in items {
+					self.check_stmt(&item.stmt, env, types);
+				}
+			}
+			StmtKind::Repeat(ref expr, ref stmt) |
+			StmtKind::While(ref expr, ref stmt) |
+			StmtKind::DoWhile(ref stmt, ref expr) => {
+				self.type_expr(expr, env, types);
+				self.check_stmt(stmt, env, types);
+			}
+			StmtKind::For(ref init, ref cond, ref step, ref body) => {
+				let mut env = env.clone();
+				self.check_local_stmt(init, &mut env, types);
+				self.type_expr(cond, &env, types);
+				self.type_expr(step, &env, types);
+				self.check_stmt(body, &env, types);
+			}
+			StmtKind::Foreach(ref expr, _, ref stmt) => {
+				self.type_expr(expr, env, types);
+				self.check_stmt(stmt, env, types);
+			}
+			StmtKind::Expr(ref expr) => {
+				self.type_expr(expr, env, types);
+			}
+			StmtKind::VarDecl(ref decl) => {
+				// Declarations outside of a block do not introduce anything
+				// that later statements could refer to.
+				let mut env = env.clone();
+				self.check_local_var(decl, &mut env, types);
+			}
+			StmtKind::Return(Some(ref expr)) => {
+				self.type_expr(expr, env, types);
+			}
+			_ => (),
+		}
+	}
+
+	/// Check a list of statements that may declare variables visible to the
+	/// statements that follow.
+	fn check_stmts(&mut self, stmts: &[Stmt], env: &TypeEnv, types: &mut TypeTable) {
+		let mut env = env.clone();
+		for stmt in stmts {
+			self.check_local_stmt(stmt, &mut env, types);
+		}
+	}
+
+	fn check_local_stmt(&mut self, stmt: &Stmt, env: &mut TypeEnv, types: &mut TypeTable) {
+		if let StmtKind::VarDecl(ref decl) = stmt.kind {
+			self.check_local_var(decl, env, types);
+		} else {
+			self.check_stmt(stmt, env, types);
+		}
+	}
+
+
+	/// Check an assignment of `rhs` to `lhs`.
+	fn check_assign(&mut self, lhs: &LValue, rhs: &Expr, env: &TypeEnv, types: &mut TypeTable) -> TyId {
+		let ty = self.type_lvalue(lhs, env, types);
+		self.check_assign_to(ty, rhs, env, types);
+		ty
+	}
+
+	/// Check that `rhs` can be assigned to a target of type `ty`, and apply
+	/// the width of the target to the expression.
+	fn check_assign_to(&mut self, ty: TyId, rhs: &Expr, env: &TypeEnv, types: &mut TypeTable) {
+		let rhs_ty = self.type_expr(rhs, env, types);
+		if ty == ERROR_TY || rhs_ty == ERROR_TY {
+			return;
+		}
+		// String literals may be assigned to strings.
+		let string_lit = *self.tys.get(ty) == Ty::String && is_string_literal(rhs);
+		if !string_lit && !self.tys.is_assignment_compatible(ty, rhs_ty) {
+			let msg = format!("value of type `{}` cannot be assigned to `{}`", self.tys.display(rhs_ty), self.tys.display(ty));
+			self.add_diag(DiagBuilder2::error(msg).span(rhs.span));
+			return;
+		}
+		let (lhs_int, rhs_int) = match (self.tys.as_int(ty), self.tys.as_int(rhs_ty)) {
+			(Some(l), Some(r)) => (l, r),
+			_ => return,
+		};
+		let lw = lhs_int.width();
+		if let Some(rw) = self.lint_width(rhs) {
+			let msg = if rw > lw {
+				Some(format!("{}-bit value is truncated to {} bits", rw, lw))
+			} else if rw < lw {
+				Some(format!("{}-bit value is extended to {} bits", rw, lw))
+			} else {
+				None
+			};
+			if let Some(msg) = msg {
+				let note = format!("target has type `{}`", self.tys.display(ty));
+				self.add_diag(DiagBuilder2::warning(msg).span(rhs.span).add_note(note));
+			}
+		}
+		self.apply_context(rhs, std::cmp::max(lw, rhs_int.width()), rhs_int.signed, types);
+	}
+
+	/// Determine the type of an lvalue and record it in the type table. Names
+	/// of parameters, genvars, constants, and enum variants cannot be assigned
+	/// to.
+	fn type_lvalue(&mut self, lv: &LValue, env: &TypeEnv, types: &mut TypeTable) -> TyId {
+		let ty = self.type_lvalue_inner(lv, env, types);
+		types.insert(lv.id, ty);
+		ty
+	}
+
+	fn type_lvalue_inner(&mut self, lv: &LValue, env: &TypeEnv, types: &mut TypeTable) -> TyId {
+		match lv.kind {
+			LValueKind::Ident(ref ident) => {
+				self.check_assignable(ident, lv.span, env);
+				self.type_ident(ident, env)
+			}
+			LValueKind::Select(ref base, ref sel) => {
+				let base = self.type_lvalue(base, env, types);
+				self.type_select(lv.span, base, sel, env, types)
+			}
+			LValueKind::Member(ref base, ref name) => {
+				if let ExprKind::Ident(ref ident) = base.kind {
+					self.check_assignable(ident, base.span, env);
+				}
+				let base = self.type_expr(base, env, types);
+				self.type_member(base, name)
+			}
+			LValueKind::Concat(ref lvs) => {
+				let mut width = 0;
+				let mut four_state = false;
+				let mut failed = false;
+				for lv in lvs {
+					let ty = self.type_lvalue(lv, env, types);
+					match self.tys.as_int(ty) {
+						Some(t) => {
+							width += t.width();
+							four_state |= t.four_state;
+						}
+						None => failed = true,
+					}
+				}
+				if failed || width == 0 {
+					ERROR_TY
+				} else {
+					self.int_ty(width, false, four_state)
+				}
+			}
+			// Streaming concatenations and patterns take on the type of the
+			// value assigned to them.
+			LValueKind::Stream(_, ref exprs) => {
+				for e in exprs {
+					self.type_lvalue(&e.target, env, types);
+				}
+				ERROR_TY
+			}
+			LValueKind::Pattern(ref lvs) => {
+				for lv in lvs {
+					self.type_lvalue(lv, env, types);
+				}
+				ERROR_TY
+			}
+		}
+	}
+
+	fn check_assignable(&mut self, ident: &Ident, span: Span, env: &TypeEnv) {
+		let kind = ident.def.and_then(|id| env.constants.get(&id)).cloned();
+		if let Some(kind) = kind {
+			self.add_diag(DiagBuilder2::error(format!("cannot assign to {} `{}`", kind, ident.name))
+				.span(span));
+		}
+	}
+
+
+	/// Determine the self-determined type of an expression and its operands,
+	/// and record them in the type table.
+	fn type_expr(&mut self, expr: &Expr, env: &TypeEnv, types: &mut TypeTable) -> TyId {
+		let ty = self.type_expr_inner(expr, env, types);
+		types.insert(expr.id, ty);
+		self.self_types.insert(expr.id, ty);
+		ty
+	}
+
+	fn type_expr_inner(&mut self, expr: &Expr, env: &TypeEnv, types: &mut TypeTable) -> TyId {
+		let in_concat = std::mem::replace(&mut self.in_concat, false);
+		match expr.kind {
+			ExprKind::Lit(ref lit) => {
+				let ty = type_literal(lit);
+				self.intern(ty)
+			}
+			ExprKind::Ident(ref ident) => self.type_ident(ident, env),
+			ExprKind::Unary(op, ref arg_expr) => {
+				let arg = self.type_expr(arg_expr, env, types);
+				if arg == ERROR_TY {
+					return ERROR_TY;
+				}
+				match op {
+					Op::LogicNot => {
+						let four_state = self.four_state(arg);
+						self.intern(Ty::bit(four_state))
+					}
+					Op::BitAnd | Op::BitNand | Op::BitOr | Op::BitNor | Op::BitXor | Op::BitXnor | Op::BitNxor => {
+						self.expect_integral(arg, arg_expr, op);
+						let four_state = self.four_state(arg);
+						self.intern(Ty::bit(four_state))
+					}
+					Op::BitNot => {
+						self.expect_integral(arg, arg_expr, op);
+						self.arith_ty(arg)
+					}
+					_ => self.arith_ty(arg),
+				}
+			}
+			ExprKind::IncDec { ref target, .. } => {
+				let ty = self.type_lvalue(target, env, types);
+				self.arith_ty(ty)
+			}
+			ExprKind::Binary(op, ref lhs, ref rhs) => self.type_binary(op, lhs, rhs, env, types),
+			ExprKind::Ternary(ref cond, ref true_expr, ref false_expr) => {
+				self.type_expr(cond, env, types);
+				let t = self.type_expr(true_expr, env, types);
+				let f = self.type_expr(false_expr, env, types);
+				if t == ERROR_TY || f == ERROR_TY {
+					return ERROR_TY;
+				}
+				match (self.tys.as_int(t), self.tys.as_int(f)) {
+					_ if t == f => t,
+					(Some(ti), Some(fi)) => {
+						let width = std::cmp::max(ti.width(), fi.width());
+						let signed = ti.signed && fi.signed;
+						self.apply_context(true_expr, width, signed, types);
+						self.apply_context(false_expr, width, signed, types);
+						self.int_ty(width, signed, ti.four_state || fi.four_state)
+					}
+					_ if self.tys.get(t).is_real() || self.tys.get(f).is_real() => self.intern(Ty::Real),
+					_ => {
+						let msg = format!("branches of conditional have incompatible types `{}` and `{}`", self.tys.display(t), self.tys.display(f));
+						self.add_diag(DiagBuilder2::error(msg).span(expr.span));
+						ERROR_TY
+					}
+				}
+			}
+			ExprKind::Assign(ref lhs, ref rhs) => self.check_assign(lhs, rhs, env, types),
+			ExprKind::SysCall(ref name, ref args) => {
+				let arg_tys = self.type_call_args(args, env, types);
+				self.type_system_call(&name.name.as_str(), &arg_tys)
+			}
+			ExprKind::Call(ref callee, ref args) => {
+				self.type_call_args(args, env, types);
+				// Methods called through a handle are not yet typed.
+				match callee.kind {
+					ExprKind::Ident(ref ident) => {
+						ident.def.and_then(|id| env.subroutines.get(&id)).cloned().unwrap_or(ERROR_TY)
+					}
+					_ => ERROR_TY,
+				}
+			}
+			ExprKind::Select(ref base, ref sel) => {
+				let base = self.type_expr(base, env, types);
+				self.type_select(expr.span, base, sel, env, types)
+			}
+			ExprKind::Member(ref base, ref name) => {
+				let base = self.type_expr(base, env, types);
+				self.type_member(base, name)
+			}
+			ExprKind::Concat(ref repeat, ref exprs) => {
+				let mut width = 0;
+				let mut four_state = false;
+				let mut is_string = false;
+				let mut failed = false;
+				for e in exprs {
+					self.in_concat = true;
+					let ty = self.type_expr(e, env, types);
+					if is_unsized_literal(e) {
+						self.add_diag(DiagBuilder2::error("unsized constant in concatenation")
+							.span(e.span)
+							.add_note("give the constant an explicit size, e.g. `32'd0`"));
+						failed = true;
+						continue;
+					}
+					match self.tys.as_int(ty) {
+						Some(t) => {
+							width += t.width();
+							four_state |= t.four_state;
+						}
+						None if *self.tys.get(ty) == Ty::String => is_string = true,
+						// Replications with a count of zero are ignored.
+						None if *self.tys.get(ty) == Ty::Void => (),
+						None => {
+							if ty != ERROR_TY {
+								let msg = format!("value of type `{}` cannot be concatenated", self.tys.display(ty));
+								self.add_diag(DiagBuilder2::error(msg).span(e.span));
+							}
+							failed = true;
+						}
+					}
+				}
+				// A replication with a count of zero is only allowed as the
+				// operand of another concatenation. IEEE 1800-2009 11.4.12.1
+				let mut zero = false;
+				if let Some(ref repeat) = *repeat {
+					self.type_expr(repeat, env, types);
+					match self.const_eval_expr(repeat, env).map(|n| n.as_int()) {
+						Some(n) if n < 0 => {
+							self.add_diag(DiagBuilder2::error(format!("replication count must not be negative, but is {}", n))
+								.span(repeat.span));
+							failed = true;
+						}
+						Some(0) if in_concat => zero = true,
+						Some(0) => {
+							self.add_diag(DiagBuilder2::error("replication count of zero outside of a concatenation")
+								.span(repeat.span)
+								.add_note("a replication may only be empty if it is part of a larger concatenation"));
+							failed = true;
+						}
+						Some(n) => match width.checked_mul(n as usize) {
+							Some(w) => width = w,
+							None => {
+								self.add_diag(DiagBuilder2::error(format!("width of replication overflows, repeating {} bits {} times", width, n))
+									.span(expr.span));
+								failed = true;
+							}
+						},
+						None => failed = true,
+					}
+				}
+				if failed {
+					ERROR_TY
+				} else if zero {
+					self.intern(Ty::Void)
+				} else if width == 0 && !is_string {
+					ERROR_TY
+				} else if is_string {
+					self.intern(Ty::String)
+				} else {
+					self.int_ty(width, false, four_state)
+				}
+			}
+			ExprKind::Inside(ref expr, ref ranges) => {
+				self.type_expr(expr, env, types);
+				for range in ranges {
+					self.type_expr(range, env, types);
+				}
+				self.intern(Ty::bit(true))
+			}
+			// Data types passed to system functions, as in `$bits(int)`.
+			ExprKind::Type(ref ty) => self.map_type(ty, &mut env.clone()),
+			ExprKind::MinTypMax(ref min, ref typ, ref max) => {
+				self.type_expr(min, env, types);
+				self.type_expr(max, env, types);
+				self.type_expr(typ, env, types)
+			}
+			// Patterns, streaming concatenations, and the remaining
+			// expressions cannot be typed without knowing the context.
+			_ => ERROR_TY,
+		}
+	}
+
+	fn type_ident(&mut self, ident: &Ident, env: &TypeEnv) -> TyId {
+		match ident.def.and_then(|id| env.decls.get(&id)) {
+			Some(&ty) => ty,
+			None => ERROR_TY,
+		}
+	}
+
+	fn type_call_args(&mut self, args: &[CallArg], env: &TypeEnv, types: &mut TypeTable) -> Vec<TyId> {
+		args.iter().map(|arg| match arg.expr {
+			Some(ref e) => self.type_expr(e, env, types),
+			None => ERROR_TY,
+		}).collect()
+	}
+
+	fn type_member(&mut self, base: TyId, name: &ast::Identifier) -> TyId {
+		let member = match *self.tys.get(base) {
+			Ty::Struct(ref s) => Some(s.members.iter().find(|&&(n, _)| n == name.name).map(|&(_, ty)| ty)),
+			// Members of interfaces, classes, and hierarchical names are not
+			// yet typed.
+			_ => None,
+		};
+		match member {
+			Some(Some(ty)) => ty,
+			Some(None) => {
+				let msg = format!("`{}` has no member `{}`", self.tys.display(base), name.name);
+				self.add_diag(DiagBuilder2::error(msg).span(name.span));
+				ERROR_TY
+			}
+			None => ERROR_TY,
+		}
+	}
+
+	fn type_binary(&mut self, op: Op, lhs: &Expr, rhs: &Expr, env: &TypeEnv, types: &mut TypeTable) -> TyId {
+		let lt = self.type_expr(lhs, env, types);
+		let rt = self.type_expr(rhs, env, types);
+		if lt == ERROR_TY || rt == ERROR_TY {
+			return ERROR_TY;
+		}
+		let real = self.tys.get(lt).is_real() || self.tys.get(rt).is_real();
+		let four_state = self.four_state(lt) || self.four_state(rt);
+		match op {
+			Op::LogicAnd | Op::LogicOr | Op::LogicImpl | Op::LogicEquiv => {
+				self.intern(Ty::bit(four_state))
+			}
+			Op::LogicEq | Op::LogicNeq | Op::CaseEq | Op::CaseNeq | Op::WildcardEq | Op::WildcardNeq |
+			Op::Lt | Op::Leq | Op::Gt | Op::Geq => {
+				// The operands of a comparison are sized to the larger of the
+				// two, but the result is always a single bit.
+				let is_string = |ty: TyId, e: &Expr| *self.tys.get(ty) == Ty::String || is_string_literal(e);
+				match (self.tys.as_int(lt), self.tys.as_int(rt)) {
+					(Some(l), Some(r)) => {
+						let width = std::cmp::max(l.width(), r.width());
+						let signed = l.signed && r.signed;
+						self.apply_context(lhs, width, signed, types);
+						self.apply_context(rhs, width, signed, types);
+					}
+					_ if real || lt == rt => (),
+					_ if is_string(lt, lhs) && is_string(rt, rhs) => (),
+					_ => {
+						let msg = format!("cannot compare `{}` and `{}`", self.tys.display(lt), self.tys.display(rt));
+						self.add_diag(DiagBuilder2::error(msg).span(Span::union(lhs.span, rhs.span)));
+						return ERROR_TY;
+					}
+				}
+				self.intern(Ty::bit(four_state))
+			}
+			Op::LogicShL | Op::LogicShR | Op::ArithShL | Op::ArithShR | Op::Pow => {
+				// The result has the type of the left operand, the right
+				// operand is self-determined.
+				if op == Op::Pow && real {
+					return self.intern(Ty::Real);
+				}
+				if !self.expect_integral(lt, lhs, op) || !self.expect_integral(rt, rhs, op) {
+					return ERROR_TY;
+				}
+				let l = self.tys.as_int(lt).unwrap();
+				self.int_ty(l.width(), l.signed, four_state)
+			}
+			_ => {
+				let arith = match op {
+					Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Mod => true,
+					_ => false,
+				};
+				if real && arith {
+					return self.intern(Ty::Real);
+				}
+				if !self.expect_integral(lt, lhs, op) || !self.expect_integral(rt, rhs, op) {
+					return ERROR_TY;
+				}
+				let (l, r) = (self.tys.as_int(lt).unwrap(), self.tys.as_int(rt).unwrap());
+				let width = std::cmp::max(l.width(), r.width());
+				let signed = l.signed && r.signed;
+				self.apply_context(lhs, width, signed, types);
+				self.apply_context(rhs, width, signed, types);
+				self.int_ty(width, signed, four_state)
+			}
+		}
+	}
+
+	/// Determine the type of a bit or range select from a value of type
+	/// `base`.
+	fn type_select(&mut self, span: Span, base: TyId, sel: &Select, env: &TypeEnv, types: &mut TypeTable) -> TyId {
+		// Determine the number of elements selected.
+		let count = match *sel {
+			Select::Range(mode, ref lhs, ref rhs) => {
+				self.type_expr(lhs, env, types);
+				self.type_expr(rhs, env, types);
+				let count = match mode {
+					ast::RangeMode::Absolute => {
+						match (self.const_eval_expr(lhs, env), self.const_eval_expr(rhs, env)) {
+							(Some(l), Some(r)) => Some(Range { left: l.as_int(), right: r.as_int() }.size()),
+							_ => None,
+						}
+					}
+					ast::RangeMode::RelativeUp | ast::RangeMode::RelativeDown => {
+						self.const_eval_expr(rhs, env).map(|v| v.as_int() as usize)
+					}
+				};
+				match count {
+					Some(c) => Some(c),
+					None => return ERROR_TY,
+				}
+			}
+			Select::Bit(ref index) => {
+				self.type_expr(index, env, types);
+				None
+			}
+		};
+
+		let base_ty = self.tys.get(base).clone();
+		match base_ty {
+			Ty::Error => ERROR_TY,
+			Ty::Array(elem, _) => match count {
+				Some(c) => self.intern(Ty::Array(elem, Range::with_width(c))),
+				None => elem,
+			},
+			Ty::String if count.is_none() => self.int_ty(8, false, false),
+			_ => match self.tys.as_int(base) {
+				Some(ref int) if !int.dims.is_empty() => {
+					// Selecting from a packed vector yields an unsigned vector
+					// of the inner dimensions.
+					let inner = int.dims[1..].iter().cloned();
+					let dims = match count {
+						Some(c) => Some(Range::with_width(c)).into_iter().chain(inner).collect(),
+						None => inner.collect(),
+					};
+					self.intern(Ty::Int(IntTy {
+						signed: false,
+						four_state: int.four_state,
+						dims: dims,
+					}))
+				}
+				_ => {
+					let msg = format!("value of type `{}` cannot be indexed", self.tys.display(base));
+					self.add_diag(DiagBuilder2::error(msg).span(span));
+					ERROR_TY
+				}
+			},
+		}
+	}
+
+	fn const_eval_expr(&mut self, expr: &Expr, env: &TypeEnv) -> Option<ConstValue> {
+		match consteval::const_eval_hir(expr, &env.consts) {
+			Ok(v) => Some(v),
+			Err(d) => {
+				self.add_diag(d);
+				None
+			}
+		}
+	}
+
+	/// Determine the result type of a system function call.
+	fn type_system_call(&mut self, name: &str, args: &[TyId]) -> TyId {
+		match name {
+			"signed" | "unsigned" => match args.first().and_then(|&t| self.tys.as_int(t)) {
+				Some(mut int) => {
+					int.signed = name == "signed";
+					self.intern(Ty::Int(int))
+				}
+				None => ERROR_TY,
+			},
+			"bits" | "clog2" | "size" | "dimensions" | "unpacked_dimensions" | "left" | "right" | "low" | "high" |
+			"increment" | "countones" | "rtoi" | "random" | "urandom" | "urandom_range" => self.intern(Ty::int()),
+			"isunknown" | "onehot" | "onehot0" | "test$plusargs" => self.intern(Ty::bit(false)),
+			"time" | "stime" | "realtobits" => self.int_ty(64, false, name == "time"),
+			"realtime" | "itor" | "bitstoreal" => self.intern(Ty::Real),
+			_ => ERROR_TY,
+		}
+	}
+
+	/// The type of the result of an arithmetic operator applied to a single
+	/// operand of the given type.
+	fn arith_ty(&mut self, ty: TyId) -> TyId {
+		match self.tys.as_int(ty) {
+			Some(int) => self.int_ty(int.width(), int.signed, int.four_state),
+			None => ty,
+		}
+	}
+
+	fn expect_integral(&mut self, ty: TyId, expr: &Expr, op: Op) -> bool {
+		if self.tys.is_integral(ty) {
+			true
+		} else {
+			let msg = format!("operator `{}` cannot be applied to a value of type `{}`", op, self.tys.display(ty));
+			self.add_diag(DiagBuilder2::error(msg).span(expr.span));
+			false
+		}
+	}
+
+	/// Propagate the width and signedness imposed by the context of an
+	/// expression down to its context-determined operands (IEEE 1800-2009
+	/// section 11.6.1 and 11.8.2).
+	fn apply_context(&mut self, expr: &Expr, width: usize, signed: bool, types: &mut TypeTable) {
+		let int = match types.get(&expr.id).map(|&ty| self.tys.get(ty)) {
+			Some(&Ty::Int(ref int)) if int.width() <= width => int.clone(),
+			_ => return,
+		};
+		if int.width() < width || int.signed != signed {
+			let ty = self.int_ty(width, signed, int.four_state);
+			types.insert(expr.id, ty);
+		}
+		match expr.kind {
+			ExprKind::Unary(Op::Add, ref expr) |
+			ExprKind::Unary(Op::Sub, ref expr) |
+			ExprKind::Unary(Op::BitNot, ref expr) => {
+				self.apply_context(expr, width, signed, types);
+			}
+			ExprKind::Binary(op, ref lhs, ref rhs) => match op {
+				Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Mod |
+				Op::BitAnd | Op::BitOr | Op::BitXor | Op::BitXnor | Op::BitNxor => {
+					self.apply_context(lhs, width, signed, types);
+					self.apply_context(rhs, width, signed, types);
+				}
+				Op::LogicShL | Op::LogicShR | Op::ArithShL | Op::ArithShR | Op::Pow => {
+					self.apply_context(lhs, width, signed, types);
+				}
+				_ => (),
+			},
+			ExprKind::Ternary(_, ref true_expr, ref false_expr) => {
+				self.apply_context(true_expr, width, signed, types);
+				self.apply_context(false_expr, width, signed, types);
+			}
+			_ => (),
+		}
+	}
+
+	/// Determine the width of an expression for the purpose of width mismatch
+	/// warnings. Unsized literals adapt to their context without a warning,
+	/// such that `x + 1` has the width of `x`. The self-determined types of
+	/// the operands are used, since `types` already holds the widths imposed
+	/// by their context.
+	fn lint_width(&self, expr: &Expr) -> Option<usize> {
+		match expr.kind {
+			_ if is_unsized_literal(expr) => None,
+			ExprKind::Unary(Op::Add, ref expr) |
+			ExprKind::Unary(Op::Sub, ref expr) |
+			ExprKind::Unary(Op::BitNot, ref expr) => self.lint_width(expr),
+			ExprKind::Binary(op, ref lhs, ref rhs) => match op {
+				Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Mod |
+				Op::BitAnd | Op::BitOr | Op::BitXor | Op::BitXnor | Op::BitNxor => {
+					max_width(self.lint_width(lhs), self.lint_width(rhs))
+				}
+				Op::LogicShL | Op::LogicShR | Op::ArithShL | Op::ArithShR | Op::Pow => {
+					self.lint_width(lhs)
+				}
+				_ => self.self_types.get(&expr.id).and_then(|&t| self.tys.width(t)),
+			},
+			ExprKind::Ternary(_, ref true_expr, ref false_expr) => {
+				max_width(self.lint_width(true_expr), self.lint_width(false_expr))
+			}
+			_ => self.self_types.get(&expr.id).and_then(|&t| self.tys.width(t)),
+		}
+	}
+}
+
+
+fn type_literal(lit: &Lit) -> Ty {
+	match *lit {
+		Lit::UnsignedInteger(_) => Ty::Int(IntTy::new(32, true, false)),
+		Lit::BasedInteger(size, signed, _, _) => {
+			let width = size.and_then(|s| s.as_str().parse().ok()).unwrap_or(32);
+			Ty::Int(IntTy::new(width, signed, true))
+		}
+		Lit::UnbasedUnsized(_) => Ty::bit(true),
+		Lit::Str(s) => Ty::Int(IntTy::new(std::cmp::max(s.as_str().len(), 1) * 8, false, false)),
+		Lit::Decimal(_) | Lit::Real(_) | Lit::Time(_) => Ty::Real,
+	}
+}
+
+fn max_width(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+	match (a, b) {
+		(Some(a), Some(b)) => Some(std::cmp::max(a, b)),
+		(a, None) => a,
+		(None, b) => b,
+	}
+}
+
+fn is_unsized_literal(expr: &Expr) -> bool {
+	match expr.kind {
+		ExprKind::Lit(Lit::UnsignedInteger(_)) |
+		ExprKind::Lit(Lit::BasedInteger(None, _, _, _)) |
+		ExprKind::Lit(Lit::UnbasedUnsized(_)) => true,
+		_ => false,
+	}
+}
+
+fn is_string_literal(expr: &Expr) -> bool {
+	match expr.kind {
+		ExprKind::Lit(Lit::Str(_)) => true,
+		_ => false,
+	}
+}
+
+fn dim_span(dim: &ast::TypeDim) -> Span {
+	match *dim {
+		ast::TypeDim::Expr(ref e) => e.span,
+		ast::TypeDim::Range(ref l, ref r) => Span::union(l.span, r.span),
+		_ => moore_common::source::INVALID_SPAN,
+	}
+}
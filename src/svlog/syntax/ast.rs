@@ -51,7 +51,7 @@ pub use self::ExprData::*;
 
 #[derive(Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct Root {
-	pub timeunits: Option<Timeunit>,
+	pub timeunits: Timeunit,
 	pub items: Vec<Item>,
 }
 
@@ -60,11 +60,17 @@ pub struct Root {
 pub enum Item {
 	Module(ModDecl),
 	Interface(IntfDecl),
+	/// An `extern module` prototype, which only carries a header.
+	ExternModule(ModDecl),
+	/// An `extern interface` prototype, which only carries a header.
+	ExternInterface(IntfDecl),
 	Package(PackageDecl),
 	Class(ClassDecl),
+	Primitive(UdpDecl),
+	Checker(CheckerDecl),
+	Bind(BindDirective),
 	Item(HierarchyItem),
 	// Program(ProgramDecl),
-	// Bind(BindDirective),
 	// Config(ConfigDecl),
 }
 
@@ -73,8 +79,13 @@ impl Item {
 		match *self {
 			Item::Module(ref decl) => decl.span,
 			Item::Interface(ref decl) => decl.span,
+			Item::ExternModule(ref decl) => decl.span,
+			Item::ExternInterface(ref decl) => decl.span,
 			Item::Package(ref decl) => decl.span,
 			Item::Class(ref decl) => decl.span,
+			Item::Primitive(ref decl) => decl.span,
+			Item::Checker(ref decl) => decl.span,
+			Item::Bind(ref bind) => bind.span,
 			Item::Item(ref item) => item.span(),
 		}
 	}
@@ -83,36 +94,55 @@ impl Item {
 		match *self {
 			Item::Module(ref decl) => "module declaration",
 			Item::Interface(ref decl) => "interface declaration",
+			Item::ExternModule(ref decl) => "extern module declaration",
+			Item::ExternInterface(ref decl) => "extern interface declaration",
 			Item::Package(ref decl) => "package declaration",
 			Item::Class(ref decl) => "class declaration",
+			Item::Primitive(ref decl) => "primitive declaration",
+			Item::Checker(ref decl) => "checker declaration",
+			Item::Bind(ref bind) => "bind directive",
 			Item::Item(ref item) => item.as_str(),
 		}
 	}
 }
 
 
-#[derive(Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct ModDecl {
 	pub id: NodeId,
 	pub span: Span,
 	pub lifetime: Lifetime, // default static
 	pub name: Name,
 	pub name_span: Span,
+	pub timeunits: Timeunit,
 	pub params: Vec<ParamDecl>,
 	pub ports: Vec<Port>,
+	/// Whether the port list is `(.*)`, in which case the parameters and ports
+	/// are taken from the corresponding extern declaration.
+	pub wildcard_ports: bool,
 	pub items: Vec<HierarchyItem>,
+	pub attrs: Vec<AttrInst>,
+	/// The last "`default_nettype" directive before the design element.
+	pub default_nettype: Option<DefaultNettype>,
 }
 
-#[derive(Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct IntfDecl {
 	pub id: NodeId,
 	pub span: Span,
 	pub lifetime: Lifetime, // default static
 	pub name: Name,
 	pub name_span: Span,
+	pub timeunits: Timeunit,
 	pub params: Vec<ParamDecl>,
 	pub ports: Vec<Port>,
+	/// Whether the port list is `(.*)`, in which case the parameters and ports
+	/// are taken from the corresponding extern declaration.
+	pub wildcard_ports: bool,
 	pub items: Vec<HierarchyItem>,
+	pub attrs: Vec<AttrInst>,
+	/// The last "`default_nettype" directive before the design element.
+	pub default_nettype: Option<DefaultNettype>,
 }
 
 #[derive(Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -124,6 +154,7 @@ pub struct PackageDecl {
 	pub name_span: Span,
 	pub timeunits: Timeunit,
 	pub items: Vec<HierarchyItem>,
+	pub attrs: Vec<AttrInst>,
 }
 
 
@@ -135,8 +166,43 @@ pub enum Lifetime {
 	Automatic,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, RustcEncodable, RustcDecodable)]
-pub struct Timeunit;
+/// The time unit and precision of a compilation unit, module, interface, or
+/// package.
+#[derive(Debug, PartialEq, Eq, Clone, Default, RustcEncodable, RustcDecodable)]
+pub struct Timeunit {
+	/// The `timeunit` declaration.
+	pub unit: Option<TimeValue>,
+	/// The `timeprecision` declaration, or the precision given after the unit
+	/// in a `timeunit` declaration.
+	pub prec: Option<TimeValue>,
+	/// The last "`timescale" directive before the design element.
+	pub timescale: Option<Timescale>,
+}
+
+/// A time unit or precision such as `10ns`, stored as the power of ten of the
+/// seconds it represents (-8 in this case).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, RustcEncodable, RustcDecodable)]
+pub struct TimeValue {
+	pub span: Span,
+	pub exp: i8,
+}
+
+/// A "`timescale" compiler directive.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, RustcEncodable, RustcDecodable)]
+pub struct Timescale {
+	pub span: Span,
+	pub unit: TimeValue,
+	pub prec: TimeValue,
+}
+
+/// A "`default_nettype" compiler directive.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, RustcEncodable, RustcDecodable)]
+pub struct DefaultNettype {
+	pub span: Span,
+	/// The type of implicit nets, or `None` if implicit nets are disabled with
+	/// `none`.
+	pub net_type: Option<NetType>,
+}
 
 
 
@@ -144,11 +210,14 @@ pub struct Timeunit;
 pub enum HierarchyItem {
 	Dummy,
 	ImportDecl(ImportDecl),
+	ExportDecl(ExportDecl),
+	DpiDecl(DpiDecl),
 	LocalparamDecl(()),
 	ParameterDecl(()),
 	ParamDecl(ParamDecl),
 	ModportDecl(ModportDecl),
 	ClassDecl(ClassDecl),
+	CovergroupDecl(CovergroupDecl),
 	Typedef(Typedef),
 	PortDecl(PortDecl),
 	Procedure(Procedure),
@@ -163,22 +232,60 @@ pub enum HierarchyItem {
 	NetDecl(NetDecl),
 	VarDecl(VarDecl),
 	Inst(Inst),
+	GateInst(GateInst),
+	SpecparamDecl(SpecparamDecl),
+	SpecifyBlock(SpecifyBlock),
+	ClockingDecl(ClockingDecl),
+	/// A `default clocking name;` statement that refers to a clocking block
+	/// declared elsewhere.
+	DefaultClocking(Span, Identifier),
+	SequenceDecl(SequenceDecl),
+	PropertyDecl(PropertyDecl),
+	NettypeDecl(NettypeDecl),
+	Alias(Alias),
+	/// A module declared within another module or interface.
+	ModuleDecl(ModDecl),
+	/// An interface declared within another module or interface.
+	InterfaceDecl(IntfDecl),
+	/// A constraint defined outside of its class, as in
+	/// `constraint C::c { ... }`.
+	Constraint(Constraint),
 }
 
 impl HierarchyItem {
 	pub fn span(&self) -> Span {
 		match *self {
 			HierarchyItem::ImportDecl(ref decl) => decl.span,
+			HierarchyItem::ExportDecl(ref decl) => decl.span,
+			HierarchyItem::DpiDecl(ref decl) => decl.span(),
 			HierarchyItem::ParamDecl(ref decl) => decl.span,
 			HierarchyItem::ModportDecl(ref decl) => decl.span,
 			HierarchyItem::ClassDecl(ref decl) => decl.span,
+			HierarchyItem::CovergroupDecl(ref decl) => decl.span,
+			HierarchyItem::Typedef(ref decl) => decl.span,
 			HierarchyItem::PortDecl(ref decl) => decl.span,
 			HierarchyItem::Procedure(ref prc) => prc.span,
 			HierarchyItem::SubroutineDecl(ref decl) => decl.span,
+			HierarchyItem::ContAssign(ref assign) => assign.span,
+			HierarchyItem::GenerateRegion(sp, _) => sp,
+			HierarchyItem::GenerateFor(ref gen) => gen.span,
+			HierarchyItem::GenerateIf(ref gen) => gen.span,
 			HierarchyItem::Assertion(ref assertion) => assertion.span,
 			HierarchyItem::NetDecl(ref decl) => decl.span,
 			HierarchyItem::VarDecl(ref decl) => decl.span,
 			HierarchyItem::Inst(ref inst) => inst.span,
+			HierarchyItem::GateInst(ref inst) => inst.span,
+			HierarchyItem::SpecparamDecl(ref decl) => decl.span,
+			HierarchyItem::SpecifyBlock(ref blk) => blk.span,
+			HierarchyItem::ClockingDecl(ref decl) => decl.span,
+			HierarchyItem::DefaultClocking(sp, _) => sp,
+			HierarchyItem::SequenceDecl(ref decl) => decl.span,
+			HierarchyItem::PropertyDecl(ref decl) => decl.span,
+			HierarchyItem::NettypeDecl(ref decl) => decl.span,
+			HierarchyItem::Alias(ref alias) => alias.span,
+			HierarchyItem::ModuleDecl(ref decl) => decl.span,
+			HierarchyItem::InterfaceDecl(ref decl) => decl.span,
+			HierarchyItem::Constraint(ref decl) => decl.span,
 			_ => unimplemented!(), // TODO remove this and have the compiler complain
 		}
 	}
@@ -186,16 +293,38 @@ impl HierarchyItem {
 	pub fn as_str(&self) -> &'static str {
 		match *self {
 			HierarchyItem::ImportDecl(ref decl) => "import declaration",
+			HierarchyItem::ExportDecl(ref decl) => "export declaration",
+			HierarchyItem::DpiDecl(ref decl) => "DPI declaration",
 			HierarchyItem::ParamDecl(ref decl) => "parameter declaration",
 			HierarchyItem::ModportDecl(ref decl) => "modport declaration",
 			HierarchyItem::ClassDecl(ref decl) => "class declaration",
+			HierarchyItem::CovergroupDecl(ref decl) => "covergroup declaration",
+			HierarchyItem::Typedef(ref decl) => "typedef",
 			HierarchyItem::PortDecl(ref decl) => "port declaration",
 			HierarchyItem::Procedure(ref prc) => "procedure declaration",
 			HierarchyItem::SubroutineDecl(ref decl) => "subroutine declaration",
+			HierarchyItem::ContAssign(ref assign) => "continuous assignment",
+			HierarchyItem::GenvarDecl(ref decls) => "genvar declaration",
+			HierarchyItem::GenerateRegion(..) => "generate region",
+			HierarchyItem::GenerateFor(ref gen) => "generate for loop",
+			HierarchyItem::GenerateIf(ref gen) => "generate if",
+			HierarchyItem::GenerateCase(ref gen) => "generate case",
 			HierarchyItem::Assertion(ref assertion) => "assertion",
 			HierarchyItem::NetDecl(ref decl) => "net declaration",
 			HierarchyItem::VarDecl(ref decl) => "variable declaration",
 			HierarchyItem::Inst(ref inst) => "instantiation",
+			HierarchyItem::GateInst(ref inst) => "gate instantiation",
+			HierarchyItem::SpecparamDecl(ref decl) => "specparam declaration",
+			HierarchyItem::SpecifyBlock(ref blk) => "specify block",
+			HierarchyItem::ClockingDecl(ref decl) => "clocking block",
+			HierarchyItem::DefaultClocking(..) => "default clocking statement",
+			HierarchyItem::SequenceDecl(ref decl) => "sequence declaration",
+			HierarchyItem::PropertyDecl(ref decl) => "property declaration",
+			HierarchyItem::NettypeDecl(ref decl) => "nettype declaration",
+			HierarchyItem::Alias(ref alias) => "alias statement",
+			HierarchyItem::ModuleDecl(ref decl) => "module declaration",
+			HierarchyItem::InterfaceDecl(ref decl) => "interface declaration",
+			HierarchyItem::Constraint(ref decl) => "constraint",
 			_ => unimplemented!(), // TODO remove this and have the compiler complain
 		}
 	}
@@ -205,6 +334,7 @@ impl HierarchyItem {
 
 #[derive(Debug, PartialEq, Eq, Clone, RustcEncodable, RustcDecodable)]
 pub struct Type {
+	pub id: NodeId,
 	pub span: Span,
 	pub data: TypeData,
 	pub sign: TypeSign,
@@ -308,12 +438,14 @@ pub enum Port {
 		name: Identifier,
 		dims: Vec<TypeDim>,
 		expr: Option<Expr>,
+		attrs: Vec<AttrInst>,
 	},
 	Explicit {
 		span: Span,
 		dir: Option<PortDir>,
 		name: Identifier,
 		expr: Option<Expr>,
+		attrs: Vec<AttrInst>,
 	},
 	Named {
 		span: Span,
@@ -323,6 +455,7 @@ pub enum Port {
 		name: Identifier,
 		dims: Vec<TypeDim>,
 		expr: Option<Expr>,
+		attrs: Vec<AttrInst>,
 	},
 	Implicit(Expr),
 }
@@ -346,6 +479,7 @@ pub struct PortDecl {
 	pub var: bool,
 	pub ty: Type,
 	pub names: Vec<VarDeclName>,
+	pub attrs: Vec<AttrInst>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy, RustcEncodable, RustcDecodable)]
@@ -376,6 +510,10 @@ pub enum NetType {
 	Wire,
 	WireAnd,
 	WireOr,
+	/// An `interconnect` net, which is typeless.
+	Interconnect,
+	/// A user-defined nettype. The declaration's type names the nettype.
+	User,
 }
 
 
@@ -385,6 +523,7 @@ pub struct Procedure {
 	pub span: Span,
 	pub kind: ProcedureKind,
 	pub stmt: Stmt,
+	pub attrs: Vec<AttrInst>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy, RustcEncodable, RustcDecodable)]
@@ -401,9 +540,11 @@ pub enum ProcedureKind {
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct Stmt {
+	pub id: NodeId,
 	pub span: Span,
 	pub label: Option<Name>,
 	pub data: StmtData,
+	pub attrs: Vec<AttrInst>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -427,6 +568,8 @@ pub enum StmtData {
 		rhs: Expr,
 		delay: Option<DelayControl>,
 		event: Option<()>,
+		/// The cycle delay of a clocking drive, e.g. `cb.q <= ##1 d`.
+		cycle: Option<CycleDelay>,
 	},
 	TimedStmt(TimingControl, Box<Stmt>),
 	CaseStmt {
@@ -459,9 +602,11 @@ pub enum StmtData {
 impl Stmt {
 	pub fn new_null(span: Span) -> Stmt {
 		Stmt {
+			id: DUMMY_NODE_ID,
 			span: span,
 			label: None,
 			data: NullStmt,
+			attrs: Vec::new(),
 		}
 	}
 }
@@ -518,9 +663,12 @@ pub enum EventControlData {
 	Expr(EventExpr),
 }
 
+/// A cycle delay such as `##1` or `##(n+1)`, counted in cycles of the
+/// default clocking.
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct CycleDelay {
-
+	pub span: Span,
+	pub expr: Expr,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -556,6 +704,7 @@ pub struct VarDecl {
 	pub lifetime: Option<Lifetime>,
 	pub ty: Type,
 	pub names: Vec<VarDeclName>,
+	pub attrs: Vec<AttrInst>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -579,13 +728,15 @@ pub struct GenvarDecl {
 
 
 
-// TODO: Assign an id to each and every expression. This will later allow the
-// types of each expression to be recorded properly, and simplifies the act of
-// assigning IDs. Maybe expression IDs should be distinct from node IDs?
+/// An expression. Each expression is assigned a node ID during renumbering,
+/// under which the results of later analyses such as its type are recorded.
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct Expr {
+	pub id: NodeId,
 	pub span: Span,
 	pub data: ExprData,
+	/// The attributes of the operator or call, e.g. `a + (* foo *) b`.
+	pub attrs: Vec<AttrInst>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -646,7 +797,18 @@ pub enum ExprData {
 		expr: Box<Expr>,
 		name: Identifier,
 	},
+	/// A scoped name such as `pkg::name`.
+	ScopeExpr(Box<Expr>, Identifier),
+	/// A set membership test, e.g. `x inside {1, [3:5], arr}`.
+	InsideExpr(Box<Expr>, Vec<Expr>),
 	PatternExpr(Vec<PatternField>),
+	/// A call to `randomize` with inline constraints, e.g.
+	/// `obj.randomize() with (x) { x < 10; }`.
+	RandomizeWithExpr {
+		call: Box<Expr>,
+		names: Vec<Identifier>,
+		items: Vec<ConstraintItem>,
+	},
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -735,10 +897,15 @@ impl EventExpr {
 pub struct ClassDecl {
 	pub span: Span,
 	pub virt: bool,
+	/// Whether this is an interface class.
+	pub intf: bool,
 	pub lifetime: Lifetime, // default static
 	pub name: Identifier,
 	pub params: Vec<ParamDecl>,
 	pub extends: Option<(Type, Vec<CallArg>)>,
+	/// The interface classes implemented by a class, or extended by an
+	/// interface class.
+	pub implements: Vec<Type>,
 	pub items: Vec<ClassItem>,
 }
 
@@ -763,12 +930,16 @@ pub enum ClassItemQualifier {
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub enum ClassItemData {
-	Property,
+	Property {
+		ty: Type,
+		names: Vec<VarDeclName>,
+	},
 	SubroutineDecl(SubroutineDecl),
+	/// An extern or pure virtual method, which only has a prototype.
 	ExternSubroutine(SubroutinePrototype),
 	Constraint(Constraint),
 	ClassDecl,
-	CovergroupDecl,
+	CovergroupDecl(CovergroupDecl),
 	LocalparamDecl(()),
 	ParameterDecl(()),
 	Null,
@@ -788,6 +959,7 @@ pub struct Typedef {
 	pub name: Identifier,
 	pub ty: Type,
 	pub dims: Vec<TypeDim>,
+	pub attrs: Vec<AttrInst>,
 }
 
 
@@ -797,6 +969,9 @@ pub struct Constraint {
 	pub span: Span,
 	pub kind: ConstraintKind,
 	pub statik: bool,
+	/// The class of a constraint defined outside of it, as in
+	/// `constraint C::c { ... }`.
+	pub class: Option<Identifier>,
 	pub name: Name,
 	pub name_span: Span,
 	pub items: Vec<ConstraintItem>,
@@ -818,9 +993,193 @@ pub struct ConstraintItem {
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub enum ConstraintItemData {
-	If,
-	Foreach,
+	/// An expression that must hold, e.g. `soft x < 10;`.
+	Expr {
+		soft: bool,
+		expr: Expr,
+	},
+	/// A distribution, e.g. `x dist { 0 := 1, [1:7] :/ 3 };`.
+	Dist {
+		soft: bool,
+		expr: Expr,
+		items: Vec<DistItem>,
+	},
+	/// A uniqueness constraint, e.g. `unique { a, b, arr };`.
+	Unique(Vec<Expr>),
+	/// An implication, e.g. `mode == 0 -> { x < 4; y == 0; }`.
+	Implication {
+		cond: Expr,
+		items: Vec<ConstraintItem>,
+	},
+	If {
+		cond: Expr,
+		main_items: Vec<ConstraintItem>,
+		else_items: Option<Vec<ConstraintItem>>,
+	},
+	Foreach {
+		expr: Expr,
+		vars: Vec<Option<Identifier>>,
+		items: Vec<ConstraintItem>,
+	},
+	/// A `disable soft x;` item.
+	DisableSoft(Expr),
+	/// A `solve a, b before c;` ordering.
+	SolveBefore(Vec<Expr>, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct DistItem {
+	pub span: Span,
+	/// The value or `[lo:hi]` range the weight applies to.
+	pub value: Expr,
+	pub weight: Option<(DistWeight, Expr)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum DistWeight {
+	/// `:=` assigns the weight to each value in the range.
+	PerValue,
+	/// `:/` divides the weight among the values in the range.
+	PerRange,
+}
+
+
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct CovergroupDecl {
+	pub span: Span,
+	pub name: Identifier,
+	pub ports: Vec<SubroutinePort>,
+	pub event: Option<CoverageEvent>,
+	pub items: Vec<CovergroupItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum CoverageEvent {
+	/// A clocking event, e.g. `@(posedge clk)`.
+	Clocking(EventControl),
+	/// A user-defined sample function, e.g. `with function sample(int x)`.
+	Sample(Vec<SubroutinePort>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum CovergroupItem {
+	Option(CoverageOption),
+	Coverpoint(Coverpoint),
+	Cross(Cross),
+}
+
+/// An `option.name = expr` or `type_option.name = expr` assignment.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct CoverageOption {
+	pub span: Span,
+	pub type_option: bool,
+	pub name: Identifier,
+	pub expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct Coverpoint {
+	pub id: NodeId,
+	pub span: Span,
+	pub name: Option<Identifier>,
+	pub expr: Expr,
+	pub iff: Option<Expr>,
+	pub bins: Vec<BinsOrOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct Cross {
+	pub id: NodeId,
+	pub span: Span,
+	pub name: Option<Identifier>,
+	pub items: Vec<Identifier>,
+	pub iff: Option<Expr>,
+	pub bins: Vec<BinsOrOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum BinsOrOption {
+	Option(CoverageOption),
+	Bins(Bins),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct Bins {
+	pub span: Span,
+	pub kind: BinsKind,
+	pub wildcard: bool,
+	pub name: Identifier,
+	/// The array size of the bin, which is `Some(None)` for `name[]`.
+	pub array: Option<Option<Expr>>,
+	pub data: BinsData,
+	pub with: Option<Expr>,
+	pub iff: Option<Expr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum BinsKind {
+	Bins,
+	IllegalBins,
+	IgnoreBins,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum BinsData {
+	/// A list of values and ranges, e.g. `{ [0:3], 7 }`.
+	Values(Vec<Expr>),
+	/// A list of transition sequences, e.g. `(0 => 1[*2] => 2), (3 => 4)`.
+	Transitions(Vec<Vec<TransItem>>),
+	/// A coverpoint name or set expression.
 	Expr(Expr),
+	/// The selection of a cross bin, e.g. `binsof(a) intersect {0}`.
+	Select(SelectExpr),
+	Default,
+	DefaultSequence,
+}
+
+/// One step of a transition sequence, e.g. `[1:3][*2]`.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct TransItem {
+	pub span: Span,
+	pub values: Vec<Expr>,
+	pub repeat: Option<TransRepeat>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct TransRepeat {
+	pub kind: TransRepeatKind,
+	pub min: Expr,
+	pub max: Option<Expr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum TransRepeatKind {
+	/// `[* n]`
+	Consecutive,
+	/// `[-> n]`
+	Goto,
+	/// `[= n]`
+	NonConsecutive,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct SelectExpr {
+	pub span: Span,
+	pub data: SelectExprData,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum SelectExprData {
+	/// `binsof(cp.bin) intersect { ... }`
+	Binsof {
+		target: Identifier,
+		bin: Option<Identifier>,
+		intersect: Vec<Expr>,
+	},
+	Not(Box<SelectExpr>),
+	And(Box<SelectExpr>, Box<SelectExpr>),
+	Or(Box<SelectExpr>, Box<SelectExpr>),
 }
 
 
@@ -830,12 +1189,14 @@ pub struct SubroutineDecl {
 	pub span: Span,
 	pub prototype: SubroutinePrototype,
 	pub items: Vec<SubroutineItem>,
+	pub attrs: Vec<AttrInst>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct SubroutinePrototype {
 	pub span: Span,
 	pub kind: SubroutineKind,
+	pub retty: Option<Type>,
 	pub name: Identifier,
 	pub args: Vec<SubroutinePort>,
 }
@@ -897,6 +1258,25 @@ pub struct NetDecl {
 	pub ty: Type,
 	pub delay: Option<Expr>,
 	pub names: Vec<VarDeclName>,
+	pub attrs: Vec<AttrInst>,
+}
+
+/// A user-defined nettype, e.g. `nettype real wreal with res_avg;`.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct NettypeDecl {
+	pub span: Span,
+	pub ty: Type,
+	pub name: Identifier,
+	/// The resolution function, optionally qualified with the name of a
+	/// package or class.
+	pub resolve: Option<(Option<Identifier>, Identifier)>,
+}
+
+/// An alias statement, e.g. `alias a = b[3:0] = {c, d};`.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct Alias {
+	pub span: Span,
+	pub nets: Vec<Expr>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -964,6 +1344,67 @@ pub struct ImportItem {
 	pub name: Option<Identifier>, // None means `import pkg::*`
 }
 
+/// A package export declaration, as per IEEE 1800-2009 section 26.6.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct ExportDecl {
+	pub span: Span,
+	pub items: Vec<ImportItem>, // empty means `export *::*`
+}
+
+/// A DPI import or export declaration, as per IEEE 1800-2009 section 35.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum DpiDecl {
+	/// `import "DPI-C" [context|pure] [c_name =] function ...;`
+	Import {
+		span: Span,
+		spec: Name,
+		property: Option<DpiProperty>,
+		cident: Option<Identifier>,
+		prototype: SubroutinePrototype,
+	},
+	/// `export "DPI-C" [c_name =] function name;`
+	Export {
+		span: Span,
+		spec: Name,
+		cident: Option<Identifier>,
+		kind: SubroutineKind,
+		name: Identifier,
+	},
+}
+
+impl DpiDecl {
+	pub fn span(&self) -> Span {
+		match *self {
+			DpiDecl::Import{span, ..} => span,
+			DpiDecl::Export{span, ..} => span,
+		}
+	}
+
+	/// The name of the subroutine on the SystemVerilog side.
+	pub fn sv_name(&self) -> &Identifier {
+		match *self {
+			DpiDecl::Import{ref prototype, ..} => &prototype.name,
+			DpiDecl::Export{ref name, ..} => name,
+		}
+	}
+
+	/// The name of the subroutine on the C side. This is the C identifier
+	/// alias if one is given, or the SystemVerilog name otherwise.
+	pub fn c_name(&self) -> Name {
+		match *self {
+			DpiDecl::Import{cident: Some(ref id), ..} |
+			DpiDecl::Export{cident: Some(ref id), ..} => id.name,
+			_ => self.sv_name().name,
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum DpiProperty {
+	Context,
+	Pure,
+}
+
 
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -992,7 +1433,7 @@ pub enum ConcurrentAssertion {
 	AssertProperty(PropSpec, AssertionActionBlock),
 	AssumeProperty(PropSpec, AssertionActionBlock),
 	CoverProperty(PropSpec, Stmt),
-	CoverSequence,
+	CoverSequence(PropSpec, Stmt),
 	ExpectProperty(PropSpec, AssertionActionBlock),
 	RestrictProperty(PropSpec),
 }
@@ -1014,19 +1455,38 @@ pub struct SeqExpr {
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub enum SeqExprData {
+	/// An expression, or an instance of a named sequence.
 	Expr(Expr, Option<SeqRep>),
 	BinOp(SeqBinOp, Box<SeqExpr>, Box<SeqExpr>),
 	Throughout(Expr, Box<SeqExpr>),
 	Clocked(EventExpr, Box<SeqExpr>),
+	/// A cycle delay `lhs ##n rhs`, or `##n rhs` if there is no left hand side.
+	Delay(Option<Box<SeqExpr>>, SeqRange, Box<SeqExpr>),
+	/// A parenthesized sequence `(seq, items...)` with optional match items
+	/// and repetition.
+	Paren(Box<SeqExpr>, Vec<Expr>, Option<SeqRep>),
+	/// A `first_match(seq, items...)` operator.
+	FirstMatch(Box<SeqExpr>, Vec<Expr>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub enum SeqRep {
-	Consec(Expr),    // [* expr]
-	ConsecStar,      // [*]
-	ConsecPlus,      // [+]
-	Nonconsec(Expr), // [= expr]
-	Goto(Expr),      // [-> expr]
+	Consec(SeqRange),    // [* range], [*], [+]
+	Nonconsec(SeqRange), // [= range]
+	Goto(SeqRange),      // [-> range]
+}
+
+/// A number of cycles or repetitions in a sequence.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum SeqRange {
+	/// A fixed count, e.g. `##2` or `[*2]`.
+	Single(Expr),
+	/// A range, e.g. `[1:3]`. The upper bound is `None` for `$`.
+	Range(Expr, Option<Expr>),
+	/// `[*]`, equivalent to `[0:$]`.
+	Star,
+	/// `[+]`, equivalent to `[1:$]`.
+	Plus,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -1040,7 +1500,12 @@ pub enum SeqBinOp {
 
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
-pub struct PropSpec;
+pub struct PropSpec {
+	pub span: Span,
+	pub clock: Option<EventExpr>,
+	pub disable: Option<Expr>,
+	pub prop: PropExpr,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct PropExpr {
@@ -1055,6 +1520,32 @@ pub enum PropExprData {
 	Not(Box<PropExpr>),
 	BinOp(PropBinOp, Box<PropExpr>, Box<PropExpr>),
 	Clocked(EventExpr, Box<PropExpr>),
+	If(Expr, Box<PropExpr>, Option<Box<PropExpr>>),
+	Case(Expr, Vec<PropCaseItem>),
+	/// `nexttime` or `s_nexttime` (if strong), with an optional cycle count.
+	Nexttime(bool, Option<Expr>, Box<PropExpr>),
+	/// `always` or `s_always` (if strong), with an optional range.
+	Always(bool, Option<SeqRange>, Box<PropExpr>),
+	/// `eventually` or `s_eventually` (if strong), with an optional range.
+	Eventually(bool, Option<SeqRange>, Box<PropExpr>),
+	/// `accept_on`, `reject_on`, and their synchronous variants.
+	Abort(PropAbortOp, Expr, Box<PropExpr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct PropCaseItem {
+	pub span: Span,
+	/// The values that select this item. Empty for the `default` item.
+	pub exprs: Vec<Expr>,
+	pub prop: PropExpr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum PropAbortOp {
+	AcceptOn,
+	RejectOn,
+	SyncAcceptOn,
+	SyncRejectOn,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -1090,6 +1581,76 @@ pub enum PropBinOp {
 
 
 
+/// A named sequence declaration.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct SequenceDecl {
+	pub span: Span,
+	pub name: Identifier,
+	pub ports: Vec<AssertionPort>,
+	/// The local variables of the sequence.
+	pub vars: Vec<VarDecl>,
+	pub seq: SeqExpr,
+}
+
+/// A named property declaration.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct PropertyDecl {
+	pub span: Span,
+	pub name: Identifier,
+	pub ports: Vec<AssertionPort>,
+	/// The local variables of the property.
+	pub vars: Vec<VarDecl>,
+	pub spec: PropSpec,
+}
+
+/// A formal argument of a sequence, property, or checker.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct AssertionPort {
+	pub span: Span,
+	/// The direction of a `local` argument of a sequence or property, or the
+	/// direction of a checker port. `None` if the argument is not local.
+	pub local: Option<PortDir>,
+	pub ty: AssertionPortType,
+	pub name: Identifier,
+	pub dims: Vec<TypeDim>,
+	pub default: Option<Expr>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum AssertionPortType {
+	Untyped,
+	Sequence,
+	Property,
+	Type(Type),
+}
+
+
+
+/// A checker declaration.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct CheckerDecl {
+	pub span: Span,
+	pub name: Identifier,
+	pub ports: Vec<AssertionPort>,
+	pub items: Vec<HierarchyItem>,
+}
+
+/// A bind directive, which instantiates a module, interface, or checker in
+/// the scope of another module or interface without modifying it.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct BindDirective {
+	pub span: Span,
+	/// The module or interface into which the instantiation is bound, or the
+	/// hierarchical name of a single instance, e.g. `top.cpu0`.
+	pub target: Vec<Identifier>,
+	/// The hierarchical names of the instances of the target into which the
+	/// instantiation is bound. Empty if all instances are targeted.
+	pub insts: Vec<Vec<Identifier>>,
+	pub inst: Inst,
+}
+
+
+
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct Inst {
 	pub span: Span,
@@ -1097,8 +1658,13 @@ pub struct Inst {
 	pub target: Identifier,
 	/// The parameters in the module to be assigned.
 	pub params: Vec<ParamAssignment>,
+	/// The delay of an instance of a user-defined primitive given as a single
+	/// value, e.g. `#5`. A parenthesized list of delays cannot be told apart
+	/// from a parameter value assignment and is kept in `params`.
+	pub delay: Option<Delay3>,
 	/// The names and ports of the module instantiations.
 	pub names: Vec<InstName>,
+	pub attrs: Vec<AttrInst>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -1109,6 +1675,300 @@ pub struct InstName {
 	pub conns: Vec<PortConn>,
 }
 
+/// An instantiation of a built-in gate or switch primitive, as per IEEE
+/// 1800-2009 section 28.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct GateInst {
+	pub span: Span,
+	pub kind: GateKind,
+	pub strength: Option<GateStrength>,
+	pub delay: Option<Delay3>,
+	pub names: Vec<GateInstName>,
+	pub attrs: Vec<AttrInst>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct GateInstName {
+	pub span: Span,
+	pub name: Option<Identifier>,
+	pub dims: Vec<TypeDim>,
+	pub terminals: Vec<Expr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum GateKind {
+	// N-input gates
+	And,
+	Nand,
+	Or,
+	Nor,
+	Xor,
+	Xnor,
+	// N-output gates
+	Buf,
+	Not,
+	// Enable gates
+	Bufif0,
+	Bufif1,
+	Notif0,
+	Notif1,
+	// MOS switches
+	Nmos,
+	Pmos,
+	Rnmos,
+	Rpmos,
+	Cmos,
+	Rcmos,
+	// Bidirectional pass switches
+	Tran,
+	Rtran,
+	Tranif0,
+	Tranif1,
+	Rtranif0,
+	Rtranif1,
+	// Pull gates
+	Pullup,
+	Pulldown,
+}
+
+impl GateKind {
+	pub fn as_str(&self) -> &'static str {
+		match *self {
+			GateKind::And => "and",
+			GateKind::Nand => "nand",
+			GateKind::Or => "or",
+			GateKind::Nor => "nor",
+			GateKind::Xor => "xor",
+			GateKind::Xnor => "xnor",
+			GateKind::Buf => "buf",
+			GateKind::Not => "not",
+			GateKind::Bufif0 => "bufif0",
+			GateKind::Bufif1 => "bufif1",
+			GateKind::Notif0 => "notif0",
+			GateKind::Notif1 => "notif1",
+			GateKind::Nmos => "nmos",
+			GateKind::Pmos => "pmos",
+			GateKind::Rnmos => "rnmos",
+			GateKind::Rpmos => "rpmos",
+			GateKind::Cmos => "cmos",
+			GateKind::Rcmos => "rcmos",
+			GateKind::Tran => "tran",
+			GateKind::Rtran => "rtran",
+			GateKind::Tranif0 => "tranif0",
+			GateKind::Tranif1 => "tranif1",
+			GateKind::Rtranif0 => "rtranif0",
+			GateKind::Rtranif1 => "rtranif1",
+			GateKind::Pullup => "pullup",
+			GateKind::Pulldown => "pulldown",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum GateStrength {
+	/// A drive strength such as `(strong0, weak1)`.
+	Drive(DriveStrength, DriveStrength),
+	/// A single pull strength such as `(pull1)`, only valid on pull gates.
+	Pull(DriveStrength),
+}
+
+/// A delay with up to three values, for the rise, fall, and turn-off
+/// transitions, e.g. `#5` or `#(1, 2:3:4, 5)`.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct Delay3 {
+	pub span: Span,
+	pub values: Vec<Expr>,
+}
+
+
+
+/// A user-defined primitive, as per IEEE 1800-2009 section 29.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct UdpDecl {
+	pub span: Span,
+	pub name: Identifier,
+	pub output: Identifier,
+	pub inputs: Vec<Identifier>,
+	/// Whether the output is declared as `reg`, making this a sequential UDP.
+	pub sequential: bool,
+	pub init: Option<Expr>,
+	pub entries: Vec<UdpEntry>,
+}
+
+/// A row in the table of a UDP. The `state` is only present in sequential
+/// UDPs.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct UdpEntry {
+	pub span: Span,
+	pub inputs: Vec<UdpSymbol>,
+	pub state: Option<UdpSymbol>,
+	pub output: UdpSymbol,
+}
+
+/// A symbol in a UDP table. Letters are normalized to lowercase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum UdpSymbol {
+	/// One of `0`, `1`, `x`, `?`, or `b`.
+	Level(char),
+	/// A transition such as `(01)` or `(?1)`.
+	Edge(char, char),
+	/// One of the edge abbreviations `r`, `f`, `p`, `n`, or `*`.
+	EdgeAbbrev(char),
+	/// The `-` output of sequential UDPs.
+	NoChange,
+}
+
+
+
+/// A specify block, as per IEEE 1800-2009 section 30.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct SpecifyBlock {
+	pub span: Span,
+	pub items: Vec<SpecifyItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum SpecifyItem {
+	Specparam(SpecparamDecl),
+	/// A `pulsestyle_onevent` or `pulsestyle_ondetect` declaration.
+	Pulsestyle(Span, PulseStyle, Vec<Expr>),
+	/// A `showcancelled` or `noshowcancelled` declaration.
+	Showcancelled(Span, bool, Vec<Expr>),
+	Path(PathDecl),
+	TimingCheck(TimingCheck),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum PulseStyle {
+	OnEvent,
+	OnDetect,
+}
+
+/// A specparam declaration, either in a module or in a specify block.
+///
+/// ```text
+/// "specparam" [packed_dimension] list_of_specparam_assignments ";"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct SpecparamDecl {
+	pub span: Span,
+	pub dims: Vec<TypeDim>,
+	pub assigns: Vec<SpecparamAssign>,
+}
+
+/// A single assignment in a specparam declaration. For `PATHPULSE$`
+/// specparams the value is the reject limit, optionally followed by the
+/// error limit.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct SpecparamAssign {
+	pub span: Span,
+	pub name: Identifier,
+	pub value: Expr,
+	pub error: Option<Expr>,
+}
+
+/// A module path declaration, as per IEEE 1800-2009 section 30.4. This covers
+/// simple, edge-sensitive, and state-dependent paths.
+///
+/// ```text
+/// [("if" "(" expr ")" | "ifnone")] "(" [edge] inputs [+|-] ("=>"|"*>") outputs ")" "=" delays
+/// [("if" "(" expr ")" | "ifnone")] "(" [edge] inputs [+|-] ("=>"|"*>") "(" outputs [+|-] ":" expr ")" ")" "=" delays
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct PathDecl {
+	pub span: Span,
+	pub cond: Option<PathCond>,
+	pub edge: EdgeIdent,
+	pub inputs: Vec<Expr>,
+	pub polarity: Option<PathPolarity>,
+	/// Whether this is a full connection `*>` rather than a parallel
+	/// connection `=>`.
+	pub full: bool,
+	pub outputs: Vec<Expr>,
+	/// The data source expression of an edge-sensitive path, together with
+	/// its polarity.
+	pub data_source: Option<(Option<PathPolarity>, Expr)>,
+	/// The 1, 2, 3, 6, or 12 path delay values.
+	pub delays: Vec<Expr>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum PathCond {
+	If(Expr),
+	Ifnone,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum PathPolarity {
+	Positive,
+	Negative,
+}
+
+/// A system timing check such as `$setup` or `$width`, as per IEEE 1800-2009
+/// section 31.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct TimingCheck {
+	pub span: Span,
+	pub kind: TimingCheckKind,
+	/// The reference and data events, in the order they appear in the source.
+	/// `$period` and `$width` only have a reference event.
+	pub events: Vec<TimingCheckEvent>,
+	/// The limits of the check. This also holds the threshold of `$width`
+	/// and the edge offsets of `$nochange`.
+	pub limits: Vec<Expr>,
+	pub notifier: Option<Identifier>,
+	/// The optional arguments after the notifier, e.g. the conditions and
+	/// delayed signals of `$setuphold`, or the flags of `$timeskew`.
+	pub extra: Vec<Option<Expr>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum TimingCheckKind {
+	Setup,
+	Hold,
+	SetupHold,
+	Recovery,
+	Removal,
+	RecRem,
+	Skew,
+	TimeSkew,
+	FullSkew,
+	Period,
+	Width,
+	NoChange,
+}
+
+impl TimingCheckKind {
+	pub fn as_str(&self) -> &'static str {
+		match *self {
+			TimingCheckKind::Setup => "$setup",
+			TimingCheckKind::Hold => "$hold",
+			TimingCheckKind::SetupHold => "$setuphold",
+			TimingCheckKind::Recovery => "$recovery",
+			TimingCheckKind::Removal => "$removal",
+			TimingCheckKind::RecRem => "$recrem",
+			TimingCheckKind::Skew => "$skew",
+			TimingCheckKind::TimeSkew => "$timeskew",
+			TimingCheckKind::FullSkew => "$fullskew",
+			TimingCheckKind::Period => "$period",
+			TimingCheckKind::Width => "$width",
+			TimingCheckKind::NoChange => "$nochange",
+		}
+	}
+}
+
+/// An event in a timing check, e.g. `posedge clk &&& en` or
+/// `edge [01, 10] d`.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct TimingCheckEvent {
+	pub span: Span,
+	pub edge: EdgeIdent,
+	/// The transitions listed after an `edge` keyword, e.g. `('0', '1')`.
+	pub descriptors: Vec<(char, char)>,
+	pub terminal: Expr,
+	pub cond: Option<Expr>,
+}
+
 
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -1126,7 +1986,120 @@ pub struct ModportItem {
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub enum ModportPort {
-	Port,
+	/// A list of ports with a common direction, as in `input a, .b(c[0])`.
+	Simple {
+		span: Span,
+		dir: PortDir,
+		ports: Vec<ModportSimplePort>,
+	},
+	/// A list of subroutines imported or exported through the modport.
+	Tf {
+		span: Span,
+		kind: ModportTfKind,
+		ports: Vec<ModportTfPort>,
+	},
+	/// A `clocking name` port that exposes a clocking block.
+	Clocking(Identifier),
+}
+
+/// A simple modport port. Either names an item of the interface, or declares
+/// a port `.name(expr)` that maps to an expression over the interface items.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct ModportSimplePort {
+	pub span: Span,
+	pub name: Identifier,
+	/// Whether the port is given as `.name(expr)`, which may omit the
+	/// expression.
+	pub explicit: bool,
+	pub expr: Option<Expr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum ModportTfKind {
+	Import,
+	Export,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum ModportTfPort {
+	/// A subroutine given by name, as in `import f`.
+	Name(Identifier),
+	/// A subroutine given by its prototype, as in `import function int f()`.
+	Prototype(SubroutinePrototype),
+}
+
+impl ModportTfPort {
+	/// The name of the subroutine.
+	pub fn name(&self) -> &Identifier {
+		match *self {
+			ModportTfPort::Name(ref name) => name,
+			ModportTfPort::Prototype(ref proto) => &proto.name,
+		}
+	}
+}
+
+
+
+/// A clocking block, as per IEEE 1800-2009 section 14.
+///
+/// ```text
+/// ["default"|"global"] "clocking" [ident] clocking_event ";" {clocking_item} "endclocking" [":" ident]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct ClockingDecl {
+	pub span: Span,
+	pub kind: ClockingKind,
+	pub name: Option<Identifier>,
+	pub event: EventControl,
+	pub items: Vec<ClockingItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum ClockingKind {
+	Normal,
+	Default,
+	Global,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum ClockingItem {
+	/// A `default input ... output ...;` item.
+	DefaultSkew {
+		span: Span,
+		input: Option<ClockingSkew>,
+		output: Option<ClockingSkew>,
+	},
+	/// A list of clocking signals with a common direction and skew.
+	Signals {
+		span: Span,
+		dir: ClockingDir,
+		signals: Vec<ClockingSignal>,
+	},
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum ClockingDir {
+	Input(Option<ClockingSkew>),
+	Output(Option<ClockingSkew>),
+	InputOutput(Option<ClockingSkew>, Option<ClockingSkew>),
+	Inout,
+}
+
+/// A clocking skew such as `#1step`, `negedge`, or `posedge #2`.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct ClockingSkew {
+	pub span: Span,
+	pub edge: EdgeIdent,
+	pub delay: Option<DelayControl>,
+}
+
+/// A signal in a clocking block, optionally assigned a hierarchical
+/// expression, e.g. `data = top.dut.data`.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct ClockingSignal {
+	pub span: Span,
+	pub name: Identifier,
+	pub expr: Option<Expr>,
 }
 
 
@@ -1144,6 +2117,7 @@ pub struct ParamDecl {
 	pub span: Span,
 	pub local: bool,
 	pub kind: ParamKind,
+	pub attrs: Vec<AttrInst>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -1193,6 +2167,7 @@ pub struct ContAssign {
 	pub delay: Option<Expr>,
 	pub delay_control: Option<DelayControl>,
 	pub assignments: Vec<(Expr, Expr)>,
+	pub attrs: Vec<AttrInst>,
 }
 
 
@@ -1213,9 +2188,22 @@ pub struct GenerateIf {
 	pub else_block: Option<GenerateBlock>,
 }
 
+/// A case-generate construct.
+/// IEEE 1800-2009 27.5
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub struct GenerateCase {
-	// TODO
+	pub span: Span,
+	pub expr: Expr,
+	pub items: Vec<GenerateCaseItem>,
+}
+
+/// An item of a case-generate construct. The `default` item has no
+/// expressions.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct GenerateCaseItem {
+	pub span: Span,
+	pub exprs: Vec<Expr>,
+	pub block: GenerateBlock,
 }
 
 /// A body of a generate construct. May contains hierarchy items or more
@@ -1239,6 +2227,7 @@ pub struct ParamAssignment {
 pub struct PortConn {
 	pub span: Span,
 	pub kind: PortConnKind,
+	pub attrs: Vec<AttrInst>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
@@ -1255,3 +2244,96 @@ pub enum PortConnMode {
 	Unconnected, // `.name()` case
 	Connected(Expr), // `.name(expr)` case
 }
+
+/// An attribute instance, e.g. `(* full_case, parallel_case = 1 *)`.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct AttrInst {
+	pub span: Span,
+	pub specs: Vec<AttrSpec>,
+}
+
+/// A single attribute within an attribute instance, e.g. `foo = 1`.
+#[derive(Debug, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct AttrSpec {
+	pub span: Span,
+	pub name: Identifier,
+	pub value: Option<Expr>,
+}
+
+/// A node that may carry attribute instances.
+pub trait HasAttrs {
+	/// The attribute instances attached to this node, in source order.
+	fn attrs(&self) -> &[AttrInst];
+
+	/// Find an attribute by name. If the attribute is given multiple times,
+	/// the last occurrence wins.
+	fn find_attr(&self, name: &str) -> Option<&AttrSpec> {
+		self.attrs().iter().rev()
+			.flat_map(|inst| inst.specs.iter().rev())
+			.find(|spec| &*spec.name.name.as_str() == name)
+	}
+
+	/// Check whether an attribute with the given name is present.
+	fn has_attr(&self, name: &str) -> bool {
+		self.find_attr(name).is_some()
+	}
+}
+
+macro_rules! impl_has_attrs {
+	($($name:ident),*) => {
+		$(impl HasAttrs for $name {
+			fn attrs(&self) -> &[AttrInst] {
+				&self.attrs
+			}
+		})*
+	}
+}
+
+impl_has_attrs!(ModDecl, IntfDecl, PackageDecl, Stmt, Expr, VarDecl, NetDecl,
+	PortDecl, Procedure, ContAssign, Inst, GateInst, ParamDecl, SubroutineDecl,
+	Typedef, PortConn);
+
+impl HasAttrs for Port {
+	fn attrs(&self) -> &[AttrInst] {
+		match *self {
+			Port::Intf{ ref attrs, .. } => attrs,
+			Port::Explicit{ ref attrs, .. } => attrs,
+			Port::Named{ ref attrs, .. } => attrs,
+			Port::Implicit(ref expr) => &expr.attrs,
+		}
+	}
+}
+
+impl HasAttrs for HierarchyItem {
+	fn attrs(&self) -> &[AttrInst] {
+		match *self {
+			HierarchyItem::ParamDecl(ref decl) => &decl.attrs,
+			HierarchyItem::Typedef(ref decl) => &decl.attrs,
+			HierarchyItem::PortDecl(ref decl) => &decl.attrs,
+			HierarchyItem::Procedure(ref prc) => &prc.attrs,
+			HierarchyItem::SubroutineDecl(ref decl) => &decl.attrs,
+			HierarchyItem::ContAssign(ref assign) => &assign.attrs,
+			HierarchyItem::NetDecl(ref decl) => &decl.attrs,
+			HierarchyItem::VarDecl(ref decl) => &decl.attrs,
+			HierarchyItem::Inst(ref inst) => &inst.attrs,
+			HierarchyItem::GateInst(ref inst) => &inst.attrs,
+			HierarchyItem::ModuleDecl(ref decl) => &decl.attrs,
+			HierarchyItem::InterfaceDecl(ref decl) => &decl.attrs,
+			_ => &[],
+		}
+	}
+}
+
+impl HasAttrs for Item {
+	fn attrs(&self) -> &[AttrInst] {
+		match *self {
+			Item::Module(ref decl) => &decl.attrs,
+			Item::Interface(ref decl) => &decl.attrs,
+			Item::ExternModule(ref decl) => &decl.attrs,
+			Item::ExternInterface(ref decl) => &decl.attrs,
+			Item::Package(ref decl) => &decl.attrs,
+			Item::Item(ref item) => item.attrs(),
+			_ => &[],
+		}
+	}
+}
@@ -0,0 +1,419 @@
+// Copyright (c) 2017 Fabian Schuiki
+
+//! This module generates C headers from DPI import and export declarations.
+//! Argument and return types are mapped as per IEEE 1800-2009 Annex H, such
+//! that the emitted prototypes can be compiled against `svdpi.h`.
+
+use std;
+use std::collections::HashMap;
+use ast::{self, NodeId};
+use resolve::NameResolution;
+use moore_common::errors::*;
+use moore_common::name::*;
+use moore_common::source::*;
+
+
+/// Generate a C header declaring all DPI imports and exports in `asts`. The
+/// `guard` is used as the name of the include guard macro. Names of typedefs
+/// are looked up in `nameres`, which must have been produced from `asts`.
+pub fn generate_header(asts: &[ast::Root], nameres: &NameResolution, guard: &str) -> Result<String, ()> {
+	let mut gen = HeaderGen {
+		severity: Severity::Note,
+		imports: Vec::new(),
+		exports: Vec::new(),
+		seen: HashMap::new(),
+		nameres: nameres,
+		typedefs: HashMap::new(),
+	};
+	for ast in asts {
+		for item in &ast.items {
+			let items = match *item {
+				ast::Item::Module(ref decl) => &decl.items,
+				ast::Item::Interface(ref decl) => &decl.items,
+				ast::Item::Package(ref decl) => &decl.items,
+				ast::Item::Item(ast::HierarchyItem::Typedef(ref td)) => {
+					gen.typedefs.insert(td.name.id, td);
+					continue;
+				}
+				_ => continue,
+			};
+			for item in items {
+				if let ast::HierarchyItem::Typedef(ref td) = *item {
+					gen.typedefs.insert(td.name.id, td);
+				}
+			}
+		}
+	}
+	for ast in asts {
+		let mut root_items = Vec::new();
+		for item in &ast.items {
+			match *item {
+				ast::Item::Module(ref decl) => gen.collect(&decl.items.iter().collect::<Vec<_>>()),
+				ast::Item::Interface(ref decl) => gen.collect(&decl.items.iter().collect::<Vec<_>>()),
+				ast::Item::Package(ref decl) => gen.collect(&decl.items.iter().collect::<Vec<_>>()),
+				ast::Item::Item(ref item) => root_items.push(item),
+				_ => (),
+			}
+		}
+		gen.collect(&root_items);
+	}
+	if gen.severity >= Severity::Error {
+		return Err(());
+	}
+
+	let mut out = String::new();
+	out.push_str("/* Generated by moore. Do not edit. */\n");
+	out.push_str(&format!("#ifndef {0}\n#define {0}\n\n", guard));
+	out.push_str("#include \"svdpi.h\"\n\n");
+	out.push_str("#ifdef __cplusplus\nextern \"C\" {\n#endif\n");
+	if !gen.imports.is_empty() {
+		out.push_str("\n/* Imported functions and tasks */\n");
+		for line in &gen.imports {
+			out.push_str(line);
+			out.push('\n');
+		}
+	}
+	if !gen.exports.is_empty() {
+		out.push_str("\n/* Exported functions and tasks */\n");
+		for line in &gen.exports {
+			out.push_str(line);
+			out.push('\n');
+		}
+	}
+	out.push_str("\n#ifdef __cplusplus\n}\n#endif\n\n");
+	out.push_str(&format!("#endif /* {} */\n", guard));
+	Ok(out)
+}
+
+
+struct HeaderGen<'a> {
+	severity: Severity,
+	imports: Vec<String>,
+	exports: Vec<String>,
+	/// The prototypes emitted so far, indexed by C name. Used to drop
+	/// duplicate declarations and detect conflicting ones.
+	seen: HashMap<Name, (String, Span)>,
+	nameres: &'a NameResolution,
+	/// The typedefs, indexed by the node ID of their name.
+	typedefs: HashMap<NodeId, &'a ast::Typedef>,
+}
+
+/// A subroutine argument, either from an ANSI-style port list or from a port
+/// declaration within the subroutine body.
+struct Arg<'a> {
+	span: Span,
+	dir: ast::SubroutinePortDir,
+	ty: &'a ast::Type,
+	name: Option<Name>,
+	dims: &'a [ast::TypeDim],
+}
+
+/// The C representation of a SystemVerilog type at the DPI boundary.
+enum CType {
+	/// A value passed directly, such as `int` or `svBit`.
+	Value(String),
+	/// A packed array passed as a pointer to its canonical representation,
+	/// such as `svBitVecVal`.
+	Vector(&'static str),
+}
+
+impl<'a> HeaderGen<'a> {
+	fn add_diag(&mut self, diag: DiagBuilder2) {
+		self.severity = std::cmp::max(self.severity, diag.severity);
+		println!("{}", diag);
+	}
+
+	/// Collect the DPI declarations in a list of items. Exports are matched
+	/// against the subroutines declared in the same list.
+	fn collect(&mut self, items: &[&ast::HierarchyItem]) {
+		for item in items {
+			if let ast::HierarchyItem::DpiDecl(ref decl) = **item {
+				match *decl {
+					ast::DpiDecl::Import{ref prototype, ..} => self.collect_import(decl, prototype),
+					ast::DpiDecl::Export{kind, ref name, ..} => self.collect_export(decl, kind, name, items),
+				}
+			}
+		}
+	}
+
+	fn collect_import(&mut self, decl: &ast::DpiDecl, prototype: &ast::SubroutinePrototype) {
+		let args = prototype_args(prototype);
+		if let Ok(line) = self.prototype(decl.c_name(), prototype, &args) {
+			if self.is_new(decl.c_name(), &line, decl.span()) {
+				self.imports.push(line);
+			}
+		}
+	}
+
+	fn collect_export(&mut self, decl: &ast::DpiDecl, kind: ast::SubroutineKind, name: &ast::Identifier, items: &[&ast::HierarchyItem]) {
+		let sub = items.iter().filter_map(|item| match **item {
+			ast::HierarchyItem::SubroutineDecl(ref sd) if sd.prototype.name.name == name.name => Some(sd),
+			_ => None,
+		}).next();
+		let sub = match sub {
+			Some(sd) => sd,
+			None => {
+				self.add_diag(DiagBuilder2::error(format!("no function or task `{}` declared in this scope", name.name)).span(name.span));
+				return;
+			}
+		};
+		if sub.prototype.kind != kind {
+			self.add_diag(DiagBuilder2::error(format!("`{}` is exported as a {}, but declared as a {}", name.name, kind_str(kind), kind_str(sub.prototype.kind)))
+				.span(name.span)
+				.add_note("declared here:")
+				.span(sub.prototype.name.span));
+			return;
+		}
+
+		// The arguments are either given in the prototype, or declared within
+		// the subroutine body.
+		let args = if sub.prototype.args.is_empty() {
+			body_args(&sub.items)
+		} else {
+			prototype_args(&sub.prototype)
+		};
+		if let Ok(line) = self.prototype(decl.c_name(), &sub.prototype, &args) {
+			if self.is_new(decl.c_name(), &line, decl.span()) {
+				self.exports.push(line);
+			}
+		}
+	}
+
+	/// Check whether a prototype has not been emitted yet. Complains if a
+	/// different prototype with the same C name has been emitted before.
+	fn is_new(&mut self, name: Name, line: &str, span: Span) -> bool {
+		let prev = match self.seen.get(&name) {
+			Some(&(ref prev, sp)) => Some((prev == line, sp)),
+			None => None,
+		};
+		match prev {
+			Some((true, _)) => false,
+			Some((false, sp)) => {
+				self.add_diag(DiagBuilder2::error(format!("conflicting DPI declarations of C function `{}`", name))
+					.span(span)
+					.add_note("previous declaration was here:")
+					.span(sp));
+				false
+			}
+			None => {
+				self.seen.insert(name, (line.to_owned(), span));
+				true
+			}
+		}
+	}
+
+	/// Generate the C prototype of a subroutine.
+	fn prototype(&mut self, name: Name, prototype: &ast::SubroutinePrototype, args: &[Arg]) -> Result<String, ()> {
+		// Map the return type. Tasks return an int that indicates whether
+		// they have been disabled.
+		let ret = match (prototype.kind, prototype.retty.as_ref()) {
+			(ast::SubroutineKind::Task, _) => String::from("int"),
+			(ast::SubroutineKind::Func, None) => String::from("void"),
+			(ast::SubroutineKind::Func, Some(ty)) => match ty.data {
+				ast::VoidType => String::from("void"),
+				_ => match self.map_type(ty)? {
+					CType::Value(s) => s,
+					CType::Vector(_) => {
+						self.add_diag(DiagBuilder2::error(format!("packed arrays cannot be returned by DPI function `{}`", prototype.name.name)).span(ty.span));
+						return Err(());
+					}
+				},
+			},
+		};
+
+		// Map the arguments.
+		let mut cargs = Vec::new();
+		for arg in args {
+			let cty = self.map_arg(arg)?;
+			cargs.push(match arg.name {
+				Some(n) => format!("{} {}", cty, n),
+				None => cty,
+			});
+		}
+		if cargs.is_empty() {
+			cargs.push(String::from("void"));
+		}
+		Ok(format!("extern {} {}({});", ret, name, cargs.join(", ")))
+	}
+
+	/// Map an argument to its C type, taking the direction and unpacked
+	/// dimensions into account.
+	fn map_arg(&mut self, arg: &Arg) -> Result<String, ()> {
+		let input = match arg.dir {
+			ast::SubroutinePortDir::Input => true,
+			ast::SubroutinePortDir::Output |
+			ast::SubroutinePortDir::Inout => false,
+			ast::SubroutinePortDir::Ref |
+			ast::SubroutinePortDir::ConstRef => {
+				self.add_diag(DiagBuilder2::error("ref arguments cannot be passed through DPI").span(arg.span));
+				return Err(());
+			}
+		};
+		let cty = self.map_type(arg.ty)?;
+
+		// Unsized unpacked arrays are passed as open array handles, sized ones
+		// as a pointer to their first element.
+		if !arg.dims.is_empty() {
+			for dim in arg.dims {
+				match *dim {
+					ast::TypeDim::Expr(_) |
+					ast::TypeDim::Range(..) |
+					ast::TypeDim::Unsized => (),
+					ast::TypeDim::Queue |
+					ast::TypeDim::Associative => {
+						self.add_diag(DiagBuilder2::error("queues and associative arrays cannot be passed through DPI").span(arg.span));
+						return Err(());
+					}
+				}
+			}
+			let open = arg.dims.iter().any(|d| *d == ast::TypeDim::Unsized);
+			let constness = if input { "const " } else { "" };
+			return Ok(match (open, cty) {
+				(true, _) => format!("{}svOpenArrayHandle", constness),
+				(false, CType::Value(s)) => format!("{}{}*", constness, s),
+				(false, CType::Vector(s)) => format!("{}{}*", constness, s),
+			});
+		}
+
+		Ok(match (input, cty) {
+			(true, CType::Value(s)) => s,
+			(false, CType::Value(s)) => format!("{}*", s),
+			(true, CType::Vector(s)) => format!("const {}*", s),
+			(false, CType::Vector(s)) => format!("{}*", s),
+		})
+	}
+
+	/// Map a SystemVerilog type to its C counterpart as per IEEE 1800-2009
+	/// Annex H.7.
+	fn map_type(&mut self, ty: &ast::Type) -> Result<CType, ()> {
+		let unsigned = ty.sign == ast::TypeSign::Unsigned;
+		let value = |s: &str, u: &str| Ok(CType::Value(String::from(if unsigned { u } else { s })));
+		let packed = !ty.dims.is_empty();
+		match ty.data {
+			ast::BitType if packed => Ok(CType::Vector("svBitVecVal")),
+			ast::LogicType |
+			ast::RegType |
+			ast::ImplicitType if packed => Ok(CType::Vector("svLogicVecVal")),
+			ast::TimeType => Ok(CType::Vector("svLogicVecVal")),
+			ast::ByteType |
+			ast::ShortIntType |
+			ast::IntType |
+			ast::LongIntType if packed => Ok(CType::Vector("svBitVecVal")),
+			ast::BitType => value("svBit", "svBit"),
+			ast::LogicType |
+			ast::RegType |
+			ast::ImplicitType => value("svLogic", "svLogic"),
+			ast::ByteType => value("char", "unsigned char"),
+			ast::ShortIntType => value("short int", "unsigned short int"),
+			ast::IntType => value("int", "unsigned int"),
+			ast::LongIntType => value("long long", "unsigned long long"),
+			ast::ShortRealType if !packed => value("float", "float"),
+			ast::RealType |
+			ast::RealtimeType if !packed => value("double", "double"),
+			ast::ChandleType if !packed => value("void*", "void*"),
+			ast::StringType if !packed => value("const char*", "const char*"),
+			ast::EnumType(ref base, _) if !packed => match *base {
+				Some(ref base) => self.map_type(base),
+				None => value("int", "int"),
+			},
+			ast::NamedType(_) |
+			ast::ScopedType { member: false, .. } => match self.resolve_typedef(ty) {
+				Some(resolved) => self.map_type(&resolved),
+				None => Err(()),
+			},
+			_ => {
+				self.add_diag(DiagBuilder2::error("type cannot be mapped to C; DPI supports only built-in types").span(ty.span));
+				Err(())
+			}
+		}
+	}
+
+	/// Replace a reference to a typedef with the built-in type it stands for,
+	/// following typedefs of typedefs. Signing and packed dimensions given at
+	/// the reference are carried over. Names are looked up as resolved in the
+	/// scope of the reference, taking imports into account. Reports an error
+	/// and yields `None` for names that are not visible, classes, and typedefs
+	/// of unpacked arrays.
+	fn resolve_typedef(&mut self, ty: &ast::Type) -> Option<ast::Type> {
+		let mut resolved = ty.clone();
+		for _ in 0..MAX_TYPEDEF_DEPTH {
+			let ident = match resolved.data {
+				ast::NamedType(ref ident) |
+				ast::ScopedType { name: ref ident, member: false, .. } => ident.clone(),
+				_ => return Some(resolved),
+			};
+			let td = match self.nameres.get(&ident.id) {
+				Some(id) => self.typedefs.get(id).cloned(),
+				None => {
+					self.add_diag(DiagBuilder2::error(format!("`{}` is not visible here", ident.name)).span(ident.span));
+					return None;
+				}
+			};
+			let td = match td {
+				Some(td) if td.dims.is_empty() => td,
+				_ => {
+					self.add_diag(DiagBuilder2::error(format!("`{}` cannot be mapped to C; DPI supports only built-in types and typedefs thereof", ident.name))
+						.span(ty.span));
+					return None;
+				}
+			};
+			let mut next = td.ty.clone();
+			next.span = ty.span;
+			next.dims.extend(resolved.dims.iter().cloned());
+			if resolved.sign != ast::TypeSign::None {
+				next.sign = resolved.sign;
+			}
+			resolved = next;
+		}
+		self.add_diag(DiagBuilder2::error(format!("typedefs of `{}` refer to each other", ty.span.extract())).span(ty.span));
+		None
+	}
+}
+
+/// The maximum number of typedefs followed when resolving a type. This guards
+/// against typedefs that refer to each other.
+const MAX_TYPEDEF_DEPTH: usize = 64;
+
+fn kind_str(kind: ast::SubroutineKind) -> &'static str {
+	match kind {
+		ast::SubroutineKind::Func => "function",
+		ast::SubroutineKind::Task => "task",
+	}
+}
+
+/// Gather the arguments of an ANSI-style port list. Ports without explicit
+/// direction inherit the direction of the previous port.
+fn prototype_args(prototype: &ast::SubroutinePrototype) -> Vec<Arg> {
+	let mut dir = ast::SubroutinePortDir::Input;
+	prototype.args.iter().map(|port| {
+		if let Some(d) = port.dir {
+			dir = d;
+		}
+		Arg {
+			span: port.span,
+			dir: dir,
+			ty: &port.ty,
+			name: port.name.as_ref().map(|n| n.name.name),
+			dims: port.name.as_ref().map(|n| &n.dims[..]).unwrap_or(&[]),
+		}
+	}).collect()
+}
+
+/// Gather the arguments declared within a subroutine body.
+fn body_args(items: &[ast::SubroutineItem]) -> Vec<Arg> {
+	let mut args = Vec::new();
+	for item in items {
+		if let ast::SubroutineItem::PortDecl(ref decl) = *item {
+			for name in &decl.names {
+				args.push(Arg {
+					span: name.span,
+					dir: decl.dir,
+					ty: &decl.ty,
+					name: Some(name.name),
+					dims: &name.dims,
+				});
+			}
+		}
+	}
+	args
+}
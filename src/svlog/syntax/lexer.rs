@@ -251,6 +251,21 @@ impl<'a> Lexer<'a> {
 					};
 				},
 
+				// Compiler directives that the preprocessor passes on, such as
+				// "`timescale", are introduced with a backtick.
+				// IEEE 1800-2009 22 Compiler directives
+				(CatTokenKind::Symbol('`'), sp) => {
+					self.bump()?;
+					return match self.peek[0].0 {
+						CatTokenKind::Text |
+						CatTokenKind::Symbol('_') => {
+							let (m, msp) = self.match_ident()?;
+							Ok((CompDir(name_table.intern(&m, true)), Span::union(sp,msp)))
+						}
+						_ => Err(DiagBuilder2::fatal("Expected compiler directive after '`'").span(sp))
+					};
+				}
+
 				// Escaped identifiers are introduced with a backslash and last
 				// until the next whitespace or newline character.
 				// IEEE 1800-2009 5.6.1 Escaped identifiers
@@ -286,6 +301,50 @@ impl<'a> Lexer<'a> {
 						let mut s = String::new();
 						s.push_str(&sp.extract());
 						self.bump()?; // eat the digits that were pushed onto the string above
+						self.eat_digits_into(&mut s, &mut sp)?;
+
+						// A period followed by digits introduces the
+						// fractional part of a real number, an `e` the
+						// exponent.
+						// IEEE 1800-2009 5.7.2 Real literal constants
+						let mut real = false;
+						if let ((CatTokenKind::Symbol('.'), psp), (CatTokenKind::Digits, _)) = (self.peek[0], self.peek[1]) {
+							s.push('.');
+							sp.expand(psp);
+							self.bump()?;
+							self.eat_digits_into(&mut s, &mut sp)?;
+							real = true;
+						}
+						if self.try_exponent_into(&mut s, &mut sp)? {
+							real = true;
+						}
+
+						// Digits immediately followed by a time unit form a
+						// time literal, e.g. `10ns`, `1.5ns`, or `1step`.
+						// IEEE 1800-2009 5.8 Time literals
+						if let (CatTokenKind::Text, usp) = self.peek[0] {
+							let unit = usp.extract();
+							if is_time_unit(&unit) {
+								s.push_str(&unit);
+								sp.expand(usp);
+								self.bump()?;
+								return Ok((Literal(Time(name_table.intern(&s, true))), sp));
+							}
+						}
+						if real {
+							return Ok((Literal(Real(name_table.intern(&s, true))), sp));
+						}
+
+						// Digits immediately followed by `x` or `z` levels, as
+						// in the edges `(0x)` of a UDP table or `edge [1x]` of
+						// a timing check, end here. The parser splits such
+						// sequences into individual level symbols.
+						if let (CatTokenKind::Text, tsp) = self.peek[0] {
+							if is_level_symbols(&tsp.extract()) {
+								return Ok((Literal(UnsignedInteger(name_table.intern(&s, true))), sp));
+							}
+						}
+
 						self.eat_number_body_into(&mut s, &mut sp, false)?;
 						name_table.intern(&s, true)
 					};
@@ -468,6 +527,55 @@ impl<'a> Lexer<'a> {
 
 	/// Eats all text, digits, and underscore tokens, accumulating them (except
 	/// for the underscores) in a String.
+	/// Eats a sequence of decimal digits and underscores, and accumulates the
+	/// digits into a string.
+	fn eat_digits_into(&mut self, into: &mut String, span: &mut Span) -> DiagResult2<()> {
+		loop {
+			match self.peek[0] {
+				(CatTokenKind::Digits, sp) => {
+					into.push_str(&sp.extract());
+					span.expand(sp);
+				}
+				(CatTokenKind::Symbol('_'), _) => (),
+				_ => break
+			}
+			self.bump()?;
+		}
+		Ok(())
+	}
+
+	/// Eats the exponent of a real number, e.g. `e3` or `E-12`, if one is
+	/// present. Returns whether an exponent was found.
+	fn try_exponent_into(&mut self, into: &mut String, span: &mut Span) -> DiagResult2<bool> {
+		let (tkn, sp) = self.peek[0];
+		if tkn != CatTokenKind::Text {
+			return Ok(false);
+		}
+		let text = sp.extract();
+		if !text.starts_with('e') && !text.starts_with('E') {
+			return Ok(false);
+		}
+		if text.len() > 1 && text[1..].chars().all(|c| c >= '0' && c <= '9') {
+			into.push_str(&text);
+			span.expand(sp);
+			self.bump()?;
+		} else if text.len() == 1 {
+			let sign = match (self.peek[1].0, self.peek[2].0) {
+				(CatTokenKind::Symbol(c), CatTokenKind::Digits) if c == '+' || c == '-' => c,
+				_ => return Ok(false),
+			};
+			into.push_str(&text);
+			into.push(sign);
+			span.expand(self.peek[1].1);
+			self.bump()?;
+			self.bump()?;
+		} else {
+			return Ok(false);
+		}
+		self.eat_digits_into(into, span)?;
+		Ok(true)
+	}
+
 	fn eat_number_body_into(&mut self, into: &mut String, span: &mut Span, allow_alphabetic: bool) -> DiagResult2<()> {
 		loop {
 			match self.peek[0] {
@@ -504,6 +612,26 @@ impl<'a> Iterator for Lexer<'a> {
 }
 
 
+/// Check whether a string consists of the level symbols `0`, `1`, `x`, and
+/// `z` only.
+fn is_level_symbols(s: &str) -> bool {
+	s.chars().all(|c| match c {
+		'0' | '1' | 'x' | 'X' | 'z' | 'Z' => true,
+		_ => false,
+	})
+}
+
+
+/// Check whether a string is a valid time unit, or the special `step` unit of
+/// clocking skews.
+fn is_time_unit(s: &str) -> bool {
+	match s {
+		"s" | "ms" | "us" | "ns" | "ps" | "fs" | "step" => true,
+		_ => false,
+	}
+}
+
+
 
 #[cfg(test)]
 mod tests {
@@ -603,6 +731,17 @@ mod tests {
 		]);
 	}
 
+	/// Level symbols following digits, as in edges, are separate tokens.
+	#[test]
+	fn digits_followed_by_levels() {
+		check(
+			"(0x) 1X 01z", &[
+			OpenDelim(Paren), Literal(UnsignedInteger(name("0"))), Ident(name("x")), CloseDelim(Paren),
+			Literal(UnsignedInteger(name("1"))), Ident(name("X")),
+			Literal(UnsignedInteger(name("01"))), Ident(name("z")),
+		]);
+	}
+
 	#[test]
 	#[should_panic(expected = "Unsigned number or size of literal must be a decimal")]
 	fn unsized_literal_constant_numbers_illegal() {
@@ -640,6 +779,49 @@ mod tests {
 		]);
 	}
 
+	/// According to IEEE 1800-2009 5.8
+	#[test]
+	fn time_literals() {
+		check(
+			"10ns; 1step; 40 ps", &[
+			Literal(Time(name("10ns"))), Semicolon,
+			Literal(Time(name("1step"))), Semicolon,
+			Literal(UnsignedInteger(name("40"))), Ident(name("ps")),
+		]);
+		check(
+			"1.5ns 2.25 1_000ps", &[
+			Literal(Time(name("1.5ns"))),
+			Literal(Real(name("2.25"))),
+			Literal(Time(name("1000ps"))),
+		]);
+	}
+
+	/// According to IEEE 1800-2009 5.7.2
+	#[test]
+	fn real_literals() {
+		check(
+			"1.2 0.1 2394.26331 1.2E12 1.30e-2 0.1e-0 23E10 29E-2 236.123_763_e-12", &[
+			Literal(Real(name("1.2"))),
+			Literal(Real(name("0.1"))),
+			Literal(Real(name("2394.26331"))),
+			Literal(Real(name("1.2E12"))),
+			Literal(Real(name("1.30e-2"))),
+			Literal(Real(name("0.1e-0"))),
+			Literal(Real(name("23E10"))),
+			Literal(Real(name("29E-2"))),
+			Literal(Real(name("236.123763e-12"))),
+		]);
+	}
+
+	#[test]
+	fn compiler_directive() {
+		check(
+			"`timescale 1ns / 1ps", &[
+			CompDir(name("timescale")),
+			Literal(Time(name("1ns"))), Operator(Op::Div), Literal(Time(name("1ps"))),
+		]);
+	}
+
 	/// According to IEEE 1800-2009 5.9
 	#[test]
 	fn multiline_string_literal() {
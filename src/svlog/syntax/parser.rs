@@ -179,6 +179,12 @@ struct Parser<'a> {
 	last_span: Span,
 	severity: Severity,
 	consumed: usize,
+	/// The "`timescale" directives encountered so far, together with the
+	/// index of the first token they apply to.
+	timescales: Vec<(usize, ast::Timescale)>,
+	/// The "`default_nettype" directives encountered so far, together with
+	/// the index of the first token they apply to.
+	default_nettypes: Vec<(usize, ast::DefaultNettype)>,
 }
 
 impl<'a> AbstractParser for Parser<'a> {
@@ -239,6 +245,8 @@ impl<'a> Parser<'a> {
 			last_span: INVALID_SPAN,
 			severity: Severity::Note,
 			consumed: 0,
+			timescales: Vec::new(),
+			default_nettypes: Vec::new(),
 		}
 	}
 
@@ -249,11 +257,102 @@ impl<'a> Parser<'a> {
 		while self.queue.len() <= min_tokens {
 			match self.input.next_token() {
 				Ok((Eof, sp)) => self.queue.push_back((Eof, sp)),
+				Ok((CompDir(name), sp)) => self.handle_directive(name, sp),
 				Ok(tkn) => self.queue.push_back(tkn),
 				Err(x) => self.add_diag(x),
 			}
 		}
 	}
+
+	/// Handle a compiler directive passed on by the preprocessor. Directives
+	/// are not part of the token stream, but affect the items that follow.
+	fn handle_directive(&mut self, name: Name, span: Span) {
+		match &*name.as_str() {
+			"timescale" => self.handle_timescale(span),
+			"default_nettype" => self.handle_default_nettype(span),
+			_ => self.add_diag(DiagBuilder2::error(format!("Compiler directive `{} cannot appear here", name)).span(span)),
+		}
+	}
+
+	/// Handle a "`timescale" directive.
+	fn handle_timescale(&mut self, span: Span) {
+		let unit = self.eat_directive_time_value();
+		match self.input.next_token() {
+			Ok((Operator(Op::Div), _)) => (),
+			Ok((tkn, sp)) => {
+				self.add_diag(DiagBuilder2::error(format!("Expected `/` between unit and precision of `timescale, found {} instead", tkn)).span(sp));
+				self.queue.push_back((tkn, sp));
+				return;
+			}
+			Err(x) => { self.add_diag(x); return; }
+		}
+		let prec = self.eat_directive_time_value();
+		if let (Some(unit), Some(prec)) = (unit, prec) {
+			let index = self.consumed + self.queue.len();
+			self.timescales.push((index, ast::Timescale {
+				span: Span::union(span, prec.span),
+				unit: unit,
+				prec: prec,
+			}));
+		}
+	}
+
+	/// Handle a "`default_nettype" directive, which sets the type of the
+	/// implicit nets in the design elements that follow.
+	/// IEEE 1800-2009 22.8
+	fn handle_default_nettype(&mut self, span: Span) {
+		let (tkn, sp) = match self.input.next_token() {
+			Ok(x) => x,
+			Err(x) => { self.add_diag(x); return; }
+		};
+		let net_type = match tkn {
+			Ident(name) if &*name.as_str() == "none" => None,
+			_ => match as_net_type(tkn) {
+				Some(NetType::Supply0) | Some(NetType::Supply1) | Some(NetType::Interconnect) | Some(NetType::User) | None => {
+					self.add_diag(DiagBuilder2::error(format!("Expected a net type or `none` after `default_nettype, found {} instead", tkn)).span(sp));
+					return;
+				}
+				x => x,
+			}
+		};
+		let index = self.consumed + self.queue.len();
+		self.default_nettypes.push((index, ast::DefaultNettype {
+			span: Span::union(span, sp),
+			net_type: net_type,
+		}));
+	}
+
+	/// Eat the unit or precision of a "`timescale" directive.
+	fn eat_directive_time_value(&mut self) -> Option<ast::TimeValue> {
+		let first = match self.input.next_token() {
+			Ok(x) => x,
+			Err(x) => { self.add_diag(x); return None; }
+		};
+		let second = match first.0 {
+			Literal(UnsignedInteger(_)) => match self.input.next_token() {
+				Ok(x) => Some(x),
+				Err(x) => { self.add_diag(x); return None; }
+			},
+			_ => None,
+		};
+		match as_time_value(first, second) {
+			Ok(v) => Some(v),
+			Err(d) => {
+				self.add_diag(d);
+				None
+			}
+		}
+	}
+
+	/// The "`timescale" directive in effect at the current token.
+	fn timescale(&self) -> Option<ast::Timescale> {
+		self.timescales.iter().rev().find(|&&(index, _)| index <= self.consumed).map(|&(_, ts)| ts)
+	}
+
+	/// The "`default_nettype" directive in effect at the current token.
+	fn default_nettype(&self) -> Option<ast::DefaultNettype> {
+		self.default_nettypes.iter().rev().find(|&&(index, _)| index <= self.consumed).map(|&(_, nt)| nt)
+	}
 }
 
 
@@ -426,6 +525,47 @@ fn try_identifier(p: &mut AbstractParser) -> ReportedResult<Option<ast::Identifi
 }
 
 
+/// Parse any number of attribute instances. The attribute values are parsed
+/// at a precedence that excludes `*`, such that the closing `*)` is not taken
+/// to be a multiplication; more complex values need to be parenthesized.
+///
+/// ```text
+/// attribute_instance: "(*" attr_spec {"," attr_spec} "*)"
+/// attr_spec: ident ["=" expr]
+/// ```
+fn try_attrs(p: &mut AbstractParser) -> ReportedResult<Vec<ast::AttrInst>> {
+	let mut attrs = Vec::new();
+	// Make sure not to confuse the `@(*)` event control with an attribute.
+	while p.peek(0).0 == OpenDelim(Paren) && p.peek(1).0 == Operator(Op::Mul) && p.peek(2).0 != CloseDelim(Paren) {
+		let mut span = p.peek(0).1;
+		p.bump();
+		p.bump();
+		let specs = comma_list_nonempty(p, Operator(Op::Mul), "attribute", |p|{
+			let name = parse_identifier(p, "attribute name")?;
+			let value = if p.try_eat(Operator(Op::Assign)) {
+				Some(parse_expr_prec(p, Precedence::Pow)?)
+			} else {
+				None
+			};
+			let span = Span::union(name.span, p.last_span());
+			Ok(ast::AttrSpec {
+				span: span,
+				name: name,
+				value: value,
+			})
+		})?;
+		p.require_reported(Operator(Op::Mul))?;
+		p.require_reported(CloseDelim(Paren))?;
+		span.expand(p.last_span());
+		attrs.push(ast::AttrInst {
+			span: span,
+			specs: specs,
+		});
+	}
+	Ok(attrs)
+}
+
+
 trait Predicate {
 	fn matches(&mut self, &mut AbstractParser) -> bool;
 	fn recover(&mut self, &mut AbstractParser, consume: bool);
@@ -479,15 +619,21 @@ pub fn parse(input: Lexer) -> Result<Root, ()> {
 
 fn parse_source_text(p: &mut Parser) -> Root {
 	let mut root = Root {
-		timeunits: None,
+		timeunits: Timeunit::default(),
 		items: Vec::new(),
 	};
 
-	// Parse the optional timeunits declaration.
-	// TODO
-
 	// Parse the descriptions in the source text.
 	while !p.is_fatal() && p.peek(0).0 != Eof {
+		let leading = root.items.is_empty();
+		match try_timeunits_decl(p, &mut root.timeunits, leading) {
+			Ok(true) => continue,
+			Ok(false) => (),
+			Err(()) => {
+				p.recover_balanced(&[Semicolon], true);
+				continue;
+			}
+		}
 		match parse_item(p) {
 			Ok(item) => root.items.push(item),
 			Err(()) => () // parse_item handles recovery, so no need to do anything here
@@ -499,29 +645,64 @@ fn parse_source_text(p: &mut Parser) -> Root {
 
 
 fn parse_item(p: &mut Parser) -> ReportedResult<ast::Item> {
+	let attrs = try_attrs(p)?;
 	let (tkn,sp) = p.peek(0);
 	match tkn {
-		Keyword(Kw::Module) => parse_module_decl(p).map(|d| ast::Item::Module(d)),
-		Keyword(Kw::Interface) => parse_interface_decl(p).map(|d| ast::Item::Interface(d)),
-		Keyword(Kw::Package) => parse_package_decl(p).map(|d| ast::Item::Package(d)),
+		Keyword(Kw::Module) => {
+			let timescale = p.timescale();
+			let nettype = p.default_nettype();
+			parse_module_decl(p, timescale).map(|d| ast::Item::Module(ModDecl { attrs: attrs, default_nettype: nettype, ..d }))
+		}
+		Keyword(Kw::Interface) if p.peek(1).0 != Keyword(Kw::Class) => {
+			let timescale = p.timescale();
+			let nettype = p.default_nettype();
+			parse_interface_decl(p, timescale).map(|d| ast::Item::Interface(IntfDecl { attrs: attrs, default_nettype: nettype, ..d }))
+		}
+		Keyword(Kw::Extern) => {
+			let timescale = p.timescale();
+			parse_extern_decl(p, timescale, attrs)
+		}
+		Keyword(Kw::Package) => parse_package_decl(p).map(|d| ast::Item::Package(PackageDecl { attrs: attrs, ..d })),
+		_ if !attrs.is_empty() => {
+			let span = Span::union(attrs[0].span, attrs[attrs.len()-1].span);
+			let mut item = parse_item(p)?;
+			match item {
+				ast::Item::Item(ref mut item) => attach_item_attrs(p, item, attrs),
+				_ => p.add_diag(DiagBuilder2::warning("Attributes on this item are ignored").span(span)),
+			}
+			Ok(item)
+		}
 		// Keyword(Kw::Program) => parse_program_decl(p).map(|d| ast::Item::Program(d)),
 		Keyword(Kw::Class) => parse_class_decl(p).map(|d| ast::Item::Class(d)),
+		Keyword(Kw::Virtual) | Keyword(Kw::Interface) if p.peek(1).0 == Keyword(Kw::Class) => parse_class_decl(p).map(|d| ast::Item::Class(d)),
+		Keyword(Kw::Primitive) => parse_udp_decl(p).map(|d| ast::Item::Primitive(d)),
+		Keyword(Kw::Checker) => parse_checker_decl(p).map(|d| ast::Item::Checker(d)),
+		Keyword(Kw::Bind) => parse_bind_directive(p).map(|d| ast::Item::Bind(d)),
+		Keyword(Kw::Import) | Keyword(Kw::Export) if is_dpi_spec(p.peek(1).0) => parse_dpi_decl(p).map(|d| ast::Item::Item(HierarchyItem::DpiDecl(d))),
 		Keyword(Kw::Import) => parse_import_decl(p).map(|i| ast::Item::Item(HierarchyItem::ImportDecl(i))),
 		Keyword(Kw::Typedef) => parse_typedef(p).map(|d| ast::Item::Item(HierarchyItem::Typedef(d))),
+		Keyword(Kw::Nettype) => parse_nettype_decl(p).map(|d| ast::Item::Item(HierarchyItem::NettypeDecl(d))),
+		Keyword(Kw::Constraint) => parse_out_of_class_constraint(p).map(|d| ast::Item::Item(HierarchyItem::Constraint(d))),
+		Keyword(Kw::Static) if p.peek(1).0 == Keyword(Kw::Constraint) => parse_out_of_class_constraint(p).map(|d| ast::Item::Item(HierarchyItem::Constraint(d))),
 		// TODO: Actually according to the standard, any package_item can appear
 		// at this level of the source text. No clue how wires or variables
 		// would behave if they are declared at such a high level,. Maybe we
 		// should just accept these items here, and complain about what we do
 		// not support during lowering to HIR.
 		tkn => {
-			p.add_diag(DiagBuilder2::error(format!("Expected module, interface, package, program, class, import, or typedef, instead got `{}`", tkn)).span(sp));
+			p.add_diag(DiagBuilder2::error(format!("Expected module, interface, extern, package, program, class, primitive, checker, bind, import, typedef, nettype, or constraint, instead got `{}`", tkn)).span(sp));
 			p.recover_balanced(&[
 				Keyword(Kw::Module),
 				Keyword(Kw::Interface),
+				Keyword(Kw::Extern),
 				Keyword(Kw::Package),
 				Keyword(Kw::Program),
 				Keyword(Kw::Class),
-				Keyword(Kw::Typedef)
+				Keyword(Kw::Primitive),
+				Keyword(Kw::Checker),
+				Keyword(Kw::Bind),
+				Keyword(Kw::Typedef),
+				Keyword(Kw::Nettype)
 			], false);
 			Err(())
 		}
@@ -541,40 +722,17 @@ fn as_lifetime(tkn: Token) -> Option<Lifetime> {
 }
 
 
-fn parse_interface_decl(p: &mut Parser) -> ReportedResult<IntfDecl> {
+fn parse_interface_decl(p: &mut AbstractParser, timescale: Option<ast::Timescale>) -> ReportedResult<IntfDecl> {
 	let mut span = p.peek(0).1;
 	p.require_reported(Keyword(Kw::Interface))?;
 	let result = recovered(p, Keyword(Kw::Endinterface), |p|{
-
-		// Eat the optional lifetime.
-		let lifetime = match as_lifetime(p.peek(0).0) {
-			Some(l) => { p.bump(); l },
-			None => Lifetime::Static,
-		};
-
-		// Eat the interface name.
-		let (name, name_sp) = p.eat_ident("interface name")?;
-
-		// TODO: Parse package import declarations.
-
-		// Eat the parameter port list.
-		let param_ports = if p.try_eat(Hashtag) {
-			parse_parameter_port_list(p)?
-		} else {
-			Vec::new()
-		};
-
-		// Eat the optional list of ports.
-		let ports = if p.try_eat(OpenDelim(Paren)) {
-			parse_port_list(p)?
-		} else {
-			Vec::new()
-		};
+		let mut timeunits = Timeunit { timescale: timescale, ..Timeunit::default() };
+		let hdr = parse_design_header(p, "interface")?;
 
 		// Eat the semicolon at the end of the header.
 		if !p.try_eat(Semicolon) {
 			let q = p.peek(0).1.end();
-			p.add_diag(DiagBuilder2::error(format!("Missing semicolon \";\" after header of interface \"{}\"", name)).span(q));
+			p.add_diag(DiagBuilder2::error(format!("Missing semicolon \";\" after header of interface \"{}\"", hdr.name)).span(q));
 		}
 
 		// Eat the items in the interface.
@@ -583,6 +741,9 @@ fn parse_interface_decl(p: &mut Parser) -> ReportedResult<IntfDecl> {
 			if p.try_eat(Semicolon) {
 				continue;
 			}
+			if try_timeunits_decl(p, &mut timeunits, items.is_empty())? {
+				continue;
+			}
 			items.push(parse_hierarchy_item(p)?);
 		}
 
@@ -590,12 +751,16 @@ fn parse_interface_decl(p: &mut Parser) -> ReportedResult<IntfDecl> {
 		Ok(IntfDecl {
 			id: DUMMY_NODE_ID,
 			span: span,
-			lifetime: lifetime,
-			name: name,
-			name_span: name_sp,
-			params: param_ports,
-			ports: ports,
+			lifetime: hdr.lifetime,
+			name: hdr.name,
+			name_span: hdr.name_span,
+			timeunits: timeunits,
+			params: hdr.params,
+			ports: hdr.ports,
+			wildcard_ports: hdr.wildcard_ports,
 			items: items,
+			attrs: Vec::new(),
+			default_nettype: None,
 		})
 	});
 	p.require_reported(Keyword(Kw::Endinterface))?;
@@ -603,6 +768,122 @@ fn parse_interface_decl(p: &mut Parser) -> ReportedResult<IntfDecl> {
 }
 
 
+/// The header shared by module and interface declarations and their extern
+/// prototypes.
+struct DesignHeader {
+	lifetime: Lifetime,
+	name: Name,
+	name_span: Span,
+	params: Vec<ParamDecl>,
+	ports: Vec<Port>,
+	wildcard_ports: bool,
+}
+
+/// Parse the lifetime, name, parameter port list, and port list of a module or
+/// interface, up to but excluding the semicolon.
+fn parse_design_header(p: &mut AbstractParser, what: &str) -> ReportedResult<DesignHeader> {
+	// Eat the optional lifetime.
+	let lifetime = match as_lifetime(p.peek(0).0) {
+		Some(l) => { p.bump(); l },
+		None => Lifetime::Static,
+	};
+
+	// Eat the name.
+	let (name, name_span) = p.eat_ident(&format!("{} name", what))?;
+
+	// TODO: Parse package import declarations.
+
+	// Eat the optional parameter port list.
+	let params = if p.try_eat(Hashtag) {
+		parse_parameter_port_list(p)?
+	} else {
+		Vec::new()
+	};
+
+	// Eat the optional list of ports. Not having such a list requires the ports
+	// to be defined further down in the body. A `(.*)` port list refers to the
+	// ports of the corresponding extern declaration.
+	let mut wildcard_ports = false;
+	let ports = if p.peek(0).0 == OpenDelim(Paren) && p.peek(1).0 == Period && p.peek(2).0 == Operator(Op::Mul) {
+		p.bump();
+		p.bump();
+		p.bump();
+		p.require_reported(CloseDelim(Paren))?;
+		wildcard_ports = true;
+		Vec::new()
+	} else if p.try_eat(OpenDelim(Paren)) {
+		parse_port_list(p)?
+	} else {
+		Vec::new()
+	};
+
+	Ok(DesignHeader {
+		lifetime: lifetime,
+		name: name,
+		name_span: name_span,
+		params: params,
+		ports: ports,
+		wildcard_ports: wildcard_ports,
+	})
+}
+
+
+/// Parse an `extern module` or `extern interface` prototype. The header is
+/// recorded as a declaration without any items.
+/// IEEE 1800-2009 23.2.1
+fn parse_extern_decl(p: &mut AbstractParser, timescale: Option<ast::Timescale>, attrs: Vec<ast::AttrInst>) -> ReportedResult<ast::Item> {
+	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Extern))?;
+	let (tkn, sp) = p.peek(0);
+	let what = match tkn {
+		Keyword(Kw::Module) => "module",
+		Keyword(Kw::Interface) => "interface",
+		_ => {
+			p.add_diag(DiagBuilder2::error(format!("Expected `module` or `interface` after `extern`, instead got `{}`", tkn)).span(sp));
+			return Err(());
+		}
+	};
+	p.bump();
+	let hdr = parse_design_header(p, what)?;
+	if hdr.wildcard_ports {
+		p.add_diag(DiagBuilder2::error(format!("extern {} `{}` must list its ports explicitly", what, hdr.name)).span(hdr.name_span));
+	}
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+	let timeunits = Timeunit { timescale: timescale, ..Timeunit::default() };
+	Ok(match tkn {
+		Keyword(Kw::Module) => ast::Item::ExternModule(ModDecl {
+			id: DUMMY_NODE_ID,
+			span: span,
+			lifetime: hdr.lifetime,
+			name: hdr.name,
+			name_span: hdr.name_span,
+			timeunits: timeunits,
+			params: hdr.params,
+			ports: hdr.ports,
+			wildcard_ports: false,
+			items: Vec::new(),
+			attrs: attrs,
+			default_nettype: None,
+		}),
+		_ => ast::Item::ExternInterface(IntfDecl {
+			id: DUMMY_NODE_ID,
+			span: span,
+			lifetime: hdr.lifetime,
+			name: hdr.name,
+			name_span: hdr.name_span,
+			timeunits: timeunits,
+			params: hdr.params,
+			ports: hdr.ports,
+			wildcard_ports: false,
+			items: Vec::new(),
+			attrs: attrs,
+			default_nettype: None,
+		}),
+	})
+}
+
+
 fn parse_parameter_port_list(p: &mut AbstractParser) -> ReportedResult<Vec<ParamDecl>> {
 	let mut local = false;
 
@@ -674,6 +955,7 @@ fn parse_parameter_port_list(p: &mut AbstractParser) -> ReportedResult<Vec<Param
 			span: outer_span,
 			local: local,
 			kind: kind,
+			attrs: Vec::new(),
 		})
 	}))
 }
@@ -719,41 +1001,17 @@ fn parse_constant_expr(p: &mut AbstractParser) -> ReportedResult<()> {
 
 /// Parse a module declaration, assuming that the leading `module` keyword has
 /// already been consumed.
-fn parse_module_decl(p: &mut Parser) -> ReportedResult<ModDecl> {
+fn parse_module_decl(p: &mut AbstractParser, timescale: Option<ast::Timescale>) -> ReportedResult<ModDecl> {
 	let mut span = p.peek(0).1;
 	p.require_reported(Keyword(Kw::Module))?;
 	let result = recovered(p, Keyword(Kw::Endmodule), |p|{
-
-		// Eat the optional lifetime.
-		let lifetime = match as_lifetime(p.peek(0).0) {
-			Some(l) => { p.bump(); l },
-			None => Lifetime::Static,
-		};
-
-		// Eat the module name.
-		let (name, name_sp) = p.eat_ident("module name")?;
-
-		// TODO: Parse package import declarations.
-
-		// Eat the optional parameter port list.
-		let params = if p.try_eat(Hashtag) {
-			parse_parameter_port_list(p)?
-		} else {
-			Vec::new()
-		};
-
-		// Eat the optional list of ports. Not having such a list requires the ports
-		// to be defined further down in the body of the module.
-		let ports = if p.try_eat(OpenDelim(Paren)) {
-			parse_port_list(p)?
-		} else {
-			Vec::new()
-		};
+		let mut timeunits = Timeunit { timescale: timescale, ..Timeunit::default() };
+		let hdr = parse_design_header(p, "module")?;
 
 		// Eat the semicolon after the header.
 		if !p.try_eat(Semicolon) {
 			let q = p.peek(0).1.end();
-			p.add_diag(DiagBuilder2::error(format!("Missing ; after header of module \"{}\"", name)).span(q));
+			p.add_diag(DiagBuilder2::error(format!("Missing ; after header of module \"{}\"", hdr.name)).span(q));
 		}
 
 		// Parse the module items.
@@ -762,6 +1020,9 @@ fn parse_module_decl(p: &mut Parser) -> ReportedResult<ModDecl> {
 			if p.try_eat(Semicolon) {
 				continue;
 			}
+			if try_timeunits_decl(p, &mut timeunits, items.is_empty())? {
+				continue;
+			}
 			items.push(parse_hierarchy_item(p)?);
 		}
 
@@ -769,12 +1030,16 @@ fn parse_module_decl(p: &mut Parser) -> ReportedResult<ModDecl> {
 		Ok(ModDecl {
 			id: DUMMY_NODE_ID,
 			span: span,
-			lifetime: lifetime,
-			name: name,
-			name_span: name_sp,
-			params: params,
-			ports: ports,
+			lifetime: hdr.lifetime,
+			name: hdr.name,
+			name_span: hdr.name_span,
+			timeunits: timeunits,
+			params: hdr.params,
+			ports: hdr.ports,
+			wildcard_ports: hdr.wildcard_ports,
 			items: items,
+			attrs: Vec::new(),
+			default_nettype: None,
 		})
 	});
 	let sp = p.peek(0).1;
@@ -783,10 +1048,12 @@ fn parse_module_decl(p: &mut Parser) -> ReportedResult<ModDecl> {
 }
 
 
-fn parse_package_decl(p: &mut AbstractParser) -> ReportedResult<PackageDecl> {
+fn parse_package_decl(p: &mut Parser) -> ReportedResult<PackageDecl> {
 	let mut span = p.peek(0).1;
+	let timescale = p.timescale();
 	p.require_reported(Keyword(Kw::Package))?;
 	let result = recovered(p, Keyword(Kw::Endpackage), |p|{
+		let mut timeunits = Timeunit { timescale: timescale, ..Timeunit::default() };
 
 		// Parse the optional lifetime.
 		let lifetime = match as_lifetime(p.peek(0).0) {
@@ -798,15 +1065,15 @@ fn parse_package_decl(p: &mut AbstractParser) -> ReportedResult<PackageDecl> {
 		let (name, name_span) = p.eat_ident("package name")?;
 		p.require_reported(Semicolon)?;
 
-		// TODO: Parse the optional timeunits declaration.
-		let timeunits = Timeunit;
-
 		// Parse the package items.
 		let mut items = Vec::new();
 		while !p.is_fatal() && p.peek(0).0 != Keyword(Kw::Endpackage) && p.peek(0).0 != Eof {
 			if p.try_eat(Semicolon) {
 				continue;
 			}
+			if try_timeunits_decl(p, &mut timeunits, items.is_empty())? {
+				continue;
+			}
 			items.push(parse_hierarchy_item(p)?);
 		}
 
@@ -819,6 +1086,7 @@ fn parse_package_decl(p: &mut AbstractParser) -> ReportedResult<PackageDecl> {
 			name_span: name_span,
 			timeunits: timeunits,
 			items: items,
+			attrs: Vec::new(),
 		})
 	});
 	p.require_reported(Keyword(Kw::Endpackage))?;
@@ -826,6 +1094,108 @@ fn parse_package_decl(p: &mut AbstractParser) -> ReportedResult<PackageDecl> {
 }
 
 
+/// Parse a `timeunit` or `timeprecision` declaration into `timeunits`, if one
+/// is present. `leading` indicates that no other items precede the declaration
+/// in the current time scope; after other items, only repetitions of earlier
+/// declarations are allowed.
+fn try_timeunits_decl(p: &mut AbstractParser, timeunits: &mut Timeunit, leading: bool) -> ReportedResult<bool> {
+	match p.peek(0).0 {
+		Keyword(Kw::Timeunit) => {
+			p.bump();
+			let unit = parse_time_value(p)?;
+			merge_time_value(p, &mut timeunits.unit, unit, leading, "timeunit");
+			if p.try_eat(Operator(Op::Div)) {
+				let prec = parse_time_value(p)?;
+				merge_time_value(p, &mut timeunits.prec, prec, leading, "timeprecision");
+			}
+		}
+		Keyword(Kw::Timeprecision) => {
+			p.bump();
+			let prec = parse_time_value(p)?;
+			merge_time_value(p, &mut timeunits.prec, prec, leading, "timeprecision");
+		}
+		_ => return Ok(false),
+	}
+	p.require_reported(Semicolon)?;
+	Ok(true)
+}
+
+fn merge_time_value(p: &mut AbstractParser, slot: &mut Option<ast::TimeValue>, value: ast::TimeValue, leading: bool, what: &str) {
+	match *slot {
+		Some(prev) if prev.exp != value.exp => {
+			p.add_diag(DiagBuilder2::error(format!("`{}` does not match the earlier declaration", what))
+				.span(value.span)
+				.add_note("Earlier declaration was here:")
+				.span(prev.span));
+		}
+		Some(_) => (),
+		None if !leading => {
+			p.add_diag(DiagBuilder2::error(format!("`{}` must precede all other items", what)).span(value.span));
+		}
+		None => *slot = Some(value),
+	}
+}
+
+/// Parse a time unit or precision, e.g. `10ns` or `1 ps`.
+fn parse_time_value(p: &mut AbstractParser) -> ReportedResult<ast::TimeValue> {
+	let first = p.peek(0);
+	let second = match (first.0, p.peek(1).0) {
+		(Literal(UnsignedInteger(_)), Ident(_)) => Some(p.peek(1)),
+		_ => None,
+	};
+	match as_time_value(first, second) {
+		Ok(v) => {
+			p.bump();
+			if second.is_some() {
+				p.bump();
+			}
+			Ok(v)
+		}
+		Err(d) => {
+			p.add_diag(d);
+			Err(())
+		}
+	}
+}
+
+/// Convert a time literal, or an integer followed by a unit identifier, to a
+/// time value. Only the magnitudes 1, 10, and 100 are allowed.
+fn as_time_value(first: TokenAndSpan, second: Option<TokenAndSpan>) -> Result<ast::TimeValue, DiagBuilder2> {
+	let (magnitude, unit, span) = match (first, second) {
+		((Literal(Time(n)), sp), None) => {
+			let s = n.as_str();
+			let i = s.find(|c: char| !c.is_digit(10)).unwrap_or(s.len());
+			(String::from(&s[..i]), String::from(&s[i..]), sp)
+		}
+		((Literal(UnsignedInteger(n)), sp), Some((Ident(u), usp))) => {
+			(String::from(&*n.as_str()), String::from(&*u.as_str()), Span::union(sp, usp))
+		}
+		((tkn, sp), _) => {
+			return Err(DiagBuilder2::error(format!("Expected time unit such as `1ns` or `100ps`, found {} instead", tkn)).span(sp));
+		}
+	};
+	let mag = match magnitude.as_str() {
+		"1" => 0,
+		"10" => 1,
+		"100" => 2,
+		_ => return Err(DiagBuilder2::error(format!("Time unit must be 1, 10, or 100 {}, not {}", unit, magnitude)).span(span)),
+	};
+	let exp = match unit.as_str() {
+		"s" => 0,
+		"ms" => -3,
+		"us" => -6,
+		"ns" => -9,
+		"ps" => -12,
+		"fs" => -15,
+		_ => return Err(DiagBuilder2::error(format!("`{}` is not a valid time unit", unit)).span(span)),
+	};
+	Ok(ast::TimeValue {
+		span: span,
+		exp: exp + mag,
+	})
+}
+
+
 fn parse_program_decl(p: &mut AbstractParser) -> ReportedResult<()> {
 	p.require_reported(Keyword(Kw::Program))?;
 	let result = recovered(p, Keyword(Kw::Endprogram), |p|{
@@ -839,6 +1209,43 @@ fn parse_program_decl(p: &mut AbstractParser) -> ReportedResult<()> {
 
 
 fn parse_hierarchy_item(p: &mut AbstractParser) -> ReportedResult<HierarchyItem> {
+	let attrs = try_attrs(p)?;
+	let mut item = parse_bare_hierarchy_item(p)?;
+	attach_item_attrs(p, &mut item, attrs);
+	Ok(item)
+}
+
+
+/// Attach attribute instances to a hierarchy item. Items that cannot carry
+/// attributes produce a warning.
+fn attach_item_attrs(p: &mut AbstractParser, item: &mut HierarchyItem, attrs: Vec<ast::AttrInst>) {
+	if attrs.is_empty() {
+		return;
+	}
+	let slot = match *item {
+		HierarchyItem::ParamDecl(ref mut decl) => &mut decl.attrs,
+		HierarchyItem::Typedef(ref mut decl) => &mut decl.attrs,
+		HierarchyItem::PortDecl(ref mut decl) => &mut decl.attrs,
+		HierarchyItem::Procedure(ref mut prc) => &mut prc.attrs,
+		HierarchyItem::SubroutineDecl(ref mut decl) => &mut decl.attrs,
+		HierarchyItem::ContAssign(ref mut assign) => &mut assign.attrs,
+		HierarchyItem::NetDecl(ref mut decl) => &mut decl.attrs,
+		HierarchyItem::VarDecl(ref mut decl) => &mut decl.attrs,
+		HierarchyItem::Inst(ref mut inst) => &mut inst.attrs,
+		HierarchyItem::GateInst(ref mut inst) => &mut inst.attrs,
+		HierarchyItem::ModuleDecl(ref mut decl) => &mut decl.attrs,
+		HierarchyItem::InterfaceDecl(ref mut decl) => &mut decl.attrs,
+		ref other => {
+			let span = Span::union(attrs[0].span, attrs[attrs.len()-1].span);
+			p.add_diag(DiagBuilder2::warning(format!("Attributes on {} are ignored", other.as_str())).span(span));
+			return;
+		}
+	};
+	*slot = attrs;
+}
+
+
+fn parse_bare_hierarchy_item(p: &mut AbstractParser) -> ReportedResult<HierarchyItem> {
 	// First attempt the simple cases where a keyword reliably identifies the
 	// following item.
 	match p.peek(0).0 {
@@ -850,9 +1257,18 @@ fn parse_hierarchy_item(p: &mut AbstractParser) -> ReportedResult<HierarchyItem>
 			return Ok(HierarchyItem::ParamDecl(decl));
 		}
 		Keyword(Kw::Modport)    => return parse_modport_decl(p).map(|x| HierarchyItem::ModportDecl(x)),
+		// Nested design elements inherit the time units of the enclosing one.
+		Keyword(Kw::Module)     => return parse_module_decl(p, None).map(|x| HierarchyItem::ModuleDecl(x)),
+		Keyword(Kw::Interface) | Keyword(Kw::Virtual) if p.peek(1).0 == Keyword(Kw::Class) => return parse_class_decl(p).map(|x| HierarchyItem::ClassDecl(x)),
+		Keyword(Kw::Interface)  => return parse_interface_decl(p, None).map(|x| HierarchyItem::InterfaceDecl(x)),
 		Keyword(Kw::Class)      => return parse_class_decl(p).map(|x| HierarchyItem::ClassDecl(x)),
+		Keyword(Kw::Covergroup) => return parse_covergroup_decl(p).map(|x| HierarchyItem::CovergroupDecl(x)),
+		Keyword(Kw::Constraint) => return parse_out_of_class_constraint(p).map(|x| HierarchyItem::Constraint(x)),
+		Keyword(Kw::Static) if p.peek(1).0 == Keyword(Kw::Constraint) => return parse_out_of_class_constraint(p).map(|x| HierarchyItem::Constraint(x)),
 		Keyword(Kw::Typedef)    => return parse_typedef(p).map(|x| HierarchyItem::Typedef(x)),
+		Keyword(Kw::Import) | Keyword(Kw::Export) if is_dpi_spec(p.peek(1).0) => return parse_dpi_decl(p).map(|x| HierarchyItem::DpiDecl(x)),
 		Keyword(Kw::Import)     => return parse_import_decl(p).map(|x| HierarchyItem::ImportDecl(x)),
+		Keyword(Kw::Export)     => return parse_export_decl(p).map(|x| HierarchyItem::ExportDecl(x)),
 
 		// Structured procedures as per IEEE 1800-2009 section 9.2
 		Keyword(Kw::Initial)     => return parse_procedure(p, ProcedureKind::Initial).map(|x| HierarchyItem::Procedure(x)),
@@ -899,6 +1315,47 @@ fn parse_hierarchy_item(p: &mut AbstractParser) -> ReportedResult<HierarchyItem>
 		Keyword(Kw::Cover) |
 		Keyword(Kw::Expect) |
 		Keyword(Kw::Restrict) => return parse_assertion(p).map(|x| HierarchyItem::Assertion(x)),
+		Ident(_) | EscIdent(_) if p.peek(1).0 == Colon && is_assertion_keyword(p.peek(2).0) => {
+			let mut span = p.peek(0).1;
+			let label = p.eat_ident("assertion label")?;
+			p.require_reported(Colon)?;
+			let mut assertion = parse_assertion(p)?;
+			span.expand(p.last_span());
+			assertion.span = span;
+			assertion.label = Some(label);
+			return Ok(HierarchyItem::Assertion(assertion));
+		}
+		Keyword(Kw::Sequence) => return parse_sequence_decl(p).map(|x| HierarchyItem::SequenceDecl(x)),
+		Keyword(Kw::Property) => return parse_property_decl(p).map(|x| HierarchyItem::PropertyDecl(x)),
+
+		// Gate and switch instantiations
+		tkn if as_gate_kind(tkn).is_some() => return parse_gate_inst(p).map(|x| HierarchyItem::GateInst(x)),
+
+		// Clocking blocks
+		Keyword(Kw::Clocking) => return parse_clocking_decl(p).map(|x| HierarchyItem::ClockingDecl(x)),
+		Keyword(Kw::Global) if p.peek(1).0 == Keyword(Kw::Clocking) => return parse_clocking_decl(p).map(|x| HierarchyItem::ClockingDecl(x)),
+		Keyword(Kw::Default) if p.peek(1).0 == Keyword(Kw::Clocking) => {
+			// A `default clocking name;` refers to an existing clocking
+			// block, rather than declaring a new one.
+			if p.peek(3).0 == Semicolon {
+				let mut span = p.peek(0).1;
+				p.bump();
+				p.bump();
+				let name = parse_identifier(p, "clocking block name")?;
+				p.require_reported(Semicolon)?;
+				span.expand(p.last_span());
+				return Ok(HierarchyItem::DefaultClocking(span, name));
+			}
+			return parse_clocking_decl(p).map(|x| HierarchyItem::ClockingDecl(x));
+		}
+
+		// User-defined nettypes and net aliases
+		Keyword(Kw::Nettype) => return parse_nettype_decl(p).map(|x| HierarchyItem::NettypeDecl(x)),
+		Keyword(Kw::Alias)   => return parse_alias(p).map(|x| HierarchyItem::Alias(x)),
+
+		// Specify blocks and specparams
+		Keyword(Kw::Specify)   => return parse_specify_block(p).map(|x| HierarchyItem::SpecifyBlock(x)),
+		Keyword(Kw::Specparam) => return parse_specparam_decl(p).map(|x| HierarchyItem::SpecparamDecl(x)),
 
 		_ => ()
 	}
@@ -1139,14 +1596,32 @@ fn parse_modport_port_decl(p: &mut AbstractParser) -> ReportedResult<ast::Modpor
 	// keywords.
 	if let Some(dir) = as_port_direction(tkn) {
 		p.bump();
+		let mut ports = Vec::new();
 		loop {
+			let mut port_span = p.peek(0).1;
 			if p.try_eat(Period) {
 				let name = parse_identifier(p, "port name")?;
 				p.require_reported(OpenDelim(Paren))?;
-				let expr = parse_expr(p)?;
+				let expr = match p.peek(0).0 {
+					CloseDelim(Paren) => None,
+					_ => Some(parse_expr(p)?),
+				};
 				p.require_reported(CloseDelim(Paren))?;
+				port_span.expand(p.last_span());
+				ports.push(ast::ModportSimplePort {
+					span: port_span,
+					name: name,
+					explicit: true,
+					expr: expr,
+				});
 			} else {
 				let name = parse_identifier(p, "port name")?;
+				ports.push(ast::ModportSimplePort {
+					span: name.span,
+					name: name,
+					explicit: false,
+					expr: None,
+				});
 			}
 
 			// Decide whether we should continue iterating and thus consuming
@@ -1164,16 +1639,61 @@ fn parse_modport_port_decl(p: &mut AbstractParser) -> ReportedResult<ast::Modpor
 				_ => break,
 			}
 		}
-		return Ok(ast::ModportPort::Port);
+		let mut span = span;
+		span.expand(p.last_span());
+		return Ok(ast::ModportPort::Simple {
+			span: span,
+			dir: dir,
+			ports: ports,
+		});
 	}
 
-	// TODO: Parse modport_tf_port.
+	// Attempt to parse a list of imported or exported subroutines.
+	let tf_kind = match tkn {
+		Keyword(Kw::Import) => Some(ast::ModportTfKind::Import),
+		Keyword(Kw::Export) => Some(ast::ModportTfKind::Export),
+		_ => None,
+	};
+	if let Some(kind) = tf_kind {
+		p.bump();
+		let mut ports = Vec::new();
+		loop {
+			match p.peek(0).0 {
+				Keyword(Kw::Function) | Keyword(Kw::Task) => {
+					ports.push(ast::ModportTfPort::Prototype(parse_subroutine_prototype(p)?));
+				}
+				_ => ports.push(ast::ModportTfPort::Name(parse_identifier(p, "subroutine name")?)),
+			}
+
+			// Same as for the simple ports above, except that a function or
+			// task prototype may follow the comma.
+			match (p.peek(0).0, p.peek(1).0) {
+				(Comma, Keyword(Kw::Function)) |
+				(Comma, Keyword(Kw::Task)) => {
+					p.bump();
+					continue;
+				}
+				(Comma, Keyword(_)) => break,
+				(Comma, _) => {
+					p.bump();
+					continue;
+				},
+				_ => break,
+			}
+		}
+		let mut span = span;
+		span.expand(p.last_span());
+		return Ok(ast::ModportPort::Tf {
+			span: span,
+			kind: kind,
+			ports: ports,
+		});
+	}
 
 	// Attempt to parse a clocking declaration.
 	if p.try_eat(Keyword(Kw::Clocking)) {
-		// TODO: Parse modport_clocking_declaration.
-		p.add_diag(DiagBuilder2::error("modport clocking declaration not implemented").span(span));
-		return Err(());
+		let name = parse_identifier(p, "clocking block name")?;
+		return Ok(ast::ModportPort::Clocking(name));
 	}
 
 	// If we've come thus far, none of the above matched.
@@ -1194,10 +1714,193 @@ fn as_port_direction(tkn: Token) -> Option<PortDir> {
 }
 
 
-/// Parse an implicit or explicit type. This is a catch-all function that will
-/// always succeed unless one of the explicit types contains a syntax error. For
-/// all other tokens the function will at least return an ImplicitType if none
-/// could be consumed. You might have to use `parse_explicit_type` and
+/// Parse a clocking block.
+///
+/// ```text
+/// ["default"|"global"] "clocking" [ident] clocking_event ";" {clocking_item} "endclocking" [":" ident]
+/// clocking_event: "@" ident | "@" "(" event_expr ")"
+/// ```
+fn parse_clocking_decl(p: &mut AbstractParser) -> ReportedResult<ClockingDecl> {
+	let mut span = p.peek(0).1;
+	let kind = if p.try_eat(Keyword(Kw::Default)) {
+		ClockingKind::Default
+	} else if p.try_eat(Keyword(Kw::Global)) {
+		ClockingKind::Global
+	} else {
+		ClockingKind::Normal
+	};
+	p.require_reported(Keyword(Kw::Clocking))?;
+
+	// Only default and global clocking blocks may omit the name.
+	let name = try_identifier(p)?;
+	if name.is_none() && kind == ClockingKind::Normal {
+		let q = p.peek(0).1;
+		p.add_diag(DiagBuilder2::error("Expected clocking block name; only default and global clocking blocks may be unnamed").span(q));
+		return Err(());
+	}
+
+	let event = match try_event_control(p)? {
+		Some(ec) => ec,
+		None => {
+			let q = p.peek(0).1;
+			p.add_diag(DiagBuilder2::error("Expected clocking event, e.g. `@(posedge clk)`").span(q));
+			return Err(());
+		}
+	};
+	p.require_reported(Semicolon)?;
+	let items = repeat_until(p, Keyword(Kw::Endclocking), parse_clocking_item)?;
+	p.require_reported(Keyword(Kw::Endclocking))?;
+
+	// Parse the optional clocking block name after "endclocking".
+	if p.try_eat(Colon) {
+		let (n, sp) = p.eat_ident("clocking block name")?;
+		if Some(n) != name.as_ref().map(|x| x.name) {
+			p.add_diag(DiagBuilder2::error(format!("Clocking block name {} disagrees with the name given before", n)).span(sp));
+			return Err(());
+		}
+	}
+	span.expand(p.last_span());
+
+	if kind == ClockingKind::Global && !items.is_empty() {
+		p.add_diag(DiagBuilder2::error("Global clocking blocks cannot contain clocking items").span(span));
+		return Err(());
+	}
+
+	Ok(ClockingDecl {
+		span: span,
+		kind: kind,
+		name: name,
+		event: event,
+		items: items,
+	})
+}
+
+
+/// Parse a clocking item.
+///
+/// ```text
+/// "default" ["input" clocking_skew] ["output" clocking_skew] ";"
+/// clocking_direction clocking_decl_assign {"," clocking_decl_assign} ";"
+/// clocking_direction: "input" [skew] ["output" [skew]] | "output" [skew] | "inout"
+/// clocking_decl_assign: ident ["=" expr]
+/// ```
+fn parse_clocking_item(p: &mut AbstractParser) -> ReportedResult<ClockingItem> {
+	let mut span = p.peek(0).1;
+
+	// Handle the default skew.
+	if p.try_eat(Keyword(Kw::Default)) {
+		let input = if p.try_eat(Keyword(Kw::Input)) {
+			Some(parse_clocking_skew(p)?)
+		} else {
+			None
+		};
+		let output = if p.try_eat(Keyword(Kw::Output)) {
+			Some(parse_clocking_skew(p)?)
+		} else {
+			None
+		};
+		if input.is_none() && output.is_none() {
+			let q = p.peek(0).1;
+			p.add_diag(DiagBuilder2::error("Expected input or output skew after `default`").span(q));
+			return Err(());
+		}
+		p.require_reported(Semicolon)?;
+		span.expand(p.last_span());
+		return Ok(ClockingItem::DefaultSkew {
+			span: span,
+			input: input,
+			output: output,
+		});
+	}
+
+	// Handle the signal declarations.
+	let dir = match p.peek(0) {
+		(Keyword(Kw::Input), _) => {
+			p.bump();
+			let skew = try_clocking_skew(p)?;
+			if p.try_eat(Keyword(Kw::Output)) {
+				ClockingDir::InputOutput(skew, try_clocking_skew(p)?)
+			} else {
+				ClockingDir::Input(skew)
+			}
+		}
+		(Keyword(Kw::Output), _) => {
+			p.bump();
+			ClockingDir::Output(try_clocking_skew(p)?)
+		}
+		(Keyword(Kw::Inout), _) => {
+			p.bump();
+			ClockingDir::Inout
+		}
+		(tkn, sp) => {
+			p.add_diag(DiagBuilder2::error(format!("Expected clocking item, found {} instead", tkn)).span(sp));
+			return Err(());
+		}
+	};
+	let signals = comma_list_nonempty(p, Semicolon, "clocking signal", |p|{
+		let name = parse_identifier(p, "signal name")?;
+		let expr = if p.try_eat(Operator(Op::Assign)) {
+			Some(parse_expr(p)?)
+		} else {
+			None
+		};
+		Ok(ClockingSignal {
+			span: Span::union(name.span, p.last_span()),
+			name: name,
+			expr: expr,
+		})
+	})?;
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+	Ok(ClockingItem::Signals {
+		span: span,
+		dir: dir,
+		signals: signals,
+	})
+}
+
+
+/// Parse an optional clocking skew.
+///
+/// ```text
+/// edge_ident [delay_control]
+/// delay_control
+/// ```
+fn try_clocking_skew(p: &mut AbstractParser) -> ReportedResult<Option<ClockingSkew>> {
+	let mut span = p.peek(0).1;
+	let edge = as_edge_ident(p.peek(0).0);
+	if edge != EdgeIdent::Implicit {
+		p.bump();
+	}
+	let delay = try_delay_control(p)?;
+	if edge == EdgeIdent::Implicit && delay.is_none() {
+		return Ok(None);
+	}
+	span.expand(p.last_span());
+	Ok(Some(ClockingSkew {
+		span: span,
+		edge: edge,
+		delay: delay,
+	}))
+}
+
+
+fn parse_clocking_skew(p: &mut AbstractParser) -> ReportedResult<ClockingSkew> {
+	match try_clocking_skew(p)? {
+		Some(x) => Ok(x),
+		None => {
+			let q = p.peek(0).1;
+			p.add_diag(DiagBuilder2::error("Expected clocking skew, e.g. `#1step` or `negedge`").span(q));
+			Err(())
+		}
+	}
+}
+
+
+/// Parse an implicit or explicit type. This is a catch-all function that will
+/// always succeed unless one of the explicit types contains a syntax error. For
+/// all other tokens the function will at least return an ImplicitType if none
+/// could be consumed. You might have to use `parse_explicit_type` and
 /// `parse_implicit_type` separately if the type is to be embedded in a larger
 /// function. For example, a variable declaration with implicit type looks like
 /// an explicit type at first glance. Only after reaching the trailing `[=,;]`
@@ -1266,6 +1969,7 @@ fn parse_type_suffix(p: &mut AbstractParser, ty: Type) -> ReportedResult<Type> {
 			let params = parse_parameter_assignments(p)?;
 			let span = Span::union(sp, p.last_span());
 			parse_type_suffix(p, ast::Type {
+				id: DUMMY_NODE_ID,
 				span: span,
 				data: ast::SpecializedType(Box::new(ty), params),
 				sign: ast::TypeSign::None,
@@ -1300,6 +2004,7 @@ fn parse_type_signing_and_dimensions(p: &mut AbstractParser, mut span: Span, dat
 	span.expand(p.last_span());
 
 	Ok(Type {
+		id: DUMMY_NODE_ID,
 		span: span,
 		data: data,
 		sign: sign,
@@ -1507,10 +2212,7 @@ fn try_dimension(p: &mut AbstractParser) -> ReportedResult<Option<(TypeDim, Span
 	let mut span = p.last_span();
 
 	let dim = match p.peek(0).0 {
-		CloseDelim(Brack) => {
-			p.bump();
-			TypeDim::Unsized
-		}
+		CloseDelim(Brack) => TypeDim::Unsized,
 		Operator(Op::Mul) => {
 			p.bump();
 			TypeDim::Associative
@@ -1571,6 +2273,7 @@ fn try_dimension(p: &mut AbstractParser) -> ReportedResult<Option<(TypeDim, Span
 fn parse_list_of_port_connections(p: &mut AbstractParser) -> ReportedResult<Vec<PortConn>> {
 	comma_list(p, CloseDelim(Paren), "list of port connections", |p|{
 		let mut span = p.peek(0).1;
+		let attrs = try_attrs(p)?;
 
 		// A period introduces a named port connection. Otherwise this is an
 		// unnamed connection.
@@ -1598,6 +2301,7 @@ fn parse_list_of_port_connections(p: &mut AbstractParser) -> ReportedResult<Vec<
 		Ok(ast::PortConn {
 			span: span,
 			kind: kind,
+			attrs: attrs,
 		})
 	})
 }
@@ -1645,22 +2349,33 @@ fn parse_expr_prec(p: &mut AbstractParser, precedence: Precedence) -> ReportedRe
 			let expr = try_flanked(p, Paren, parse_expr)?;
 			span.expand(p.last_span());
 			return Ok(Expr {
+				id: DUMMY_NODE_ID,
 				span: span,
 				data: ArrayNewExpr(Box::new(dim_expr), expr.map(|x| Box::new(x))),
+				attrs: Vec::new(),
 			});
 		} else {
 			if let Some(args) = try_flanked(p, Paren, parse_call_args)? {
 				span.expand(p.last_span());
 				return Ok(Expr {
+					id: DUMMY_NODE_ID,
 					span: span,
 					data: ConstructorCallExpr(args),
+					attrs: Vec::new(),
 				});
 			} else {
-				let expr = parse_expr(p)?;
+				// A bare `new`, as in `c = new;`, calls the constructor
+				// without arguments.
+				let expr = match p.peek(0).0 {
+					Semicolon | Comma | CloseDelim(_) => None,
+					_ => Some(Box::new(parse_expr(p)?)),
+				};
 				span.expand(p.last_span());
 				return Ok(Expr {
+					id: DUMMY_NODE_ID,
 					span: span,
-					data: ClassNewExpr(Some(Box::new(expr))),
+					data: ClassNewExpr(expr),
+					attrs: Vec::new(),
 				});
 			}
 		}
@@ -1677,10 +2392,11 @@ fn parse_expr_suffix(p: &mut AbstractParser, prefix: Expr, precedence: Precedenc
 	// p.add_diag(DiagBuilder2::note(format!("expr_suffix with precedence {:?}", precedence)).span(prefix.span));
 
 	// Try to parse the index and call expressions.
-	let (tkn, sp) = p.peek(0);
+	let tkn = p.peek(0).0;
 	match tkn {
-		// Index: "[" range_expression "]"
-		OpenDelim(Brack) if precedence <= Precedence::Postfix => {
+		// Index: "[" range_expression "]". Repetitions such as `[*2]`,
+		// `[->2]`, and `[=2]` are left to the caller.
+		OpenDelim(Brack) if precedence <= Precedence::Postfix && !is_repetition_op(p.peek(1).0) => {
 			p.bump();
 			let expr = match parse_range_expr(p) {
 				Ok(x) => x,
@@ -1691,21 +2407,30 @@ fn parse_expr_suffix(p: &mut AbstractParser, prefix: Expr, precedence: Precedenc
 			};
 			p.require_reported(CloseDelim(Brack))?;
 			let expr = Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(prefix.span, p.last_span()),
 				data: IndexExpr {
 					indexee: Box::new(prefix),
 					index: Box::new(expr),
 				},
+				attrs: Vec::new(),
 			};
 			return parse_expr_suffix(p, expr, precedence);
 		}
 
-		// Call: "(" [list_of_arguments] ")"
+		// Call: {attribute_instance} "(" [list_of_arguments] ")"
 		OpenDelim(Paren) if precedence <= Precedence::Postfix => {
-			let args = flanked(p, Paren, parse_call_args)?;
+			let attrs = try_attrs(p)?;
+			// System functions such as `$bits` also accept data types.
+			let args = match prefix.data {
+				SysIdentExpr(_) => flanked(p, Paren, parse_sys_call_args)?,
+				_ => flanked(p, Paren, parse_call_args)?,
+			};
 			let expr = Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(prefix.span, p.last_span()),
 				data: CallExpr(Box::new(prefix), args),
+				attrs: attrs,
 			};
 			return parse_expr_suffix(p, expr, precedence);
 		}
@@ -1713,8 +2438,14 @@ fn parse_expr_suffix(p: &mut AbstractParser, prefix: Expr, precedence: Precedenc
 		// expr "." ident
 		Period if precedence <= Precedence::Scope => {
 			p.bump();
-			let (name, name_span) = p.eat_ident("member name")?;
+			// The constructor of the base class is called as `super.new`.
+			let (name, name_span) = if p.try_eat(Keyword(Kw::New)) {
+				(get_name_table().intern("new", true), p.last_span())
+			} else {
+				p.eat_ident("member name")?
+			};
 			let expr = Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(prefix.span, p.last_span()),
 				data: MemberExpr {
 					expr: Box::new(prefix),
@@ -1724,33 +2455,40 @@ fn parse_expr_suffix(p: &mut AbstractParser, prefix: Expr, precedence: Precedenc
 						name: name,
 					},
 				},
+				attrs: Vec::new(),
 			};
 			return parse_expr_suffix(p, expr, precedence);
 		}
 
 		// expr "::" ident
 		Namespace if precedence <= Precedence::Scope => {
-			p.add_diag(DiagBuilder2::fatal("Don't know how to handle the scope operator").span(sp));
-			return Err(());
-			// p.bump();
-			// p.eat_ident("scope name")?;
-			// let expr = Expr {
-			// 	span: Span::union(prefix.span, p.last_span()),
-			// 	data: DummyExpr,
-			// };
-			// return parse_expr_suffix(p, expr, precedence);
+			p.bump();
+			let (name, name_span) = p.eat_ident("scope member")?;
+			let expr = Expr {
+				id: DUMMY_NODE_ID,
+				span: Span::union(prefix.span, p.last_span()),
+				data: ScopeExpr(Box::new(prefix), Identifier {
+					id: DUMMY_NODE_ID,
+					span: name_span,
+					name: name,
+				}),
+				attrs: Vec::new(),
+			};
+			return parse_expr_suffix(p, expr, precedence);
 		}
 
 		// expr "++"
 		Operator(Op::Inc) if precedence <= Precedence::Unary => {
 			p.bump();
 			let expr = Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(prefix.span, p.last_span()),
 				data: UnaryExpr {
 					op: Op::Inc,
 					expr: Box::new(prefix),
 					postfix: true,
 				},
+				attrs: Vec::new(),
 			};
 			return parse_expr_suffix(p, expr, precedence);
 		}
@@ -1759,29 +2497,65 @@ fn parse_expr_suffix(p: &mut AbstractParser, prefix: Expr, precedence: Precedenc
 		Operator(Op::Dec) if precedence <= Precedence::Unary => {
 			p.bump();
 			let expr = Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(prefix.span, p.last_span()),
 				data: UnaryExpr {
 					op: Op::Dec,
 					expr: Box::new(prefix),
 					postfix: true,
 				},
+				attrs: Vec::new(),
+			};
+			return parse_expr_suffix(p, expr, precedence);
+		}
+
+		// expr "inside" "{" open_range_list "}"
+		Keyword(Kw::Inside) if precedence <= Precedence::Relational => {
+			p.bump();
+			let ranges = flanked(p, Brace, |p| comma_list_nonempty(p, CloseDelim(Brace), "set member", parse_value_range))?;
+			let expr = Expr {
+				id: DUMMY_NODE_ID,
+				span: Span::union(prefix.span, p.last_span()),
+				data: InsideExpr(Box::new(prefix), ranges),
+				attrs: Vec::new(),
+			};
+			return parse_expr_suffix(p, expr, precedence);
+		}
+
+		// randomize_call "with" ["(" [identifier_list] ")"] constraint_block
+		Keyword(Kw::With) if precedence <= Precedence::Postfix && is_randomize_call(&prefix) => {
+			p.bump();
+			let names = try_flanked(p, Paren, |p| comma_list(p, CloseDelim(Paren), "identifier", |p| parse_identifier(p, "identifier")))?;
+			let items = flanked(p, Brace, |p| repeat_until(p, CloseDelim(Brace), parse_constraint_item))?;
+			let expr = Expr {
+				id: DUMMY_NODE_ID,
+				span: Span::union(prefix.span, p.last_span()),
+				data: RandomizeWithExpr {
+					call: Box::new(prefix),
+					names: names.unwrap_or_default(),
+					items: items,
+				},
+				attrs: Vec::new(),
 			};
 			return parse_expr_suffix(p, expr, precedence);
 		}
 
-		// expr "?" expr ":" expr
+		// expr "?" {attribute_instance} expr ":" expr
 		Ternary if precedence < Precedence::Ternary => {
 			p.bump();
+			let attrs = try_attrs(p)?;
 			let true_expr = parse_expr_prec(p, Precedence::Ternary)?;
 			p.require_reported(Colon)?;
 			let false_expr = parse_expr_prec(p, Precedence::Ternary)?;
 			let expr = Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(prefix.span, p.last_span()),
 				data: TernaryExpr {
 					cond: Box::new(prefix),
 					true_expr: Box::new(true_expr),
 					false_expr: Box::new(false_expr),
 				},
+				attrs: attrs,
 			};
 			return parse_expr_suffix(p, expr, precedence);
 		}
@@ -1795,12 +2569,14 @@ fn parse_expr_suffix(p: &mut AbstractParser, prefix: Expr, precedence: Precedenc
 			p.bump();
 			let rhs = parse_expr_prec(p, Precedence::Assignment)?;
 			let expr = Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(prefix.span, p.last_span()),
 				data: AssignExpr {
 					op: op,
 					lhs: Box::new(prefix),
 					rhs: Box::new(rhs),
 				},
+				attrs: Vec::new(),
 			};
 			return parse_expr_suffix(p, expr, precedence);
 		}
@@ -1811,14 +2587,17 @@ fn parse_expr_suffix(p: &mut AbstractParser, prefix: Expr, precedence: Precedenc
 		let prec = op.get_precedence();
 		if precedence <= prec {
 			p.bump();
+			let attrs = try_attrs(p)?;
 			let rhs = parse_expr_prec(p, prec)?;
 			let expr = Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(prefix.span, p.last_span()),
 				data: BinaryExpr {
 					op: op,
 					lhs: Box::new(prefix),
 					rhs: Box::new(rhs),
 				},
+				attrs: attrs,
 			};
 			return parse_expr_suffix(p, expr, precedence);
 		}
@@ -1835,27 +2614,33 @@ fn parse_expr_first(p: &mut AbstractParser, precedence: Precedence) -> ReportedR
 	match p.peek(0) {
 		(Operator(Op::Inc), _) if precedence <= Precedence::Unary => {
 			p.bump();
+			let attrs = try_attrs(p)?;
 			let expr = parse_expr_prec(p, Precedence::Unary)?;
 			return Ok(Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(first, p.last_span()),
 				data: UnaryExpr {
 					op: Op::Inc,
 					expr: Box::new(expr),
 					postfix: false,
 				},
+				attrs: attrs,
 			});
 		}
 
 		(Operator(Op::Dec), _) if precedence <= Precedence::Unary => {
 			p.bump();
+			let attrs = try_attrs(p)?;
 			let expr = parse_expr_prec(p, Precedence::Unary)?;
 			return Ok(Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(first, p.last_span()),
 				data: UnaryExpr {
 					op: Op::Dec,
 					expr: Box::new(expr),
 					postfix: false,
 				},
+				attrs: attrs,
 			});
 		}
 
@@ -1870,14 +2655,17 @@ fn parse_expr_first(p: &mut AbstractParser, precedence: Precedence) -> ReportedR
 	// Try the unary operators next.
 	if let Some(op) = as_unary_operator(p.peek(0).0) {
 		p.bump();
+		let attrs = try_attrs(p)?;
 		let expr = parse_expr_prec(p, Precedence::Unary)?;
 		return Ok(Expr {
+			id: DUMMY_NODE_ID,
 			span: Span::union(first, p.last_span()),
 			data: UnaryExpr {
 				op: op,
 				expr: Box::new(expr),
 				postfix: false,
 			},
+			attrs: attrs,
 		});
 	}
 
@@ -1893,8 +2681,10 @@ fn parse_primary_expr(p: &mut AbstractParser) -> ReportedResult<Expr> {
 		Literal(lit) => {
 			p.bump();
 			return Ok(Expr {
+				id: DUMMY_NODE_ID,
 				span: sp,
 				data: LiteralExpr(lit),
+				attrs: Vec::new(),
 			});
 		}
 
@@ -1902,23 +2692,42 @@ fn parse_primary_expr(p: &mut AbstractParser) -> ReportedResult<Expr> {
 		Ident(n) | EscIdent(n) => {
 			p.bump();
 			return Ok(Expr {
+				id: DUMMY_NODE_ID,
 				span: sp,
 				data: IdentExpr(Identifier {
 					id: DUMMY_NODE_ID,
 					span: sp,
 					name: n,
 				}),
+				attrs: Vec::new(),
+			});
+		}
+		// The class handles `this` and `super` are treated as identifiers.
+		Keyword(Kw::This) | Keyword(Kw::Super) => {
+			p.bump();
+			let name = if tkn == Keyword(Kw::This) { "this" } else { "super" };
+			return Ok(Expr {
+				id: DUMMY_NODE_ID,
+				span: sp,
+				data: IdentExpr(Identifier {
+					id: DUMMY_NODE_ID,
+					span: sp,
+					name: get_name_table().intern(name, true),
+				}),
+				attrs: Vec::new(),
 			});
 		}
 		SysIdent(n) => {
 			p.bump();
 			return Ok(Expr {
+				id: DUMMY_NODE_ID,
 				span: sp,
 				data: SysIdentExpr(Identifier {
 					id: DUMMY_NODE_ID,
 					span: sp,
 					name: n,
 				}),
+				attrs: Vec::new(),
 			});
 		}
 
@@ -1927,8 +2736,10 @@ fn parse_primary_expr(p: &mut AbstractParser) -> ReportedResult<Expr> {
 			p.bump();
 			if p.try_eat(CloseDelim(Brace)) {
 				return Ok(Expr {
+					id: DUMMY_NODE_ID,
 					span: Span::union(sp, p.last_span()),
 					data: EmptyQueueExpr,
+					attrs: Vec::new(),
 				});
 			}
 			let data = match parse_concat_expr(p) {
@@ -1940,8 +2751,10 @@ fn parse_primary_expr(p: &mut AbstractParser) -> ReportedResult<Expr> {
 			};
 			p.require_reported(CloseDelim(Brace))?;
 			return Ok(Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(sp, p.last_span()),
 				data: data,
+				attrs: Vec::new(),
 			});
 		}
 
@@ -1964,8 +2777,10 @@ fn parse_primary_expr(p: &mut AbstractParser) -> ReportedResult<Expr> {
 			p.bump();
 			let fields = flanked(p, Brace, |p| comma_list_nonempty(p, CloseDelim(Brace), "pattern field", parse_pattern_field))?;
 			return Ok(Expr {
+				id: DUMMY_NODE_ID,
 				span: Span::union(sp, p.last_span()),
 				data: PatternExpr(fields),
+				attrs: Vec::new(),
 			});
 		}
 
@@ -2184,12 +2999,14 @@ fn parse_primary_parenthesis(p: &mut AbstractParser) -> ReportedResult<Expr> {
 		p.require_reported(Colon)?;
 		let max = parse_expr_prec(p, Precedence::Min)?;
 		Ok(Expr {
+			id: DUMMY_NODE_ID,
 			span: Span::union(first.span, max.span),
 			data: MinTypMaxExpr {
 				min: Box::new(first),
 				typ: Box::new(typ),
 				max: Box::new(max),
 			},
+			attrs: Vec::new(),
 		})
 	} else {
 		Ok(first)
@@ -2221,12 +3038,14 @@ fn parse_range_expr(p: &mut AbstractParser) -> ReportedResult<Expr> {
 	let second_expr = parse_expr(p)?;
 	span.expand(p.last_span());
 	Ok(Expr {
+		id: DUMMY_NODE_ID,
 		span: span,
 		data: RangeExpr {
 			mode: mode,
 			lhs: Box::new(first_expr),
 			rhs: Box::new(second_expr),
 		},
+		attrs: Vec::new(),
 	})
 }
 
@@ -2497,12 +3316,20 @@ fn parse_port_list(p: &mut AbstractParser) -> ReportedResult<Vec<Port>> {
 
 /// Parse a single port declaration. These can take a few different forms.
 fn parse_port(p: &mut AbstractParser) -> ReportedResult<ast::Port> {
+	let attrs = try_attrs(p)?;
 	let mut pp = ParallelParser::new();
 	pp.add_greedy("interface port", parse_interface_port);
 	pp.add_greedy("explicit port",  parse_explicit_port);
 	pp.add_greedy("named port",     parse_named_port);
 	pp.add_greedy("implicit port",  parse_implicit_port);
-	pp.finish(p, "port")
+	let mut port = pp.finish(p, "port")?;
+	match port {
+		ast::Port::Intf{ attrs: ref mut a, .. } |
+		ast::Port::Explicit{ attrs: ref mut a, .. } |
+		ast::Port::Named{ attrs: ref mut a, .. } => *a = attrs,
+		ast::Port::Implicit(ref mut expr) => expr.attrs = attrs,
+	}
+	Ok(port)
 }
 
 
@@ -2544,6 +3371,7 @@ fn parse_interface_port(p: &mut AbstractParser) -> ReportedResult<ast::Port> {
 		name: name,
 		dims: dims,
 		expr: expr,
+		attrs: Vec::new(),
 	})
 }
 
@@ -2580,6 +3408,7 @@ fn parse_explicit_port(p: &mut AbstractParser) -> ReportedResult<ast::Port> {
 		dir: dir,
 		name: name,
 		expr: expr,
+		attrs: Vec::new(),
 	})
 }
 
@@ -2651,6 +3480,7 @@ fn parse_named_port(p: &mut AbstractParser) -> ReportedResult<ast::Port> {
 		name: name,
 		dims: dims,
 		expr: expr,
+		attrs: Vec::new(),
 	})
 }
 
@@ -2699,6 +3529,7 @@ fn parse_procedure(p: &mut AbstractParser, kind: ProcedureKind) -> ReportedResul
 		span: span,
 		kind: kind,
 		stmt: stmt,
+		attrs: Vec::new(),
 	})
 }
 
@@ -2725,6 +3556,7 @@ fn parse_subroutine_decl(p: &mut AbstractParser) -> ReportedResult<SubroutineDec
 		span: span,
 		prototype: prototype,
 		items: items,
+		attrs: Vec::new(),
 	})
 }
 
@@ -2768,6 +3600,7 @@ fn parse_subroutine_prototype(p: &mut AbstractParser) -> ReportedResult<Subrouti
 	Ok(SubroutinePrototype {
 		span: span,
 		kind: kind,
+		retty: retty,
 		name: name,
 		args: args,
 	})
@@ -2788,72 +3621,7 @@ fn parse_subroutine_prototype_tail(p: &mut AbstractParser) -> ReportedResult<(as
 	};
 
 	// Consume the port list.
-	let args = try_flanked(p, Paren, |p| comma_list(p, CloseDelim(Paren), "subroutine port", |p|{
-		let mut span = p.peek(0).1;
-
-		// Consume the optional port direction.
-		let dir = try_subroutine_port_dir(p);
-
-		// Consume the optional "var" keyword.
-		let var = p.try_eat(Keyword(Kw::Var));
-
-		// Branch to parse ports with explicit and implicit type.
-		let mut pp = ParallelParser::new();
-		pp.add("explicit type", |p|{
-			let ty = parse_explicit_type(p)?;
-			Ok((ty, tail(p)?))
-		});
-		pp.add("implicit type", |p|{
-			let ty = parse_implicit_type(p)?;
-			Ok((ty, tail(p)?))
-		});
-		let (ty, name) = pp.finish(p, "explicit or implicit type")?;
-
-		// The `tail` function handles everything that follows the data type. To
-		// ensure that the ports are parsed correctly, the function must fail if
-		// the port is not immediately followed by a "," or ")". Otherwise
-		// implicit and explicit types cannot be distinguished.
-		fn tail(p: &mut AbstractParser) -> ReportedResult<Option<SubroutinePortName>> {
-			// Parse the optional port identifier.
-			let data = if let Some(name) = try_identifier(p)? {
-				// Parse the optional dimensions.
-				let (dims, _) = parse_optional_dimensions(p)?;
-
-				// Parse the optional initial assignment.
-				let expr = if p.try_eat(Operator(Op::Assign)) {
-					Some(parse_expr(p)?)
-				} else {
-					None
-				};
-
-				Some(SubroutinePortName {
-					name: name,
-					dims: dims,
-					expr: expr,
-				})
-			} else {
-				None
-			};
-
-			// Ensure that we have consumed all tokens for this port.
-			match p.peek(0) {
-				(Comma,_) | (CloseDelim(Paren),_) => Ok(data),
-				(_, sp) => {
-					p.add_diag(DiagBuilder2::error("Expected , or ) after subroutine port").span(sp));
-					Err(())
-				}
-			}
-		}
-
-		span.expand(p.last_span());
-		Ok(SubroutinePort {
-			span: span,
-			dir: dir,
-			var: var,
-			ty: ty,
-			name: name,
-		})
-	}))?.unwrap_or(Vec::new());
+	let args = parse_subroutine_ports(p)?;
 
 	// Wrap things up.
 	p.require_reported(Semicolon)?;
@@ -2861,39 +3629,113 @@ fn parse_subroutine_prototype_tail(p: &mut AbstractParser) -> ReportedResult<(as
 }
 
 
-fn try_subroutine_port_dir(p: &mut AbstractParser) -> Option<SubroutinePortDir> {
-	match (p.peek(0).0, p.peek(1).0) {
-		(Keyword(Kw::Input),  _) => { p.bump(); Some(SubroutinePortDir::Input) },
-		(Keyword(Kw::Output), _) => { p.bump(); Some(SubroutinePortDir::Output) },
-		(Keyword(Kw::Inout),  _) => { p.bump(); Some(SubroutinePortDir::Inout) },
-		(Keyword(Kw::Ref),    _) => { p.bump(); Some(SubroutinePortDir::Ref) },
-		(Keyword(Kw::Const), Keyword(Kw::Ref)) => { p.bump(); p.bump(); Some(SubroutinePortDir::ConstRef) },
-		_ => None,
-	}
+/// Parse an optional list of subroutine ports in parentheses.
+fn parse_subroutine_ports(p: &mut AbstractParser) -> ReportedResult<Vec<SubroutinePort>> {
+	Ok(try_flanked(p, Paren, |p| comma_list(p, CloseDelim(Paren), "subroutine port", parse_subroutine_port))?.unwrap_or(Vec::new()))
 }
 
 
-fn parse_subroutine_item(p: &mut AbstractParser) -> ReportedResult<SubroutineItem> {
+fn parse_subroutine_port(p: &mut AbstractParser) -> ReportedResult<SubroutinePort> {
 	let mut span = p.peek(0).1;
 
-	// Try to parse a port declaration of the form:
-	// direction ["var"] type_or_implicit [name_assignment {"," name_assignment}]
-	if let Some(dir) = try_subroutine_port_dir(p) {
+	// Consume the optional port direction.
+	let dir = try_subroutine_port_dir(p);
 
-		// Consume the optional "var" keyword.
-		let var = p.try_eat(Keyword(Kw::Var));
+	// Consume the optional "var" keyword.
+	let var = p.try_eat(Keyword(Kw::Var));
 
-		// Branch to handle the cases of implicit and explicit data type.
-		let mut pp = ParallelParser::new();
-		pp.add("explicit type", |p|{
-			let ty = parse_explicit_type(p)?;
-			let names = comma_list_nonempty(p, Semicolon, "port declaration", parse_variable_decl_assignment)?;
-			p.require_reported(Semicolon)?;
-			Ok((ty, names))
-		});
-		pp.add("implicit type", |p|{
-			let ty = parse_implicit_type(p)?;
-			let names = comma_list_nonempty(p, Semicolon, "port declaration", parse_variable_decl_assignment)?;
+	// Branch to parse ports with explicit and implicit type.
+	let mut pp = ParallelParser::new();
+	pp.add("explicit type", |p|{
+		let ty = parse_explicit_type(p)?;
+		Ok((ty, tail(p)?))
+	});
+	pp.add("implicit type", |p|{
+		let ty = parse_implicit_type(p)?;
+		Ok((ty, tail(p)?))
+	});
+	let (ty, name) = pp.finish(p, "explicit or implicit type")?;
+
+	// The `tail` function handles everything that follows the data type. To
+	// ensure that the ports are parsed correctly, the function must fail if
+	// the port is not immediately followed by a "," or ")". Otherwise
+	// implicit and explicit types cannot be distinguished.
+	fn tail(p: &mut AbstractParser) -> ReportedResult<Option<SubroutinePortName>> {
+		// Parse the optional port identifier.
+		let data = if let Some(name) = try_identifier(p)? {
+			// Parse the optional dimensions.
+			let (dims, _) = parse_optional_dimensions(p)?;
+
+			// Parse the optional initial assignment.
+			let expr = if p.try_eat(Operator(Op::Assign)) {
+				Some(parse_expr(p)?)
+			} else {
+				None
+			};
+
+			Some(SubroutinePortName {
+				name: name,
+				dims: dims,
+				expr: expr,
+			})
+		} else {
+			None
+		};
+
+		// Ensure that we have consumed all tokens for this port.
+		match p.peek(0) {
+			(Comma,_) | (CloseDelim(Paren),_) => Ok(data),
+			(_, sp) => {
+				p.add_diag(DiagBuilder2::error("Expected , or ) after subroutine port").span(sp));
+				Err(())
+			}
+		}
+	}
+
+	span.expand(p.last_span());
+	Ok(SubroutinePort {
+		span: span,
+		dir: dir,
+		var: var,
+		ty: ty,
+		name: name,
+	})
+}
+
+
+fn try_subroutine_port_dir(p: &mut AbstractParser) -> Option<SubroutinePortDir> {
+	match (p.peek(0).0, p.peek(1).0) {
+		(Keyword(Kw::Input),  _) => { p.bump(); Some(SubroutinePortDir::Input) },
+		(Keyword(Kw::Output), _) => { p.bump(); Some(SubroutinePortDir::Output) },
+		(Keyword(Kw::Inout),  _) => { p.bump(); Some(SubroutinePortDir::Inout) },
+		(Keyword(Kw::Ref),    _) => { p.bump(); Some(SubroutinePortDir::Ref) },
+		(Keyword(Kw::Const), Keyword(Kw::Ref)) => { p.bump(); p.bump(); Some(SubroutinePortDir::ConstRef) },
+		_ => None,
+	}
+}
+
+
+fn parse_subroutine_item(p: &mut AbstractParser) -> ReportedResult<SubroutineItem> {
+	let mut span = p.peek(0).1;
+
+	// Try to parse a port declaration of the form:
+	// direction ["var"] type_or_implicit [name_assignment {"," name_assignment}]
+	if let Some(dir) = try_subroutine_port_dir(p) {
+
+		// Consume the optional "var" keyword.
+		let var = p.try_eat(Keyword(Kw::Var));
+
+		// Branch to handle the cases of implicit and explicit data type.
+		let mut pp = ParallelParser::new();
+		pp.add("explicit type", |p|{
+			let ty = parse_explicit_type(p)?;
+			let names = comma_list_nonempty(p, Semicolon, "port declaration", parse_variable_decl_assignment)?;
+			p.require_reported(Semicolon)?;
+			Ok((ty, names))
+		});
+		pp.add("implicit type", |p|{
+			let ty = parse_implicit_type(p)?;
+			let names = comma_list_nonempty(p, Semicolon, "port declaration", parse_variable_decl_assignment)?;
 			p.require_reported(Semicolon)?;
 			Ok((ty, names))
 		});
@@ -2932,14 +3774,17 @@ fn parse_stmt(p: &mut AbstractParser) -> ReportedResult<Stmt> {
 		None
 	};
 
-	// Parse the actual statement item.
+	// Parse the attributes and the actual statement item.
+	let attrs = try_attrs(p)?;
 	let data = parse_stmt_data(p, &mut label)?;
 	span.expand(p.last_span());
 
 	Ok(Stmt {
+		id: DUMMY_NODE_ID,
 		span: span,
 		label: label,
 		data: data,
+		attrs: attrs,
 	})
 }
 
@@ -3031,29 +3876,7 @@ fn parse_stmt_data(p: &mut AbstractParser, label: &mut Option<Name>) -> Reported
 		}
 		Keyword(Kw::Foreach) => {
 			p.bump();
-			let (expr, vars) = flanked(p, Paren, |p|{
-				let expr = parse_expr_prec(p, Precedence::Scope)?;
-				let vars = flanked(p, Brack, |p|{
-					let mut v = Vec::new();
-					while p.peek(0).0 != Eof && p.peek(0).0 != CloseDelim(Brack) {
-						if p.peek(0).0 != Comma {
-							v.push(Some(parse_identifier(p, "loop variable name")?));
-						} else {
-							v.push(None)
-						}
-						match p.peek(0) {
-							(Comma, _) => p.bump(),
-							(CloseDelim(Brack), _) => (),
-							(tkn, sp) => {
-								p.add_diag(DiagBuilder2::error(format!("expected , or ] after loop variable; found {} instead", tkn)).span(sp));
-								return Err(());
-							}
-						}
-					}
-					Ok(v)
-				})?;
-				Ok((expr, vars))
-			})?;
+			let (expr, vars) = flanked(p, Paren, parse_foreach_head)?;
 			let stmt = Box::new(parse_stmt(p)?);
 			ForeachStmt(expr, vars, stmt)
 		}
@@ -3257,6 +4080,7 @@ fn parse_continuous_assign(p: &mut AbstractParser) -> ReportedResult<ContAssign>
 		delay: delay,
 		delay_control: delay_control,
 		assignments: assignments,
+		attrs: Vec::new(),
 	})
 }
 
@@ -3469,14 +4293,34 @@ fn try_event_control(p: &mut AbstractParser) -> ReportedResult<Option<EventContr
 	}))
 }
 
+/// Parse a cycle delay.
+///
+/// ```text
+/// "##" (integral_number | ident | "(" expr ")")
+/// ```
 fn try_cycle_delay(p: &mut AbstractParser) -> ReportedResult<Option<CycleDelay>> {
 	if !p.try_eat(DoubleHashtag) {
 		return Ok(None)
 	}
+	let mut span = p.last_span();
 
-	let q = p.last_span();
-	p.add_diag(DiagBuilder2::error("Don't know how to parse cycle delay").span(q));
-	Err(())
+	let (tkn, sp) = p.peek(0);
+	let expr = match tkn {
+		OpenDelim(Paren) => flanked(p, Paren, parse_expr)?,
+		Literal(UnsignedInteger(..)) |
+		Ident(..) |
+		EscIdent(..) => parse_expr_first(p, Precedence::Max)?,
+		_ => {
+			p.add_diag(DiagBuilder2::error("Expected number of cycles after ##").span(sp));
+			return Err(());
+		}
+	};
+	span.expand(p.last_span());
+
+	Ok(Some(CycleDelay {
+		span: span,
+		expr: expr,
+	}))
 }
 
 
@@ -3510,9 +4354,11 @@ fn parse_assign_stmt(p: &mut AbstractParser) -> ReportedResult<StmtData> {
 	if tkn == Operator(Op::Leq) {
 		p.bump();
 
-		// Parse the optional delay and event control.
+		// Parse the optional delay and event control, or the cycle delay of
+		// a clocking drive.
 		let delay_control = try_delay_control(p)?;
 		let event_control = /*try_event_control(p)?*/ None;
+		let cycle_delay = try_cycle_delay(p)?;
 
 		// Parse the right-hand side of the assignment.
 		let rhs = parse_expr(p)?;
@@ -3523,6 +4369,7 @@ fn parse_assign_stmt(p: &mut AbstractParser) -> ReportedResult<StmtData> {
 			rhs: rhs,
 			delay: delay_control,
 			event: event_control,
+			cycle: cycle_delay,
 		});
 	}
 
@@ -3531,6 +4378,32 @@ fn parse_assign_stmt(p: &mut AbstractParser) -> ReportedResult<StmtData> {
 }
 
 
+/// Parse the array and loop variables of a foreach loop, e.g. `arr[i, , k]`.
+fn parse_foreach_head(p: &mut AbstractParser) -> ReportedResult<(Expr, Vec<Option<Identifier>>)> {
+	let expr = parse_expr_prec(p, Precedence::Scope)?;
+	let vars = flanked(p, Brack, |p|{
+		let mut v = Vec::new();
+		while p.peek(0).0 != Eof && p.peek(0).0 != CloseDelim(Brack) {
+			if p.peek(0).0 != Comma {
+				v.push(Some(parse_identifier(p, "loop variable name")?));
+			} else {
+				v.push(None)
+			}
+			match p.peek(0) {
+				(Comma, _) => p.bump(),
+				(CloseDelim(Brack), _) => (),
+				(tkn, sp) => {
+					p.add_diag(DiagBuilder2::error(format!("expected , or ] after loop variable; found {} instead", tkn)).span(sp));
+					return Err(());
+				}
+			}
+		}
+		Ok(v)
+	})?;
+	Ok((expr, vars))
+}
+
+
 fn parse_expr_stmt(p: &mut AbstractParser) -> ReportedResult<StmtData> {
 	let expr = parse_expr_prec(p, Precedence::Unary)?;
 	p.require_reported(Semicolon)?;
@@ -3625,6 +4498,30 @@ fn as_edge_ident(tkn: Token) -> EdgeIdent {
 
 
 fn parse_call_args(p: &mut AbstractParser) -> ReportedResult<Vec<CallArg>> {
+	parse_call_args_with(p, parse_expr)
+}
+
+/// Parse the arguments of a system task or function call. Positional
+/// arguments may be data types, as in `$bits(logic [3:0])`. These are wrapped
+/// in a type expression; whether the function accepts a type is checked
+/// during name resolution.
+fn parse_sys_call_args(p: &mut AbstractParser) -> ReportedResult<Vec<CallArg>> {
+	parse_call_args_with(p, |p| {
+		match parse_type_or_expr(p, &[Comma, CloseDelim(Paren)])? {
+			ast::TypeOrExpr::Expr(expr) => Ok(expr),
+			ast::TypeOrExpr::Type(ty) => Ok(Expr {
+				id: DUMMY_NODE_ID,
+				span: ty.span,
+				data: TypeExpr(Box::new(ty)),
+				attrs: Vec::new(),
+			}),
+		}
+	})
+}
+
+fn parse_call_args_with<F>(p: &mut AbstractParser, mut parse_arg: F) -> ReportedResult<Vec<CallArg>>
+	where F: FnMut(&mut AbstractParser) -> ReportedResult<Expr>
+{
 	let mut v = Vec::new();
 	if p.peek(0).0 == CloseDelim(Paren) {
 		return Ok(v);
@@ -3658,7 +4555,7 @@ fn parse_call_args(p: &mut AbstractParser) -> ReportedResult<Vec<CallArg>> {
 				});
 			}
 			(_, mut sp) => {
-				let expr = parse_expr(p)?;
+				let expr = parse_arg(p)?;
 				sp.expand(p.last_span());
 				v.push(CallArg {
 					span: sp,
@@ -3799,9 +4696,35 @@ fn parse_generate_if(p: &mut AbstractParser) -> ReportedResult<GenerateIf> {
 fn parse_generate_case(p: &mut AbstractParser) -> ReportedResult<GenerateCase> {
 	let mut span = p.peek(0).1;
 	p.require_reported(Keyword(Kw::Case))?;
-	let q = p.last_span();
-	p.add_diag(DiagBuilder2::error("Don't know how to parse case-generate statements").span(q));
-	Err(())
+	let expr = flanked(p, Paren, parse_expr)?;
+
+	// Parse the case items.
+	let mut items = Vec::new();
+	while p.peek(0).0 != Keyword(Kw::Endcase) && p.peek(0).0 != Eof {
+		let mut item_span = p.peek(0).1;
+		let exprs = if p.try_eat(Keyword(Kw::Default)) {
+			p.try_eat(Colon);
+			Vec::new()
+		} else {
+			let exprs = comma_list_nonempty(p, Colon, "case item expression", parse_expr)?;
+			p.require_reported(Colon)?;
+			exprs
+		};
+		let block = parse_generate_block(p)?;
+		item_span.expand(p.last_span());
+		items.push(GenerateCaseItem {
+			span: item_span,
+			exprs: exprs,
+			block: block,
+		});
+	}
+	p.require_reported(Keyword(Kw::Endcase))?;
+	span.expand(p.last_span());
+	Ok(GenerateCase {
+		span: span,
+		expr: expr,
+		items: items,
+	})
 }
 
 
@@ -3878,8 +4801,9 @@ fn parse_class_decl(p: &mut AbstractParser) -> ReportedResult<ClassDecl> {
 	let mut span = p.peek(0).1;
 	let result = recovered(p, Keyword(Kw::Endclass), |p|{
 
-		// Eat the optional "virtual" keyword.
+		// Eat the optional "virtual" or "interface" keyword.
 		let virt = p.try_eat(Keyword(Kw::Virtual));
+		let intf = !virt && p.try_eat(Keyword(Kw::Interface));
 
 		// Eat the "class" keyword.
 		p.require_reported(Keyword(Kw::Class))?;
@@ -3900,28 +4824,43 @@ fn parse_class_decl(p: &mut AbstractParser) -> ReportedResult<ClassDecl> {
 			Vec::new()
 		};
 
-		// Parse the optional inheritance clause.
-		let extends = if p.try_eat(Keyword(Kw::Extends)) {
+		// Parse the optional inheritance clause. Interface classes may extend
+		// multiple other interface classes, which are treated like the ones a
+		// class implements.
+		let mut implements = Vec::new();
+		let extends = if intf {
+			if p.try_eat(Keyword(Kw::Extends)) {
+				implements = comma_list_nonempty(p, Semicolon, "interface class", parse_data_type)?;
+			}
+			None
+		} else if p.try_eat(Keyword(Kw::Extends)) {
 			let superclass = parse_data_type(p)?;
 			let args = try_flanked(p, Paren, parse_call_args)?.unwrap_or(Vec::new());
 			Some((superclass, args))
 		} else {
 			None
 		};
+
+		// Parse the optional list of implemented interface classes.
+		if !intf && p.try_eat(Keyword(Kw::Implements)) {
+			implements = comma_list_nonempty(p, Semicolon, "interface class", parse_data_type)?;
+		}
 		p.require_reported(Semicolon)?;
 
 		// Parse the class items.
 		let items = repeat_until(p, Keyword(Kw::Endclass), parse_class_item)?;
-		Ok((virt, lifetime, name, params, extends, items))
+		Ok((virt, intf, lifetime, name, params, extends, implements, items))
 	});
 	p.require_reported(Keyword(Kw::Endclass))?;
 
 	let (
 		virt,
+		intf,
 		lifetime,
 		name,
 		params,
 		extends,
+		implements,
 		items
 	) = result?;
 
@@ -3938,10 +4877,12 @@ fn parse_class_decl(p: &mut AbstractParser) -> ReportedResult<ClassDecl> {
 	Ok(ClassDecl {
 		span: span,
 		virt: virt,
+		intf: intf,
 		lifetime: lifetime,
 		name: name,
 		params: params,
 		extends: extends,
+		implements: implements,
 		items: items,
 	})
 }
@@ -3975,6 +4916,17 @@ fn parse_class_item(p: &mut AbstractParser) -> ReportedResult<ClassItem> {
 		_ => ()
 	}
 
+	// Parse covergroup declarations.
+	if p.peek(0).0 == Keyword(Kw::Covergroup) {
+		let decl = parse_covergroup_decl(p)?;
+		span.expand(p.last_span());
+		return Ok(ClassItem {
+			span: span,
+			qualifiers: Vec::new(),
+			data: ClassItemData::CovergroupDecl(decl),
+		});
+	}
+
 	// Parse "extern" task and function prototypes.
 	if p.try_eat(Keyword(Kw::Extern)) {
 		let proto = parse_subroutine_prototype(p)?;
@@ -3989,16 +4941,34 @@ fn parse_class_item(p: &mut AbstractParser) -> ReportedResult<ClassItem> {
 	// Parse the optional class item qualifiers.
 	let qualifiers = parse_class_item_qualifiers(p)?;
 
+	// Pure virtual methods only consist of a prototype.
+	if qualifiers.iter().any(|&(q, _)| q == ClassItemQualifier::Pure) && p.peek(0).0 != Keyword(Kw::Constraint) {
+		let proto = parse_subroutine_prototype(p)?;
+		span.expand(p.last_span());
+		return Ok(ClassItem {
+			span: span,
+			qualifiers: qualifiers,
+			data: ClassItemData::ExternSubroutine(proto),
+		})
+	}
+
 	let data = {
 		let mut pp = ParallelParser::new();
 		pp.add("class property", |p| {
 			let ty = parse_data_type(p)?;
 			let names = comma_list_nonempty(p, Semicolon, "data declaration", parse_variable_decl_assignment)?;
 			p.require_reported(Semicolon)?;
-			Ok(ClassItemData::Property)
+			Ok(ClassItemData::Property { ty: ty, names: names })
 		});
 		pp.add("class function or task", |p| parse_subroutine_decl(p).map(|d| ClassItemData::SubroutineDecl(d)));
-		pp.add("class constraint", |p| parse_constraint(p).map(|c| ClassItemData::Constraint(c)));
+		pp.add("class constraint", |p| {
+			let c = parse_constraint(p)?;
+			if let Some(ref class) = c.class {
+				p.add_diag(DiagBuilder2::error(format!("Constraint `{}` is declared within a class, and cannot name class `{}`", c.name, class.name)).span(class.span));
+				return Err(());
+			}
+			Ok(ClassItemData::Constraint(c))
+		});
 		pp.finish(p, "class item")?
 	};
 	span.expand(p.last_span());
@@ -4062,15 +5032,23 @@ fn parse_constraint(p: &mut AbstractParser) -> ReportedResult<Constraint> {
 	// Parse the "constraint" keyword.
 	p.require_reported(Keyword(Kw::Constraint))?;
 
-	// Parse the constraint name.
+	// Parse the class of a constraint defined outside of it, and the
+	// constraint name.
+	let class = if p.peek(1).0 == Namespace {
+		let class = parse_identifier(p, "class name")?;
+		p.bump();
+		Some(class)
+	} else {
+		None
+	};
 	let (name, name_span) = p.eat_ident("constraint name")?;
 
-	let items = if p.try_eat(Semicolon) {
+	let (kind, items) = if p.try_eat(Semicolon) {
 		let kind = match kind {
 			ConstraintKind::Decl => ConstraintKind::Proto,
 			x => x,
 		};
-		Vec::new()
+		(kind, Vec::new())
 	} else {
 		// Make sure that no "extern" or "pure" keyword was used, as these are
 		// only valid for prototypes.
@@ -4078,7 +5056,7 @@ fn parse_constraint(p: &mut AbstractParser) -> ReportedResult<Constraint> {
 			p.add_diag(DiagBuilder2::error("Only constraint prototypes can be extern or pure").span(kind_span));
 			return Err(());
 		}
-		flanked(p, Brace, |p| repeat_until(p, CloseDelim(Brace), parse_constraint_item))?
+		(kind, flanked(p, Brace, |p| repeat_until(p, CloseDelim(Brace), parse_constraint_item))?)
 	};
 	span.expand(p.last_span());
 
@@ -4086,6 +5064,7 @@ fn parse_constraint(p: &mut AbstractParser) -> ReportedResult<Constraint> {
 		span: span,
 		kind: kind,
 		statik: statik,
+		class: class,
 		name: name,
 		name_span: name_span,
 		items: items,
@@ -4093,6 +5072,26 @@ fn parse_constraint(p: &mut AbstractParser) -> ReportedResult<Constraint> {
 }
 
 
+/// Parse a constraint defined outside of its class.
+/// ```text
+/// ["static"] "constraint" ident "::" ident "{" {constraint_item} "}"
+/// ```
+fn parse_out_of_class_constraint(p: &mut AbstractParser) -> ReportedResult<Constraint> {
+	let c = parse_constraint(p)?;
+	if c.class.is_none() {
+		p.add_diag(DiagBuilder2::error(format!("Constraint `{}` is declared outside of a class, but does not name one", c.name))
+			.span(c.name_span)
+			.add_note(format!("use `constraint <class>::{} {{ ... }}` to define the constraint of a class", c.name)));
+		return Err(());
+	}
+	if c.kind != ConstraintKind::Decl {
+		p.add_diag(DiagBuilder2::error(format!("Constraint `{}` is declared outside of its class, but has no body", c.name)).span(c.span));
+		return Err(());
+	}
+	Ok(c)
+}
+
+
 fn parse_constraint_item(p: &mut AbstractParser) -> ReportedResult<ConstraintItem> {
 	let mut span = p.peek(0).1;
 	let data = parse_constraint_item_data(p)?;
@@ -4105,53 +5104,582 @@ fn parse_constraint_item(p: &mut AbstractParser) -> ReportedResult<ConstraintIte
 
 
 fn parse_constraint_item_data(p: &mut AbstractParser) -> ReportedResult<ConstraintItemData> {
-	// Handle the trivial cases that start with a keyword first.
-	if p.try_eat(Keyword(Kw::If)) {
-		let q = p.last_span();
-		p.add_diag(DiagBuilder2::error("Don't know how to parse `if` constraint items").span(q));
-		return Err(());
+	// Handle the cases that start with a keyword first.
+	match p.peek(0).0 {
+		// "if" "(" expr ")" constraint_set ["else" constraint_set]
+		Keyword(Kw::If) => {
+			p.bump();
+			let cond = flanked(p, Paren, parse_expr)?;
+			let main_items = parse_constraint_set(p)?;
+			let else_items = if p.try_eat(Keyword(Kw::Else)) {
+				Some(parse_constraint_set(p)?)
+			} else {
+				None
+			};
+			return Ok(ConstraintItemData::If {
+				cond: cond,
+				main_items: main_items,
+				else_items: else_items,
+			});
+		}
+
+		// "foreach" "(" array "[" loop_variables "]" ")" constraint_set
+		Keyword(Kw::Foreach) => {
+			p.bump();
+			let (expr, vars) = flanked(p, Paren, parse_foreach_head)?;
+			let items = parse_constraint_set(p)?;
+			return Ok(ConstraintItemData::Foreach {
+				expr: expr,
+				vars: vars,
+				items: items,
+			});
+		}
+
+		// "solve" variables "before" variables ";"
+		Keyword(Kw::Solve) => {
+			p.bump();
+			let first = comma_list_nonempty(p, Keyword(Kw::Before), "solve variable", parse_expr)?;
+			p.require_reported(Keyword(Kw::Before))?;
+			let then = comma_list_nonempty(p, Semicolon, "solve variable", parse_expr)?;
+			p.require_reported(Semicolon)?;
+			return Ok(ConstraintItemData::SolveBefore(first, then));
+		}
+
+		// "disable" "soft" expr ";"
+		Keyword(Kw::Disable) => {
+			p.bump();
+			p.require_reported(Keyword(Kw::Soft))?;
+			let expr = parse_expr(p)?;
+			p.require_reported(Semicolon)?;
+			return Ok(ConstraintItemData::DisableSoft(expr));
+		}
+
+		// "unique" "{" open_range_list "}" ";"
+		Keyword(Kw::Unique) => {
+			p.bump();
+			let exprs = flanked(p, Brace, |p| comma_list_nonempty(p, CloseDelim(Brace), "unique value", parse_value_range))?;
+			p.require_reported(Semicolon)?;
+			return Ok(ConstraintItemData::Unique(exprs));
+		}
+
+		_ => ()
 	}
 
-	if p.try_eat(Keyword(Kw::Foreach)) {
-		let q = p.last_span();
-		p.add_diag(DiagBuilder2::error("Don't know how to parse `foreach` constraint items").span(q));
-		return Err(());
+	// If we arrive here, the item starts with an expression. Implications
+	// are not parsed as part of the expression, since their right-hand side
+	// is a constraint set.
+	let soft = p.try_eat(Keyword(Kw::Soft));
+	let mut expr = parse_expr_prec(p, Precedence::Ternary)?;
+	if p.peek(0).0 == Ternary {
+		expr = parse_expr_suffix(p, expr, Precedence::Implication)?;
+	}
+
+	// expr "->" constraint_set
+	if !soft && p.try_eat(Operator(Op::LogicImpl)) {
+		let items = parse_constraint_set(p)?;
+		return Ok(ConstraintItemData::Implication {
+			cond: expr,
+			items: items,
+		});
+	}
+
+	// expr "dist" "{" dist_list "}" ";"
+	if p.try_eat(Keyword(Kw::Dist)) {
+		let items = flanked(p, Brace, |p| comma_list_nonempty(p, CloseDelim(Brace), "distribution item", parse_dist_item))?;
+		p.require_reported(Semicolon)?;
+		return Ok(ConstraintItemData::Dist {
+			soft: soft,
+			expr: expr,
+			items: items,
+		});
 	}
 
-	// If we arrive here, the item starts with an expression.
-	let expr = parse_expr(p)?;
 	p.require_reported(Semicolon)?;
-	Ok(ConstraintItemData::Expr(expr))
+	Ok(ConstraintItemData::Expr {
+		soft: soft,
+		expr: expr,
+	})
 }
 
 
-struct ParallelParser<'a, R: Clone> {
-	branches: Vec<(String, Box<FnMut(&mut AbstractParser) -> ReportedResult<R> + 'a>, bool)>,
+/// Parse a constraint set, which is either a single constraint item or a
+/// list of items enclosed in braces.
+fn parse_constraint_set(p: &mut AbstractParser) -> ReportedResult<Vec<ConstraintItem>> {
+	if p.peek(0).0 == OpenDelim(Brace) {
+		flanked(p, Brace, |p| repeat_until(p, CloseDelim(Brace), parse_constraint_item))
+	} else {
+		Ok(vec![parse_constraint_item(p)?])
+	}
 }
 
-impl<'a, R: Clone> ParallelParser<'a, R> {
-	pub fn new() -> Self {
-		ParallelParser {
-			branches: Vec::new(),
+
+fn parse_dist_item(p: &mut AbstractParser) -> ReportedResult<DistItem> {
+	let mut span = p.peek(0).1;
+	let value = parse_value_range(p)?;
+
+	// The weight is introduced by either ":=" or ":/", which the lexer emits
+	// as two separate tokens.
+	let weight = match (p.peek(0).0, p.peek(1).0) {
+		(Colon, Operator(Op::Assign)) => Some(DistWeight::PerValue),
+		(Colon, Operator(Op::Div)) => Some(DistWeight::PerRange),
+		_ => None,
+	};
+	let weight = match weight {
+		Some(w) => {
+			p.bump();
+			p.bump();
+			Some((w, parse_expr(p)?))
 		}
-	}
+		None => None,
+	};
+	span.expand(p.last_span());
 
-	pub fn add<F>(&mut self, name: &str, func: F)
-	where F: FnMut(&mut AbstractParser) -> ReportedResult<R> + 'a {
-		self.branches.push((name.to_owned(), Box::new(func), false));
+	Ok(DistItem {
+		span: span,
+		value: value,
+		weight: weight,
+	})
+}
+
+
+/// Parse a value range, which is either an expression or a `[lo:hi]` range.
+/// The expression cannot be an assignment, such that the `=>` of a
+/// transition may follow it.
+fn parse_value_range(p: &mut AbstractParser) -> ReportedResult<Expr> {
+	if p.peek(0).0 == OpenDelim(Brack) {
+		flanked(p, Brack, parse_range_expr)
+	} else {
+		parse_expr_prec(p, Precedence::Implication)
 	}
+}
 
-	pub fn add_greedy<F>(&mut self, name: &str, func: F)
-	where F: FnMut(&mut AbstractParser) -> ReportedResult<R> + 'a {
-		self.branches.push((name.to_owned(), Box::new(func), true));
+
+/// Check whether a token following an opening bracket introduces a
+/// repetition rather than an index.
+fn is_repetition_op(tkn: Token) -> bool {
+	match tkn {
+		Operator(Op::Mul) | Operator(Op::LogicImpl) | Operator(Op::Assign) => true,
+		_ => false,
 	}
+}
 
-	pub fn finish(self, p: &mut AbstractParser, msg: &str) -> ReportedResult<R> {
-		let (tkn, q) = p.peek(0);
-		// p.add_diag(DiagBuilder2::note(format!("Trying as {:?}", self.branches.iter().map(|&(ref x,_)| x).collect::<Vec<_>>())).span(q));
 
-		// Create a separate speculative parser for each branch.
-		let mut results = Vec::new();
+/// Check whether an expression is a call to `randomize`, which may be
+/// followed by inline constraints.
+fn is_randomize_call(expr: &Expr) -> bool {
+	let callee = match expr.data {
+		CallExpr(ref callee, _) => callee,
+		_ => expr,
+	};
+	match callee.data {
+		IdentExpr(ref ident) | MemberExpr { name: ref ident, .. } => &*ident.name.as_str() == "randomize",
+		_ => false,
+	}
+}
+
+
+fn parse_covergroup_decl(p: &mut AbstractParser) -> ReportedResult<CovergroupDecl> {
+	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Covergroup))?;
+	let name = parse_identifier(p, "covergroup name")?;
+	let ports = parse_subroutine_ports(p)?;
+
+	// Parse the optional coverage event.
+	let event = if p.peek(0).0 == At && p.peek(1).0 == At {
+		let q = p.peek(0).1;
+		p.add_diag(DiagBuilder2::error("Block event expressions in covergroups are not supported").span(q));
+		return Err(());
+	} else if let Some(ec) = try_event_control(p)? {
+		Some(CoverageEvent::Clocking(ec))
+	} else if p.try_eat(Keyword(Kw::With)) {
+		p.require_reported(Keyword(Kw::Function))?;
+		let (n, sp) = p.eat_ident("`sample`")?;
+		if &*n.as_str() != "sample" {
+			p.add_diag(DiagBuilder2::error(format!("Expected `sample`, found `{}` instead", n)).span(sp));
+			return Err(());
+		}
+		Some(CoverageEvent::Sample(parse_subroutine_ports(p)?))
+	} else {
+		None
+	};
+	p.require_reported(Semicolon)?;
+
+	let items = repeat_until(p, Keyword(Kw::Endgroup), parse_covergroup_item)?;
+	p.require_reported(Keyword(Kw::Endgroup))?;
+
+	// Parse the optional covergroup name after "endgroup".
+	if p.try_eat(Colon) {
+		let (n, sp) = p.eat_ident("covergroup name")?;
+		if n != name.name {
+			p.add_diag(DiagBuilder2::error(format!("Covergroup name {} disagrees with name {} given before", n, name.name)).span(sp));
+			return Err(());
+		}
+	}
+
+	span.expand(p.last_span());
+	Ok(CovergroupDecl {
+		span: span,
+		name: name,
+		ports: ports,
+		event: event,
+		items: items,
+	})
+}
+
+
+fn parse_covergroup_item(p: &mut AbstractParser) -> ReportedResult<CovergroupItem> {
+	if let Some(option) = try_coverage_option(p)? {
+		return Ok(CovergroupItem::Option(option));
+	}
+	let mut span = p.peek(0).1;
+
+	// Parse the optional label.
+	let name = match (p.peek(0).0, p.peek(1).0) {
+		(Ident(_), Colon) | (EscIdent(_), Colon) => {
+			let name = parse_identifier(p, "coverpoint or cross name")?;
+			p.bump();
+			Some(name)
+		}
+		_ => None,
+	};
+
+	match p.peek(0) {
+		// "coverpoint" expr ["iff" "(" expr ")"] bins_or_empty
+		(Keyword(Kw::Coverpoint), _) => {
+			p.bump();
+			let expr = parse_expr(p)?;
+			let iff = try_iff(p)?;
+			let bins = parse_bins_or_empty(p, false)?;
+			span.expand(p.last_span());
+			Ok(CovergroupItem::Coverpoint(Coverpoint {
+				id: DUMMY_NODE_ID,
+				span: span,
+				name: name,
+				expr: expr,
+				iff: iff,
+				bins: bins,
+			}))
+		}
+
+		// "cross" cross_item "," cross_item {"," cross_item} ["iff" "(" expr ")"] cross_body
+		(Keyword(Kw::Cross), _) => {
+			p.bump();
+			let mut items = vec![parse_identifier(p, "coverpoint name")?];
+			while p.try_eat(Comma) {
+				items.push(parse_identifier(p, "coverpoint name")?);
+			}
+			if items.len() < 2 {
+				p.add_diag(DiagBuilder2::error("A cross requires at least two coverpoints").span(span));
+				return Err(());
+			}
+			let iff = try_iff(p)?;
+			let bins = parse_bins_or_empty(p, true)?;
+			span.expand(p.last_span());
+			Ok(CovergroupItem::Cross(Cross {
+				id: DUMMY_NODE_ID,
+				span: span,
+				name: name,
+				items: items,
+				iff: iff,
+				bins: bins,
+			}))
+		}
+
+		(tkn, sp) => {
+			p.add_diag(DiagBuilder2::error(format!("Expected coverpoint, cross, or coverage option; found {} instead", tkn)).span(sp));
+			Err(())
+		}
+	}
+}
+
+
+/// Try to parse a coverage option such as `option.weight = 2;`.
+fn try_coverage_option(p: &mut AbstractParser) -> ReportedResult<Option<CoverageOption>> {
+	let type_option = match (p.peek(0).0, p.peek(1).0) {
+		(Ident(n), Period) if &*n.as_str() == "option" => false,
+		(Ident(n), Period) if &*n.as_str() == "type_option" => true,
+		_ => return Ok(None),
+	};
+	let mut span = p.peek(0).1;
+	p.bump();
+	p.bump();
+	let name = parse_identifier(p, "option name")?;
+	p.require_reported(Operator(Op::Assign))?;
+	let expr = parse_expr(p)?;
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+	Ok(Some(CoverageOption {
+		span: span,
+		type_option: type_option,
+		name: name,
+		expr: expr,
+	}))
+}
+
+
+/// Try to parse an `iff (expr)` guard.
+fn try_iff(p: &mut AbstractParser) -> ReportedResult<Option<Expr>> {
+	if p.try_eat(Keyword(Kw::Iff)) {
+		Ok(Some(flanked(p, Paren, parse_expr)?))
+	} else {
+		Ok(None)
+	}
+}
+
+
+/// Parse the body of a coverpoint or cross, which is either a list of bins
+/// and options in braces, or a semicolon.
+fn parse_bins_or_empty(p: &mut AbstractParser, cross: bool) -> ReportedResult<Vec<BinsOrOption>> {
+	if p.try_eat(Semicolon) {
+		return Ok(Vec::new());
+	}
+	flanked(p, Brace, |p| repeat_until(p, CloseDelim(Brace), |p|{
+		if let Some(option) = try_coverage_option(p)? {
+			Ok(BinsOrOption::Option(option))
+		} else {
+			parse_bins(p, cross).map(BinsOrOption::Bins)
+		}
+	}))
+}
+
+
+fn parse_bins(p: &mut AbstractParser, cross: bool) -> ReportedResult<Bins> {
+	let mut span = p.peek(0).1;
+	let wildcard = !cross && p.try_eat(Keyword(Kw::Wildcard));
+	let kind = match p.peek(0) {
+		(Keyword(Kw::Bins), _) => BinsKind::Bins,
+		(Keyword(Kw::IllegalBins), _) => BinsKind::IllegalBins,
+		(Keyword(Kw::IgnoreBins), _) => BinsKind::IgnoreBins,
+		(tkn, sp) => {
+			p.add_diag(DiagBuilder2::error(format!("Expected bins, illegal_bins, ignore_bins, or coverage option; found {} instead", tkn)).span(sp));
+			return Err(());
+		}
+	};
+	p.bump();
+	let name = parse_identifier(p, "bin name")?;
+
+	// Parse the optional array size, as in `name[]` or `name[4]`.
+	let array = if !cross && p.try_eat(OpenDelim(Brack)) {
+		if p.try_eat(CloseDelim(Brack)) {
+			Some(None)
+		} else {
+			let expr = parse_expr(p)?;
+			p.require_reported(CloseDelim(Brack))?;
+			Some(Some(expr))
+		}
+	} else {
+		None
+	};
+	p.require_reported(Operator(Op::Assign))?;
+
+	let data = match p.peek(0).0 {
+		_ if cross => BinsData::Select(parse_select_expr(p)?),
+		OpenDelim(Brace) => BinsData::Values(flanked(p, Brace, |p| comma_list_nonempty(p, CloseDelim(Brace), "bin value", parse_value_range))?),
+		OpenDelim(Paren) => {
+			let mut v = vec![flanked(p, Paren, parse_trans_set)?];
+			while p.try_eat(Comma) {
+				v.push(flanked(p, Paren, parse_trans_set)?);
+			}
+			BinsData::Transitions(v)
+		}
+		Keyword(Kw::Default) => {
+			p.bump();
+			if p.try_eat(Keyword(Kw::Sequence)) {
+				BinsData::DefaultSequence
+			} else {
+				BinsData::Default
+			}
+		}
+		_ => BinsData::Expr(parse_expr(p)?),
+	};
+
+	// Parse the optional "with" filter and "iff" guard.
+	let with = if p.try_eat(Keyword(Kw::With)) {
+		Some(flanked(p, Paren, parse_expr)?)
+	} else {
+		None
+	};
+	let iff = try_iff(p)?;
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+
+	Ok(Bins {
+		span: span,
+		kind: kind,
+		wildcard: wildcard,
+		name: name,
+		array: array,
+		data: data,
+		with: with,
+		iff: iff,
+	})
+}
+
+
+/// Parse a sequence of transition steps separated by `=>`.
+fn parse_trans_set(p: &mut AbstractParser) -> ReportedResult<Vec<TransItem>> {
+	let mut v = vec![parse_trans_item(p)?];
+	// The lexer emits "=>" as two separate tokens.
+	while p.peek(0).0 == Operator(Op::Assign) && p.peek(1).0 == Operator(Op::Gt) {
+		p.bump();
+		p.bump();
+		v.push(parse_trans_item(p)?);
+	}
+	Ok(v)
+}
+
+
+fn parse_trans_item(p: &mut AbstractParser) -> ReportedResult<TransItem> {
+	let mut span = p.peek(0).1;
+	let mut values = vec![parse_value_range(p)?];
+	while p.try_eat(Comma) {
+		values.push(parse_value_range(p)?);
+	}
+
+	// Parse the optional repetition, as in `[* 2]`, `[-> 1:3]`, or `[= 2]`.
+	let kind = match (p.peek(0).0, p.peek(1).0) {
+		(OpenDelim(Brack), Operator(Op::Mul)) => Some(TransRepeatKind::Consecutive),
+		(OpenDelim(Brack), Operator(Op::LogicImpl)) => Some(TransRepeatKind::Goto),
+		(OpenDelim(Brack), Operator(Op::Assign)) => Some(TransRepeatKind::NonConsecutive),
+		_ => None,
+	};
+	let repeat = match kind {
+		Some(kind) => {
+			p.bump();
+			p.bump();
+			let min = parse_expr(p)?;
+			let max = if p.try_eat(Colon) {
+				Some(parse_expr(p)?)
+			} else {
+				None
+			};
+			p.require_reported(CloseDelim(Brack))?;
+			Some(TransRepeat {
+				kind: kind,
+				min: min,
+				max: max,
+			})
+		}
+		None => None,
+	};
+	span.expand(p.last_span());
+
+	Ok(TransItem {
+		span: span,
+		values: values,
+		repeat: repeat,
+	})
+}
+
+
+/// Parse the select expression of a cross bin. The `&&` operator binds
+/// stronger than `||`.
+fn parse_select_expr(p: &mut AbstractParser) -> ReportedResult<SelectExpr> {
+	let mut expr = parse_select_expr_and(p)?;
+	while p.try_eat(Operator(Op::LogicOr)) {
+		let rhs = parse_select_expr_and(p)?;
+		expr = SelectExpr {
+			span: Span::union(expr.span, rhs.span),
+			data: SelectExprData::Or(Box::new(expr), Box::new(rhs)),
+		};
+	}
+	Ok(expr)
+}
+
+
+fn parse_select_expr_and(p: &mut AbstractParser) -> ReportedResult<SelectExpr> {
+	let mut expr = parse_select_expr_primary(p)?;
+	while p.try_eat(Operator(Op::LogicAnd)) {
+		let rhs = parse_select_expr_primary(p)?;
+		expr = SelectExpr {
+			span: Span::union(expr.span, rhs.span),
+			data: SelectExprData::And(Box::new(expr), Box::new(rhs)),
+		};
+	}
+	Ok(expr)
+}
+
+
+fn parse_select_expr_primary(p: &mut AbstractParser) -> ReportedResult<SelectExpr> {
+	let mut span = p.peek(0).1;
+	match p.peek(0).0 {
+		// "!" select_condition
+		Operator(Op::LogicNot) => {
+			p.bump();
+			let expr = parse_select_expr_primary(p)?;
+			span.expand(p.last_span());
+			Ok(SelectExpr {
+				span: span,
+				data: SelectExprData::Not(Box::new(expr)),
+			})
+		}
+
+		// "(" select_expression ")"
+		OpenDelim(Paren) => flanked(p, Paren, parse_select_expr),
+
+		// "binsof" "(" name ["." bin] ")" ["intersect" "{" range_list "}"]
+		Keyword(Kw::Binsof) => {
+			p.bump();
+			let (target, bin) = flanked(p, Paren, |p|{
+				let target = parse_identifier(p, "coverpoint name")?;
+				let bin = if p.try_eat(Period) {
+					Some(parse_identifier(p, "bin name")?)
+				} else {
+					None
+				};
+				Ok((target, bin))
+			})?;
+			let intersect = if p.try_eat(Keyword(Kw::Intersect)) {
+				flanked(p, Brace, |p| comma_list_nonempty(p, CloseDelim(Brace), "intersect value", parse_value_range))?
+			} else {
+				Vec::new()
+			};
+			span.expand(p.last_span());
+			Ok(SelectExpr {
+				span: span,
+				data: SelectExprData::Binsof {
+					target: target,
+					bin: bin,
+					intersect: intersect,
+				},
+			})
+		}
+
+		tkn => {
+			p.add_diag(DiagBuilder2::error(format!("Expected binsof, !, or (; found {} instead", tkn)).span(span));
+			Err(())
+		}
+	}
+}
+
+
+struct ParallelParser<'a, R: Clone> {
+	branches: Vec<(String, Box<FnMut(&mut AbstractParser) -> ReportedResult<R> + 'a>, bool)>,
+}
+
+impl<'a, R: Clone> ParallelParser<'a, R> {
+	pub fn new() -> Self {
+		ParallelParser {
+			branches: Vec::new(),
+		}
+	}
+
+	pub fn add<F>(&mut self, name: &str, func: F)
+	where F: FnMut(&mut AbstractParser) -> ReportedResult<R> + 'a {
+		self.branches.push((name.to_owned(), Box::new(func), false));
+	}
+
+	pub fn add_greedy<F>(&mut self, name: &str, func: F)
+	where F: FnMut(&mut AbstractParser) -> ReportedResult<R> + 'a {
+		self.branches.push((name.to_owned(), Box::new(func), true));
+	}
+
+	pub fn finish(self, p: &mut AbstractParser, msg: &str) -> ReportedResult<R> {
+		let (tkn, q) = p.peek(0);
+		// p.add_diag(DiagBuilder2::note(format!("Trying as {:?}", self.branches.iter().map(|&(ref x,_)| x).collect::<Vec<_>>())).span(q));
+
+		// Create a separate speculative parser for each branch.
+		let mut results = Vec::new();
 		let mut matched = Vec::new();
 		for (name, mut func, greedy) in self.branches {
 			// p.add_diag(DiagBuilder2::note(format!("Trying as {}", name)).span(q));
@@ -4317,31 +5845,86 @@ fn parse_typedef(p: &mut AbstractParser) -> ReportedResult<Typedef> {
 		name: name,
 		ty: ty,
 		dims: dims,
+		attrs: Vec::new(),
 	})
 }
 
 
-fn parse_port_decl(p: &mut AbstractParser) -> ReportedResult<PortDecl> {
+/// Parse a nettype declaration.
+/// ```text
+/// "nettype" data_type ident ["with" [(package|class) "::"] ident] ";"
+/// ```
+fn parse_nettype_decl(p: &mut AbstractParser) -> ReportedResult<ast::NettypeDecl> {
 	let mut span = p.peek(0).1;
-
-	// Consume the port direction.
-	let dir = match as_port_direction(p.peek(0).0) {
-		Some(x) => { p.bump(); x },
-		None => {
-			p.add_diag(DiagBuilder2::error("Expected port direction (inout, input, output, or ref)").span(span));
-			return Err(());
+	p.require_reported(Keyword(Kw::Nettype))?;
+	let ty = parse_explicit_type(p)?;
+	let name = parse_identifier(p, "nettype name")?;
+	let resolve = if p.try_eat(Keyword(Kw::With)) {
+		let first = parse_identifier(p, "resolution function name")?;
+		if p.try_eat(Namespace) {
+			Some((Some(first), parse_identifier(p, "resolution function name")?))
+		} else {
+			Some((None, first))
 		}
-	};
-
-	// Consume the optional net type or "var" keyword.
-	let net_type = as_net_type(p.peek(0).0);
-	let var = if net_type.is_some() {
-		p.bump();
-		false
 	} else {
-		p.try_eat(Keyword(Kw::Var))
+		None
 	};
-
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+	Ok(ast::NettypeDecl {
+		span: span,
+		ty: ty,
+		name: name,
+		resolve: resolve,
+	})
+}
+
+
+/// Parse an alias statement.
+/// ```text
+/// "alias" net_lvalue "=" net_lvalue {"=" net_lvalue} ";"
+/// ```
+fn parse_alias(p: &mut AbstractParser) -> ReportedResult<ast::Alias> {
+	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Alias))?;
+	// Parse above assignment precedence, such that the `=` separates the
+	// nets rather than forming an assignment expression.
+	let mut nets = vec![parse_expr_prec(p, Precedence::Implication)?];
+	p.require_reported(Operator(Op::Assign))?;
+	nets.push(parse_expr_prec(p, Precedence::Implication)?);
+	while p.try_eat(Operator(Op::Assign)) {
+		nets.push(parse_expr_prec(p, Precedence::Implication)?);
+	}
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+	Ok(ast::Alias {
+		span: span,
+		nets: nets,
+	})
+}
+
+
+fn parse_port_decl(p: &mut AbstractParser) -> ReportedResult<PortDecl> {
+	let mut span = p.peek(0).1;
+
+	// Consume the port direction.
+	let dir = match as_port_direction(p.peek(0).0) {
+		Some(x) => { p.bump(); x },
+		None => {
+			p.add_diag(DiagBuilder2::error("Expected port direction (inout, input, output, or ref)").span(span));
+			return Err(());
+		}
+	};
+
+	// Consume the optional net type or "var" keyword.
+	let net_type = as_net_type(p.peek(0).0);
+	let var = if net_type.is_some() {
+		p.bump();
+		false
+	} else {
+		p.try_eat(Keyword(Kw::Var))
+	};
+
 	// Branch to handle explicit and implicit types.
 	let mut pp = ParallelParser::new();
 	pp.add("explicit type", |p|{
@@ -4369,6 +5952,7 @@ fn parse_port_decl(p: &mut AbstractParser) -> ReportedResult<PortDecl> {
 		var: var,
 		ty: ty,
 		names: names,
+		attrs: Vec::new(),
 	})
 }
 
@@ -4387,6 +5971,7 @@ fn as_net_type(tkn: Token) -> Option<NetType> {
 		Keyword(Kw::Wire)    => Some(NetType::Wire),
 		Keyword(Kw::Wand)    => Some(NetType::WireAnd),
 		Keyword(Kw::Wor)     => Some(NetType::WireOr),
+		Keyword(Kw::Interconnect) => Some(NetType::Interconnect),
 		_ => None
 	}
 }
@@ -4427,6 +6012,21 @@ fn parse_net_decl(p: &mut AbstractParser) -> ReportedResult<NetDecl> {
 	});
 	let (ty, (delay, names)) = pp.finish(p, "explicit or implicit type")?;
 
+	// Interconnect nets carry no data type, strength, or initial value.
+	if net_type == NetType::Interconnect {
+		if ty.data != ast::ImplicitType {
+			p.add_diag(DiagBuilder2::error("Interconnect nets cannot have a data type").span(ty.span));
+		}
+		if strength.is_some() || kind != NetKind::None {
+			p.add_diag(DiagBuilder2::error("Interconnect nets cannot have a strength, `vectored`, or `scalared`").span(span));
+		}
+		for name in &names {
+			if let Some(ref init) = name.init {
+				p.add_diag(DiagBuilder2::error(format!("Interconnect net `{}` cannot be assigned a value", name.name)).span(init.span));
+			}
+		}
+	}
+
 	// This function handles parsing of everything after the type.
 	fn tail(p: &mut AbstractParser) -> ReportedResult<(Option<Expr>, Vec<VarDeclName>)> {
 		// Parse the optional delay.
@@ -4453,6 +6053,7 @@ fn parse_net_decl(p: &mut AbstractParser) -> ReportedResult<NetDecl> {
 		ty: ty,
 		delay: delay,
 		names: names,
+		attrs: Vec::new(),
 	})
 }
 
@@ -4522,50 +6123,193 @@ fn as_charge_strength(tkn: Token) -> Option<ChargeStrength> {
 fn parse_import_decl(p: &mut AbstractParser) -> ReportedResult<ImportDecl> {
 	let mut span = p.peek(0).1;
 	p.require_reported(Keyword(Kw::Import))?;
-	let items = comma_list_nonempty(p, Semicolon, "import item", |p|{
-		// package_ident "::" ident
-		// package_ident "::" "*"
-		let pkg = parse_identifier(p, "package name")?;
-		p.require_reported(Namespace)?;
-		let (tkn, sp) = p.peek(0);
-		match tkn {
-			// package_ident "::" "*"
-			Operator(Op::Mul) => {
-				p.bump();
-				Ok(ImportItem {
-					pkg: pkg,
-					name: None,
-				})
-			}
+	let items = comma_list_nonempty(p, Semicolon, "import item", parse_import_item)?;
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+	Ok(ImportDecl {
+		span: span,
+		items: items,
+	})
+}
 
-			// package_ident "::" ident
-			Ident(n) | EscIdent(n) => {
-				p.bump();
-				Ok(ImportItem {
-					pkg: pkg,
-					name: Some(ast::Identifier {
-						id: DUMMY_NODE_ID,
-						span: sp,
-						name: n,
-					}),
-				})
-			}
 
-			_ => {
-				p.add_diag(DiagBuilder2::error("Expected identifier or * after :: in import declaration").span(sp));
-				Err(())
-			}
-		}
-	})?;
+/// Parse a package export declaration.
+/// ```text
+/// "export" "*" "::" "*" ";"
+/// "export" package_import_item {"," package_import_item} ";"
+/// ```
+fn parse_export_decl(p: &mut AbstractParser) -> ReportedResult<ast::ExportDecl> {
+	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Export))?;
+	let items = if p.try_eat(Operator(Op::Mul)) {
+		p.require_reported(Namespace)?;
+		p.require_reported(Operator(Op::Mul))?;
+		Vec::new()
+	} else {
+		comma_list_nonempty(p, Semicolon, "export item", parse_import_item)?
+	};
 	p.require_reported(Semicolon)?;
 	span.expand(p.last_span());
-	Ok(ImportDecl {
+	Ok(ast::ExportDecl {
 		span: span,
 		items: items,
 	})
 }
 
 
+/// Parse a package import item, as used in import and export declarations.
+fn parse_import_item(p: &mut AbstractParser) -> ReportedResult<ImportItem> {
+	// package_ident "::" ident
+	// package_ident "::" "*"
+	let pkg = parse_identifier(p, "package name")?;
+	p.require_reported(Namespace)?;
+	let (tkn, sp) = p.peek(0);
+	match tkn {
+		// package_ident "::" "*"
+		Operator(Op::Mul) => {
+			p.bump();
+			Ok(ImportItem {
+				pkg: pkg,
+				name: None,
+			})
+		}
+
+		// package_ident "::" ident
+		Ident(n) | EscIdent(n) => {
+			p.bump();
+			Ok(ImportItem {
+				pkg: pkg,
+				name: Some(ast::Identifier {
+					id: DUMMY_NODE_ID,
+					span: sp,
+					name: n,
+				}),
+			})
+		}
+
+		_ => {
+			p.add_diag(DiagBuilder2::error("Expected identifier or * after :: in import declaration").span(sp));
+			Err(())
+		}
+	}
+}
+
+
+/// Check whether a token is the string literal that introduces a DPI import
+/// or export declaration, as opposed to a package import.
+fn is_dpi_spec(tkn: Token) -> bool {
+	match tkn {
+		Literal(Str(_)) => true,
+		_ => false,
+	}
+}
+
+
+/// Parse a DPI import or export declaration.
+/// ```text
+/// "import" dpi_spec_string ["context"|"pure"] [c_ident "="] subroutine_prototype ";"
+/// "export" dpi_spec_string [c_ident "="] ("function"|"task") ident ";"
+/// ```
+fn parse_dpi_decl(p: &mut AbstractParser) -> ReportedResult<DpiDecl> {
+	let mut span = p.peek(0).1;
+	let import = match p.peek(0).0 {
+		Keyword(Kw::Import) => { p.bump(); true },
+		Keyword(Kw::Export) => { p.bump(); false },
+		_ => {
+			p.add_diag(DiagBuilder2::error("Expected DPI import or export declaration").span(span));
+			return Err(());
+		}
+	};
+
+	// Consume the spec string, which must be "DPI-C" or the deprecated
+	// "DPI".
+	let spec = match p.peek(0) {
+		(Literal(Str(s)), sp) => {
+			p.bump();
+			if &*s.as_str() != "DPI-C" && &*s.as_str() != "DPI" {
+				p.add_diag(DiagBuilder2::error(format!("Unknown DPI spec string \"{}\"; expected \"DPI-C\"", s)).span(sp));
+				return Err(());
+			}
+			s
+		}
+		(tkn, sp) => {
+			p.add_diag(DiagBuilder2::error(format!("Expected DPI spec string, but found {} instead", tkn)).span(sp));
+			return Err(());
+		}
+	};
+
+	// Consume the optional "context" or "pure" property of imports.
+	let property = if !import {
+		None
+	} else if p.try_eat(Keyword(Kw::Context)) {
+		Some(DpiProperty::Context)
+	} else if p.try_eat(Keyword(Kw::Pure)) {
+		Some(DpiProperty::Pure)
+	} else {
+		None
+	};
+
+	// Consume the optional C identifier alias.
+	let cident = match (p.peek(0).0, p.peek(1).0) {
+		(Ident(_), Operator(Op::Assign)) => {
+			let id = parse_identifier(p, "C identifier")?;
+			p.bump();
+			Some(id)
+		}
+		_ => None,
+	};
+
+	let decl = if import {
+		let prototype = parse_subroutine_prototype(p)?;
+		if property == Some(DpiProperty::Pure) && prototype.kind == SubroutineKind::Task {
+			p.add_diag(DiagBuilder2::error(format!("Imported task `{}` cannot be pure", prototype.name.name)).span(prototype.span));
+			return Err(());
+		}
+		span.expand(p.last_span());
+		DpiDecl::Import {
+			span: span,
+			spec: spec,
+			property: property,
+			cident: cident,
+			prototype: prototype,
+		}
+	} else {
+		let kind = match p.peek(0) {
+			(Keyword(Kw::Function), _) => { p.bump(); SubroutineKind::Func },
+			(Keyword(Kw::Task), _) => { p.bump(); SubroutineKind::Task },
+			(tkn, sp) => {
+				p.add_diag(DiagBuilder2::error(format!("Expected function or task after DPI export, but found {} instead", tkn)).span(sp));
+				return Err(());
+			}
+		};
+		let name = parse_identifier(p, "function or task name")?;
+		p.require_reported(Semicolon)?;
+		span.expand(p.last_span());
+		DpiDecl::Export {
+			span: span,
+			spec: spec,
+			cident: cident,
+			kind: kind,
+			name: name,
+		}
+	};
+	Ok(decl)
+}
+
+
+/// Check whether a token is one of the keywords that introduce an assertion.
+fn is_assertion_keyword(tkn: Token) -> bool {
+	match tkn {
+		Keyword(Kw::Assert) |
+		Keyword(Kw::Assume) |
+		Keyword(Kw::Cover) |
+		Keyword(Kw::Expect) |
+		Keyword(Kw::Restrict) => true,
+		_ => false,
+	}
+}
+
+
 fn parse_assertion(p: &mut AbstractParser) -> ReportedResult<Assertion> {
 	let mut span = p.peek(0).1;
 
@@ -4614,9 +6358,13 @@ fn parse_assertion(p: &mut AbstractParser) -> ReportedResult<Assertion> {
 		Keyword(Kw::Cover) if is_sequence => {
 			p.bump();
 			p.bump();
-			p.add_diag(DiagBuilder2::error("Don't know how to parse cover sequences").span(span));
-			return Err(());
-			// AssertionData::Concurrent(ConcurrentAssertion::CoverSequence)
+			let prop = flanked(p, Paren, parse_property_spec)?;
+			if as_sequence(&prop.prop).is_none() {
+				p.add_diag(DiagBuilder2::error("Expected a sequence after `cover sequence`, found a property instead").span(prop.prop.span));
+				return Err(());
+			}
+			let stmt = parse_stmt(p)?;
+			AssertionData::Concurrent(ConcurrentAssertion::CoverSequence(prop, stmt))
 		}
 
 		// `expect`
@@ -4722,9 +6470,9 @@ fn parse_assertion_action_block(p: &mut AbstractParser) -> ReportedResult<Assert
 fn parse_property_spec(p: &mut AbstractParser) -> ReportedResult<PropSpec> {
 	let mut span = p.peek(0).1;
 
-	// Parse the optional event expression.
-	let event = if p.try_eat(At) {
-		Some(parse_event_expr(p, EventPrecedence::Min)?)
+	// Parse the optional clocking event.
+	let clock = if p.try_eat(At) {
+		Some(parse_event_expr(p, EventPrecedence::Max)?)
 	} else {
 		None
 	};
@@ -4739,136 +6487,480 @@ fn parse_property_spec(p: &mut AbstractParser) -> ReportedResult<PropSpec> {
 
 	// Parse the property expression.
 	let prop = parse_propexpr(p)?;
-	Ok(PropSpec)
+	span.expand(p.last_span());
+	Ok(PropSpec {
+		span: span,
+		clock: clock,
+		disable: disable,
+		prop: prop,
+	})
 }
 
 
+/// Parse a sequence declaration.
+///
+/// ```text
+/// "sequence" ident ["(" [ports] ")"] ";" {var_decl} seq_expr [";"] "endsequence" [":" ident]
+/// ```
+fn parse_sequence_decl(p: &mut AbstractParser) -> ReportedResult<SequenceDecl> {
+	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Sequence))?;
+	let name = parse_identifier(p, "sequence name")?;
+	let result = recovered(p, Keyword(Kw::Endsequence), |p|{
+		let ports = try_flanked(p, Paren, |p| parse_assertion_ports(p, false))?.unwrap_or_default();
+		p.require_reported(Semicolon)?;
+		let vars = parse_assertion_vars(p)?;
+		let seq = parse_seqexpr(p)?;
+		p.try_eat(Semicolon);
+		Ok((ports, vars, seq))
+	});
+	p.require_reported(Keyword(Kw::Endsequence))?;
+	let (ports, vars, seq) = result?;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-#[allow(dead_code)]
-enum PropSeqPrecedence {
-	Min,
-	AlEvIfAccRejSyn,
-	ImplFollow, // right-associative
-	Until,      // right-associative
-	Iff,        // right-associative
-	Or,         // left-associative
-	And,        // left-associative
-	NotNexttime,
-	Intersect,  // left-associative
-	Within,     // left-associative
-	Throughout, // right-associative
-	CycleDelay, // left-associative
-	Brack,
-	Max,
-}
-
+	// Parse the optional sequence name after "endsequence".
+	if p.try_eat(Colon) {
+		let (n, sp) = p.eat_ident("sequence name")?;
+		if n != name.name {
+			p.add_diag(DiagBuilder2::error(format!("Sequence name {} disagrees with the name given before", n)).span(sp));
+			return Err(());
+		}
+	}
+	span.expand(p.last_span());
 
-fn parse_propexpr(p: &mut AbstractParser) -> ReportedResult<PropExpr> {
-	parse_propexpr_prec(p, PropSeqPrecedence::Min)
+	Ok(SequenceDecl {
+		span: span,
+		name: name,
+		ports: ports,
+		vars: vars,
+		seq: seq,
+	})
 }
 
 
-fn parse_propexpr_prec(p: &mut AbstractParser, precedence: PropSeqPrecedence) -> ReportedResult<PropExpr> {
+/// Parse a property declaration.
+///
+/// ```text
+/// "property" ident ["(" [ports] ")"] ";" {var_decl} prop_spec [";"] "endproperty" [":" ident]
+/// ```
+fn parse_property_decl(p: &mut AbstractParser) -> ReportedResult<PropertyDecl> {
 	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Property))?;
+	let name = parse_identifier(p, "property name")?;
+	let result = recovered(p, Keyword(Kw::Endproperty), |p|{
+		let ports = try_flanked(p, Paren, |p| parse_assertion_ports(p, false))?.unwrap_or_default();
+		p.require_reported(Semicolon)?;
+		let vars = parse_assertion_vars(p)?;
+		let spec = parse_property_spec(p)?;
+		p.try_eat(Semicolon);
+		Ok((ports, vars, spec))
+	});
+	p.require_reported(Keyword(Kw::Endproperty))?;
+	let (ports, vars, spec) = result?;
 
-	// To parse property expressions we need a parallel parser. For certain
-	// cases it is unclear if a parenthesized expression is a sequence or a
-	// property expression, e.g.:
-	//
-	// (foo) |=> bar
-	// ^^^^^ sequence or property?
-	//
-	// Both sequences and property expressions support parenthesis. However the
-	// |=> operator is only defined for sequences on the left hand side. If the
-	// parenthesis are parsed as a property and foo as a sequence, the above
-	// code fails to parse since the sequence on the left has effectively become
-	// a property. If the parenthesis are parsed as a sequence, all is well. To
-	// resolve these kinds of issues, we need a parallel parser.
-	let mut pp = ParallelParser::new();
-	pp.add_greedy("sequence expression", move |p| parse_propexpr_seq(p, precedence));
-	pp.add_greedy("property expression", move |p| parse_propexpr_nonseq(p, precedence));
-	let data = pp.finish(p, "sequence or primary property expression")?;
-
+	// Parse the optional property name after "endproperty".
+	if p.try_eat(Colon) {
+		let (n, sp) = p.eat_ident("property name")?;
+		if n != name.name {
+			p.add_diag(DiagBuilder2::error(format!("Property name {} disagrees with the name given before", n)).span(sp));
+			return Err(());
+		}
+	}
 	span.expand(p.last_span());
-	let expr = PropExpr {
+
+	Ok(PropertyDecl {
 		span: span,
-		data: data,
-	};
-	parse_propexpr_suffix(p, expr, precedence)
+		name: name,
+		ports: ports,
+		vars: vars,
+		spec: spec,
+	})
 }
 
 
-fn parse_propexpr_nonseq(p: &mut AbstractParser, precedence: PropSeqPrecedence) -> ReportedResult<PropExprData> {
-	// Handle the trivial case of expressions introduced by a symbol or keyword.
-	match p.peek(0).0 {
-		// Parenthesized property expression.
-		OpenDelim(Paren) => return flanked(p, Paren, parse_propexpr).map(|pe| pe.data),
+/// Parse the formal arguments of a sequence, property, or checker. Arguments
+/// without a type inherit the type of the previous argument, and checker
+/// ports without a direction inherit the direction of the previous port.
+///
+/// ```text
+/// ["local" [port_direction]] [type | "sequence" | "property" | "untyped"] ident {dimension} ["=" expr]
+/// [port_direction] [type | "sequence" | "property" | "untyped"] ident {dimension} ["=" expr]
+/// ```
+fn parse_assertion_ports(p: &mut AbstractParser, checker: bool) -> ReportedResult<Vec<AssertionPort>> {
+	let mut prev_ty = AssertionPortType::Untyped;
+	let mut prev_dir = PortDir::Input;
+	comma_list(p, CloseDelim(Paren), if checker { "checker port" } else { "sequence or property argument" }, |p|{
+		let mut span = p.peek(0).1;
 
-		// "not" operator
-		Keyword(Kw::Not) => {
-			p.bump();
-			let expr = parse_propexpr_prec(p, PropSeqPrecedence::NotNexttime)?;
-			return Ok(PropExprData::Not(Box::new(expr)));
-		}
+		// Parse the direction of checker ports, or the optional `local`
+		// keyword and direction of sequence and property arguments.
+		let local = if checker {
+			match p.peek(0).0 {
+				Keyword(Kw::Input)  => { p.bump(); prev_dir = PortDir::Input; }
+				Keyword(Kw::Output) => { p.bump(); prev_dir = PortDir::Output; }
+				_ => (),
+			}
+			Some(prev_dir)
+		} else if p.try_eat(Keyword(Kw::Local)) {
+			match p.peek(0).0 {
+				Keyword(Kw::Input)  => { p.bump(); Some(PortDir::Input) }
+				Keyword(Kw::Output) => { p.bump(); Some(PortDir::Output) }
+				Keyword(Kw::Inout)  => { p.bump(); Some(PortDir::Inout) }
+				_ => Some(PortDir::Input),
+			}
+		} else {
+			None
+		};
 
-		// Clocking event
-		At => {
-			p.bump();
-			let ev = parse_event_expr(p, EventPrecedence::Min)?;
-			let expr = parse_propexpr(p)?;
-			return Ok(PropExprData::Clocked(ev, Box::new(expr)));
-		}
+		// Parse the type, unless the argument name follows immediately.
+		let ty = match p.peek(0).0 {
+			Keyword(Kw::Untyped)  => { p.bump(); AssertionPortType::Untyped }
+			Keyword(Kw::Sequence) => { p.bump(); AssertionPortType::Sequence }
+			Keyword(Kw::Property) => { p.bump(); AssertionPortType::Property }
+			Ident(_) | EscIdent(_) if is_assertion_port_name_end(p.peek(1).0) => prev_ty.clone(),
+			OpenDelim(Brack) => AssertionPortType::Type(parse_implicit_type(p)?),
+			_ => AssertionPortType::Type(parse_explicit_type(p)?),
+		};
+		prev_ty = ty.clone();
 
-		_ => {
-			let q = p.peek(0).1;
-			p.add_diag(DiagBuilder2::error("Expected primary property expression").span(q));
-			return Err(());
+		let name = parse_identifier(p, "argument name")?;
+		let (dims, _) = parse_optional_dimensions(p)?;
+		let default = if p.try_eat(Operator(Op::Assign)) {
+			Some(parse_expr(p)?)
+		} else {
+			None
+		};
+		span.expand(p.last_span());
+		Ok(AssertionPort {
+			span: span,
+			local: local,
+			ty: ty,
+			name: name,
+			dims: dims,
+			default: default,
+		})
+	})
+}
+
+fn is_assertion_port_name_end(tkn: Token) -> bool {
+	match tkn {
+		Comma | CloseDelim(Paren) | OpenDelim(Brack) | Operator(Op::Assign) => true,
+		_ => false,
+	}
+}
+
+
+/// Parse the local variable declarations at the beginning of a sequence or
+/// property declaration.
+fn parse_assertion_vars(p: &mut AbstractParser) -> ReportedResult<Vec<VarDecl>> {
+	let mut vars = Vec::new();
+	loop {
+		let is_decl = match (p.peek(0).0, p.peek(1).0) {
+			(Keyword(Kw::Var), _) |
+			(Keyword(Kw::Bit), _) |
+			(Keyword(Kw::Logic), _) |
+			(Keyword(Kw::Reg), _) |
+			(Keyword(Kw::Byte), _) |
+			(Keyword(Kw::Shortint), _) |
+			(Keyword(Kw::Int), _) |
+			(Keyword(Kw::Longint), _) |
+			(Keyword(Kw::Integer), _) |
+			(Keyword(Kw::Time), _) |
+			(Keyword(Kw::Shortreal), _) |
+			(Keyword(Kw::Real), _) |
+			(Keyword(Kw::Realtime), _) |
+			(Keyword(Kw::String), _) => true,
+			(Ident(_), Ident(_)) |
+			(Ident(_), EscIdent(_)) |
+			(EscIdent(_), Ident(_)) |
+			(EscIdent(_), EscIdent(_)) => true,
+			_ => false,
+		};
+		if !is_decl {
+			return Ok(vars);
+		}
+		vars.push(parse_var_decl(p)?);
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(dead_code)]
+enum PropSeqPrecedence {
+	Min,
+	AlEvIfAccRejSyn,
+	ImplFollow, // right-associative
+	Until,      // right-associative
+	Iff,        // right-associative
+	Or,         // left-associative
+	And,        // left-associative
+	NotNexttime,
+	Intersect,  // left-associative
+	Within,     // left-associative
+	Throughout, // right-associative
+	CycleDelay, // left-associative
+	Brack,
+	Max,
+}
+
+
+/// Extract the sequence from a property expression that consists of nothing
+/// but a sequence.
+fn as_sequence(prop: &PropExpr) -> Option<&SeqExpr> {
+	match prop.data {
+		PropExprData::SeqOp(PropSeqOp::None, ref seq) => Some(seq),
+		_ => None,
+	}
+}
+
+/// Convert a property expression that consists of nothing but a sequence into
+/// that sequence. Emits an error if the expression is a property.
+fn into_sequence(p: &mut AbstractParser, prop: PropExpr, what: &str) -> ReportedResult<SeqExpr> {
+	match prop.data {
+		PropExprData::SeqOp(PropSeqOp::None, seq) => Ok(seq),
+		_ => {
+			p.add_diag(DiagBuilder2::error(format!("Expected a sequence {}, found a property instead", what)).span(prop.span));
+			Err(())
 		}
 	}
 }
 
+fn seq_to_prop(seq: SeqExpr) -> PropExpr {
+	PropExpr {
+		span: seq.span,
+		data: PropExprData::SeqOp(PropSeqOp::None, seq),
+	}
+}
+
+
+fn parse_propexpr(p: &mut AbstractParser) -> ReportedResult<PropExpr> {
+	parse_propexpr_prec(p, PropSeqPrecedence::Min)
+}
+
+
+/// Parse a property or sequence expression. Both are parsed by the same
+/// functions, since sequences are valid properties and a lot of operators
+/// apply to both. Sequences are kept in `PropExprData::SeqOp` for as long as
+/// the operators applied to them yield sequences, such that operators that
+/// require a sequence operand can unwrap them again.
+fn parse_propexpr_prec(p: &mut AbstractParser, precedence: PropSeqPrecedence) -> ReportedResult<PropExpr> {
+	let prefix = parse_propexpr_first(p, precedence)?;
+	parse_propexpr_suffix(p, prefix, precedence)
+}
+
+
+fn parse_propexpr_first(p: &mut AbstractParser, precedence: PropSeqPrecedence) -> ReportedResult<PropExpr> {
+	let mut span = p.peek(0).1;
+	let data = match p.peek(0).0 {
+		// "not" operator
+		Keyword(Kw::Not) => {
+			p.bump();
+			let expr = parse_propexpr_prec(p, PropSeqPrecedence::NotNexttime)?;
+			PropExprData::Not(Box::new(expr))
+		}
+
+		// "nexttime" ["[" expr "]"] prop
+		Keyword(Kw::Nexttime) | Keyword(Kw::SNexttime) => {
+			let strong = p.peek(0).0 == Keyword(Kw::SNexttime);
+			p.bump();
+			let index = try_flanked(p, Brack, parse_expr)?;
+			let expr = parse_propexpr_prec(p, PropSeqPrecedence::NotNexttime)?;
+			PropExprData::Nexttime(strong, index, Box::new(expr))
+		}
+
+		// "always" ["[" range "]"] prop
+		// "eventually" ["[" range "]"] prop
+		Keyword(Kw::Always) | Keyword(Kw::SAlways) | Keyword(Kw::Eventually) | Keyword(Kw::SEventually) => {
+			let tkn = p.peek(0).0;
+			p.bump();
+			let range = try_flanked(p, Brack, parse_seq_range)?;
+			let expr = Box::new(parse_propexpr_prec(p, PropSeqPrecedence::AlEvIfAccRejSyn)?);
+			match tkn {
+				Keyword(Kw::Always)     => PropExprData::Always(false, range, expr),
+				Keyword(Kw::SAlways)    => PropExprData::Always(true, range, expr),
+				Keyword(Kw::Eventually) => PropExprData::Eventually(false, range, expr),
+				_                       => PropExprData::Eventually(true, range, expr),
+			}
+		}
+
+		// "if" "(" expr ")" prop ["else" prop]
+		Keyword(Kw::If) => {
+			p.bump();
+			let cond = flanked(p, Paren, parse_expr)?;
+			let main = parse_propexpr_prec(p, PropSeqPrecedence::AlEvIfAccRejSyn)?;
+			let other = if p.try_eat(Keyword(Kw::Else)) {
+				Some(Box::new(parse_propexpr_prec(p, PropSeqPrecedence::AlEvIfAccRejSyn)?))
+			} else {
+				None
+			};
+			PropExprData::If(cond, Box::new(main), other)
+		}
+
+		// "case" "(" expr ")" {item} "endcase"
+		Keyword(Kw::Case) => {
+			p.bump();
+			let expr = flanked(p, Paren, parse_expr)?;
+			let items = repeat_until(p, Keyword(Kw::Endcase), parse_prop_case_item)?;
+			p.require_reported(Keyword(Kw::Endcase))?;
+			if items.is_empty() {
+				p.add_diag(DiagBuilder2::error("Property case statement needs at least one item").span(span));
+				return Err(());
+			}
+			PropExprData::Case(expr, items)
+		}
+
+		// "accept_on" "(" expr ")" prop, and friends
+		Keyword(Kw::AcceptOn) | Keyword(Kw::RejectOn) | Keyword(Kw::SyncAcceptOn) | Keyword(Kw::SyncRejectOn) => {
+			let op = match p.peek(0).0 {
+				Keyword(Kw::AcceptOn)     => PropAbortOp::AcceptOn,
+				Keyword(Kw::RejectOn)     => PropAbortOp::RejectOn,
+				Keyword(Kw::SyncAcceptOn) => PropAbortOp::SyncAcceptOn,
+				_                         => PropAbortOp::SyncRejectOn,
+			};
+			p.bump();
+			let cond = flanked(p, Paren, parse_expr)?;
+			let expr = parse_propexpr_prec(p, PropSeqPrecedence::AlEvIfAccRejSyn)?;
+			PropExprData::Abort(op, cond, Box::new(expr))
+		}
 
-fn parse_propexpr_seq(p: &mut AbstractParser, precedence: PropSeqPrecedence) -> ReportedResult<PropExprData> {
-	// Consume a strong, weak, or regular sequence operator.
-	let (seqop, seqexpr) = match p.peek(0).0 {
-		Keyword(Kw::Strong) => {
+		// "strong" "(" seq ")" and "weak" "(" seq ")"
+		Keyword(Kw::Strong) | Keyword(Kw::Weak) => {
+			let op = if p.peek(0).0 == Keyword(Kw::Strong) { PropSeqOp::Strong } else { PropSeqOp::Weak };
 			p.bump();
-			(PropSeqOp::Strong, flanked(p, Paren, parse_seqexpr)?)
+			let seq = flanked(p, Paren, parse_seqexpr)?;
+			PropExprData::SeqOp(op, seq)
 		}
-		Keyword(Kw::Weak) => {
+
+		// Clocking event. Clocked sequences remain sequences.
+		At => {
 			p.bump();
-			(PropSeqOp::Weak, flanked(p, Paren, parse_seqexpr)?)
+			let ev = parse_event_expr(p, EventPrecedence::Max)?;
+			let expr = parse_propexpr_prec(p, PropSeqPrecedence::AlEvIfAccRejSyn)?;
+			span.expand(p.last_span());
+			match expr.data {
+				PropExprData::SeqOp(PropSeqOp::None, seq) => PropExprData::SeqOp(PropSeqOp::None, SeqExpr {
+					span: span,
+					data: SeqExprData::Clocked(ev, Box::new(seq)),
+				}),
+				data => PropExprData::Clocked(ev, Box::new(PropExpr { span: expr.span, data: data })),
+			}
 		}
-		_ => (PropSeqOp::None, parse_seqexpr_prec(p, precedence)?)
+
+		// Everything else is a primary sequence expression, or a
+		// parenthesized property.
+		_ => return parse_seqexpr_first(p, precedence),
+	};
+	span.expand(p.last_span());
+	Ok(PropExpr {
+		span: span,
+		data: data,
+	})
+}
+
+
+fn parse_prop_case_item(p: &mut AbstractParser) -> ReportedResult<PropCaseItem> {
+	let mut span = p.peek(0).1;
+	let exprs = if p.try_eat(Keyword(Kw::Default)) {
+		p.try_eat(Colon);
+		Vec::new()
+	} else {
+		let exprs = comma_list_nonempty(p, Colon, "case expression", parse_expr)?;
+		p.require_reported(Colon)?;
+		exprs
 	};
+	let prop = parse_propexpr(p)?;
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+	Ok(PropCaseItem {
+		span: span,
+		exprs: exprs,
+		prop: prop,
+	})
+}
+
+
+fn parse_propexpr_suffix(p: &mut AbstractParser, prefix: PropExpr, precedence: PropSeqPrecedence) -> ReportedResult<PropExpr> {
+	let tkn = p.peek(0).0;
+
+	// Handle the cycle delay, which concatenates two sequences.
+	if tkn == DoubleHashtag && precedence < PropSeqPrecedence::CycleDelay {
+		let lhs = into_sequence(p, prefix, "before `##`")?;
+		let range = parse_seq_delay(p)?;
+		let rhs = parse_propexpr_prec(p, PropSeqPrecedence::CycleDelay)?;
+		let rhs = into_sequence(p, rhs, "after `##`")?;
+		let seq = SeqExpr {
+			span: Span::union(lhs.span, rhs.span),
+			data: SeqExprData::Delay(Some(Box::new(lhs)), range, Box::new(rhs)),
+		};
+		return parse_propexpr_suffix(p, seq_to_prop(seq), precedence);
+	}
+
+	// Handle the "throughout" operator, which requires an expression on its
+	// left hand side.
+	if tkn == Keyword(Kw::Throughout) && precedence <= PropSeqPrecedence::Throughout {
+		let expr = match prefix.data {
+			PropExprData::SeqOp(PropSeqOp::None, SeqExpr { data: SeqExprData::Expr(expr, None), .. }) => expr,
+			_ => {
+				p.add_diag(DiagBuilder2::error("Expected an expression before `throughout`").span(prefix.span));
+				return Err(());
+			}
+		};
+		p.bump();
+		let rhs = parse_propexpr_prec(p, PropSeqPrecedence::Throughout)?;
+		let rhs = into_sequence(p, rhs, "after `throughout`")?;
+		let seq = SeqExpr {
+			span: Span::union(expr.span, rhs.span),
+			data: SeqExprData::Throughout(expr, Box::new(rhs)),
+		};
+		return parse_propexpr_suffix(p, seq_to_prop(seq), precedence);
+	}
+
+	// Handle the binary operators that are only defined on sequences.
+	if let Some((op, prec)) = match tkn {
+		Keyword(Kw::Within)    => Some((SeqBinOp::Within,    PropSeqPrecedence::Within)),
+		Keyword(Kw::Intersect) => Some((SeqBinOp::Intersect, PropSeqPrecedence::Intersect)),
+		_ => None
+	}{
+		if precedence < prec {
+			let what = format!("before `{}`", tkn);
+			let lhs = into_sequence(p, prefix, &what)?;
+			p.bump();
+			let rhs = parse_propexpr_prec(p, prec)?;
+			let what = format!("after `{}`", tkn);
+			let rhs = into_sequence(p, rhs, &what)?;
+			let seq = SeqExpr {
+				span: Span::union(lhs.span, rhs.span),
+				data: SeqExprData::BinOp(op, Box::new(lhs), Box::new(rhs)),
+			};
+			return parse_propexpr_suffix(p, seq_to_prop(seq), precedence);
+		}
+	}
 
 	// Handle the operators that have a sequence expression on their left hand
 	// side.
 	if precedence <= PropSeqPrecedence::ImplFollow {
-		if let Some(op) = match p.peek(0).0 {
+		if let Some(op) = match tkn {
 			Operator(Op::SeqImplOl)    => Some(PropSeqBinOp::ImplOverlap),
 			Operator(Op::SeqImplNol)   => Some(PropSeqBinOp::ImplNonoverlap),
 			Operator(Op::SeqFollowOl)  => Some(PropSeqBinOp::FollowOverlap),
 			Operator(Op::SeqFollowNol) => Some(PropSeqBinOp::FollowNonoverlap),
 			_ => None
 		}{
+			let what = format!("before `{}`", tkn);
+			let lhs = into_sequence(p, prefix, &what)?;
 			p.bump();
-			let expr = parse_propexpr_prec(p, PropSeqPrecedence::ImplFollow)?;
-			return Ok(PropExprData::SeqBinOp(op, seqop, seqexpr, Box::new(expr)));
+			let rhs = parse_propexpr_prec(p, PropSeqPrecedence::ImplFollow)?;
+			let expr = PropExpr {
+				span: Span::union(lhs.span, rhs.span),
+				data: PropExprData::SeqBinOp(op, PropSeqOp::None, lhs, Box::new(rhs)),
+			};
+			return parse_propexpr_suffix(p, expr, precedence);
 		}
 	}
 
-	// Otherwise this is just a simple sequence operator.
-	Ok(PropExprData::SeqOp(seqop, seqexpr))
-}
-
-
-fn parse_propexpr_suffix(p: &mut AbstractParser, prefix: PropExpr, precedence: PropSeqPrecedence) -> ReportedResult<PropExpr> {
-
 	// Handle the binary operators that have a property expression on both their
-	// left and right hand side.
-	if let Some((op, prec, rassoc)) = match p.peek(0).0 {
+	// left and right hand side. The "and" and "or" operators yield a sequence
+	// if both operands are sequences.
+	if let Some((op, prec, rassoc)) = match tkn {
 		Keyword(Kw::Or)         => Some((PropBinOp::Or,         PropSeqPrecedence::Or,    false)),
 		Keyword(Kw::And)        => Some((PropBinOp::And,        PropSeqPrecedence::And,   false)),
 		Keyword(Kw::Until)      => Some((PropBinOp::Until,      PropSeqPrecedence::Until, true)),
@@ -4882,10 +6974,28 @@ fn parse_propexpr_suffix(p: &mut AbstractParser, prefix: PropExpr, precedence: P
 		if precedence < prec || (rassoc && precedence == prec) {
 			p.bump();
 			let rhs = parse_propexpr_prec(p, prec)?;
-			return Ok(PropExpr {
-				span: Span::union(prefix.span, rhs.span),
-				data: PropExprData::BinOp(op, Box::new(prefix), Box::new(rhs))
-			});
+			let (lhs_span, rhs_span) = (prefix.span, rhs.span);
+			let span = Span::union(lhs_span, rhs_span);
+			let seq_op = match op {
+				PropBinOp::Or => Some(SeqBinOp::Or),
+				PropBinOp::And => Some(SeqBinOp::And),
+				_ => None,
+			};
+			let expr = match (seq_op, prefix.data, rhs.data) {
+				(Some(seq_op), PropExprData::SeqOp(PropSeqOp::None, lhs), PropExprData::SeqOp(PropSeqOp::None, rhs)) => seq_to_prop(SeqExpr {
+					span: span,
+					data: SeqExprData::BinOp(seq_op, Box::new(lhs), Box::new(rhs)),
+				}),
+				(_, lhs, rhs) => PropExpr {
+					span: span,
+					data: PropExprData::BinOp(
+						op,
+						Box::new(PropExpr { span: lhs_span, data: lhs }),
+						Box::new(PropExpr { span: rhs_span, data: rhs }),
+					),
+				},
+			};
+			return parse_propexpr_suffix(p, expr, precedence);
 		}
 	}
 
@@ -4894,92 +7004,179 @@ fn parse_propexpr_suffix(p: &mut AbstractParser, prefix: PropExpr, precedence: P
 
 
 fn parse_seqexpr(p: &mut AbstractParser) -> ReportedResult<SeqExpr> {
-	parse_seqexpr_prec(p, PropSeqPrecedence::Min)
+	let expr = parse_propexpr(p)?;
+	into_sequence(p, expr, "expression")
 }
 
 
-fn parse_seqexpr_prec(p: &mut AbstractParser, precedence: PropSeqPrecedence) -> ReportedResult<SeqExpr> {
+/// Parse a primary sequence expression, or a parenthesized property.
+fn parse_seqexpr_first(p: &mut AbstractParser, precedence: PropSeqPrecedence) -> ReportedResult<PropExpr> {
 	let mut span = p.peek(0).1;
+	let data = match p.peek(0).0 {
+		// "##" delay seq
+		DoubleHashtag => {
+			let range = parse_seq_delay(p)?;
+			let rhs = parse_propexpr_prec(p, PropSeqPrecedence::CycleDelay)?;
+			let rhs = into_sequence(p, rhs, "after `##`")?;
+			SeqExprData::Delay(None, range, Box::new(rhs))
+		}
 
-	// See parse_propexpr_prec for an explanation of why we need a parallel
-	// parser here.
-	let mut pp = ParallelParser::new();
-	pp.add_greedy("expression", move |p| parse_seqexpr_expr(p, precedence));
-	pp.add_greedy("sequence", move |p| parse_seqexpr_nonexpr(p, precedence));
-	let data = pp.finish(p, "sequence or primary property expression")?;
+		// "first_match" "(" seq {"," match_item} ")"
+		Keyword(Kw::FirstMatch) => {
+			p.bump();
+			let (seq, items) = flanked(p, Paren, parse_seqexpr_with_match_items)?;
+			SeqExprData::FirstMatch(Box::new(seq), items)
+		}
+
+		// Parentheses either enclose an expression, a sequence with optional
+		// match items, or a property. Try the expression first, such that
+		// `(a + b) == c` remains a single expression.
+		OpenDelim(Paren) => {
+			let mut pp = ParallelParser::new();
+			pp.add_greedy("expression", |p| {
+				let expr = parse_expr(p)?;
+				let rep = try_flanked(p, Brack, parse_seqrep)?;
+				Ok(Err((expr, rep)))
+			});
+			pp.add_greedy("sequence or property", |p| {
+				flanked(p, Paren, |p| {
+					let expr = parse_propexpr(p)?;
+					if p.peek(0).0 != Comma {
+						return Ok(Ok((expr, Vec::new())));
+					}
+					let seq = into_sequence(p, expr, "before match items")?;
+					p.bump();
+					let items = comma_list_nonempty(p, CloseDelim(Paren), "match item", parse_expr)?;
+					Ok(Ok((seq_to_prop(seq), items)))
+				})
+			});
+			match pp.finish(p, "expression, sequence, or property")? {
+				Err((expr, rep)) => SeqExprData::Expr(expr, rep),
+				Ok((expr, items)) => {
+					// Only sequences may carry match items or repetitions.
+					let rep = if p.peek(0).0 == OpenDelim(Brack) && is_seqrep_start(p.peek(1).0, p.peek(2).0) {
+						try_flanked(p, Brack, parse_seqrep)?
+					} else {
+						None
+					};
+					if items.is_empty() && rep.is_none() {
+						let mut expr = expr;
+						expr.span = Span::union(span, p.last_span());
+						return Ok(expr);
+					}
+					let seq = into_sequence(p, expr, "before match items or repetition")?;
+					SeqExprData::Paren(Box::new(seq), items, rep)
+				}
+			}
+		}
 
+		// Otherwise this is an expression, or an instance of a named sequence,
+		// with an optional repetition.
+		_ => {
+			let expr = parse_expr(p)?;
+			let rep = try_flanked(p, Brack, parse_seqrep)?;
+			SeqExprData::Expr(expr, rep)
+		}
+	};
 	span.expand(p.last_span());
-	let expr = SeqExpr {
+	Ok(seq_to_prop(SeqExpr {
 		span: span,
 		data: data,
-	};
-	parse_seqexpr_suffix(p, expr, precedence)
+	}))
 }
 
 
-fn parse_seqexpr_expr(p: &mut AbstractParser, precedence: PropSeqPrecedence) -> ReportedResult<SeqExprData> {
-	// TODO: Handle all the non-trivial cases.
-	let q = p.peek(0).1;
-	p.add_diag(DiagBuilder2::error("Don't know how to parse sequence expression that don't start with an expression").span(q));
-	Err(())
+fn parse_seqexpr_with_match_items(p: &mut AbstractParser) -> ReportedResult<(SeqExpr, Vec<Expr>)> {
+	let seq = parse_seqexpr(p)?;
+	let items = if p.try_eat(Comma) {
+		comma_list_nonempty(p, CloseDelim(Paren), "match item", parse_expr)?
+	} else {
+		Vec::new()
+	};
+	Ok((seq, items))
 }
 
 
-fn parse_seqexpr_nonexpr(p: &mut AbstractParser, precedence: PropSeqPrecedence) -> ReportedResult<SeqExprData> {
+/// Check whether the tokens after an opening bracket start a sequence
+/// repetition, rather than an index.
+fn is_seqrep_start(first: Token, second: Token) -> bool {
+	is_repetition_op(first) || (first == Operator(Op::Add) && second == CloseDelim(Brack))
+}
 
-	// If we arrive here, the only possibility left is that this sequence starts
-	// with and expression or distribution.
-	let expr = parse_expr(p)?;
 
-	// Handle the case of the "throughout" operator that has an expression on
-	// its left hand side.
-	if precedence <= PropSeqPrecedence::Throughout && p.try_eat(Keyword(Kw::Throughout)) {
-		let rhs = parse_seqexpr_prec(p, PropSeqPrecedence::Throughout)?;
-		return Ok(SeqExprData::Throughout(expr, Box::new(rhs)));
+/// Parse a cycle delay range.
+///
+/// ```text
+/// "##" (integral_number | ident | "(" expr ")" | "[" range "]")
+/// ```
+fn parse_seq_delay(p: &mut AbstractParser) -> ReportedResult<SeqRange> {
+	if p.peek(0).0 == DoubleHashtag && p.peek(1).0 == OpenDelim(Brack) {
+		p.bump();
+		return flanked(p, Brack, parse_seq_range);
+	}
+	match try_cycle_delay(p)? {
+		Some(delay) => Ok(SeqRange::Single(delay.expr)),
+		None => {
+			let q = p.peek(0).1;
+			p.add_diag(DiagBuilder2::error("Expected cycle delay `##`").span(q));
+			Err(())
+		}
 	}
-
-	// Parse the optional repetition.
-	let rep = try_flanked(p, Brack, parse_seqrep)?;
-
-	Ok(SeqExprData::Expr(expr, rep))
 }
 
 
-fn parse_seqexpr_suffix(p: &mut AbstractParser, prefix: SeqExpr, precedence: PropSeqPrecedence) -> ReportedResult<SeqExpr> {
-	// TODO: Handle all the binary operators.
-	Ok(prefix)
+/// Parse a cycle or repetition range.
+///
+/// ```text
+/// "*" | "+" | expr [":" (expr | "$")]
+/// ```
+fn parse_seq_range(p: &mut AbstractParser) -> ReportedResult<SeqRange> {
+	match (p.peek(0).0, p.peek(1).0) {
+		(Operator(Op::Mul), CloseDelim(Brack)) => { p.bump(); return Ok(SeqRange::Star) }
+		(Operator(Op::Add), CloseDelim(Brack)) => { p.bump(); return Ok(SeqRange::Plus) }
+		_ => ()
+	}
+	let lo = parse_expr(p)?;
+	if !p.try_eat(Colon) {
+		return Ok(SeqRange::Single(lo));
+	}
+	if p.try_eat(Dollar) {
+		Ok(SeqRange::Range(lo, None))
+	} else {
+		Ok(SeqRange::Range(lo, Some(parse_expr(p)?)))
+	}
 }
 
 
 fn parse_seqrep(p: &mut AbstractParser) -> ReportedResult<SeqRep> {
 	match p.peek(0).0 {
 		// [*]
-		// [* expr]
+		// [* range]
 		Operator(Op::Mul) => {
 			p.bump();
 			if p.peek(0).0 == CloseDelim(Brack) {
-				Ok(SeqRep::ConsecStar)
+				Ok(SeqRep::Consec(SeqRange::Star))
 			} else {
-				Ok(SeqRep::Consec(parse_expr(p)?))
+				Ok(SeqRep::Consec(parse_seq_range(p)?))
 			}
 		}
 
 		// [+]
 		Operator(Op::Add) => {
 			p.bump();
-			Ok(SeqRep::ConsecPlus)
+			Ok(SeqRep::Consec(SeqRange::Plus))
 		}
 
-		// [= expr]
+		// [= range]
 		Operator(Op::Assign) => {
 			p.bump();
-			Ok(SeqRep::Nonconsec(parse_expr(p)?))
+			Ok(SeqRep::Nonconsec(parse_seq_range(p)?))
 		}
 
-		// [-> expr]
+		// [-> range]
 		Operator(Op::LogicImpl) => {
 			p.bump();
-			Ok(SeqRep::Goto(parse_expr(p)?))
+			Ok(SeqRep::Goto(parse_seq_range(p)?))
 		}
 
 		_ => {
@@ -4998,11 +7195,15 @@ fn parse_inst(p: &mut AbstractParser) -> ReportedResult<ast::Inst> {
 	let target = parse_identifier(p, "module name")?;
 	// TODO: Add support for interface instantiations.
 
-	// Consume the optional parameter value assignment.
-	let params = if p.try_eat(Hashtag) {
-		parse_parameter_assignments(p)?
-	} else {
-		Vec::new()
+	// Consume the optional parameter value assignment. Instances of
+	// user-defined primitives may instead carry a delay such as `#5`.
+	let (params, delay) = match (p.peek(0).0, p.peek(1).0) {
+		(Hashtag, OpenDelim(Paren)) => {
+			p.bump();
+			(parse_parameter_assignments(p)?, None)
+		}
+		(Hashtag, _) => (Vec::new(), try_delay3(p)?),
+		_ => (Vec::new(), None),
 	};
 
 	// Consume the instantiations.
@@ -5026,7 +7227,1037 @@ fn parse_inst(p: &mut AbstractParser) -> ReportedResult<ast::Inst> {
 		span: span,
 		target: target,
 		params: params,
+		delay: delay,
 		names: names,
+		attrs: Vec::new(),
+	})
+}
+
+
+fn as_gate_kind(tkn: Token) -> Option<GateKind> {
+	match tkn {
+		Keyword(Kw::And)      => Some(GateKind::And),
+		Keyword(Kw::Nand)     => Some(GateKind::Nand),
+		Keyword(Kw::Or)       => Some(GateKind::Or),
+		Keyword(Kw::Nor)      => Some(GateKind::Nor),
+		Keyword(Kw::Xor)      => Some(GateKind::Xor),
+		Keyword(Kw::Xnor)     => Some(GateKind::Xnor),
+		Keyword(Kw::Buf)      => Some(GateKind::Buf),
+		Keyword(Kw::Not)      => Some(GateKind::Not),
+		Keyword(Kw::Bufif0)   => Some(GateKind::Bufif0),
+		Keyword(Kw::Bufif1)   => Some(GateKind::Bufif1),
+		Keyword(Kw::Notif0)   => Some(GateKind::Notif0),
+		Keyword(Kw::Notif1)   => Some(GateKind::Notif1),
+		Keyword(Kw::Nmos)     => Some(GateKind::Nmos),
+		Keyword(Kw::Pmos)     => Some(GateKind::Pmos),
+		Keyword(Kw::Rnmos)    => Some(GateKind::Rnmos),
+		Keyword(Kw::Rpmos)    => Some(GateKind::Rpmos),
+		Keyword(Kw::Cmos)     => Some(GateKind::Cmos),
+		Keyword(Kw::Rcmos)    => Some(GateKind::Rcmos),
+		Keyword(Kw::Tran)     => Some(GateKind::Tran),
+		Keyword(Kw::Rtran)    => Some(GateKind::Rtran),
+		Keyword(Kw::Tranif0)  => Some(GateKind::Tranif0),
+		Keyword(Kw::Tranif1)  => Some(GateKind::Tranif1),
+		Keyword(Kw::Rtranif0) => Some(GateKind::Rtranif0),
+		Keyword(Kw::Rtranif1) => Some(GateKind::Rtranif1),
+		Keyword(Kw::Pullup)   => Some(GateKind::Pullup),
+		Keyword(Kw::Pulldown) => Some(GateKind::Pulldown),
+		_ => None
+	}
+}
+
+
+/// Determine the number of terminals a gate accepts, as a minimum and an
+/// optional maximum.
+fn gate_terminals(kind: GateKind) -> (usize, Option<usize>) {
+	match kind {
+		GateKind::And | GateKind::Nand | GateKind::Or |
+		GateKind::Nor | GateKind::Xor | GateKind::Xnor |
+		GateKind::Buf | GateKind::Not => (2, None),
+		GateKind::Bufif0 | GateKind::Bufif1 |
+		GateKind::Notif0 | GateKind::Notif1 |
+		GateKind::Nmos | GateKind::Pmos |
+		GateKind::Rnmos | GateKind::Rpmos |
+		GateKind::Tranif0 | GateKind::Tranif1 |
+		GateKind::Rtranif0 | GateKind::Rtranif1 => (3, Some(3)),
+		GateKind::Cmos | GateKind::Rcmos => (4, Some(4)),
+		GateKind::Tran | GateKind::Rtran => (2, Some(2)),
+		GateKind::Pullup | GateKind::Pulldown => (1, Some(1)),
+	}
+}
+
+
+/// Determine the maximum number of delay values a gate accepts.
+fn gate_delays(kind: GateKind) -> usize {
+	match kind {
+		GateKind::And | GateKind::Nand | GateKind::Or |
+		GateKind::Nor | GateKind::Xor | GateKind::Xnor |
+		GateKind::Buf | GateKind::Not |
+		GateKind::Tranif0 | GateKind::Tranif1 |
+		GateKind::Rtranif0 | GateKind::Rtranif1 => 2,
+		GateKind::Bufif0 | GateKind::Bufif1 |
+		GateKind::Notif0 | GateKind::Notif1 |
+		GateKind::Nmos | GateKind::Pmos |
+		GateKind::Rnmos | GateKind::Rpmos |
+		GateKind::Cmos | GateKind::Rcmos => 3,
+		GateKind::Tran | GateKind::Rtran |
+		GateKind::Pullup | GateKind::Pulldown => 0,
+	}
+}
+
+
+/// Parse a gate or switch instantiation.
+/// ```text
+/// gate_kind [strength] [delay3] [ident {dimension}] "(" terminal {"," terminal} ")" {"," ...} ";"
+/// ```
+fn parse_gate_inst(p: &mut AbstractParser) -> ReportedResult<GateInst> {
+	let mut span = p.peek(0).1;
+	let kind = match as_gate_kind(p.peek(0).0) {
+		Some(x) => { p.bump(); x },
+		None => {
+			p.add_diag(DiagBuilder2::error("Expected gate type").span(span));
+			return Err(());
+		}
+	};
+
+	// Consume the optional strength. The parenthesis may also open the list
+	// of terminals of an unnamed instance, so only treat it as a strength if
+	// a strength keyword follows.
+	let strength = if p.peek(0).0 == OpenDelim(Paren) && as_drive_strength(p.peek(1).0).is_some() {
+		let sp = p.peek(1).1;
+		let strength = flanked(p, Paren, |p|{
+			let a = as_drive_strength(p.peek(0).0).unwrap();
+			p.bump();
+			if p.try_eat(Comma) {
+				match as_drive_strength(p.peek(0).0) {
+					Some(b) => { p.bump(); Ok(GateStrength::Drive(a, b)) },
+					None => {
+						let q = p.peek(0).1;
+						p.add_diag(DiagBuilder2::error("Expected second drive strength").span(q));
+						Err(())
+					}
+				}
+			} else {
+				Ok(GateStrength::Pull(a))
+			}
+		})?;
+		match (kind, strength) {
+			(GateKind::Pullup, _) | (GateKind::Pulldown, _) => (),
+			(_, GateStrength::Pull(_)) => {
+				p.add_diag(DiagBuilder2::error(format!("`{}` gates require a drive strength for both 0 and 1", kind.as_str())).span(sp));
+				return Err(());
+			}
+			(GateKind::Nmos, _) | (GateKind::Pmos, _) | (GateKind::Rnmos, _) | (GateKind::Rpmos, _) |
+			(GateKind::Cmos, _) | (GateKind::Rcmos, _) | (GateKind::Tran, _) | (GateKind::Rtran, _) |
+			(GateKind::Tranif0, _) | (GateKind::Tranif1, _) | (GateKind::Rtranif0, _) | (GateKind::Rtranif1, _) => {
+				p.add_diag(DiagBuilder2::error(format!("`{}` switches cannot have a drive strength", kind.as_str())).span(sp));
+				return Err(());
+			}
+			_ => (),
+		}
+		Some(strength)
+	} else {
+		None
+	};
+
+	// Consume the optional delay.
+	let delay = try_delay3(p)?;
+	if let Some(ref delay) = delay {
+		let max = gate_delays(kind);
+		if max == 0 {
+			p.add_diag(DiagBuilder2::error(format!("`{}` gates cannot have a delay", kind.as_str())).span(delay.span));
+			return Err(());
+		}
+		if delay.values.len() > max {
+			p.add_diag(DiagBuilder2::error(format!("`{}` gates accept at most {} delay values, but {} given", kind.as_str(), max, delay.values.len())).span(delay.span));
+			return Err(());
+		}
+	}
+
+	// Consume the instances.
+	let names = comma_list_nonempty(p, Semicolon, "gate instance", |p|{
+		let mut span = p.peek(0).1;
+		let name = try_identifier(p)?;
+		let dims = if name.is_some() {
+			parse_optional_dimensions(p)?.0
+		} else {
+			Vec::new()
+		};
+		let terminals = flanked(p, Paren, |p| comma_list_nonempty(p, CloseDelim(Paren), "gate terminal", parse_expr))?;
+		span.expand(p.last_span());
+		match gate_terminals(kind) {
+			(min, _) if terminals.len() < min => {
+				p.add_diag(DiagBuilder2::error(format!("`{}` gates need at least {} terminals, but {} given", kind.as_str(), min, terminals.len())).span(span));
+				return Err(());
+			}
+			(_, Some(max)) if terminals.len() > max => {
+				p.add_diag(DiagBuilder2::error(format!("`{}` gates accept at most {} terminals, but {} given", kind.as_str(), max, terminals.len())).span(span));
+				return Err(());
+			}
+			_ => (),
+		}
+		Ok(GateInstName {
+			span: span,
+			name: name,
+			dims: dims,
+			terminals: terminals,
+		})
+	})?;
+
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+	Ok(GateInst {
+		span: span,
+		kind: kind,
+		strength: strength,
+		delay: delay,
+		names: names,
+		attrs: Vec::new(),
+	})
+}
+
+
+/// Try to parse a delay with up to three values.
+/// ```text
+/// "#" delay_value
+/// "#" "(" mintypmax_expr ["," mintypmax_expr ["," mintypmax_expr]] ")"
+/// ```
+fn try_delay3(p: &mut AbstractParser) -> ReportedResult<Option<Delay3>> {
+	if !p.try_eat(Hashtag) {
+		return Ok(None);
+	}
+	let mut span = p.last_span();
+	let (tkn, sp) = p.peek(0);
+	let values = match tkn {
+		// The values within the parenthesis follow the same rules as the
+		// contents of a parenthesized primary, which covers min-typ-max.
+		OpenDelim(Paren) => flanked(p, Paren, |p| comma_list_nonempty(p, CloseDelim(Paren), "delay value", parse_primary_parenthesis))?,
+		Literal(UnsignedInteger(..)) |
+		Literal(Real(..)) |
+		Literal(Time(..)) |
+		Ident(..) |
+		EscIdent(..) => vec![parse_expr_first(p, Precedence::Max)?],
+		_ => {
+			p.add_diag(DiagBuilder2::error("Expected delay value or expression after #").span(sp));
+			return Err(());
+		}
+	};
+	span.expand(p.last_span());
+	if values.len() > 3 {
+		p.add_diag(DiagBuilder2::error(format!("A delay has at most three values, but {} given", values.len())).span(span));
+		return Err(());
+	}
+	Ok(Some(Delay3 {
+		span: span,
+		values: values,
+	}))
+}
+
+
+/// A port of a user-defined primitive, as it appears in the port list or a
+/// port declaration.
+struct UdpPort {
+	dir: Option<PortDir>,
+	reg: bool,
+	name: ast::Identifier,
+	init: Option<Expr>,
+}
+
+
+/// Parse a checker declaration.
+///
+/// ```text
+/// "checker" ident ["(" [ports] ")"] ";" {item} "endchecker" [":" ident]
+/// ```
+fn parse_checker_decl(p: &mut AbstractParser) -> ReportedResult<CheckerDecl> {
+	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Checker))?;
+	let name = parse_identifier(p, "checker name")?;
+	let result = recovered(p, Keyword(Kw::Endchecker), |p|{
+		let ports = try_flanked(p, Paren, |p| parse_assertion_ports(p, true))?.unwrap_or_default();
+		p.require_reported(Semicolon)?;
+		let mut items = Vec::new();
+		while !p.is_fatal() && p.peek(0).0 != Keyword(Kw::Endchecker) && p.peek(0).0 != Eof {
+			if p.try_eat(Semicolon) {
+				continue;
+			}
+			items.push(parse_hierarchy_item(p)?);
+		}
+		Ok((ports, items))
+	});
+	p.require_reported(Keyword(Kw::Endchecker))?;
+	let (ports, items) = result?;
+
+	// Parse the optional checker name after "endchecker".
+	if p.try_eat(Colon) {
+		let (n, sp) = p.eat_ident("checker name")?;
+		if n != name.name {
+			p.add_diag(DiagBuilder2::error(format!("Checker name {} disagrees with the name given before", n)).span(sp));
+			return Err(());
+		}
+	}
+	span.expand(p.last_span());
+
+	Ok(CheckerDecl {
+		span: span,
+		name: name,
+		ports: ports,
+		items: items,
+	})
+}
+
+
+/// Parse a bind directive.
+///
+/// ```text
+/// "bind" hierarchical_ident [":" hierarchical_ident {"," hierarchical_ident}] instantiation
+/// ```
+fn parse_bind_directive(p: &mut AbstractParser) -> ReportedResult<BindDirective> {
+	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Bind))?;
+	let target = parse_hierarchical_name(p, "bind target")?;
+	let mut insts = Vec::new();
+	if p.try_eat(Colon) {
+		loop {
+			insts.push(parse_hierarchical_name(p, "instance name")?);
+			if !p.try_eat(Comma) {
+				break;
+			}
+		}
+	}
+	let inst = parse_inst(p)?;
+	span.expand(p.last_span());
+	Ok(BindDirective {
+		span: span,
+		target: target,
+		insts: insts,
+		inst: inst,
+	})
+}
+
+
+/// Parse a hierarchical name such as `top.cpu0.alu`.
+fn parse_hierarchical_name(p: &mut AbstractParser, msg: &str) -> ReportedResult<Vec<Identifier>> {
+	let mut names = vec![parse_identifier(p, msg)?];
+	while p.try_eat(Period) {
+		names.push(parse_identifier(p, msg)?);
+	}
+	Ok(names)
+}
+
+
+/// Parse a user-defined primitive.
+/// ```text
+/// "primitive" ident "(" ident {"," ident} ")" ";" udp_port_decl {udp_port_decl} udp_body "endprimitive" [":" ident]
+/// "primitive" ident "(" udp_port_decl {"," udp_port_decl} ")" ";" udp_body "endprimitive" [":" ident]
+/// udp_body: ["initial" ident "=" expr ";"] "table" {udp_entry} "endtable"
+/// ```
+fn parse_udp_decl(p: &mut AbstractParser) -> ReportedResult<UdpDecl> {
+	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Primitive))?;
+	let name = parse_identifier(p, "primitive name")?;
+
+	// Consume the port list. The ports are either declared right away, or
+	// only listed and declared in the body of the primitive.
+	let mut ports = flanked(p, Paren, |p|{
+		let mut dir = None;
+		comma_list_nonempty(p, CloseDelim(Paren), "primitive port", |p|{
+			let mut port = parse_udp_port(p)?;
+			if port.dir.is_some() {
+				dir = port.dir;
+			}
+			port.dir = dir;
+			Ok(port)
+		})
+	})?;
+	p.require_reported(Semicolon)?;
+
+	// Consume the port declarations of non-ANSI primitives and merge them
+	// into the port list.
+	if ports[0].dir.is_none() {
+		loop {
+			match p.peek(0).0 {
+				Keyword(Kw::Output) | Keyword(Kw::Input) | Keyword(Kw::Reg) => (),
+				_ => break,
+			}
+			let first = parse_udp_port(p)?;
+			let mut decls = vec![first];
+			while p.try_eat(Comma) {
+				let mut port = parse_udp_port(p)?;
+				port.dir = decls[0].dir;
+				port.reg = decls[0].reg;
+				decls.push(port);
+			}
+			p.require_reported(Semicolon)?;
+			for decl in decls {
+				let port = match ports.iter_mut().find(|port| port.name.name == decl.name.name) {
+					Some(x) => x,
+					None => {
+						p.add_diag(DiagBuilder2::error(format!("`{}` is not a port of primitive `{}`", decl.name.name, name.name)).span(decl.name.span));
+						return Err(());
+					}
+				};
+				if decl.dir.is_some() {
+					port.dir = decl.dir;
+				}
+				port.reg |= decl.reg;
+				if decl.init.is_some() {
+					port.init = decl.init;
+				}
+			}
+		}
+	}
+
+	// Check that the first port is the only output, and all others are
+	// inputs.
+	let mut ports = ports.into_iter();
+	let output = ports.next().unwrap();
+	if output.dir != Some(PortDir::Output) {
+		p.add_diag(DiagBuilder2::error(format!("The first port of primitive `{}` must be an output", name.name)).span(output.name.span));
+		return Err(());
+	}
+	let mut inputs = Vec::new();
+	for port in ports {
+		if port.dir != Some(PortDir::Input) || port.reg || port.init.is_some() {
+			p.add_diag(DiagBuilder2::error(format!("Port `{}` of primitive `{}` must be an input", port.name.name, name.name)).span(port.name.span));
+			return Err(());
+		}
+		inputs.push(port.name);
+	}
+	let sequential = output.reg;
+	let mut init = output.init;
+
+	// Consume the optional initial statement of sequential primitives.
+	if p.try_eat(Keyword(Kw::Initial)) {
+		let q = p.last_span();
+		let target = parse_identifier(p, "output port name")?;
+		p.require_reported(Operator(Op::Assign))?;
+		init = Some(parse_expr(p)?);
+		p.require_reported(Semicolon)?;
+		if !sequential {
+			p.add_diag(DiagBuilder2::error(format!("Only sequential primitives can have an initial statement; declare `{}` as `output reg`", output.name.name)).span(q));
+			return Err(());
+		}
+		if target.name != output.name.name {
+			p.add_diag(DiagBuilder2::error(format!("Initial statement must assign to output `{}`", output.name.name)).span(target.span));
+			return Err(());
+		}
+	} else if init.is_some() && !sequential {
+		p.add_diag(DiagBuilder2::error(format!("Only sequential primitives can have an initial value; declare `{}` as `output reg`", output.name.name)).span(output.name.span));
+		return Err(());
+	}
+
+	// Consume the table.
+	p.require_reported(Keyword(Kw::Table))?;
+	let num_inputs = inputs.len();
+	let entries = repeat_until(p, Keyword(Kw::Endtable), |p| parse_udp_entry(p, num_inputs, sequential))?;
+	p.require_reported(Keyword(Kw::Endtable))?;
+	p.require_reported(Keyword(Kw::Endprimitive))?;
+
+	// Parse the optional primitive name after "endprimitive".
+	if p.try_eat(Colon) {
+		let (n, sp) = p.eat_ident("primitive name")?;
+		if n != name.name {
+			p.add_diag(DiagBuilder2::error(format!("Primitive name {} disagrees with name {} given before", n, name.name)).span(sp));
+			return Err(());
+		}
+	}
+
+	span.expand(p.last_span());
+	Ok(UdpDecl {
+		span: span,
+		name: name,
+		output: output.name,
+		inputs: inputs,
+		sequential: sequential,
+		init: init,
+		entries: entries,
+	})
+}
+
+
+/// Parse a port in the port list or a port declaration of a UDP.
+/// ```text
+/// ["output"|"input"] ["reg"] ident ["=" expr]
+/// ```
+fn parse_udp_port(p: &mut AbstractParser) -> ReportedResult<UdpPort> {
+	let dir = match p.peek(0).0 {
+		Keyword(Kw::Output) => { p.bump(); Some(PortDir::Output) },
+		Keyword(Kw::Input) => { p.bump(); Some(PortDir::Input) },
+		_ => None,
+	};
+	let reg = p.try_eat(Keyword(Kw::Reg));
+	let name = parse_identifier(p, "port name")?;
+	let init = if p.try_eat(Operator(Op::Assign)) {
+		Some(parse_expr(p)?)
+	} else {
+		None
+	};
+	Ok(UdpPort {
+		dir: dir,
+		reg: reg,
+		name: name,
+		init: init,
+	})
+}
+
+
+/// Parse a row in the table of a UDP.
+/// ```text
+/// {symbol} ":" symbol ";"
+/// {symbol} ":" symbol ":" symbol ";"
+/// ```
+fn parse_udp_entry(p: &mut AbstractParser, num_inputs: usize, sequential: bool) -> ReportedResult<UdpEntry> {
+	let mut span = p.peek(0).1;
+	let inputs = parse_udp_symbols(p)?;
+	p.require_reported(Colon)?;
+	let mut fields = vec![parse_udp_symbols(p)?];
+	if p.try_eat(Colon) {
+		fields.push(parse_udp_symbols(p)?);
+	}
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+
+	// Check the inputs.
+	if inputs.len() != num_inputs {
+		p.add_diag(DiagBuilder2::error(format!("Expected {} input symbols, but found {}", num_inputs, inputs.len())).span(span));
+		return Err(());
+	}
+	let mut edges = 0;
+	for &(sym, sp) in &inputs {
+		match sym {
+			UdpSymbol::Level(_) => (),
+			UdpSymbol::Edge(..) |
+			UdpSymbol::EdgeAbbrev(_) => edges += 1,
+			UdpSymbol::NoChange => {
+				p.add_diag(DiagBuilder2::error("`-` may only appear as the output of a sequential primitive").span(sp));
+				return Err(());
+			}
+		}
+	}
+	if edges > 0 && !sequential {
+		p.add_diag(DiagBuilder2::error("Edges may only appear in the table of a sequential primitive").span(span));
+		return Err(());
+	}
+	if edges > 1 {
+		p.add_diag(DiagBuilder2::error("At most one edge may appear per table entry").span(span));
+		return Err(());
+	}
+
+	// Check the current state and the output.
+	if fields.len() != if sequential { 2 } else { 1 } {
+		let msg = if sequential {
+			"Table entries of sequential primitives must have the form `inputs : state : output;`"
+		} else {
+			"Table entries of combinational primitives must have the form `inputs : output;`"
+		};
+		p.add_diag(DiagBuilder2::error(msg).span(span));
+		return Err(());
+	}
+	let mut single = Vec::new();
+	for field in fields {
+		if field.len() != 1 {
+			p.add_diag(DiagBuilder2::error(format!("Expected a single symbol, but found {}", field.len())).span(span));
+			return Err(());
+		}
+		single.push(field[0]);
+	}
+	let (output, osp) = single.pop().unwrap();
+	let state = single.pop();
+	match output {
+		UdpSymbol::Level('0') | UdpSymbol::Level('1') | UdpSymbol::Level('x') => (),
+		UdpSymbol::NoChange if sequential => (),
+		_ => {
+			p.add_diag(DiagBuilder2::error("Output of a table entry must be `0`, `1`, `x`, or `-` in sequential primitives").span(osp));
+			return Err(());
+		}
+	}
+	if let Some((sym, sp)) = state {
+		match sym {
+			UdpSymbol::Level(_) => (),
+			_ => {
+				p.add_diag(DiagBuilder2::error("Current state of a table entry must be a level symbol").span(sp));
+				return Err(());
+			}
+		}
+	}
+
+	Ok(UdpEntry {
+		span: span,
+		inputs: inputs.into_iter().map(|(s, _)| s).collect(),
+		state: state.map(|(s, _)| s),
+		output: output,
+	})
+}
+
+
+/// Parse the symbols of one field of a UDP table entry. The lexer merges
+/// adjacent digits and letters into numbers and identifiers, and splits digits
+/// from the levels that follow them, as in `0x`. Both are split into
+/// individual symbols again here.
+fn parse_udp_symbols(p: &mut AbstractParser) -> ReportedResult<Vec<(UdpSymbol, Span)>> {
+	let mut symbols = Vec::new();
+	loop {
+		let (tkn, sp) = p.peek(0);
+		match tkn {
+			Colon | Semicolon | Keyword(Kw::Endtable) | Eof => break,
+			OpenDelim(Paren) => {
+				p.bump();
+				let mut chars = String::new();
+				while let Some(s) = as_udp_chars(p.peek(0).0) {
+					chars.push_str(&s);
+					p.bump();
+				}
+				p.require_reported(CloseDelim(Paren))?;
+				let mut span = sp;
+				span.expand(p.last_span());
+				let levels: Vec<char> = chars.to_lowercase().chars().collect();
+				if levels.len() != 2 || levels.iter().any(|&c| !is_udp_level(c)) {
+					p.add_diag(DiagBuilder2::error(format!("Expected an edge of two level symbols such as `(01)`, but found `({})`", chars)).span(span));
+					return Err(());
+				}
+				symbols.push((UdpSymbol::Edge(levels[0], levels[1]), span));
+			}
+			_ => {
+				let chars = match as_udp_chars(tkn) {
+					Some(x) => x,
+					None => {
+						p.add_diag(DiagBuilder2::error(format!("Expected table symbol, but found {} instead", tkn)).span(sp));
+						return Err(());
+					}
+				};
+				p.bump();
+				for c in chars.to_lowercase().chars() {
+					let sym = match c {
+						c if is_udp_level(c) => UdpSymbol::Level(c),
+						'r' | 'f' | 'p' | 'n' | '*' => UdpSymbol::EdgeAbbrev(c),
+						'-' => UdpSymbol::NoChange,
+						_ => {
+							p.add_diag(DiagBuilder2::error(format!("`{}` is not a valid table symbol", c)).span(sp));
+							return Err(());
+						}
+					};
+					symbols.push((sym, sp));
+				}
+			}
+		}
+	}
+	Ok(symbols)
+}
+
+
+/// Convert a token in a UDP table to the characters it consists of.
+fn as_udp_chars(tkn: Token) -> Option<String> {
+	match tkn {
+		Literal(UnsignedInteger(n)) | Ident(n) => Some(String::from(&*n.as_str())),
+		Ternary => Some(String::from("?")),
+		Operator(Op::Mul) => Some(String::from("*")),
+		Operator(Op::Sub) => Some(String::from("-")),
+		_ => None,
+	}
+}
+
+
+fn is_udp_level(c: char) -> bool {
+	match c {
+		'0' | '1' | 'x' | '?' | 'b' => true,
+		_ => false,
+	}
+}
+
+
+fn parse_specify_block(p: &mut AbstractParser) -> ReportedResult<SpecifyBlock> {
+	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Specify))?;
+	let items = repeat_until(p, Keyword(Kw::Endspecify), parse_specify_item)?;
+	p.require_reported(Keyword(Kw::Endspecify))?;
+	span.expand(p.last_span());
+	Ok(SpecifyBlock {
+		span: span,
+		items: items,
+	})
+}
+
+
+fn parse_specify_item(p: &mut AbstractParser) -> ReportedResult<SpecifyItem> {
+	let (tkn, mut span) = p.peek(0);
+	match tkn {
+		Keyword(Kw::Specparam) => parse_specparam_decl(p).map(|x| SpecifyItem::Specparam(x)),
+		Keyword(Kw::PulsestyleOnevent) |
+		Keyword(Kw::PulsestyleOndetect) |
+		Keyword(Kw::Showcancelled) |
+		Keyword(Kw::Noshowcancelled) => {
+			p.bump();
+			let outputs = parse_path_terminals(p)?;
+			p.require_reported(Semicolon)?;
+			span.expand(p.last_span());
+			Ok(match tkn {
+				Keyword(Kw::PulsestyleOnevent) => SpecifyItem::Pulsestyle(span, PulseStyle::OnEvent, outputs),
+				Keyword(Kw::PulsestyleOndetect) => SpecifyItem::Pulsestyle(span, PulseStyle::OnDetect, outputs),
+				Keyword(Kw::Showcancelled) => SpecifyItem::Showcancelled(span, true, outputs),
+				_ => SpecifyItem::Showcancelled(span, false, outputs),
+			})
+		}
+		OpenDelim(Paren) |
+		Keyword(Kw::If) |
+		Keyword(Kw::Ifnone) => parse_path_decl(p).map(|x| SpecifyItem::Path(x)),
+		SysIdent(_) => parse_timing_check(p).map(|x| SpecifyItem::TimingCheck(x)),
+		x => {
+			p.add_diag(DiagBuilder2::error(format!("Expected specparam, path declaration, or timing check, found {} instead", x)).span(span));
+			Err(())
+		}
+	}
+}
+
+
+/// Parse a specparam declaration.
+///
+/// ```text
+/// "specparam" [packed_dimension] list_of_specparam_assignments ";"
+/// specparam_assignment: ident "=" constant_mintypmax_expression
+/// specparam_assignment: "PATHPULSE$..." "=" "(" reject_limit ["," error_limit] ")"
+/// ```
+fn parse_specparam_decl(p: &mut AbstractParser) -> ReportedResult<SpecparamDecl> {
+	let mut span = p.peek(0).1;
+	p.require_reported(Keyword(Kw::Specparam))?;
+	let (dims, _) = parse_optional_dimensions(p)?;
+	let assigns = comma_list_nonempty(p, Semicolon, "specparam assignment", |p|{
+		let name = parse_identifier(p, "specparam name")?;
+		p.require_reported(Operator(Op::Assign))?;
+		let (value, error) = if name.name.as_str().starts_with("PATHPULSE$") {
+			flanked(p, Paren, |p|{
+				let reject = parse_primary_parenthesis(p)?;
+				let error = if p.try_eat(Comma) {
+					Some(parse_primary_parenthesis(p)?)
+				} else {
+					None
+				};
+				Ok((reject, error))
+			})?
+		} else {
+			(parse_primary_parenthesis(p)?, None)
+		};
+		Ok(SpecparamAssign {
+			span: Span::union(name.span, p.last_span()),
+			name: name,
+			value: value,
+			error: error,
+		})
+	})?;
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+	Ok(SpecparamDecl {
+		span: span,
+		dims: dims,
+		assigns: assigns,
+	})
+}
+
+
+/// Parse a module path declaration.
+///
+/// ```text
+/// [("if" "(" expr ")" | "ifnone")] "(" [edge] inputs [+|-] ("=>"|"*>") outputs ")" "=" delays ";"
+/// [("if" "(" expr ")" | "ifnone")] "(" [edge] inputs [+|-] ("=>"|"*>") "(" outputs [+|-] ":" expr ")" ")" "=" delays ";"
+/// ```
+fn parse_path_decl(p: &mut AbstractParser) -> ReportedResult<PathDecl> {
+	let mut span = p.peek(0).1;
+	let cond = match p.peek(0).0 {
+		Keyword(Kw::If) => {
+			p.bump();
+			Some(PathCond::If(flanked(p, Paren, parse_expr)?))
+		}
+		Keyword(Kw::Ifnone) => {
+			p.bump();
+			Some(PathCond::Ifnone)
+		}
+		_ => None,
+	};
+
+	// Parse the path description.
+	p.require_reported(OpenDelim(Paren))?;
+	let edge = as_edge_ident(p.peek(0).0);
+	if edge != EdgeIdent::Implicit {
+		p.bump();
+	}
+	let inputs = parse_path_terminals(p)?;
+	let (polarity, full) = parse_path_connection(p)?;
+	let (outputs, data_source) = if p.try_eat(OpenDelim(Paren)) {
+		let outputs = parse_path_terminals(p)?;
+		let (tkn, sp) = p.peek(0);
+		let polarity = match tkn {
+			Colon => None,
+			AddColon => Some(PathPolarity::Positive),
+			SubColon => Some(PathPolarity::Negative),
+			x => {
+				p.add_diag(DiagBuilder2::error(format!("Expected :, +:, or -: before data source expression, found {} instead", x)).span(sp));
+				return Err(());
+			}
+		};
+		p.bump();
+		let expr = parse_expr(p)?;
+		p.require_reported(CloseDelim(Paren))?;
+		(outputs, Some((polarity, expr)))
+	} else {
+		(parse_path_terminals(p)?, None)
+	};
+	p.require_reported(CloseDelim(Paren))?;
+
+	// Parse the path delay value, which may optionally be parenthesized.
+	p.require_reported(Operator(Op::Assign))?;
+	let delays = if p.peek(0).0 == OpenDelim(Paren) {
+		flanked(p, Paren, |p| comma_list_nonempty(p, CloseDelim(Paren), "path delay", parse_primary_parenthesis))?
+	} else {
+		comma_list_nonempty(p, Semicolon, "path delay", parse_primary_parenthesis)?
+	};
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+
+	// Check the constraints that the grammar cannot express.
+	if !full && (inputs.len() != 1 || outputs.len() != 1) {
+		p.add_diag(DiagBuilder2::error("A parallel connection `=>` requires exactly one input and one output; use `*>` instead").span(span));
+		return Err(());
+	}
+	match delays.len() {
+		1 | 2 | 3 | 6 | 12 => (),
+		n => {
+			p.add_diag(DiagBuilder2::error(format!("A path delay has 1, 2, 3, 6, or 12 values, but {} given", n)).span(span));
+			return Err(());
+		}
+	}
+	if cond == Some(PathCond::Ifnone) && (edge != EdgeIdent::Implicit || data_source.is_some()) {
+		p.add_diag(DiagBuilder2::error("`ifnone` can only be used with simple module paths").span(span));
+		return Err(());
+	}
+
+	Ok(PathDecl {
+		span: span,
+		cond: cond,
+		edge: edge,
+		inputs: inputs,
+		polarity: polarity,
+		full: full,
+		outputs: outputs,
+		data_source: data_source,
+		delays: delays,
+	})
+}
+
+
+/// Parse a comma-separated list of specify terminals, i.e. identifiers with
+/// an optional bit- or part-select.
+fn parse_path_terminals(p: &mut AbstractParser) -> ReportedResult<Vec<Expr>> {
+	let mut v = vec![parse_expr_prec(p, Precedence::Postfix)?];
+	while p.try_eat(Comma) {
+		v.push(parse_expr_prec(p, Precedence::Postfix)?);
+	}
+	Ok(v)
+}
+
+
+/// Parse the connection operator of a path, together with its optional
+/// polarity. The lexer has no dedicated tokens for these, so `=>` arrives as
+/// `=` `>`, `+=>` as `+=` `>`, and `*>` as `*` `>`.
+fn parse_path_connection(p: &mut AbstractParser) -> ReportedResult<(Option<PathPolarity>, bool)> {
+	let (polarity, full, len) = match (p.peek(0).0, p.peek(1).0, p.peek(2).0) {
+		(Operator(Op::Assign), Operator(Op::Gt), _) => (None, false, 2),
+		(Operator(Op::AssignAdd), Operator(Op::Gt), _) => (Some(PathPolarity::Positive), false, 2),
+		(Operator(Op::AssignSub), Operator(Op::Gt), _) => (Some(PathPolarity::Negative), false, 2),
+		(Operator(Op::Mul), Operator(Op::Gt), _) => (None, true, 2),
+		(Operator(Op::Add), Operator(Op::Mul), Operator(Op::Gt)) => (Some(PathPolarity::Positive), true, 3),
+		(Operator(Op::Sub), Operator(Op::Mul), Operator(Op::Gt)) => (Some(PathPolarity::Negative), true, 3),
+		(x, _, _) => {
+			let sp = p.peek(0).1;
+			p.add_diag(DiagBuilder2::error(format!("Expected => or *> in path declaration, found {} instead", x)).span(sp));
+			return Err(());
+		}
+	};
+	for _ in 0..len {
+		p.bump();
+	}
+	Ok((polarity, full))
+}
+
+
+fn as_timing_check_kind(name: &str) -> Option<TimingCheckKind> {
+	Some(match name {
+		"setup" => TimingCheckKind::Setup,
+		"hold" => TimingCheckKind::Hold,
+		"setuphold" => TimingCheckKind::SetupHold,
+		"recovery" => TimingCheckKind::Recovery,
+		"removal" => TimingCheckKind::Removal,
+		"recrem" => TimingCheckKind::RecRem,
+		"skew" => TimingCheckKind::Skew,
+		"timeskew" => TimingCheckKind::TimeSkew,
+		"fullskew" => TimingCheckKind::FullSkew,
+		"period" => TimingCheckKind::Period,
+		"width" => TimingCheckKind::Width,
+		"nochange" => TimingCheckKind::NoChange,
+		_ => return None,
+	})
+}
+
+
+/// Parse a system timing check.
+///
+/// ```text
+/// sys_ident "(" events "," limits ["," [notifier] {"," [expr]}] ")" ";"
+/// ```
+fn parse_timing_check(p: &mut AbstractParser) -> ReportedResult<TimingCheck> {
+	let (tkn, mut span) = p.peek(0);
+	let kind = match tkn {
+		SysIdent(name) => match as_timing_check_kind(&*name.as_str()) {
+			Some(k) => k,
+			None => {
+				p.add_diag(DiagBuilder2::error(format!("`${}` is not a timing check", name)).span(span));
+				return Err(());
+			}
+		},
+		x => {
+			p.add_diag(DiagBuilder2::error(format!("Expected timing check, found {} instead", x)).span(span));
+			return Err(());
+		}
+	};
+	p.bump();
+
+	// The number of events, limits, and arguments following the notifier
+	// that each check accepts. `$width` has an optional threshold before the
+	// notifier, which is handled separately below.
+	let (num_events, num_limits, num_extra) = match kind {
+		TimingCheckKind::Setup |
+		TimingCheckKind::Hold |
+		TimingCheckKind::Recovery |
+		TimingCheckKind::Removal |
+		TimingCheckKind::Skew => (2, 1, 0),
+		TimingCheckKind::SetupHold |
+		TimingCheckKind::RecRem => (2, 2, 4),
+		TimingCheckKind::TimeSkew => (2, 1, 2),
+		TimingCheckKind::FullSkew => (2, 2, 2),
+		TimingCheckKind::NoChange => (2, 2, 0),
+		TimingCheckKind::Period |
+		TimingCheckKind::Width => (1, 1, 0),
+	};
+	let notifier_index = if kind == TimingCheckKind::Width { 1 } else { 0 };
+
+	p.require_reported(OpenDelim(Paren))?;
+	let mut events = Vec::new();
+	for i in 0..num_events {
+		if i > 0 {
+			p.require_reported(Comma)?;
+		}
+		events.push(parse_timing_check_event(p)?);
+	}
+	let mut limits = Vec::new();
+	for _ in 0..num_limits {
+		p.require_reported(Comma)?;
+		limits.push(parse_primary_parenthesis(p)?);
+	}
+
+	// Parse the optional trailing arguments, any of which may be left empty.
+	let mut notifier = None;
+	let mut extra = Vec::new();
+	let mut index = 0;
+	while p.try_eat(Comma) {
+		let empty = match p.peek(0).0 {
+			Comma | CloseDelim(Paren) => true,
+			_ => false,
+		};
+		if index > notifier_index + num_extra {
+			let sp = p.last_span();
+			p.add_diag(DiagBuilder2::error(format!("`{}` accepts at most {} arguments", kind.as_str(), num_events + num_limits + notifier_index + num_extra + 1)).span(sp));
+			return Err(());
+		}
+		if index < notifier_index {
+			if !empty {
+				limits.push(parse_primary_parenthesis(p)?);
+			}
+		} else if index == notifier_index {
+			if !empty {
+				notifier = Some(parse_identifier(p, "notifier")?);
+			}
+		} else {
+			extra.push(if empty { None } else { Some(parse_primary_parenthesis(p)?) });
+		}
+		index += 1;
+	}
+	p.require_reported(CloseDelim(Paren))?;
+	p.require_reported(Semicolon)?;
+	span.expand(p.last_span());
+	Ok(TimingCheck {
+		span: span,
+		kind: kind,
+		events: events,
+		limits: limits,
+		notifier: notifier,
+		extra: extra,
+	})
+}
+
+
+/// Parse a timing check event.
+///
+/// ```text
+/// [edge_ident ["[" edge_descriptor {"," edge_descriptor} "]"]] terminal ["&&&" expr]
+/// ```
+fn parse_timing_check_event(p: &mut AbstractParser) -> ReportedResult<TimingCheckEvent> {
+	let mut span = p.peek(0).1;
+	let edge = as_edge_ident(p.peek(0).0);
+	if edge != EdgeIdent::Implicit {
+		p.bump();
+	}
+
+	// Parse the optional edge descriptors, such as `edge [01, 10]`.
+	let mut descriptors = Vec::new();
+	if edge == EdgeIdent::Edge && p.try_eat(OpenDelim(Brack)) {
+		loop {
+			// A descriptor such as `0x` is lexed as a number followed by an
+			// identifier, so concatenate the tokens up to the next separator.
+			let (tkn, mut sp) = p.peek(0);
+			let mut text = String::new();
+			while let Some(s) = as_udp_chars(p.peek(0).0) {
+				text.push_str(&s);
+				p.bump();
+				sp.expand(p.last_span());
+			}
+			let chars: Vec<char> = text.to_lowercase().chars().collect();
+			let valid = chars.len() == 2 && chars[0] != chars[1] && chars.iter().all(|&c| match c {
+				'0' | '1' | 'x' | 'z' => true,
+				_ => false,
+			});
+			if !valid {
+				if text.is_empty() {
+					p.add_diag(DiagBuilder2::error(format!("Expected edge descriptor such as 01 or 10, found {} instead", tkn)).span(sp));
+				} else {
+					p.add_diag(DiagBuilder2::error(format!("Expected edge descriptor such as 01 or 10, found `{}` instead", text)).span(sp));
+				}
+				return Err(());
+			}
+			descriptors.push((chars[0], chars[1]));
+			if !p.try_eat(Comma) {
+				break;
+			}
+		}
+		p.require_reported(CloseDelim(Brack))?;
+	}
+
+	let terminal = parse_expr_prec(p, Precedence::Postfix)?;
+
+	// Parse the optional condition, introduced by `&&&`. The lexer produces
+	// this as `&&` followed by `&`.
+	let cond = if p.peek(0).0 == Operator(Op::LogicAnd) && p.peek(1).0 == Operator(Op::BitAnd) {
+		p.bump();
+		p.bump();
+		Some(parse_expr(p)?)
+	} else {
+		None
+	};
+	span.expand(p.last_span());
+	Ok(TimingCheckEvent {
+		span: span,
+		edge: edge,
+		descriptors: descriptors,
+		terminal: terminal,
+		cond: cond,
 	})
 }
 
@@ -5076,6 +8307,7 @@ fn parse_var_decl(p: &mut AbstractParser) -> ReportedResult<ast::VarDecl> {
 		lifetime: lifetime,
 		ty: ty,
 		names: names,
+		attrs: Vec::new(),
 	})
 }
 
@@ -5184,6 +8416,7 @@ fn parse_param_decl(p: &mut AbstractParser, keyword_optional: bool) -> ReportedR
 		span: span,
 		local: local,
 		kind: kind,
+		attrs: Vec::new(),
 	})
 }
 
@@ -445,8 +445,10 @@ impl<'a> Preprocessor<'a> {
 				}
 			}
 
-			// Ignore the "`timescale" directive for now.
-			Directive::Timescale => {
+			// Active "`timescale" and "`default_nettype" directives never make
+			// it here, since they are passed on to the parser. Skip inactive
+			// ones.
+			Directive::Timescale | Directive::DefaultNettype => {
 				while let Some((tkn, _)) = self.token {
 					if tkn == Newline {
 						break;
@@ -542,6 +544,17 @@ impl<'a> Iterator for Preprocessor<'a> {
 				Some((Symbol('`'), sp_backtick)) => {
 					self.bump(); // consume the backtick
 					if let Some((name, sp)) = self.try_eat_name() {
+						// The "`timescale" and "`default_nettype" directives
+						// are passed on to the parser, which associates them
+						// with the design elements that follow.
+						if (name == "timescale" || name == "default_nettype") && !self.is_inactive() {
+							if let Some(tkn) = self.token {
+								self.macro_stack.push(tkn);
+							}
+							self.token = Some((Text, sp));
+							return Some(Ok((Symbol('`'), sp_backtick)));
+						}
+
 						// We arrive here if the sequence a backtick
 						// followed by text was encountered. In this case we
 						// call upon the handle_directive function to
@@ -596,6 +609,7 @@ enum Directive {
 	Elsif,
 	Endif,
 	Timescale,
+	DefaultNettype,
 	Unknown,
 }
 
@@ -612,6 +626,7 @@ thread_local!(static DIRECTIVES_TABLE: HashMap<&'static str, Directive> = {
 	table.insert("elsif", Elsif);
 	table.insert("endif", Endif);
 	table.insert("timescale", Timescale);
+	table.insert("default_nettype", DefaultNettype);
 	table
 });
 
@@ -1,31 +1,69 @@
 // Copyright (c) 2017 Fabian Schuiki
 
-//! This module implements AST node renumbering.
+//! This module implements AST node renumbering. Every declaration,
+//! identifier, expression, statement, and type is assigned a unique node ID,
+//! under which later passes record their results.
 
+use std;
+use std::collections::HashMap;
 use super::ast::{self, NodeId};
 use moore_common::errors::*;
+use moore_common::source::Span;
 
-pub fn renumber(asts: &mut [ast::Root]) {
+/// Assign node IDs to the ASTs. Returns the span of each node.
+pub fn renumber(asts: &mut [ast::Root]) -> NodeSpans {
 	let mut rn = RenumberPass::new();
 	for ast in asts {
 		rn.renumber_ast(ast);
 	}
+	rn.spans
+}
+
+/// The spans of the nodes that have been assigned an ID. This allows tools to
+/// map the results of later passes, e.g. the definition a name resolves to,
+/// back to the source text.
+#[derive(Debug, Default)]
+pub struct NodeSpans {
+	spans: HashMap<NodeId, Span>,
+}
+
+impl NodeSpans {
+	/// Find the span of a node.
+	pub fn get(&self, id: NodeId) -> Option<Span> {
+		self.spans.get(&id).cloned()
+	}
+
+	/// Find the innermost node whose span covers the given span.
+	pub fn find(&self, span: Span) -> Option<NodeId> {
+		self.spans.iter()
+			.filter(|&(_, s)| s.source == span.source && s.begin <= span.begin && span.end <= s.end)
+			.min_by_key(|&(&id, s)| (s.end - s.begin, std::cmp::Reverse(id.as_usize())))
+			.map(|(&id, _)| id)
+	}
+
+	/// The number of nodes.
+	pub fn len(&self) -> usize {
+		self.spans.len()
+	}
 }
 
 struct RenumberPass {
 	next_id: usize,
+	spans: NodeSpans,
 }
 
 impl RenumberPass {
 	pub fn new() -> RenumberPass {
 		RenumberPass {
 			next_id: 1,
+			spans: NodeSpans::default(),
 		}
 	}
 
-	pub fn alloc_id(&mut self) -> NodeId {
+	pub fn alloc_id(&mut self, span: Span) -> NodeId {
 		let n = NodeId::new(self.next_id);
 		self.next_id += 1;
+		self.spans.spans.insert(n, span);
 		n
 	}
 
@@ -37,27 +75,56 @@ impl RenumberPass {
 
 	pub fn renumber_item(&mut self, item: &mut ast::Item) {
 		match *item {
-			ast::Item::Module(ref mut decl) => {
-				decl.id = self.alloc_id();
-				self.renumber_param_ports(&mut decl.params);
-				self.renumber_ports(&mut decl.ports);
+			ast::Item::Module(ref mut decl) |
+			ast::Item::ExternModule(ref mut decl) => self.renumber_module_decl(decl),
+			ast::Item::Interface(ref mut decl) |
+			ast::Item::ExternInterface(ref mut decl) => self.renumber_interface_decl(decl),
+			ast::Item::Package(ref mut decl) => {
+				decl.id = self.alloc_id(decl.span);
 				self.renumber_hierarchy_items(&mut decl.items);
 			}
-			ast::Item::Interface(ref mut decl) => {
-				decl.id = self.alloc_id();
-				self.renumber_param_ports(&mut decl.params);
-				self.renumber_ports(&mut decl.ports);
+			ast::Item::Class(ref mut decl) => self.renumber_class_decl(decl),
+			ast::Item::Primitive(ref mut decl) => self.renumber_udp_decl(decl),
+			ast::Item::Checker(ref mut decl) => {
+				decl.name.id = self.alloc_id(decl.name.span);
+				self.renumber_assertion_ports(&mut decl.ports);
 				self.renumber_hierarchy_items(&mut decl.items);
 			}
-			ast::Item::Package(ref mut decl) => {
-				decl.id = self.alloc_id();
-				self.renumber_hierarchy_items(&mut decl.items);
+			ast::Item::Bind(ref mut bind) => {
+				for name in bind.target.iter_mut().chain(bind.insts.iter_mut().flat_map(|x| x.iter_mut())) {
+					name.id = self.alloc_id(name.span);
+				}
+				self.renumber_inst(&mut bind.inst);
 			}
-			ast::Item::Class(ref mut decl) => self.renumber_class_decl(decl),
 			ast::Item::Item(ref mut item) => self.renumber_hierarchy_item(item),
 		}
 	}
 
+	pub fn renumber_module_decl(&mut self, decl: &mut ast::ModDecl) {
+		decl.id = self.alloc_id(decl.span);
+		self.renumber_param_ports(&mut decl.params);
+		self.renumber_ports(&mut decl.ports);
+		self.renumber_hierarchy_items(&mut decl.items);
+	}
+
+	pub fn renumber_interface_decl(&mut self, decl: &mut ast::IntfDecl) {
+		decl.id = self.alloc_id(decl.span);
+		self.renumber_param_ports(&mut decl.params);
+		self.renumber_ports(&mut decl.ports);
+		self.renumber_hierarchy_items(&mut decl.items);
+	}
+
+	pub fn renumber_udp_decl(&mut self, decl: &mut ast::UdpDecl) {
+		decl.name.id = self.alloc_id(decl.name.span);
+		decl.output.id = self.alloc_id(decl.output.span);
+		for input in &mut decl.inputs {
+			input.id = self.alloc_id(input.span);
+		}
+		if let Some(ref mut e) = decl.init {
+			self.renumber_expr(e);
+		}
+	}
+
 	// TODO: Rename this function to renumber_param_decls.
 	pub fn renumber_param_ports(&mut self, params: &mut [ast::ParamDecl]) {
 		for param in params {
@@ -68,7 +135,7 @@ impl RenumberPass {
 	// TODO: Replace this function with calls to renumber_param_decl.
 	pub fn renumber_param_port(&mut self, param: &mut ast::ParamDecl) {
 		self.renumber_param_decl(param);
-		// param.name.id = self.alloc_id();
+		// param.name.id = self.alloc_id(param.name.span);
 		// self.renumber_dims(&mut param.dims);
 		// if let Some(ref mut e) = param.expr {
 		// 	self.renumber_expr(e);
@@ -85,9 +152,9 @@ impl RenumberPass {
 		match *port {
 			ast::Port::Intf { ref mut modport, ref mut name, ref mut dims, ref mut expr, .. } => {
 				if let Some(ref mut modport) = *modport {
-					modport.id = self.alloc_id();
+					modport.id = self.alloc_id(modport.span);
 				}
-				name.id = self.alloc_id();
+				name.id = self.alloc_id(name.span);
 				self.renumber_dims(dims);
 				if let Some(ref mut expr) = *expr {
 					self.renumber_expr(expr);
@@ -95,7 +162,7 @@ impl RenumberPass {
 			}
 
 			ast::Port::Explicit { ref mut name, ref mut expr, .. } => {
-				name.id = self.alloc_id();
+				name.id = self.alloc_id(name.span);
 				if let Some(ref mut expr) = *expr {
 					self.renumber_expr(expr);
 				}
@@ -103,7 +170,7 @@ impl RenumberPass {
 
 			ast::Port::Named { ref mut ty, ref mut name, ref mut dims, ref mut expr, .. } => {
 				self.renumber_type(ty);
-				name.id = self.alloc_id();
+				name.id = self.alloc_id(name.span);
 				self.renumber_dims(dims);
 				if let Some(ref mut expr) = *expr {
 					self.renumber_expr(expr);
@@ -124,19 +191,40 @@ impl RenumberPass {
 		match *item {
 			ast::HierarchyItem::Procedure(ref mut prc) => self.renumber_stmt(&mut prc.stmt),
 			ast::HierarchyItem::ImportDecl(ref mut decl) => self.renumber_import_decl(decl),
+			ast::HierarchyItem::ExportDecl(ref mut decl) => self.renumber_export_decl(decl),
+			ast::HierarchyItem::DpiDecl(ref mut decl) => self.renumber_dpi_decl(decl),
+			ast::HierarchyItem::SubroutineDecl(ref mut decl) => self.renumber_subroutine_decl(decl),
 			ast::HierarchyItem::Assertion(ref mut assertion) => self.renumber_assertion(assertion),
 			ast::HierarchyItem::VarDecl(ref mut decl) => self.renumber_var_decl(decl),
 			ast::HierarchyItem::NetDecl(ref mut decl) => self.renumber_net_decl(decl),
+			ast::HierarchyItem::ModuleDecl(ref mut decl) => self.renumber_module_decl(decl),
+			ast::HierarchyItem::InterfaceDecl(ref mut decl) => self.renumber_interface_decl(decl),
 			ast::HierarchyItem::ModportDecl(ref mut decl) => {
 				for item in &mut decl.items {
-					item.name.id = self.alloc_id();
-					// TODO: Renumber modport_port_decls.
+					item.name.id = self.alloc_id(item.name.span);
+					for port in &mut item.ports {
+						match *port {
+							ast::ModportPort::Simple { ref mut ports, .. } => for port in ports {
+								port.name.id = self.alloc_id(port.name.span);
+								if let Some(ref mut expr) = port.expr {
+									self.renumber_expr(expr);
+								}
+							},
+							ast::ModportPort::Tf { ref mut ports, .. } => for port in ports {
+								match *port {
+									ast::ModportTfPort::Name(ref mut name) => name.id = self.alloc_id(name.span),
+									ast::ModportTfPort::Prototype(ref mut proto) => self.renumber_subroutine_prototype(proto),
+								}
+							},
+							ast::ModportPort::Clocking(ref mut name) => name.id = self.alloc_id(name.span),
+						}
+					}
 				}
 			}
 			ast::HierarchyItem::ParamDecl(ref mut decl) => self.renumber_param_decl(decl),
 			ast::HierarchyItem::ContAssign(ref mut assign) => self.renumber_continuous_assignment(assign),
 			ast::HierarchyItem::GenvarDecl(ref mut decls) => for decl in decls {
-				decl.id = self.alloc_id();
+				decl.id = self.alloc_id(decl.span);
 				if let Some(ref mut e) = decl.init {
 					self.renumber_expr(e);
 				}
@@ -155,25 +243,85 @@ impl RenumberPass {
 					self.renumber_generate_block(b);
 				}
 			}
-			ast::HierarchyItem::GenerateCase(ref mut gc) => (),
+			ast::HierarchyItem::GenerateCase(ref mut gc) => {
+				self.renumber_expr(&mut gc.expr);
+				for item in &mut gc.items {
+					for e in &mut item.exprs {
+						self.renumber_expr(e);
+					}
+					self.renumber_generate_block(&mut item.block);
+				}
+			}
 			ast::HierarchyItem::Typedef(ref mut td) => self.renumber_typedef(td),
 			ast::HierarchyItem::ClassDecl(ref mut decl) => self.renumber_class_decl(decl),
-			ast::HierarchyItem::Inst(ref mut stmt) => {
-				stmt.target.id = self.alloc_id();
-				self.renumber_param_assignments(&mut stmt.params);
+			ast::HierarchyItem::CovergroupDecl(ref mut decl) => self.renumber_covergroup_decl(decl),
+			ast::HierarchyItem::PortDecl(ref mut decl) => {
+				self.renumber_type(&mut decl.ty);
+				self.renumber_var_decl_names(&mut decl.names);
+			}
+			ast::HierarchyItem::Inst(ref mut stmt) => self.renumber_inst(stmt),
+			ast::HierarchyItem::GateInst(ref mut stmt) => {
+				if let Some(ref mut delay) = stmt.delay {
+					for e in &mut delay.values {
+						self.renumber_expr(e);
+					}
+				}
 				for inst in &mut stmt.names {
-					inst.name.id = self.alloc_id();
+					if let Some(ref mut name) = inst.name {
+						name.id = self.alloc_id(name.span);
+					}
 					self.renumber_dims(&mut inst.dims);
-					self.renumber_port_conns(&mut inst.conns);
+					for e in &mut inst.terminals {
+						self.renumber_expr(e);
+					}
+				}
+			}
+
+			ast::HierarchyItem::SpecparamDecl(ref mut decl) => self.renumber_specparam_decl(decl),
+			ast::HierarchyItem::SpecifyBlock(ref mut blk) => self.renumber_specify_block(blk),
+			ast::HierarchyItem::ClockingDecl(ref mut decl) => self.renumber_clocking_decl(decl),
+			ast::HierarchyItem::DefaultClocking(_, ref mut name) => name.id = self.alloc_id(name.span),
+			ast::HierarchyItem::SequenceDecl(ref mut decl) => self.renumber_sequence_decl(decl),
+			ast::HierarchyItem::PropertyDecl(ref mut decl) => self.renumber_property_decl(decl),
+			ast::HierarchyItem::NettypeDecl(ref mut decl) => {
+				decl.name.id = self.alloc_id(decl.name.span);
+				self.renumber_type(&mut decl.ty);
+				if let Some((ref mut scope, ref mut func)) = decl.resolve {
+					if let Some(ref mut scope) = *scope {
+						scope.id = self.alloc_id(scope.span);
+					}
+					func.id = self.alloc_id(func.span);
+				}
+			}
+			ast::HierarchyItem::Constraint(ref mut c) => {
+				if let Some(ref mut class) = c.class {
+					class.id = self.alloc_id(class.span);
 				}
+				self.renumber_constraint_items(&mut c.items);
 			}
+			ast::HierarchyItem::Alias(ref mut alias) => for net in &mut alias.nets {
+				self.renumber_expr(net);
+			},
 
 			// Unimplemented cases.
 			ast::HierarchyItem::Dummy |
 			ast::HierarchyItem::LocalparamDecl(_) |
-			ast::HierarchyItem::ParameterDecl(_) |
-			ast::HierarchyItem::PortDecl(_) |
-			ast::HierarchyItem::SubroutineDecl(_)  => ()
+			ast::HierarchyItem::ParameterDecl(_) => ()
+		}
+	}
+
+	pub fn renumber_inst(&mut self, stmt: &mut ast::Inst) {
+		stmt.target.id = self.alloc_id(stmt.target.span);
+		self.renumber_param_assignments(&mut stmt.params);
+		if let Some(ref mut delay) = stmt.delay {
+			for e in &mut delay.values {
+				self.renumber_expr(e);
+			}
+		}
+		for inst in &mut stmt.names {
+			inst.name.id = self.alloc_id(inst.name.span);
+			self.renumber_dims(&mut inst.dims);
+			self.renumber_port_conns(&mut inst.conns);
 		}
 	}
 
@@ -211,6 +359,7 @@ impl RenumberPass {
 	}
 
 	pub fn renumber_stmt(&mut self, stmt: &mut ast::Stmt) {
+		stmt.id = self.alloc_id(stmt.span);
 		match stmt.data {
 			ast::SequentialBlock(ref mut stmts) |
 			ast::ParallelBlock(ref mut stmts, _) => self.renumber_stmts(stmts),
@@ -225,12 +374,15 @@ impl RenumberPass {
 				self.renumber_expr(lhs);
 				self.renumber_expr(rhs);
 			}
-			ast::NonblockingAssignStmt{ref mut lhs, ref mut rhs, ref mut delay, ref mut event, ..} => {
+			ast::NonblockingAssignStmt{ref mut lhs, ref mut rhs, ref mut delay, ref mut event, ref mut cycle} => {
 				self.renumber_expr(lhs);
 				self.renumber_expr(rhs);
 				if let Some(ref mut dc) = *delay {
 					self.renumber_expr(&mut dc.expr);
 				}
+				if let Some(ref mut cd) = *cycle {
+					self.renumber_cycle_delay(cd);
+				}
 				if let Some(ref mut ec) = *event {
 					unimplemented!();
 					// self.renumber_event_control(ec);
@@ -275,9 +427,10 @@ impl RenumberPass {
 				self.renumber_expr(expr);
 				for var in vars {
 					if let Some(ref mut ident) = *var {
-						ident.id = self.alloc_id();
+						ident.id = self.alloc_id(ident.span);
 					}
 				}
+				self.renumber_stmt(stmt);
 			}
 			ast::ExprStmt(ref mut expr) => self.renumber_expr(expr),
 			ast::VarDeclStmt(ref mut decl) => self.renumber_var_decl(decl),
@@ -297,9 +450,10 @@ impl RenumberPass {
 	}
 
 	pub fn renumber_expr(&mut self, expr: &mut ast::Expr) {
+		expr.id = self.alloc_id(expr.span);
 		match expr.data {
 			ast::IdentExpr(ref mut ident) |
-			ast::SysIdentExpr(ref mut ident) => ident.id = self.alloc_id(),
+			ast::SysIdentExpr(ref mut ident) => ident.id = self.alloc_id(ident.span),
 			ast::IndexExpr{ref mut indexee, ref mut index} => {
 				self.renumber_expr(indexee);
 				self.renumber_expr(index);
@@ -356,7 +510,11 @@ impl RenumberPass {
 			}
 			ast::MemberExpr{ref mut expr, ref mut name} => {
 				self.renumber_expr(expr);
-				name.id = self.alloc_id();
+				name.id = self.alloc_id(name.span);
+			}
+			ast::ScopeExpr(ref mut expr, ref mut name) => {
+				self.renumber_expr(expr);
+				name.id = self.alloc_id(name.span);
 			}
 			ast::PatternExpr(ref mut fields) => for field in fields {
 				match field.data {
@@ -378,15 +536,193 @@ impl RenumberPass {
 					}
 				}
 			},
+			ast::InsideExpr(ref mut expr, ref mut ranges) => {
+				self.renumber_expr(expr);
+				for range in ranges {
+					self.renumber_expr(range);
+				}
+			}
+			ast::RandomizeWithExpr{ref mut call, ref mut names, ref mut items} => {
+				self.renumber_expr(call);
+				for name in names {
+					name.id = self.alloc_id(name.span);
+				}
+				self.renumber_constraint_items(items);
+			}
+
+			ast::TypeExpr(ref mut ty) => self.renumber_type(ty),
 
 			// Trivial cases.
 			ast::LiteralExpr(_) |
-			ast::EmptyQueueExpr |
-			ast::TypeExpr(_) => (),
+			ast::EmptyQueueExpr => (),
 			ast::DummyExpr => println!("{}", DiagBuilder2::warning("found dummy expression during renumbering").span(expr.span).add_note("you might want to fix the parser to produce an actual expression")),
 		}
 	}
 
+	pub fn renumber_constraint_items(&mut self, items: &mut [ast::ConstraintItem]) {
+		for item in items {
+			self.renumber_constraint_item(item);
+		}
+	}
+
+	pub fn renumber_constraint_item(&mut self, item: &mut ast::ConstraintItem) {
+		match item.data {
+			ast::ConstraintItemData::Expr{ref mut expr, ..} |
+			ast::ConstraintItemData::DisableSoft(ref mut expr) => self.renumber_expr(expr),
+			ast::ConstraintItemData::Dist{ref mut expr, ref mut items, ..} => {
+				self.renumber_expr(expr);
+				for item in items {
+					self.renumber_expr(&mut item.value);
+					if let Some((_, ref mut e)) = item.weight {
+						self.renumber_expr(e);
+					}
+				}
+			}
+			ast::ConstraintItemData::Unique(ref mut exprs) => for expr in exprs {
+				self.renumber_expr(expr);
+			},
+			ast::ConstraintItemData::Implication{ref mut cond, ref mut items} => {
+				self.renumber_expr(cond);
+				self.renumber_constraint_items(items);
+			}
+			ast::ConstraintItemData::If{ref mut cond, ref mut main_items, ref mut else_items} => {
+				self.renumber_expr(cond);
+				self.renumber_constraint_items(main_items);
+				if let Some(ref mut items) = *else_items {
+					self.renumber_constraint_items(items);
+				}
+			}
+			ast::ConstraintItemData::Foreach{ref mut expr, ref mut vars, ref mut items} => {
+				self.renumber_expr(expr);
+				for var in vars {
+					if let Some(ref mut v) = *var {
+						v.id = self.alloc_id(v.span);
+					}
+				}
+				self.renumber_constraint_items(items);
+			}
+			ast::ConstraintItemData::SolveBefore(ref mut first, ref mut then) => {
+				for expr in first.iter_mut().chain(then.iter_mut()) {
+					self.renumber_expr(expr);
+				}
+			}
+		}
+	}
+
+	pub fn renumber_covergroup_decl(&mut self, decl: &mut ast::CovergroupDecl) {
+		decl.name.id = self.alloc_id(decl.name.span);
+		self.renumber_subroutine_ports(&mut decl.ports);
+		match decl.event {
+			Some(ast::CoverageEvent::Clocking(ref mut ec)) => self.renumber_event_control(ec),
+			Some(ast::CoverageEvent::Sample(ref mut ports)) => self.renumber_subroutine_ports(ports),
+			None => (),
+		}
+		for item in &mut decl.items {
+			match *item {
+				ast::CovergroupItem::Option(ref mut option) => self.renumber_expr(&mut option.expr),
+				ast::CovergroupItem::Coverpoint(ref mut cp) => {
+					cp.id = self.alloc_id(cp.span);
+					if let Some(ref mut name) = cp.name {
+						name.id = self.alloc_id(name.span);
+					}
+					self.renumber_expr(&mut cp.expr);
+					if let Some(ref mut e) = cp.iff {
+						self.renumber_expr(e);
+					}
+					self.renumber_bins(&mut cp.bins);
+				}
+				ast::CovergroupItem::Cross(ref mut cross) => {
+					cross.id = self.alloc_id(cross.span);
+					if let Some(ref mut name) = cross.name {
+						name.id = self.alloc_id(name.span);
+					}
+					for item in &mut cross.items {
+						item.id = self.alloc_id(item.span);
+					}
+					if let Some(ref mut e) = cross.iff {
+						self.renumber_expr(e);
+					}
+					self.renumber_bins(&mut cross.bins);
+				}
+			}
+		}
+	}
+
+	pub fn renumber_bins(&mut self, bins: &mut [ast::BinsOrOption]) {
+		for bin in bins {
+			let bin = match *bin {
+				ast::BinsOrOption::Option(ref mut option) => {
+					self.renumber_expr(&mut option.expr);
+					continue;
+				}
+				ast::BinsOrOption::Bins(ref mut bin) => bin,
+			};
+			bin.name.id = self.alloc_id(bin.name.span);
+			if let Some(Some(ref mut e)) = bin.array {
+				self.renumber_expr(e);
+			}
+			match bin.data {
+				ast::BinsData::Values(ref mut exprs) => for expr in exprs {
+					self.renumber_expr(expr);
+				},
+				ast::BinsData::Transitions(ref mut seqs) => for item in seqs.iter_mut().flat_map(|s| s.iter_mut()) {
+					for expr in &mut item.values {
+						self.renumber_expr(expr);
+					}
+					if let Some(ref mut repeat) = item.repeat {
+						self.renumber_expr(&mut repeat.min);
+						if let Some(ref mut e) = repeat.max {
+							self.renumber_expr(e);
+						}
+					}
+				},
+				ast::BinsData::Expr(ref mut expr) => self.renumber_expr(expr),
+				ast::BinsData::Select(ref mut sel) => self.renumber_select_expr(sel),
+				ast::BinsData::Default |
+				ast::BinsData::DefaultSequence => (),
+			}
+			if let Some(ref mut e) = bin.with {
+				self.renumber_expr(e);
+			}
+			if let Some(ref mut e) = bin.iff {
+				self.renumber_expr(e);
+			}
+		}
+	}
+
+	pub fn renumber_select_expr(&mut self, expr: &mut ast::SelectExpr) {
+		match expr.data {
+			ast::SelectExprData::Binsof{ref mut target, ref mut bin, ref mut intersect} => {
+				target.id = self.alloc_id(target.span);
+				if let Some(ref mut b) = *bin {
+					b.id = self.alloc_id(b.span);
+				}
+				for expr in intersect {
+					self.renumber_expr(expr);
+				}
+			}
+			ast::SelectExprData::Not(ref mut expr) => self.renumber_select_expr(expr),
+			ast::SelectExprData::And(ref mut lhs, ref mut rhs) |
+			ast::SelectExprData::Or(ref mut lhs, ref mut rhs) => {
+				self.renumber_select_expr(lhs);
+				self.renumber_select_expr(rhs);
+			}
+		}
+	}
+
+	pub fn renumber_subroutine_ports(&mut self, ports: &mut [ast::SubroutinePort]) {
+		for port in ports {
+			self.renumber_type(&mut port.ty);
+			if let Some(ref mut name) = port.name {
+				name.name.id = self.alloc_id(name.name.span);
+				self.renumber_dims(&mut name.dims);
+				if let Some(ref mut e) = name.expr {
+					self.renumber_expr(e);
+				}
+			}
+		}
+	}
+
 	pub fn renumber_call_args(&mut self, args: &mut [ast::CallArg]) {
 		for arg in args {
 			self.renumber_call_arg(arg);
@@ -415,14 +751,149 @@ impl RenumberPass {
 	}
 
 	pub fn renumber_cycle_delay(&mut self, cd: &mut ast::CycleDelay) {
-		// TODO: Renumber cycle delay.
+		self.renumber_expr(&mut cd.expr);
 	}
 
-	pub fn renumber_import_decl(&mut self, decl: &mut ast::ImportDecl) {
+	pub fn renumber_clocking_decl(&mut self, decl: &mut ast::ClockingDecl) {
+		if let Some(ref mut name) = decl.name {
+			name.id = self.alloc_id(name.span);
+		}
+		self.renumber_event_control(&mut decl.event);
 		for item in &mut decl.items {
-			item.pkg.id = self.alloc_id();
+			match *item {
+				ast::ClockingItem::DefaultSkew{ref mut input, ref mut output, ..} => {
+					for skew in input.iter_mut().chain(output.iter_mut()) {
+						self.renumber_clocking_skew(skew);
+					}
+				}
+				ast::ClockingItem::Signals{ref mut dir, ref mut signals, ..} => {
+					match *dir {
+						ast::ClockingDir::Input(ref mut skew) |
+						ast::ClockingDir::Output(ref mut skew) => if let Some(ref mut skew) = *skew {
+							self.renumber_clocking_skew(skew);
+						},
+						ast::ClockingDir::InputOutput(ref mut a, ref mut b) => {
+							for skew in a.iter_mut().chain(b.iter_mut()) {
+								self.renumber_clocking_skew(skew);
+							}
+						}
+						ast::ClockingDir::Inout => (),
+					}
+					for signal in signals {
+						signal.name.id = self.alloc_id(signal.name.span);
+						if let Some(ref mut e) = signal.expr {
+							self.renumber_expr(e);
+						}
+					}
+				}
+			}
+		}
+	}
+
+	pub fn renumber_clocking_skew(&mut self, skew: &mut ast::ClockingSkew) {
+		if let Some(ref mut dc) = skew.delay {
+			self.renumber_expr(&mut dc.expr);
+		}
+	}
+
+	pub fn renumber_import_decl(&mut self, decl: &mut ast::ImportDecl) {
+		self.renumber_import_items(&mut decl.items);
+	}
+
+	pub fn renumber_export_decl(&mut self, decl: &mut ast::ExportDecl) {
+		self.renumber_import_items(&mut decl.items);
+	}
+
+	pub fn renumber_import_items(&mut self, items: &mut [ast::ImportItem]) {
+		for item in items {
+			item.pkg.id = self.alloc_id(item.pkg.span);
 			if let Some(ref mut ident) = item.name {
-				ident.id = self.alloc_id();
+				ident.id = self.alloc_id(ident.span);
+			}
+		}
+	}
+
+	pub fn renumber_dpi_decl(&mut self, decl: &mut ast::DpiDecl) {
+		match *decl {
+			ast::DpiDecl::Import{ref mut prototype, ..} => {
+				prototype.name.id = self.alloc_id(prototype.name.span);
+				if let Some(ref mut ty) = prototype.retty {
+					self.renumber_type(ty);
+				}
+				self.renumber_subroutine_ports(&mut prototype.args);
+			}
+			ast::DpiDecl::Export{ref mut name, ..} => name.id = self.alloc_id(name.span),
+		}
+	}
+
+	pub fn renumber_subroutine_decl(&mut self, decl: &mut ast::SubroutineDecl) {
+		self.renumber_subroutine_prototype(&mut decl.prototype);
+		for item in &mut decl.items {
+			match *item {
+				ast::SubroutineItem::PortDecl(ref mut decl) => {
+					self.renumber_type(&mut decl.ty);
+					self.renumber_var_decl_names(&mut decl.names);
+				}
+				ast::SubroutineItem::Stmt(ref mut stmt) => self.renumber_stmt(stmt),
+			}
+		}
+	}
+
+	pub fn renumber_subroutine_prototype(&mut self, proto: &mut ast::SubroutinePrototype) {
+		proto.name.id = self.alloc_id(proto.name.span);
+		if let Some(ref mut ty) = proto.retty {
+			self.renumber_type(ty);
+		}
+		self.renumber_subroutine_ports(&mut proto.args);
+	}
+
+		pub fn renumber_specparam_decl(&mut self, decl: &mut ast::SpecparamDecl) {
+		self.renumber_dims(&mut decl.dims);
+		for assign in &mut decl.assigns {
+			assign.name.id = self.alloc_id(assign.name.span);
+			self.renumber_expr(&mut assign.value);
+			if let Some(ref mut e) = assign.error {
+				self.renumber_expr(e);
+			}
+		}
+	}
+
+	pub fn renumber_specify_block(&mut self, blk: &mut ast::SpecifyBlock) {
+		for item in &mut blk.items {
+			match *item {
+				ast::SpecifyItem::Specparam(ref mut decl) => self.renumber_specparam_decl(decl),
+				ast::SpecifyItem::Pulsestyle(_, _, ref mut outputs) |
+				ast::SpecifyItem::Showcancelled(_, _, ref mut outputs) => for e in outputs {
+					self.renumber_expr(e);
+				},
+				ast::SpecifyItem::Path(ref mut path) => {
+					if let Some(ast::PathCond::If(ref mut e)) = path.cond {
+						self.renumber_expr(e);
+					}
+					for e in path.inputs.iter_mut().chain(path.outputs.iter_mut()).chain(path.delays.iter_mut()) {
+						self.renumber_expr(e);
+					}
+					if let Some((_, ref mut e)) = path.data_source {
+						self.renumber_expr(e);
+					}
+				}
+				ast::SpecifyItem::TimingCheck(ref mut tc) => {
+					for ev in &mut tc.events {
+						self.renumber_expr(&mut ev.terminal);
+						if let Some(ref mut e) = ev.cond {
+							self.renumber_expr(e);
+						}
+					}
+					for e in &mut tc.limits {
+						self.renumber_expr(e);
+					}
+					if let Some(ref mut n) = tc.notifier {
+						n.id = self.alloc_id(n.span);
+					}
+					for e in tc.extra.iter_mut().filter_map(|e| e.as_mut()) {
+						self.renumber_expr(e);
+					}
+				}
 			}
 		}
 	}
@@ -462,13 +933,172 @@ impl RenumberPass {
 				self.renumber_propspec(ps);
 				self.renumber_stmt(stmt);
 			}
-			ast::ConcurrentAssertion::CoverSequence => (),
+			ast::ConcurrentAssertion::CoverSequence(ref mut ps, ref mut stmt) => {
+				self.renumber_propspec(ps);
+				self.renumber_stmt(stmt);
+			}
 			ast::ConcurrentAssertion::RestrictProperty(ref mut ps) => self.renumber_propspec(ps),
 		}
 	}
 
 	pub fn renumber_propspec(&mut self, ps: &mut ast::PropSpec) {
-		// TODO: Implement as soon as we have some property specs set up.
+		if let Some(ref mut clock) = ps.clock {
+			self.renumber_event_expr(clock);
+		}
+		if let Some(ref mut disable) = ps.disable {
+			self.renumber_expr(disable);
+		}
+		self.renumber_propexpr(&mut ps.prop);
+	}
+
+	pub fn renumber_propexpr(&mut self, expr: &mut ast::PropExpr) {
+		match expr.data {
+			ast::PropExprData::SeqOp(_, ref mut seq) => self.renumber_seqexpr(seq),
+			ast::PropExprData::SeqBinOp(_, _, ref mut seq, ref mut prop) => {
+				self.renumber_seqexpr(seq);
+				self.renumber_propexpr(prop);
+			}
+			ast::PropExprData::Not(ref mut prop) => self.renumber_propexpr(prop),
+			ast::PropExprData::BinOp(_, ref mut lhs, ref mut rhs) => {
+				self.renumber_propexpr(lhs);
+				self.renumber_propexpr(rhs);
+			}
+			ast::PropExprData::Clocked(ref mut ev, ref mut prop) => {
+				self.renumber_event_expr(ev);
+				self.renumber_propexpr(prop);
+			}
+			ast::PropExprData::If(ref mut cond, ref mut main, ref mut other) => {
+				self.renumber_expr(cond);
+				self.renumber_propexpr(main);
+				if let Some(ref mut other) = *other {
+					self.renumber_propexpr(other);
+				}
+			}
+			ast::PropExprData::Case(ref mut expr, ref mut items) => {
+				self.renumber_expr(expr);
+				for item in items {
+					for e in &mut item.exprs {
+						self.renumber_expr(e);
+					}
+					self.renumber_propexpr(&mut item.prop);
+				}
+			}
+			ast::PropExprData::Nexttime(_, ref mut index, ref mut prop) => {
+				if let Some(ref mut index) = *index {
+					self.renumber_expr(index);
+				}
+				self.renumber_propexpr(prop);
+			}
+			ast::PropExprData::Always(_, ref mut range, ref mut prop) |
+			ast::PropExprData::Eventually(_, ref mut range, ref mut prop) => {
+				if let Some(ref mut range) = *range {
+					self.renumber_seq_range(range);
+				}
+				self.renumber_propexpr(prop);
+			}
+			ast::PropExprData::Abort(_, ref mut cond, ref mut prop) => {
+				self.renumber_expr(cond);
+				self.renumber_propexpr(prop);
+			}
+		}
+	}
+
+	pub fn renumber_seqexpr(&mut self, expr: &mut ast::SeqExpr) {
+		match expr.data {
+			ast::SeqExprData::Expr(ref mut expr, ref mut rep) => {
+				self.renumber_expr(expr);
+				if let Some(ref mut rep) = *rep {
+					self.renumber_seq_rep(rep);
+				}
+			}
+			ast::SeqExprData::BinOp(_, ref mut lhs, ref mut rhs) => {
+				self.renumber_seqexpr(lhs);
+				self.renumber_seqexpr(rhs);
+			}
+			ast::SeqExprData::Throughout(ref mut expr, ref mut seq) => {
+				self.renumber_expr(expr);
+				self.renumber_seqexpr(seq);
+			}
+			ast::SeqExprData::Clocked(ref mut ev, ref mut seq) => {
+				self.renumber_event_expr(ev);
+				self.renumber_seqexpr(seq);
+			}
+			ast::SeqExprData::Delay(ref mut lhs, ref mut range, ref mut rhs) => {
+				if let Some(ref mut lhs) = *lhs {
+					self.renumber_seqexpr(lhs);
+				}
+				self.renumber_seq_range(range);
+				self.renumber_seqexpr(rhs);
+			}
+			ast::SeqExprData::Paren(ref mut seq, ref mut items, ref mut rep) => {
+				self.renumber_seqexpr(seq);
+				for item in items {
+					self.renumber_expr(item);
+				}
+				if let Some(ref mut rep) = *rep {
+					self.renumber_seq_rep(rep);
+				}
+			}
+			ast::SeqExprData::FirstMatch(ref mut seq, ref mut items) => {
+				self.renumber_seqexpr(seq);
+				for item in items {
+					self.renumber_expr(item);
+				}
+			}
+		}
+	}
+
+	pub fn renumber_seq_rep(&mut self, rep: &mut ast::SeqRep) {
+		match *rep {
+			ast::SeqRep::Consec(ref mut range) |
+			ast::SeqRep::Nonconsec(ref mut range) |
+			ast::SeqRep::Goto(ref mut range) => self.renumber_seq_range(range),
+		}
+	}
+
+	pub fn renumber_seq_range(&mut self, range: &mut ast::SeqRange) {
+		match *range {
+			ast::SeqRange::Single(ref mut expr) => self.renumber_expr(expr),
+			ast::SeqRange::Range(ref mut lo, ref mut hi) => {
+				self.renumber_expr(lo);
+				if let Some(ref mut hi) = *hi {
+					self.renumber_expr(hi);
+				}
+			}
+			ast::SeqRange::Star |
+			ast::SeqRange::Plus => (),
+		}
+	}
+
+	pub fn renumber_sequence_decl(&mut self, decl: &mut ast::SequenceDecl) {
+		decl.name.id = self.alloc_id(decl.name.span);
+		self.renumber_assertion_ports(&mut decl.ports);
+		for var in &mut decl.vars {
+			self.renumber_var_decl(var);
+		}
+		self.renumber_seqexpr(&mut decl.seq);
+	}
+
+	pub fn renumber_property_decl(&mut self, decl: &mut ast::PropertyDecl) {
+		decl.name.id = self.alloc_id(decl.name.span);
+		self.renumber_assertion_ports(&mut decl.ports);
+		for var in &mut decl.vars {
+			self.renumber_var_decl(var);
+		}
+		self.renumber_propspec(&mut decl.spec);
+	}
+
+	pub fn renumber_assertion_ports(&mut self, ports: &mut [ast::AssertionPort]) {
+		for port in ports {
+			if let ast::AssertionPortType::Type(ref mut ty) = port.ty {
+				self.renumber_type(ty);
+			}
+			port.name.id = self.alloc_id(port.name.span);
+			self.renumber_dims(&mut port.dims);
+			if let Some(ref mut default) = port.default {
+				self.renumber_expr(default);
+			}
+		}
 	}
 
 	pub fn renumber_assertion_action_block(&mut self, action: &mut ast::AssertionActionBlock) {
@@ -503,7 +1133,7 @@ impl RenumberPass {
 
 	pub fn renumber_var_decl_names(&mut self, decls: &mut [ast::VarDeclName]) {
 		for decl in decls {
-			decl.id = self.alloc_id();
+			decl.id = self.alloc_id(decl.span);
 			self.renumber_dims(&mut decl.dims);
 			if let Some(ref mut e) = decl.init {
 				self.renumber_expr(e);
@@ -512,7 +1142,7 @@ impl RenumberPass {
 	}
 
 	pub fn renumber_genvar_decl(&mut self, decl: &mut ast::GenvarDecl) {
-		decl.id = self.alloc_id();
+		decl.id = self.alloc_id(decl.span);
 		if let Some(ref mut e) = decl.init {
 			self.renumber_expr(e);
 		}
@@ -538,11 +1168,12 @@ impl RenumberPass {
 	}
 
 	pub fn renumber_type(&mut self, ty: &mut ast::Type) {
+		ty.id = self.alloc_id(ty.span);
 		match ty.data {
-			ast::NamedType(ref mut name) => name.id = self.alloc_id(),
+			ast::NamedType(ref mut name) => name.id = self.alloc_id(name.span),
 			ast::ScopedType{ty: ref mut super_ty, ref mut name, ..} => {
 				self.renumber_type(super_ty);
-				name.id = self.alloc_id();
+				name.id = self.alloc_id(name.span);
 			}
 
 			ast::EnumType(ref mut inner, ref mut names) => {
@@ -550,7 +1181,7 @@ impl RenumberPass {
 					self.renumber_type(i);
 				}
 				for name in names {
-					name.name.id = self.alloc_id();
+					name.name.id = self.alloc_id(name.name.span);
 					if let Some(ref mut e) = name.range {
 						self.renumber_expr(e);
 					}
@@ -572,7 +1203,7 @@ impl RenumberPass {
 
 			ast::SpecializedType(ref mut ty, ref mut params) => {
 				self.renumber_type(ty);
-				// TODO: Renumber parameter assignments.
+				self.renumber_param_assignments(params);
 			}
 
 			// Trivial cases.
@@ -620,14 +1251,14 @@ impl RenumberPass {
 	pub fn renumber_param_decl(&mut self, param: &mut ast::ParamDecl) {
 		match param.kind {
 			ast::ParamKind::Type(ref mut decls) => for decl in decls {
-				decl.name.id = self.alloc_id();
+				decl.name.id = self.alloc_id(decl.name.span);
 				if let Some(ref mut ty) = decl.ty {
 					self.renumber_type(ty);
 				}
 			},
 			ast::ParamKind::Value(ref mut decls) => for decl in decls {
 				self.renumber_type(&mut decl.ty);
-				decl.name.id = self.alloc_id();
+				decl.name.id = self.alloc_id(decl.name.span);
 				self.renumber_dims(&mut decl.dims);
 				if let Some(ref mut expr) = decl.expr {
 					self.renumber_expr(expr);
@@ -637,18 +1268,21 @@ impl RenumberPass {
 	}
 
 	pub fn renumber_typedef(&mut self, td: &mut ast::Typedef) {
-		td.name.id = self.alloc_id();
+		td.name.id = self.alloc_id(td.name.span);
 		self.renumber_type(&mut td.ty);
 		self.renumber_dims(&mut td.dims);
 	}
 
 	pub fn renumber_class_decl(&mut self, decl: &mut ast::ClassDecl) {
-		decl.name.id = self.alloc_id();
+		decl.name.id = self.alloc_id(decl.name.span);
 		self.renumber_param_ports(&mut decl.params);
 		if let Some((ref mut ty, ref mut args)) = decl.extends {
 			self.renumber_type(ty);
 			self.renumber_call_args(args);
 		}
+		for ty in &mut decl.implements {
+			self.renumber_type(ty);
+		}
 		self.renumber_class_items(&mut decl.items)
 	}
 
@@ -662,14 +1296,18 @@ impl RenumberPass {
 		match item.data {
 			ast::ClassItemData::Null => (),
 
+			ast::ClassItemData::Constraint(ref mut c) => self.renumber_constraint_items(&mut c.items),
+			ast::ClassItemData::CovergroupDecl(ref mut decl) => self.renumber_covergroup_decl(decl),
+			ast::ClassItemData::SubroutineDecl(ref mut decl) => self.renumber_subroutine_decl(decl),
+			ast::ClassItemData::ExternSubroutine(ref mut proto) => self.renumber_subroutine_prototype(proto),
+			ast::ClassItemData::Property { ref mut ty, ref mut names } => {
+				self.renumber_type(ty);
+				self.renumber_var_decl_names(names);
+			}
+
 			// Not yet implemented. This will show itself later when we try to
 			// bind any of these.
-			ast::ClassItemData::SubroutineDecl(_) |
-			ast::ClassItemData::ExternSubroutine(_) |
-			ast::ClassItemData::Constraint(_) |
-			ast::ClassItemData::Property |
 			ast::ClassItemData::ClassDecl |
-			ast::ClassItemData::CovergroupDecl |
 			ast::ClassItemData::LocalparamDecl(_) |
 			ast::ClassItemData::ParameterDecl(_) => (),
 		}
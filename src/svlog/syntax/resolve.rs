@@ -22,6 +22,7 @@
 
 use std;
 use super::ast::{self, NodeId};
+use super::systf::{SysArg, SysTfKind, SysTfTable};
 use moore_common::name::*;
 use moore_common::source::*;
 use moore_common::errors::*;
@@ -43,6 +44,20 @@ pub enum DefId {
 	Typedef(NodeId),
 	Class(NodeId),
 	Inst(NodeId),
+	Genvar(NodeId),
+	Covergroup(NodeId),
+	Coverpoint(NodeId),
+	Cross(NodeId),
+	Bins(NodeId),
+	Primitive(NodeId),
+	Specparam(NodeId),
+	Clocking(NodeId),
+	Clockvar(NodeId),
+	Sequence(NodeId),
+	Property(NodeId),
+	AssertionPort(NodeId),
+	Checker(NodeId),
+	Nettype(NodeId),
 }
 
 impl DefId {
@@ -66,7 +81,21 @@ impl DefId {
 			DefId::Subroutine(id) |
 			DefId::Typedef(id) |
 			DefId::Class(id) |
-			DefId::Inst(id) => id
+			DefId::Inst(id) |
+			DefId::Genvar(id) |
+			DefId::Covergroup(id) |
+			DefId::Coverpoint(id) |
+			DefId::Cross(id) |
+			DefId::Bins(id) |
+			DefId::Primitive(id) |
+			DefId::Specparam(id) |
+			DefId::Clocking(id) |
+			DefId::Clockvar(id) |
+			DefId::Sequence(id) |
+			DefId::Property(id) |
+			DefId::AssertionPort(id) |
+			DefId::Checker(id) |
+			DefId::Nettype(id) => id
 		}
 	}
 }
@@ -79,22 +108,55 @@ pub struct Def {
 
 
 pub fn resolve(session: &Session, asts: &[ast::Root]) -> Result<NameResolution, ()> {
-	let mut r = Resolver::new(session);
+	resolve_with_systfs(session, asts, &SysTfTable::new())
+}
+
+/// Resolve names, checking calls to system tasks and functions against the
+/// given table. This allows tools to provide their own system tasks.
+pub fn resolve_with_systfs(session: &Session, asts: &[ast::Root], systfs: &SysTfTable) -> Result<NameResolution, ()> {
+	let mut r = Resolver::new(session, systfs);
 	r.register_globals(asts);
 	if r.is_error() {
 		return Err(());
 	}
-	r.scopes.push(Scope::new_local());
-	for ast in asts {
+	r.with_scope(ScopeKind::Block, None, |r| for ast in asts {
 		r.resolve_ast(ast);
-	}
-	r.scopes.pop().unwrap();
+	});
 	r.finish()
 }
 
 /// The result of name resolution: An association between every identifier in
 /// the AST and a definition it points to.
-pub type NameResolution = HashMap<NodeId, NodeId>;
+#[derive(Debug, Default)]
+pub struct NameResolution {
+	/// The definition each identifier refers to.
+	pub defs: HashMap<NodeId, NodeId>,
+	/// The names declared in named blocks, functions, and tasks, indexed by the
+	/// hierarchical name of the scope (e.g. `[top, blk, i]`).
+	pub scopes: HashMap<Vec<Name>, HashMap<Name, NodeId>>,
+	/// The hierarchical names, which are resolved after elaboration.
+	pub hier_names: Vec<HierName>,
+	/// The nets implicitly declared by an identifier, indexed by the node ID
+	/// of the identifier.
+	pub implicit_nets: HashMap<NodeId, ast::NetType>,
+}
+
+/// A name that is resolved through the instance tree of the design rather
+/// than lexically, e.g. `top.u_core.result`.
+#[derive(Debug, Clone)]
+pub struct HierName {
+	/// The module, interface, or checker the name appears in.
+	pub parent: NodeId,
+	/// The member expression that forms the name.
+	pub expr: ast::Expr,
+}
+
+impl NameResolution {
+	/// Find the definition an identifier refers to.
+	pub fn get(&self, id: &NodeId) -> Option<&NodeId> {
+		self.defs.get(id)
+	}
+}
 
 /// The struct used to resolve names. This is a temporary construct that is only
 /// used to contain the session, symbol table, and the resulting name table.
@@ -104,34 +166,63 @@ struct Resolver<'a> {
 	severity: Severity,
 	scopes: Vec<Scope<'a>>,
 	defs: HashMap<NodeId, DefId>,
+	/// The local definitions of the named scopes left so far.
+	scope_defs: HashMap<Vec<Name>, HashMap<Name, NodeId>>,
+	hier_names: Vec<HierName>,
+	implicit_nets: HashMap<NodeId, ast::NetType>,
 	intf_map: HashMap<NodeId, &'a ast::IntfDecl>,
 	pkg_map: HashMap<NodeId, &'a ast::PackageDecl>,
 	mod_map: HashMap<NodeId, &'a ast::ModDecl>,
+	/// The extern declarations of modules, indexed by the node ID of the module
+	/// definition they correspond to.
+	mod_protos: HashMap<NodeId, &'a ast::ModDecl>,
+	/// The extern declarations of interfaces, indexed by the node ID of the
+	/// interface definition they correspond to.
+	intf_protos: HashMap<NodeId, &'a ast::IntfDecl>,
+	clocking_map: HashMap<NodeId, &'a ast::ClockingDecl>,
+	/// The classes, indexed by the node ID of their name.
+	class_map: HashMap<NodeId, &'a ast::ClassDecl>,
+	/// The class of the variables declared with a class type.
+	var_classes: HashMap<NodeId, NodeId>,
+	/// The classes which have been reported to inherit from themselves.
+	cyclic_classes: HashSet<NodeId>,
+	/// Whether the names being resolved are within a static method.
+	in_static_method: bool,
+	systfs: &'a SysTfTable,
+	/// Whether the expressions being resolved must be constant.
+	in_const_expr: bool,
 }
 
-// TODO: Make this into a ScopeKind enum. Then add a Scope struct that has a
-// kind, but also contains a lookup cache table for speed. Then extend the
-// resolver to provide a function that pushes a scope onto and pops it off the
-// stack again, calling a lambda in between.
+/// A scope on the resolver's stack.
 #[derive(Debug)]
-enum Scope<'a> {
-	Global(HashMap<Name, Def>),
+struct Scope<'a> {
+	kind: ScopeKind<'a>,
+	/// The hierarchical name of the scope, if it can be referred to by one.
+	path: Option<Vec<Name>>,
+	/// The names declared in the scope.
+	defs: HashMap<Name, Def>,
+	/// The names explicitly imported into the scope.
+	imports: HashMap<Name, Def>,
+	/// The packages imported with a wildcard import.
+	wildcard: Vec<&'a ast::PackageDecl>,
+	/// The results of searching the items of the scope's construct.
+	cache: HashMap<Name, Option<Def>>,
+}
+
+/// The construct a scope belongs to. Scopes of design elements and generate
+/// blocks search the items of their construct for names, since these may be
+/// used before they are declared.
+#[derive(Debug, Clone, Copy)]
+enum ScopeKind<'a> {
+	Global,
 	Module(&'a ast::ModDecl),
 	Interface(&'a ast::IntfDecl),
 	Package(&'a ast::PackageDecl),
-	Local {
-		defs: HashMap<Name, Def>,
-		imported: Vec<Scope<'a>>,
-	},
-}
-
-impl<'a> Scope<'a> {
-	pub fn new_local() -> Scope<'a> {
-		Scope::Local {
-			defs: HashMap::new(),
-			imported: Vec::new(),
-		}
-	}
+	Generate(&'a ast::GenerateBlock),
+	Checker(&'a ast::CheckerDecl),
+	Class(&'a ast::ClassDecl),
+	Subroutine,
+	Block,
 }
 
 macro_rules! assert_renumbered {
@@ -143,15 +234,27 @@ macro_rules! assert_renumbered {
 }
 
 impl<'a> Resolver<'a> {
-	pub fn new(session: &'a Session) -> Resolver<'a> {
+	pub fn new(session: &'a Session, systfs: &'a SysTfTable) -> Resolver<'a> {
 		Resolver {
 			session: session,
 			severity: Severity::Note,
 			scopes: Vec::new(),
 			defs: HashMap::new(),
+			scope_defs: HashMap::new(),
+			hier_names: Vec::new(),
+			implicit_nets: HashMap::new(),
 			intf_map: HashMap::new(),
 			pkg_map: HashMap::new(),
 			mod_map: HashMap::new(),
+			mod_protos: HashMap::new(),
+			intf_protos: HashMap::new(),
+			clocking_map: HashMap::new(),
+			class_map: HashMap::new(),
+			var_classes: HashMap::new(),
+			cyclic_classes: HashSet::new(),
+			in_static_method: false,
+			systfs: systfs,
+			in_const_expr: false,
 		}
 	}
 
@@ -171,15 +274,68 @@ impl<'a> Resolver<'a> {
 		if self.severity >= Severity::Error {
 			Err(())
 		} else {
-			Ok(self.defs.iter().map(|(k, def)| (*k, def.node_id())).collect())
+			Ok(NameResolution {
+				defs: self.defs.iter().map(|(k, def)| (*k, def.node_id())).collect(),
+				scopes: self.scope_defs,
+				hier_names: self.hier_names,
+				implicit_nets: self.implicit_nets,
+			})
+		}
+	}
+
+	/// Push a scope onto the stack, call `f`, and pop the scope off again. The
+	/// declarations of named blocks, functions, and tasks are recorded under
+	/// the hierarchical name of the scope.
+	fn with_scope<F, R>(&mut self, kind: ScopeKind<'a>, name: Option<Name>, f: F) -> R where F: FnOnce(&mut Self) -> R {
+		let parent = self.scopes.last().and_then(|s| s.path.clone());
+		let path = match (kind, name) {
+			(ScopeKind::Global, _) => None,
+			(ScopeKind::Module(_), Some(name)) |
+			(ScopeKind::Interface(_), Some(name)) |
+			(ScopeKind::Package(_), Some(name)) |
+			(ScopeKind::Checker(_), Some(name)) => Some(vec![name]),
+			(_, Some(name)) => parent.map(|mut p| { p.push(name); p }),
+			// Unnamed blocks do not add a level to the hierarchical name.
+			(ScopeKind::Block, None) => parent,
+			(_, None) => None,
+		};
+		self.scopes.push(Scope::new(kind, path));
+		let result = f(self);
+		let scope = self.scopes.pop().unwrap();
+		match (scope.kind, name, scope.path) {
+			(ScopeKind::Block, Some(_), Some(path)) |
+			(ScopeKind::Subroutine, Some(_), Some(path)) => {
+				let defs = scope.defs.iter().map(|(&n, d)| (n, d.id.node_id())).collect();
+				self.scope_defs.insert(path, defs);
+			}
+			_ => (),
 		}
+		result
 	}
 
 	pub fn register_globals(&mut self, asts: &'a [ast::Root]) {
 		let mut tbl = HashMap::new();
+		let mut externs: HashMap<Name, &'a ast::Item> = HashMap::new();
+		for ast in asts {
+			for item in &ast.items {
+				self.register_classes(item);
+			}
+		}
 		for ast in asts {
 			for item in &ast.items {
 				let (name, span, defid) = match *item {
+					// Extern declarations are matched against the definitions
+					// once all of them are known.
+					ast::Item::ExternModule(ast::ModDecl { name, name_span, .. }) |
+					ast::Item::ExternInterface(ast::IntfDecl { name, name_span, .. }) => {
+						if let Some(ex) = externs.insert(name, item) {
+							self.add_diag(DiagBuilder2::error(format!("extern declaration of `{}` has already been given", name))
+								.span(name_span)
+								.add_note("previous declaration was here")
+								.span(ex.span()));
+						}
+						continue;
+					}
 					ast::Item::Module(ref decl) => {
 						self.mod_map.insert(decl.id, decl);
 						(decl.name, decl.name_span, DefId::Module(decl.id))
@@ -193,6 +349,9 @@ impl<'a> Resolver<'a> {
 						(decl.name, decl.name_span, DefId::Package(decl.id))
 					},
 					ast::Item::Class(ref decl) => (decl.name.name, decl.name.span, DefId::Class(decl.name.id)),
+					ast::Item::Primitive(ref decl) => (decl.name.name, decl.name.span, DefId::Primitive(decl.name.id)),
+					ast::Item::Checker(ref decl) => (decl.name.name, decl.name.span, DefId::Checker(decl.name.id)),
+					ast::Item::Bind(_) => continue,
 					ast::Item::Item(ref item) => match self.register_global_item(item) {
 						Some(x) => x,
 						None => continue
@@ -214,17 +373,96 @@ impl<'a> Resolver<'a> {
 			}
 		}
 
+		// Match the extern declarations against their definitions.
+		for (name, item) in externs {
+			match (item, tbl.get(&name).map(|d| d.id)) {
+				(&ast::Item::ExternModule(ref proto), Some(DefId::Module(id))) => {
+					let decl = self.mod_map[&id];
+					self.mod_protos.insert(id, proto);
+					if !decl.wildcard_ports {
+						self.check_extern_header("module", name, (&proto.params, &proto.ports, proto.name_span), (&decl.params, &decl.ports, decl.name_span));
+					}
+				}
+				(&ast::Item::ExternInterface(ref proto), Some(DefId::Interface(id))) => {
+					let decl = self.intf_map[&id];
+					self.intf_protos.insert(id, proto);
+					if !decl.wildcard_ports {
+						self.check_extern_header("interface", name, (&proto.params, &proto.ports, proto.name_span), (&decl.params, &decl.ports, decl.name_span));
+					}
+				}
+				// An extern declaration without a definition is fine as long
+				// as it is never instantiated.
+				(_, None) => (),
+				(_, Some(_)) => {
+					let def = tbl[&name];
+					self.add_diag(DiagBuilder2::error(format!("Definition of `{}` does not match its {}", name, item.as_str()))
+						.span(def.span)
+						.add_note("extern declaration was here")
+						.span(item.span()));
+				}
+			}
+		}
+
 		// Move the declarations we found into the global scope.
-		self.scopes.push(Scope::Global(tbl));
+		let mut global = Scope::new(ScopeKind::Global, None);
+		global.defs = tbl;
+		self.scopes.push(global);
+	}
+
+	/// Make sure the parameters and ports of a module or interface definition
+	/// agree with the ones given in its extern declaration.
+	/// IEEE 1800-2009 23.2.1
+	fn check_extern_header(&mut self, what: &str, name: Name, proto: (&[ast::ParamDecl], &[ast::Port], Span), decl: (&[ast::ParamDecl], &[ast::Port], Span)) {
+		let mismatch = if param_names(proto.0) != param_names(decl.0) {
+			"parameters"
+		} else if port_signature(proto.1) != port_signature(decl.1) {
+			"ports"
+		} else {
+			return;
+		};
+		self.add_diag(DiagBuilder2::error(format!("The {} of {} `{}` do not match its extern declaration", mismatch, what, name))
+			.span(decl.2)
+			.add_note("extern declaration was here")
+			.span(proto.2)
+			.add_note("use `(.*)` to reuse the ports of the extern declaration"));
 	}
 
 	fn register_global_item(&mut self, item: &ast::HierarchyItem) -> Option<(Name, Span, DefId)> {
 		match *item {
 			ast::HierarchyItem::Typedef(ref td) => Some((td.name.name, td.name.span, DefId::Typedef(td.name.id))),
+			ast::HierarchyItem::NettypeDecl(ref decl) => Some((decl.name.name, decl.name.span, DefId::Nettype(decl.name.id))),
+			ast::HierarchyItem::DpiDecl(ast::DpiDecl::Import{ref prototype, ..}) => {
+				Some((prototype.name.name, prototype.name.span, DefId::Subroutine(prototype.name.id)))
+			}
 			_ => None
 		}
 	}
 
+	/// Record the classes declared at the top level, and in packages, modules,
+	/// and interfaces, such that base classes and the classes of handles can be
+	/// found by their node ID.
+	fn register_classes(&mut self, item: &'a ast::Item) {
+		let items = match *item {
+			ast::Item::Class(ref decl) => {
+				self.class_map.insert(decl.name.id, decl);
+				return;
+			}
+			ast::Item::Item(ast::HierarchyItem::ClassDecl(ref decl)) => {
+				self.class_map.insert(decl.name.id, decl);
+				return;
+			}
+			ast::Item::Module(ref decl) => &decl.items,
+			ast::Item::Interface(ref decl) => &decl.items,
+			ast::Item::Package(ref decl) => &decl.items,
+			_ => return,
+		};
+		for item in items {
+			if let ast::HierarchyItem::ClassDecl(ref decl) = *item {
+				self.class_map.insert(decl.name.id, decl);
+			}
+		}
+	}
+
 	pub fn resolve_ast(&mut self, ast: &'a ast::Root) {
 		for item in &ast.items {
 			self.resolve_item(item);
@@ -233,224 +471,1004 @@ impl<'a> Resolver<'a> {
 
 	pub fn resolve_item(&mut self, item: &'a ast::Item) {
 		match *item {
-			ast::Item::Module(ref decl) => {
-				self.scopes.push(Scope::Module(decl));
-				self.scopes.push(Scope::new_local());
-				self.resolve_param_ports(&decl.params);
-				self.resolve_ports(&decl.ports);
-				self.resolve_hierarchy_items(&decl.items);
-				self.scopes.pop().unwrap();
-				self.scopes.pop().unwrap();
-			}
-			ast::Item::Interface(ref decl) => {
-				self.scopes.push(Scope::Interface(decl));
-				self.scopes.push(Scope::new_local());
-				self.resolve_param_ports(&decl.params);
-				self.resolve_ports(&decl.ports);
-				self.resolve_hierarchy_items(&decl.items);
-				self.scopes.pop().unwrap();
-				self.scopes.pop().unwrap();
-			}
-			ast::Item::Package(ref decl) => self.resolve_hierarchy_items(&decl.items),
+			ast::Item::Module(ref decl) => self.resolve_module_decl(decl),
+			ast::Item::Interface(ref decl) => self.resolve_interface_decl(decl),
+			ast::Item::ExternModule(ref decl) => self.with_scope(ScopeKind::Module(decl), None, |r| {
+				r.resolve_param_ports(&decl.params);
+				r.resolve_ports(&decl.ports);
+			}),
+			ast::Item::ExternInterface(ref decl) => self.with_scope(ScopeKind::Interface(decl), None, |r| {
+				r.resolve_param_ports(&decl.params);
+				r.resolve_ports(&decl.ports);
+			}),
+			ast::Item::Package(ref decl) => {
+				for item in &decl.items {
+					match *item {
+						ast::HierarchyItem::ModuleDecl(_) |
+						ast::HierarchyItem::InterfaceDecl(_) => {
+							self.add_diag(DiagBuilder2::error(format!("{} cannot appear in a package", item.as_str())).span(item.span()));
+						}
+						_ => (),
+					}
+				}
+				self.with_scope(ScopeKind::Package(decl), Some(decl.name), |r| r.resolve_hierarchy_items(&decl.items));
+			}
 			ast::Item::Item(ref item) => self.resolve_hierarchy_item(item),
 			ast::Item::Class(ref decl) => self.resolve_class_decl(decl),
+			// Primitives only consist of their ports and a table of symbols.
+			ast::Item::Primitive(_) => (),
+			ast::Item::Checker(ref decl) => self.with_scope(ScopeKind::Checker(decl), Some(decl.name.name), |r| {
+				r.resolve_assertion_ports(&decl.ports, true);
+				r.resolve_hierarchy_items(&decl.items);
+				r.check_checker_items(&decl.items);
+			}),
+			ast::Item::Bind(ref bind) => self.resolve_bind_directive(bind),
 		}
 	}
 
-	pub fn resolve_class_decl(&mut self, node: &ast::ClassDecl) {
-		self.resolve_param_ports(&node.params);
-		if let Some((ref ty, ref args)) = node.extends {
-			self.resolve_type(ty);
-			self.resolve_call_args(args);
+	/// Resolve a module declaration. A module with a `(.*)` port list sees the
+	/// parameters and ports of its extern declaration.
+	pub fn resolve_module_decl(&mut self, decl: &'a ast::ModDecl) {
+		let resolve_body = |r: &mut Self| r.with_scope(ScopeKind::Module(decl), Some(decl.name), |r| {
+			r.resolve_param_ports(&decl.params);
+			r.resolve_ports(&decl.ports);
+			r.resolve_hierarchy_items(&decl.items);
+		});
+		if !decl.wildcard_ports {
+			return resolve_body(self);
 		}
-		for i in &node.items {
-			self.resolve_class_item(i);
+		match self.mod_protos.get(&decl.id).map(|&p| p) {
+			Some(proto) => self.with_scope(ScopeKind::Module(proto), None, resolve_body),
+			None => {
+				self.add_diag(DiagBuilder2::error(format!("Module `{}` uses `(.*)` but has no extern declaration", decl.name)).span(decl.name_span));
+				resolve_body(self);
+			}
 		}
 	}
 
-	pub fn resolve_class_item(&mut self, node: &ast::ClassItem) {
-		match node.data {
-			ast::ClassItemData::Null => (),
-
-			// Unimplemented as of now
-			ast::ClassItemData::Property |
-			ast::ClassItemData::SubroutineDecl(_) |
-			ast::ClassItemData::ExternSubroutine(_) |
-			ast::ClassItemData::Constraint(_) |
-			ast::ClassItemData::ClassDecl |
-			ast::ClassItemData::CovergroupDecl |
-			ast::ClassItemData::LocalparamDecl(()) |
-			ast::ClassItemData::ParameterDecl(()) => unimplemented!(),
+	/// Resolve an interface declaration. An interface with a `(.*)` port list
+	/// sees the parameters and ports of its extern declaration.
+	pub fn resolve_interface_decl(&mut self, decl: &'a ast::IntfDecl) {
+		let resolve_body = |r: &mut Self| r.with_scope(ScopeKind::Interface(decl), Some(decl.name), |r| {
+			r.resolve_param_ports(&decl.params);
+			r.resolve_ports(&decl.ports);
+			r.resolve_hierarchy_items(&decl.items);
+		});
+		if !decl.wildcard_ports {
+			return resolve_body(self);
+		}
+		match self.intf_protos.get(&decl.id).map(|&p| p) {
+			Some(proto) => self.with_scope(ScopeKind::Interface(proto), None, resolve_body),
+			None => {
+				self.add_diag(DiagBuilder2::error(format!("Interface `{}` uses `(.*)` but has no extern declaration", decl.name)).span(decl.name_span));
+				resolve_body(self);
+			}
 		}
 	}
 
-	pub fn resolve_param_ports(&mut self, params: &[ast::ParamDecl]) {
-		for param in params {
-			self.resolve_param_port(param);
+	/// Make sure a checker only contains items that are allowed in checkers.
+	/// Must be called after the items have been resolved.
+	fn check_checker_items(&mut self, items: &[ast::HierarchyItem]) {
+		for item in items {
+			match *item {
+				ast::HierarchyItem::NetDecl(_) |
+				ast::HierarchyItem::PortDecl(_) |
+				ast::HierarchyItem::ParamDecl(_) |
+				ast::HierarchyItem::ModportDecl(_) |
+				ast::HierarchyItem::ClassDecl(_) |
+				ast::HierarchyItem::GateInst(_) |
+				ast::HierarchyItem::SpecparamDecl(_) |
+				ast::HierarchyItem::SpecifyBlock(_) |
+				ast::HierarchyItem::Alias(_) |
+				ast::HierarchyItem::ModuleDecl(_) |
+				ast::HierarchyItem::InterfaceDecl(_) => {
+					self.add_diag(DiagBuilder2::error(format!("{} cannot appear in a checker", item.as_str())).span(item.span()));
+				}
+				ast::HierarchyItem::SubroutineDecl(ref decl) if decl.prototype.kind == ast::SubroutineKind::Task => {
+					self.add_diag(DiagBuilder2::error("Tasks cannot be declared in a checker").span(decl.prototype.name.span));
+				}
+				ast::HierarchyItem::Procedure(ref prc) if prc.kind == ast::ProcedureKind::Always => {
+					self.add_diag(DiagBuilder2::error("`always` procedures cannot appear in a checker")
+						.span(prc.span)
+						.add_note("use `always_comb`, `always_latch`, or `always_ff` instead"));
+				}
+				ast::HierarchyItem::Inst(ref inst) => match self.defs.get(&inst.target.id) {
+					Some(&DefId::Checker(_)) | None => (),
+					Some(_) => self.add_diag(DiagBuilder2::error(format!("Checkers can only instantiate other checkers, but `{}` is not a checker", inst.target.name)).span(inst.target.span)),
+				},
+				ast::HierarchyItem::GenerateRegion(_, ref items) => self.check_checker_items(items),
+				ast::HierarchyItem::GenerateFor(ref gf) => self.check_checker_items(&gf.block.items),
+				ast::HierarchyItem::GenerateIf(ref gi) => {
+					self.check_checker_items(&gi.main_block.items);
+					if let Some(ref b) = gi.else_block {
+						self.check_checker_items(&b.items);
+					}
+				}
+				ast::HierarchyItem::GenerateCase(ref gc) => for item in &gc.items {
+					self.check_checker_items(&item.block.items);
+				},
+				_ => (),
+			}
 		}
 	}
 
-	pub fn resolve_param_port(&mut self, param: &ast::ParamDecl) {
-		// TODO: Maybe register the parameter here in a local scope, such that
-		// other parameters in the parameter port list can refer to it?
-		self.resolve_param_decl(param);
-		// self.resolve_dims(&param.dims);
-		// if let Some(ref e) = param.expr {
-		// 	self.resolve_expr(e);
-		// }
+	/// Resolve a bind directive. The bound instantiation is resolved in the
+	/// scope of the target module or interface, since its port connections
+	/// refer to signals therein.
+	pub fn resolve_bind_directive(&mut self, bind: &'a ast::BindDirective) {
+		let first = &bind.target[0];
+		let mut scope = match self.resolve_ident(first) {
+			Some(Def { id: DefId::Module(id), .. }) => ScopeKind::Module(self.mod_map[&id]),
+			Some(Def { id: DefId::Interface(id), .. }) => ScopeKind::Interface(self.intf_map[&id]),
+			Some(_) => {
+				self.add_diag(DiagBuilder2::error(format!("`{}` is not a module or interface", first.name)).span(first.span));
+				return;
+			}
+			None => return,
+		};
+
+		// Follow the hierarchical name of a target instance down to the module
+		// or interface that is instantiated.
+		let mut path = format!("{}", first.name);
+		for name in &bind.target[1..] {
+			let items = match scope {
+				ScopeKind::Module(decl) => &decl.items,
+				ScopeKind::Interface(decl) => &decl.items,
+				_ => unreachable!(),
+			};
+			let (inst, inst_name) = match find_inst(items, name.name) {
+				Some(x) => x,
+				None => {
+					self.add_diag(DiagBuilder2::error(format!("`{}` is not an instance in `{}`", name.name, path)).span(name.span));
+					return;
+				}
+			};
+			let def = match self.scopes[0].find_def(inst.target.name) {
+				Some(x) => x,
+				None => return,
+			};
+			scope = match def.id {
+				DefId::Module(id) => ScopeKind::Module(self.mod_map[&id]),
+				DefId::Interface(id) => ScopeKind::Interface(self.intf_map[&id]),
+				_ => {
+					self.add_diag(DiagBuilder2::error(format!("`{}` is not an instance of a module or interface", name.name)).span(name.span));
+					return;
+				}
+			};
+			self.bind(name, Def { span: inst_name.name.span, id: DefId::Inst(inst_name.name.id) });
+			path.push('.');
+			path.push_str(&*name.name.as_str());
+		}
+
+		self.with_scope(scope, None, |r| r.resolve_inst(&bind.inst));
 	}
 
-	pub fn resolve_ports(&mut self, ports: &[ast::Port]) {
-		for port in ports {
-			self.resolve_port(port);
+	/// Resolve a class declaration. The base class and the implemented
+	/// interface classes are resolved in the enclosing scope, the items in the
+	/// scope of the class. Names not declared in the class are searched in its
+	/// base classes.
+	/// IEEE 1800-2009 8
+	pub fn resolve_class_decl(&mut self, node: &'a ast::ClassDecl) {
+		if let Some((ref ty, _)) = node.extends {
+			self.resolve_type(ty);
 		}
+		for ty in &node.implements {
+			self.resolve_type(ty);
+		}
+		self.check_class_bases(node);
+		self.check_class_cycle(node);
+		self.with_scope(ScopeKind::Class(node), Some(node.name.name), |r| {
+			r.resolve_param_ports(&node.params);
+			if let Some((_, ref args)) = node.extends {
+				r.resolve_call_args(args);
+			}
+			for item in &node.items {
+				r.resolve_class_item(node, item);
+			}
+		});
+		self.check_class_methods(node);
 	}
 
-	pub fn resolve_port(&mut self, port: &ast::Port) {
-		match *port {
-			ast::Port::Intf { ref dims, ref expr, .. } => {
-				self.resolve_dims(dims);
-				if let Some(ref expr) = *expr {
-					self.resolve_expr(expr);
+	pub fn resolve_class_item(&mut self, class: &'a ast::ClassDecl, node: &'a ast::ClassItem) {
+		match node.data {
+			ast::ClassItemData::Property { ref ty, ref names } => {
+				if class.intf {
+					self.add_diag(DiagBuilder2::error(format!("Interface class `{}` cannot declare properties", class.name.name)).span(node.span));
+				}
+				self.resolve_type(ty);
+				for name in names {
+					assert_renumbered!(name.span, name.id);
+					self.record_var_class(name.id, ty);
+					self.resolve_dims(&name.dims);
+					if let Some(ref e) = name.init {
+						self.resolve_expr(e);
+					}
 				}
 			}
-
-			ast::Port::Explicit { ref expr, .. } => {
-				if let Some(ref expr) = *expr {
-					self.resolve_expr(expr);
+			ast::ClassItemData::SubroutineDecl(ref decl) => {
+				if class.intf {
+					self.add_diag(DiagBuilder2::error(format!("Methods of interface class `{}` must be pure virtual", class.name.name)).span(decl.prototype.name.span));
 				}
+				let statik = has_qualifier(&node.qualifiers, ast::ClassItemQualifier::Static);
+				let outer = std::mem::replace(&mut self.in_static_method, statik);
+				self.resolve_subroutine_decl(decl);
+				self.in_static_method = outer;
 			}
-
-			ast::Port::Named { ref ty, ref dims, ref expr, .. } => {
-				self.resolve_type(ty);
-				self.resolve_dims(dims);
-				if let Some(ref expr) = *expr {
-					self.resolve_expr(expr);
+			ast::ClassItemData::ExternSubroutine(ref proto) => {
+				if let Some(ref ty) = proto.retty {
+					self.resolve_type(ty);
 				}
+				self.with_scope(ScopeKind::Subroutine, None, |r| r.resolve_subroutine_ports(&proto.args, true));
 			}
-
-			ast::Port::Implicit(ref expr) => self.resolve_expr(expr),
+			ast::ClassItemData::CovergroupDecl(ref decl) => self.resolve_covergroup_decl(decl),
+			ast::ClassItemData::Constraint(ref c) => self.resolve_constraint_items(&c.items),
+			ast::ClassItemData::ClassDecl |
+			ast::ClassItemData::LocalparamDecl(()) |
+			ast::ClassItemData::ParameterDecl(()) |
+			ast::ClassItemData::Null => (),
 		}
 	}
 
-	pub fn resolve_type(&mut self, ty: &ast::Type) {
-		match ty.data {
-			ast::NamedType(ref name) => {
-				self.resolve_ident(name);
-				// self.add_diag(DiagBuilder2::error("don't know how to resolve named types").span(ty.span));
+	/// Resolve a constraint defined outside of its class, as in
+	/// `constraint C::c { ... }`. The class must declare a prototype of the
+	/// constraint, and the items are resolved in the scope of the class.
+	/// IEEE 1800-2009 18.5.1
+	pub fn resolve_out_of_class_constraint(&mut self, node: &ast::Constraint) {
+		let ident = match node.class {
+			Some(ref x) => x,
+			None => return,
+		};
+		let class = match self.resolve_ident(ident) {
+			Some(Def { id: DefId::Class(id), .. }) => self.class_map.get(&id).cloned(),
+			Some(_) => {
+				self.add_diag(DiagBuilder2::error(format!("`{}` is not a class", ident.name)).span(ident.span));
+				None
 			}
+			None => None,
+		};
+		let class = match class {
+			Some(c) => c,
+			None => return,
+		};
+		let has_proto = class.items.iter().any(|item| match item.data {
+			ast::ClassItemData::Constraint(ref c) => c.name == node.name && c.kind != ast::ConstraintKind::Decl,
+			_ => false,
+		});
+		if !has_proto {
+			self.add_diag(DiagBuilder2::error(format!("Class `{}` declares no prototype of constraint `{}`", class.name.name, node.name))
+				.span(node.name_span)
+				.add_note("class declared here:")
+				.span(class.name.span));
+		}
+		self.with_scope(ScopeKind::Class(class), None, |r| r.resolve_constraint_items(&node.items));
+	}
 
-			ast::ScopedType{ty: ref super_ty, ref name, member, ..} => {
-				self.resolve_type(super_ty);
-				if member {
-					// Try to resolve the modport. This is very convoluted as of
-					// now.
-					// TODO: Clean this up later.
-					if let ast::NamedType(ref super_name) = super_ty.data {
-						if let Some(&DefId::Interface(id)) = self.defs.get(&super_name.id) {
-							if let Some(def) = search_modport_decl(&self.intf_map.get(&id).expect("interface missing from intf_map").items, name.name) {
-								self.bind(name, def);
-							} else {
-								self.add_diag(DiagBuilder2::error(format!("`{}` is not a modport declared in interface `{}`", name.name, super_name.name)).span(name.span));
-							}
-						} else {
-							self.add_diag(DiagBuilder2::error(format!("`{}` is not an interface; cannot access modport `{}` on non-interface", super_name.name, name.name)).span(super_ty.span));
-						}
-					} else {
-						self.add_diag(DiagBuilder2::error(format!("cannot access modport `{}` on non-interface", name.name)).span(super_ty.span));
+	/// Resolve the inline constraints of a call to `randomize`. Names are
+	/// searched among the members of the randomized object's class first, and
+	/// in the scope of the call otherwise. If the call lists names, as in
+	/// `obj.randomize() with (x) { ... }`, only these are members.
+	/// IEEE 1800-2009 18.7
+	fn resolve_randomize_with(&mut self, call: &ast::Expr, names: &[ast::Identifier], items: &[ast::ConstraintItem]) {
+		let callee = match call.data {
+			ast::CallExpr(ref callee, _) => callee,
+			_ => call,
+		};
+		let class = match callee.data {
+			ast::MemberExpr { ref expr, .. } => self.class_of_expr(expr),
+			_ => None,
+		};
+		let class = match class {
+			Some(c) => c,
+			None => return self.resolve_constraint_items(items),
+		};
+		if names.is_empty() {
+			self.with_scope(ScopeKind::Class(class), None, |r| r.resolve_constraint_items(items));
+			return;
+		}
+		self.with_scope(ScopeKind::Block, None, |r| {
+			for name in names {
+				match r.find_member(class, name.name).and_then(|m| m.def) {
+					Some(def) => {
+						r.bind(name, def);
+						r.define(name.name, name.span, def.id);
 					}
-				} else {
-					self.add_diag(DiagBuilder2::error("don't know how to resolve namespaced types").span(ty.span));
+					None => r.add_diag(DiagBuilder2::error(format!("Class `{}` has no member `{}`", class.name.name, name.name)).span(name.span)),
 				}
 			}
+			r.resolve_constraint_items(items);
+		});
+	}
 
-			ast::EnumType(ref inner, ref names) => {
-				// self.add_diag(DiagBuilder2::note("resolving enum type").span(ty.span));
-				if let Some(ref i) = *inner {
-					self.resolve_type(i);
-				}
-				for name in names {
-					assert_renumbered!(name.name.span, name.name.id);
-					self.define(name.name.name, name.name.span, DefId::Var(name.name.id));
-					if let Some(ref e) = name.range {
-						self.resolve_expr(e);
-					}
-					if let Some(ref e) = name.value {
+	pub fn resolve_constraint_items(&mut self, items: &[ast::ConstraintItem]) {
+		for item in items {
+			self.resolve_constraint_item(item);
+		}
+	}
+
+	pub fn resolve_constraint_item(&mut self, item: &ast::ConstraintItem) {
+		match item.data {
+			ast::ConstraintItemData::Expr{ref expr, ..} |
+			ast::ConstraintItemData::DisableSoft(ref expr) => self.resolve_expr(expr),
+			ast::ConstraintItemData::Dist{ref expr, ref items, ..} => {
+				self.resolve_expr(expr);
+				for item in items {
+					self.resolve_expr(&item.value);
+					if let Some((_, ref e)) = item.weight {
 						self.resolve_expr(e);
 					}
 				}
 			}
-
-			ast::StructType{ref members, ..} => for member in members {
-				self.resolve_type(&member.ty);
-				for name in &member.names {
-					self.resolve_dims(&name.dims);
-					if let Some(ref i) = name.init {
-						self.resolve_expr(i);
+			ast::ConstraintItemData::Unique(ref exprs) => for expr in exprs {
+				self.resolve_expr(expr);
+			},
+			ast::ConstraintItemData::Implication{ref cond, ref items} => {
+				self.resolve_expr(cond);
+				self.resolve_constraint_items(items);
+			}
+			ast::ConstraintItemData::If{ref cond, ref main_items, ref else_items} => {
+				self.resolve_expr(cond);
+				self.resolve_constraint_items(main_items);
+				if let Some(ref items) = *else_items {
+					self.resolve_constraint_items(items);
+				}
+			}
+			ast::ConstraintItemData::Foreach{ref expr, ref vars, ref items} => {
+				self.with_scope(ScopeKind::Block, None, |r| {
+					r.resolve_expr(expr);
+					for var in vars {
+						if let Some(ref ident) = *var {
+							r.define(ident.name, ident.span, DefId::Var(ident.id));
+						}
 					}
+					r.resolve_constraint_items(items);
+				});
+			}
+			ast::ConstraintItemData::SolveBefore(ref first, ref then) => {
+				for expr in first.iter().chain(then.iter()) {
+					self.resolve_expr(expr);
 				}
-			},
-
-			ast::SpecializedType(ref ty, ref params) => {
-				self.resolve_type(ty);
-				// TODO: Resolve parameter assignments.
 			}
+		}
+	}
 
-			// Trivial cases.
-			ast::ImplicitType |
-			ast::VoidType |
-			ast::StringType |
-			ast::ChandleType |
-			ast::VirtIntfType(_) |
-			ast::EventType |
-			ast::MailboxType |
-			ast::BitType |
-			ast::LogicType |
-			ast::RegType |
-			ast::ByteType |
-			ast::ShortIntType |
-			ast::IntType |
-			ast::LongIntType |
-			ast::TimeType |
-			ast::ShortRealType |
-			ast::RealType |
-			ast::RealtimeType => ()
+	/// Check that a class extends a class and implements interface classes,
+	/// and that an interface class only extends interface classes.
+	fn check_class_bases(&mut self, node: &'a ast::ClassDecl) {
+		if let Some((ref ty, _)) = node.extends {
+			match self.class_of_type(ty) {
+				Some(base) if base.intf => {
+					self.add_diag(DiagBuilder2::error(format!("Class `{}` cannot extend interface class `{}`", node.name.name, base.name.name))
+						.span(ty.span)
+						.add_note("use `implements` to implement an interface class"));
+				}
+				Some(_) => (),
+				None if !self.is_resolved_type(ty) => (),
+				None => self.add_diag(DiagBuilder2::error(format!("`{}` is not a class", ty.span.extract())).span(ty.span)),
+			}
+		}
+		for ty in &node.implements {
+			match self.class_of_type(ty) {
+				Some(base) if base.intf => (),
+				None if !self.is_resolved_type(ty) => (),
+				_ => self.add_diag(DiagBuilder2::error(format!("`{}` is not an interface class", ty.span.extract())).span(ty.span)),
+			}
 		}
-		self.resolve_dims(&ty.dims);
 	}
 
-	pub fn resolve_dims(&mut self, dims: &[ast::TypeDim]) {
-		for dim in dims {
-			self.resolve_dim(dim);
+	/// Check that a class does not inherit from itself, either directly or
+	/// through its base classes and implemented interface classes. A cycle is
+	/// only reported once, at the first of its classes that closes it.
+	/// IEEE 1800-2009 8.13
+	fn check_class_cycle(&mut self, node: &'a ast::ClassDecl) {
+		if self.cyclic_classes.contains(&node.name.id) {
+			return;
+		}
+		let mut path = vec![node];
+		if !self.find_class_cycle(node, &mut path, &mut HashSet::new()) {
+			return;
 		}
+		let mut diag = DiagBuilder2::error(format!("Class `{}` inherits from itself", node.name.name))
+			.span(node.name.span);
+		for c in &path[1..] {
+			diag = diag.add_note(format!("through class `{}`:", c.name.name)).span(c.name.span);
+		}
+		self.add_diag(diag);
+		self.cyclic_classes.extend(path.iter().map(|c| c.name.id));
 	}
 
-	pub fn resolve_dim(&mut self, dim: &ast::TypeDim) {
-		match *dim {
-			ast::TypeDim::Expr(ref e) => self.resolve_expr(e),
-			ast::TypeDim::Range(ref a, ref b) => {
-				self.resolve_expr(a);
-				self.resolve_expr(b);
+	/// Search the base classes and interface classes of the last class on
+	/// `path` for `target`. On success, `path` holds the classes through
+	/// which `target` inherits from itself.
+	fn find_class_cycle(&self, target: &'a ast::ClassDecl, path: &mut Vec<&'a ast::ClassDecl>, visited: &mut HashSet<NodeId>) -> bool {
+		let class = *path.last().unwrap();
+		let bases = class.extends.iter().map(|&(ref ty, _)| ty).chain(class.implements.iter());
+		for base in bases.filter_map(|ty| self.class_of_type(ty)) {
+			if base.name.id == target.name.id {
+				return true;
+			}
+			if visited.insert(base.name.id) {
+				path.push(base);
+				if self.find_class_cycle(target, path, visited) {
+					return true;
+				}
+				path.pop();
 			}
-			ast::TypeDim::Queue |
-			ast::TypeDim::Unsized |
-			ast::TypeDim::Associative => unimplemented!(),
 		}
+		false
 	}
 
-	pub fn resolve_hierarchy_items(&mut self, items: &[ast::HierarchyItem]) {
-		for item in items {
-			self.resolve_hierarchy_item(item);
+	/// Check that the methods of a class which override virtual methods of its
+	/// base classes agree with their prototypes, and that a non-virtual class
+	/// implements all pure virtual methods it inherits.
+	/// IEEE 1800-2009 8.19, 8.20
+	fn check_class_methods(&mut self, node: &'a ast::ClassDecl) {
+		let base = self.base_class(node);
+		for item in &node.items {
+			let proto = match item.data {
+				ast::ClassItemData::SubroutineDecl(ref decl) => &decl.prototype,
+				ast::ClassItemData::ExternSubroutine(ref proto) => proto,
+				_ => continue,
+			};
+			if &*proto.name.name.as_str() == "new" {
+				continue;
+			}
+			let overridden = base.and_then(|b| self.find_method(b, proto.name.name));
+			if let Some((class, base_proto, virt)) = overridden {
+				if virt && (base_proto.kind != proto.kind || base_proto.args.len() != proto.args.len()) {
+					self.add_diag(DiagBuilder2::error(format!("Method `{}` does not match the virtual method it overrides", proto.name.name))
+						.span(proto.name.span)
+						.add_note(format!("overridden method in class `{}`:", class.name.name))
+						.span(base_proto.name.span));
+				}
+			}
+		}
+		if node.virt || node.intf {
+			return;
 		}
-	}
 
-	pub fn resolve_hierarchy_item(&mut self, item: &ast::HierarchyItem) {
+		// Collect the methods that must be implemented, i.e. the pure virtual
+		// methods of the base classes and the methods of the implemented
+		// interface classes.
+		let mut required = Vec::new();
+		let mut class = Some(node);
+		let mut depth = 0;
+		while let Some(c) = class {
+			for item in &c.items {
+				if let ast::ClassItemData::ExternSubroutine(ref proto) = item.data {
+					if has_qualifier(&item.qualifiers, ast::ClassItemQualifier::Pure) {
+						required.push((c, proto));
+					}
+				}
+			}
+			self.collect_interface_methods(&c.implements, &mut required, 0);
+			class = self.base_class(c);
+			depth += 1;
+			if depth > MAX_CLASS_DEPTH {
+				break;
+			}
+		}
+		for (c, proto) in required {
+			match self.find_method(node, proto.name.name) {
+				Some((_, _, _)) if self.implements_method(node, proto.name.name) => (),
+				_ => {
+					let what = if c.intf { "interface class" } else { "class" };
+					self.add_diag(DiagBuilder2::error(format!("Class `{}` does not implement method `{}` of {} `{}`", node.name.name, proto.name.name, what, c.name.name))
+						.span(node.name.span)
+						.add_note("method declared here:")
+						.span(proto.name.span));
+				}
+			}
+		}
+	}
+
+	/// Collect the methods of the interface classes in a list, including the
+	/// ones they inherit.
+	fn collect_interface_methods(&self, tys: &[ast::Type], into: &mut Vec<(&'a ast::ClassDecl, &'a ast::SubroutinePrototype)>, depth: usize) {
+		if depth > MAX_CLASS_DEPTH {
+			return;
+		}
+		for ty in tys {
+			if let Some(intf) = self.class_of_type(ty) {
+				for item in &intf.items {
+					if let ast::ClassItemData::ExternSubroutine(ref proto) = item.data {
+						into.push((intf, proto));
+					}
+				}
+				self.collect_interface_methods(&intf.implements, into, depth + 1);
+			}
+		}
+	}
+
+	/// Check whether a class or one of its base classes provides an
+	/// implementation of a method.
+	fn implements_method(&self, class: &'a ast::ClassDecl, name: Name) -> bool {
+		let mut class = Some(class);
+		let mut depth = 0;
+		while let Some(c) = class {
+			let found = c.items.iter().any(|item| match item.data {
+				ast::ClassItemData::SubroutineDecl(ref decl) => decl.prototype.name.name == name,
+				ast::ClassItemData::ExternSubroutine(ref proto) => {
+					proto.name.name == name && !has_qualifier(&item.qualifiers, ast::ClassItemQualifier::Pure)
+				}
+				_ => false,
+			});
+			if found {
+				return true;
+			}
+			class = self.base_class(c);
+			depth += 1;
+			if depth > MAX_CLASS_DEPTH {
+				break;
+			}
+		}
+		false
+	}
+
+	/// Find a method in a class or its base classes. Also yields whether the
+	/// method is virtual, either explicitly or because it overrides a virtual
+	/// method.
+	fn find_method(&self, class: &'a ast::ClassDecl, name: Name) -> Option<(&'a ast::ClassDecl, &'a ast::SubroutinePrototype, bool)> {
+		let mut class = Some(class);
+		let mut found = None;
+		let mut depth = 0;
+		while let Some(c) = class {
+			for item in &c.items {
+				let proto = match item.data {
+					ast::ClassItemData::SubroutineDecl(ref decl) => &decl.prototype,
+					ast::ClassItemData::ExternSubroutine(ref proto) => proto,
+					_ => continue,
+				};
+				if proto.name.name == name {
+					let virt = c.intf ||
+						has_qualifier(&item.qualifiers, ast::ClassItemQualifier::Virtual) ||
+						has_qualifier(&item.qualifiers, ast::ClassItemQualifier::Pure);
+					match found {
+						None => found = Some((c, proto, virt)),
+						Some((fc, fp, false)) if virt => found = Some((fc, fp, true)),
+						_ => (),
+					}
+				}
+			}
+			class = self.base_class(c);
+			depth += 1;
+			if depth > MAX_CLASS_DEPTH {
+				break;
+			}
+		}
+		found
+	}
+
+	/// Find the class a type refers to, as in `C`, `pkg::C`, or `C #(8)`.
+	fn class_of_type(&self, ty: &ast::Type) -> Option<&'a ast::ClassDecl> {
+		let ident = match ty.data {
+			ast::NamedType(ref ident) |
+			ast::ScopedType { name: ref ident, member: false, .. } => ident,
+			ast::SpecializedType(ref ty, _) => return self.class_of_type(ty),
+			_ => return None,
+		};
+		match self.defs.get(&ident.id) {
+			Some(&DefId::Class(id)) => self.class_map.get(&id).cloned(),
+			_ => None,
+		}
+	}
+
+	/// Check whether the name of a named type has been resolved to a
+	/// definition. Errors for names that could not be resolved have already
+	/// been reported.
+	fn is_resolved_type(&self, ty: &ast::Type) -> bool {
+		match ty.data {
+			ast::NamedType(ref ident) |
+			ast::ScopedType { name: ref ident, .. } => self.defs.contains_key(&ident.id),
+			ast::SpecializedType(ref ty, _) => self.is_resolved_type(ty),
+			_ => true,
+		}
+	}
+
+	/// Find the base class of a class.
+	fn base_class(&self, class: &'a ast::ClassDecl) -> Option<&'a ast::ClassDecl> {
+		class.extends.as_ref().and_then(|&(ref ty, _)| self.class_of_type(ty))
+	}
+
+	/// Remember the class of a variable declared with a class type, such that
+	/// the members accessed through it can be resolved.
+	fn record_var_class(&mut self, id: NodeId, ty: &ast::Type) {
+		if let Some(class) = self.class_of_type(ty) {
+			self.var_classes.insert(id, class.name.id);
+		}
+	}
+
+	/// The innermost class the names being resolved are in.
+	fn current_class(&self) -> Option<&'a ast::ClassDecl> {
+		self.scopes.iter().rev().filter_map(|scope| match scope.kind {
+			ScopeKind::Class(decl) => Some(decl),
+			_ => None,
+		}).next()
+	}
+
+	/// Check whether a class is the same as or derived from another one.
+	fn derives_from(&self, class: &'a ast::ClassDecl, base: &'a ast::ClassDecl) -> bool {
+		let mut class = Some(class);
+		let mut depth = 0;
+		while let Some(c) = class {
+			if c.name.id == base.name.id {
+				return true;
+			}
+			class = self.base_class(c);
+			depth += 1;
+			if depth > MAX_CLASS_DEPTH {
+				break;
+			}
+		}
+		false
+	}
+
+	/// Find a member of a class or its base classes. Constraints are members
+	/// as well, but have no definition to bind to.
+	fn find_member(&self, class: &'a ast::ClassDecl, name: Name) -> Option<Member<'a>> {
+		let mut class = Some(class);
+		let mut depth = 0;
+		while let Some(c) = class {
+			if let Some(def) = search_param_ports(&c.params, name) {
+				return Some(Member { def: Some(def), class: c, qualifiers: &[] });
+			}
+			if let Some((def, qualifiers)) = search_class_items(&c.items, name) {
+				return Some(Member { def: Some(def), class: c, qualifiers: qualifiers });
+			}
+			for item in &c.items {
+				if let ast::ClassItemData::Constraint(ref cons) = item.data {
+					if cons.name == name {
+						return Some(Member { def: None, class: c, qualifiers: &item.qualifiers });
+					}
+				}
+			}
+			class = self.base_class(c);
+			depth += 1;
+			if depth > MAX_CLASS_DEPTH {
+				break;
+			}
+		}
+		None
+	}
+
+	/// Check whether a member may be accessed from the class the names are
+	/// being resolved in. Local members are only visible within their class,
+	/// protected ones also within derived classes.
+	/// IEEE 1800-2009 8.18
+	fn check_member_visibility(&mut self, member: &Member<'a>, name: Name, span: Span) {
+		let current = self.current_class();
+		let (visible, what) = if has_qualifier(member.qualifiers, ast::ClassItemQualifier::Local) {
+			(current.map(|c| c.name.id == member.class.name.id).unwrap_or(false), "local")
+		} else if has_qualifier(member.qualifiers, ast::ClassItemQualifier::Protected) {
+			(current.map(|c| self.derives_from(c, member.class)).unwrap_or(false), "protected")
+		} else {
+			(true, "")
+		};
+		if !visible {
+			self.add_diag(DiagBuilder2::error(format!("`{}` is a {} member of class `{}`", name, what, member.class.name.name))
+				.span(span)
+				.add_note("declared here:")
+				.span(member.def.map(|d| d.span).unwrap_or(member.class.name.span)));
+		}
+	}
+
+	/// Resolve the name of a member accessed through a class handle or the
+	/// class scope operator. Members accessed as `C::x` from outside of `C`
+	/// and its derived classes must be static.
+	fn resolve_class_member(&mut self, class: &'a ast::ClassDecl, name: &ast::Identifier, scoped: bool) {
+		let member = match self.find_member(class, name.name) {
+			Some(m) => m,
+			None => {
+				if !is_builtin_class_method(name.name) {
+					self.add_diag(DiagBuilder2::error(format!("Class `{}` has no member `{}`", class.name.name, name.name)).span(name.span));
+				}
+				return;
+			}
+		};
+		self.check_member_visibility(&member, name.name, name.span);
+		if let (true, Some(Def { id: DefId::Var(_), .. })) = (scoped, member.def) {
+			let inside = self.current_class().map(|c| self.derives_from(c, member.class)).unwrap_or(false);
+			if !inside && !has_qualifier(member.qualifiers, ast::ClassItemQualifier::Static) {
+				self.add_diag(DiagBuilder2::error(format!("`{}` is not a static member of class `{}`", name.name, member.class.name.name))
+					.span(name.span));
+			}
+		}
+		if let Some(def) = member.def {
+			self.bind(name, def);
+		}
+	}
+
+	/// Find the class of the object an expression refers to, e.g. of a class
+	/// handle, `this`, or `super`.
+	fn class_of_expr(&self, expr: &ast::Expr) -> Option<&'a ast::ClassDecl> {
+		let ident = match expr.data {
+			ast::IdentExpr(ref ident) |
+			ast::MemberExpr { name: ref ident, .. } |
+			ast::ScopeExpr(_, ref ident) => ident,
+			ast::IndexExpr { ref indexee, .. } => return self.class_of_expr(indexee),
+			_ => return None,
+		};
+		let id = match self.defs.get(&ident.id) {
+			Some(&DefId::Class(id)) if is_class_handle(ident.name) => id,
+			Some(&DefId::Var(id)) |
+			Some(&DefId::Port(id)) => match self.var_classes.get(&id) {
+				Some(&id) => id,
+				None => return None,
+			},
+			_ => return None,
+		};
+		self.class_map.get(&id).cloned()
+	}
+
+	/// Resolve `this` or `super`, which are bound to the current class and its
+	/// base class, respectively.
+	fn resolve_class_handle(&mut self, ident: &ast::Identifier) {
+		assert_renumbered!(ident.span, ident.id);
+		let class = match self.current_class() {
+			Some(c) => c,
+			None => {
+				self.add_diag(DiagBuilder2::error(format!("`{}` can only be used within a class", ident.name)).span(ident.span));
+				return;
+			}
+		};
+		if self.in_static_method {
+			self.add_diag(DiagBuilder2::error(format!("`{}` cannot be used in a static method", ident.name)).span(ident.span));
+			return;
+		}
+		let target = if &*ident.name.as_str() == "this" {
+			Some(class)
+		} else {
+			self.base_class(class)
+		};
+		match target {
+			Some(target) => self.bind(ident, Def { span: target.name.span, id: DefId::Class(target.name.id) }),
+			None => self.add_diag(DiagBuilder2::error(format!("Class `{}` has no base class", class.name.name)).span(ident.span)),
+		}
+	}
+
+	pub fn resolve_param_ports(&mut self, params: &[ast::ParamDecl]) {
+		for param in params {
+			self.resolve_param_port(param);
+		}
+	}
+
+	pub fn resolve_param_port(&mut self, param: &ast::ParamDecl) {
+		// TODO: Maybe register the parameter here in a local scope, such that
+		// other parameters in the parameter port list can refer to it?
+		self.resolve_param_decl(param);
+		// self.resolve_dims(&param.dims);
+		// if let Some(ref e) = param.expr {
+		// 	self.resolve_expr(e);
+		// }
+	}
+
+	pub fn resolve_ports(&mut self, ports: &[ast::Port]) {
+		for port in ports {
+			self.resolve_port(port);
+		}
+	}
+
+	pub fn resolve_port(&mut self, port: &ast::Port) {
+		match *port {
+			ast::Port::Intf { ref dims, ref expr, .. } => {
+				self.resolve_dims(dims);
+				if let Some(ref expr) = *expr {
+					self.resolve_expr(expr);
+				}
+			}
+
+			ast::Port::Explicit { ref expr, .. } => {
+				if let Some(ref expr) = *expr {
+					self.resolve_expr(expr);
+				}
+			}
+
+			ast::Port::Named { ref ty, ref dims, ref expr, .. } => {
+				self.resolve_type(ty);
+				self.resolve_dims(dims);
+				if let Some(ref expr) = *expr {
+					self.resolve_expr(expr);
+				}
+			}
+
+			ast::Port::Implicit(ref expr) => self.resolve_expr(expr),
+		}
+	}
+
+	pub fn resolve_type(&mut self, ty: &ast::Type) {
+		match ty.data {
+			ast::NamedType(ref name) => {
+				self.resolve_ident(name);
+				// self.add_diag(DiagBuilder2::error("don't know how to resolve named types").span(ty.span));
+			}
+
+			ast::ScopedType{ty: ref super_ty, ref name, member, ..} => {
+				self.resolve_type(super_ty);
+				if member {
+					// Try to resolve the modport. This is very convoluted as of
+					// now.
+					// TODO: Clean this up later.
+					if let ast::NamedType(ref super_name) = super_ty.data {
+						if let Some(&DefId::Interface(id)) = self.defs.get(&super_name.id) {
+							if let Some(def) = search_modport_decl(&self.intf_map.get(&id).expect("interface missing from intf_map").items, name.name) {
+								self.bind(name, def);
+							} else {
+								self.add_diag(DiagBuilder2::error(format!("`{}` is not a modport declared in interface `{}`", name.name, super_name.name)).span(name.span));
+							}
+						} else {
+							self.add_diag(DiagBuilder2::error(format!("`{}` is not an interface; cannot access modport `{}` on non-interface", super_name.name, name.name)).span(super_ty.span));
+						}
+					} else {
+						self.add_diag(DiagBuilder2::error(format!("cannot access modport `{}` on non-interface", name.name)).span(super_ty.span));
+					}
+				} else if let ast::NamedType(ref super_name) = super_ty.data {
+					// Resolve the type within the package, as in `pkg::t`.
+					self.resolve_scoped_name(super_name, name);
+				} else {
+					self.add_diag(DiagBuilder2::error("don't know how to resolve namespaced types").span(ty.span));
+				}
+			}
+
+			ast::EnumType(ref inner, ref names) => {
+				// self.add_diag(DiagBuilder2::note("resolving enum type").span(ty.span));
+				if let Some(ref i) = *inner {
+					self.resolve_type(i);
+				}
+				for name in names {
+					assert_renumbered!(name.name.span, name.name.id);
+					self.define(name.name.name, name.name.span, DefId::Var(name.name.id));
+					if let Some(ref e) = name.range {
+						self.resolve_expr(e);
+					}
+					if let Some(ref e) = name.value {
+						self.resolve_expr(e);
+					}
+				}
+			}
+
+			ast::StructType{ref members, ..} => for member in members {
+				self.resolve_type(&member.ty);
+				for name in &member.names {
+					self.resolve_dims(&name.dims);
+					if let Some(ref i) = name.init {
+						self.resolve_expr(i);
+					}
+				}
+			},
+
+			ast::SpecializedType(ref ty, ref params) => {
+				self.resolve_type(ty);
+				for param in params {
+					self.resolve_param_assignment(param);
+				}
+			}
+
+			// Trivial cases.
+			ast::ImplicitType |
+			ast::VoidType |
+			ast::StringType |
+			ast::ChandleType |
+			ast::VirtIntfType(_) |
+			ast::EventType |
+			ast::MailboxType |
+			ast::BitType |
+			ast::LogicType |
+			ast::RegType |
+			ast::ByteType |
+			ast::ShortIntType |
+			ast::IntType |
+			ast::LongIntType |
+			ast::TimeType |
+			ast::ShortRealType |
+			ast::RealType |
+			ast::RealtimeType => ()
+		}
+		self.resolve_dims(&ty.dims);
+	}
+
+	pub fn resolve_dims(&mut self, dims: &[ast::TypeDim]) {
+		for dim in dims {
+			self.resolve_dim(dim);
+		}
+	}
+
+	pub fn resolve_dim(&mut self, dim: &ast::TypeDim) {
+		match *dim {
+			ast::TypeDim::Expr(ref e) => self.resolve_expr(e),
+			ast::TypeDim::Range(ref a, ref b) => {
+				self.resolve_expr(a);
+				self.resolve_expr(b);
+			}
+			ast::TypeDim::Queue |
+			ast::TypeDim::Unsized |
+			ast::TypeDim::Associative => unimplemented!(),
+		}
+	}
+
+	pub fn resolve_hierarchy_items(&mut self, items: &'a [ast::HierarchyItem]) {
+		// Register the clocking blocks up front, such that clockvars can be
+		// resolved regardless of declaration order.
+		let mut default_clocking: Option<Span> = None;
+		for item in items {
+			let span = match *item {
+				ast::HierarchyItem::ClockingDecl(ref decl) => {
+					if let Some(ref name) = decl.name {
+						self.clocking_map.insert(name.id, decl);
+					}
+					if decl.kind != ast::ClockingKind::Default {
+						continue;
+					}
+					decl.span
+				}
+				ast::HierarchyItem::DefaultClocking(sp, _) => sp,
+				_ => continue,
+			};
+			if let Some(prev) = default_clocking {
+				self.add_diag(DiagBuilder2::error("Only one default clocking may be specified per module or interface").span(span).add_note("previous default clocking was here:").span(prev));
+			}
+			default_clocking = Some(span);
+		}
+
+		self.declare_implicit_nets(items);
+		for item in items {
+			self.resolve_hierarchy_item(item);
+		}
+	}
+
+	/// Declare the nets implied by undeclared identifiers that are connected
+	/// to a port or assigned by a continuous assignment. These are scalar nets
+	/// of the default net type, unless implicit nets have been disabled with
+	/// "`default_nettype none".
+	/// IEEE 1800-2009 6.10
+	fn declare_implicit_nets(&mut self, items: &[ast::HierarchyItem]) {
+		let directive = match self.default_nettype() {
+			Some(d) => d,
+			None => return,
+		};
+		for ident in items.iter().flat_map(implicit_net_idents) {
+			if self.lookup(ident.name, None).is_some() {
+				continue;
+			}
+			match directive.net_type {
+				Some(net_type) => {
+					assert_renumbered!(ident.span, ident.id);
+					self.implicit_nets.insert(ident.id, net_type);
+					self.define(ident.name, ident.span, DefId::Var(ident.id));
+				}
+				None => {
+					self.add_diag(DiagBuilder2::error(format!("`{}` has not been declared", ident.name))
+						.span(ident.span)
+						.add_note("implicit nets are disabled by this directive:")
+						.span(directive.span));
+					self.define(ident.name, ident.span, DefId::Error);
+				}
+			}
+		}
+	}
+
+	/// The "`default_nettype" directive that applies to the design element
+	/// being resolved, or `None` if the element cannot have implicit nets.
+	fn default_nettype(&self) -> Option<ast::DefaultNettype> {
+		let implicit = |span| ast::DefaultNettype { span: span, net_type: Some(ast::NetType::Wire) };
+		for scope in self.scopes.iter().rev() {
+			return match scope.kind {
+				ScopeKind::Module(decl) => Some(decl.default_nettype.unwrap_or(implicit(decl.span))),
+				ScopeKind::Interface(decl) => Some(decl.default_nettype.unwrap_or(implicit(decl.span))),
+				ScopeKind::Generate(_) | ScopeKind::Block => continue,
+				_ => None,
+			};
+		}
+		None
+	}
+
+	pub fn resolve_hierarchy_item(&mut self, item: &'a ast::HierarchyItem) {
 		match *item {
 			ast::HierarchyItem::Procedure(ref prc) => self.resolve_procedure(prc),
 			ast::HierarchyItem::VarDecl(ref decl) => self.resolve_var_decl(decl, false),
 			ast::HierarchyItem::ParamDecl(ref decl) => self.resolve_param_decl(decl),
 			ast::HierarchyItem::ImportDecl(ref decl) => self.resolve_import_decl(decl),
+			ast::HierarchyItem::ExportDecl(ref decl) => self.resolve_export_decl(decl),
 			ast::HierarchyItem::SubroutineDecl(ref decl) => self.resolve_subroutine_decl(decl),
+			ast::HierarchyItem::DpiDecl(ref decl) => self.resolve_dpi_decl(decl),
 			ast::HierarchyItem::ContAssign(ref assign) => {
 				if let Some(ref delay) = assign.delay {
 					self.resolve_expr(delay);
@@ -463,24 +1481,124 @@ impl<'a> Resolver<'a> {
 					self.resolve_expr(rhs);
 				}
 			}
-			ast::HierarchyItem::Inst(ref node) => {
-				self.resolve_ident(&node.target);
-				for p in &node.params {
-					self.resolve_param_assignment(p);
+			ast::HierarchyItem::Inst(ref node) => self.resolve_inst(node),
+			ast::HierarchyItem::GateInst(ref node) => {
+				if let Some(ref delay) = node.delay {
+					for e in &delay.values {
+						self.resolve_expr(e);
+					}
 				}
 				for n in &node.names {
 					self.resolve_dims(&n.dims);
-					for c in &n.conns {
-						self.resolve_port_conn(c);
+					for e in &n.terminals {
+						self.resolve_expr(e);
+					}
+				}
+			}
+			ast::HierarchyItem::PortDecl(ref decl) => {
+				self.resolve_type(&decl.ty);
+				for name in &decl.names {
+					self.resolve_dims(&name.dims);
+					if let Some(ref e) = name.init {
+						self.resolve_expr(e);
 					}
 				}
 			}
+			ast::HierarchyItem::Typedef(ref td) => {
+				self.resolve_type(&td.ty);
+				self.resolve_dims(&td.dims);
+			}
+			ast::HierarchyItem::NetDecl(ref decl) => self.resolve_net_decl(decl),
+			ast::HierarchyItem::NettypeDecl(ref decl) => self.resolve_nettype_decl(decl),
+			ast::HierarchyItem::Alias(ref alias) => for net in &alias.nets {
+				self.resolve_expr(net);
+			},
+			// Nested modules and interfaces see the names of the enclosing
+			// scope.
+			ast::HierarchyItem::ModuleDecl(ref decl) => {
+				self.mod_map.insert(decl.id, decl);
+				self.resolve_module_decl(decl);
+			}
+			ast::HierarchyItem::InterfaceDecl(ref decl) => {
+				self.intf_map.insert(decl.id, decl);
+				self.resolve_interface_decl(decl);
+			}
+			ast::HierarchyItem::CovergroupDecl(ref decl) => self.resolve_covergroup_decl(decl),
+			ast::HierarchyItem::ClassDecl(ref decl) => self.resolve_class_decl(decl),
+			ast::HierarchyItem::Constraint(ref decl) => self.resolve_out_of_class_constraint(decl),
+			ast::HierarchyItem::GenvarDecl(ref decls) => for decl in decls {
+				assert_renumbered!(decl.span, decl.id);
+				if let Some(ref e) = decl.init {
+					self.resolve_expr(e);
+				}
+			},
+			ast::HierarchyItem::SpecparamDecl(ref decl) => self.resolve_specparam_decl(decl),
+			ast::HierarchyItem::SpecifyBlock(ref blk) => self.resolve_specify_block(blk),
+			ast::HierarchyItem::ClockingDecl(ref decl) => self.resolve_clocking_decl(decl),
+			ast::HierarchyItem::DefaultClocking(_, ref name) => self.resolve_clocking_ident(name),
+			ast::HierarchyItem::Assertion(ref assertion) => self.resolve_assertion(assertion),
+			ast::HierarchyItem::SequenceDecl(ref decl) => self.resolve_sequence_decl(decl),
+			ast::HierarchyItem::PropertyDecl(ref decl) => self.resolve_property_decl(decl),
+			ast::HierarchyItem::ModportDecl(ref decl) => for item in &decl.items {
+				for port in &item.ports {
+					self.resolve_modport_port(port);
+				}
+			},
+			ast::HierarchyItem::GenerateRegion(_, ref items) => self.resolve_hierarchy_items(items),
+			ast::HierarchyItem::GenerateFor(ref gf) => {
+				// The loop header gets its own scope such that genvars declared
+				// inline are only visible within the loop.
+				self.with_scope(ScopeKind::Block, None, |r| {
+					r.resolve_stmt(&gf.init);
+					r.resolve_expr(&gf.cond);
+					r.resolve_expr(&gf.step);
+					r.resolve_generate_block(&gf.block);
+				});
+			}
+			ast::HierarchyItem::GenerateIf(ref gi) => {
+				self.resolve_expr(&gi.cond);
+				self.resolve_generate_block(&gi.main_block);
+				if let Some(ref b) = gi.else_block {
+					self.resolve_generate_block(b);
+				}
+			}
+			ast::HierarchyItem::GenerateCase(ref gc) => {
+				self.resolve_expr(&gc.expr);
+				for item in &gc.items {
+					for e in &item.exprs {
+						self.resolve_expr(e);
+					}
+					self.resolve_generate_block(&item.block);
+				}
+			}
 
 			// TODO: Implement the missing items.
 			_ => ()
 		}
 	}
 
+	pub fn resolve_inst(&mut self, node: &ast::Inst) {
+		self.resolve_ident(&node.target);
+		for p in &node.params {
+			self.resolve_param_assignment(p);
+		}
+		if let Some(ref delay) = node.delay {
+			for e in &delay.values {
+				self.resolve_expr(e);
+			}
+		}
+		for n in &node.names {
+			self.resolve_dims(&n.dims);
+			for c in &n.conns {
+				self.resolve_port_conn(c);
+			}
+		}
+	}
+
+	pub fn resolve_generate_block(&mut self, block: &'a ast::GenerateBlock) {
+		self.with_scope(ScopeKind::Generate(block), block.label, |r| r.resolve_hierarchy_items(&block.items));
+	}
+
 	pub fn resolve_param_assignment(&mut self, node: &ast::ParamAssignment) {
 		match node.expr {
 			ast::TypeOrExpr::Type(ref n) => self.resolve_type(n),
@@ -512,16 +1630,9 @@ impl<'a> Resolver<'a> {
 
 	pub fn resolve_stmt(&mut self, stmt: &ast::Stmt) {
 		match stmt.data {
-			ast::SequentialBlock(ref stmts) => {
-				// TODO: Push a scope for the block onto the stack.
-				self.scopes.push(Scope::new_local());
-				self.resolve_stmts(stmts);
-				self.scopes.pop().unwrap();
-			},
+			ast::SequentialBlock(ref stmts) |
 			ast::ParallelBlock(ref stmts, _) => {
-				self.scopes.push(Scope::new_local());
-				self.resolve_stmts(stmts);
-				self.scopes.pop().unwrap();
+				self.with_scope(ScopeKind::Block, stmt.label, |r| r.resolve_stmts(stmts));
 			}
 			ast::IfStmt{ref cond, ref main_stmt, ref else_stmt, ..} => {
 				self.resolve_expr(cond);
@@ -534,11 +1645,14 @@ impl<'a> Resolver<'a> {
 				self.resolve_expr(lhs);
 				self.resolve_expr(rhs);
 			}
-			ast::NonblockingAssignStmt{ref lhs, ref rhs, ref delay, ref event} => {
+			ast::NonblockingAssignStmt{ref lhs, ref rhs, ref delay, ref event, ref cycle} => {
 				self.resolve_expr(lhs);
 				if let Some(ref dc) = *delay {
 					self.resolve_expr(&dc.expr);
 				}
+				if let Some(ref cd) = *cycle {
+					self.resolve_cycle_delay(cd);
+				}
 				if let Some(ref ec) = *event {
 					// self.resolve_event_control(ec);
 				}
@@ -563,39 +1677,55 @@ impl<'a> Resolver<'a> {
 				}
 			}
 			ast::ForeverStmt(ref stmt) => self.resolve_stmt(stmt),
-			ast::RepeatStmt(ref expr, ref stmt) |
-			ast::WhileStmt(ref expr, ref stmt) |
-			ast::WaitExprStmt(ref expr, ref stmt) => {
-				self.scopes.push(Scope::new_local());
+			ast::WaitExprStmt(ref expr, ref body) => {
 				self.resolve_expr(expr);
-				self.resolve_stmt(stmt);
-				self.scopes.pop().unwrap();
+				self.resolve_stmt(body);
 			}
-			ast::DoStmt(ref stmt, ref expr) => {
-				self.scopes.push(Scope::new_local());
-				self.resolve_stmt(stmt);
-				self.resolve_expr(expr);
-				self.scopes.pop().unwrap();
+			ast::RepeatStmt(ref expr, ref body) |
+			ast::WhileStmt(ref expr, ref body) => {
+				self.with_scope(ScopeKind::Block, stmt.label, |r| {
+					r.resolve_expr(expr);
+					r.resolve_stmt(body);
+				});
 			}
-			ast::ForStmt(ref init, ref cond, ref step, ref stmt) => {
-				self.scopes.push(Scope::new_local());
-				self.resolve_stmt(init);
-				self.resolve_expr(cond);
-				self.resolve_expr(step);
-				self.resolve_stmt(stmt);
-				self.scopes.pop().unwrap();
+			ast::DoStmt(ref body, ref expr) => {
+				self.with_scope(ScopeKind::Block, stmt.label, |r| {
+					r.resolve_stmt(body);
+					r.resolve_expr(expr);
+				});
 			}
-			ast::ForeachStmt(ref expr, ref vars, ref stmt) => {
-				self.scopes.push(Scope::new_local());
-				self.resolve_expr(expr);
-				for var in vars {
-					if let Some(ref ident) = *var {
-						self.define(ident.name, ident.span, DefId::Var(ident.id));
+			// The variables declared in the header of a loop are only visible
+			// within the loop.
+			ast::ForStmt(ref init, ref cond, ref step, ref body) => {
+				self.with_scope(ScopeKind::Block, stmt.label, |r| {
+					r.resolve_stmt(init);
+					r.resolve_expr(cond);
+					r.resolve_expr(step);
+					r.resolve_stmt(body);
+				});
+			}
+			ast::ForeachStmt(ref expr, ref vars, ref body) => {
+				self.with_scope(ScopeKind::Block, stmt.label, |r| {
+					r.resolve_expr(expr);
+					for var in vars {
+						if let Some(ref ident) = *var {
+							r.define(ident.name, ident.span, DefId::Var(ident.id));
+						}
 					}
-				}
-				self.scopes.pop().unwrap();
+					r.resolve_stmt(body);
+				});
 			}
-			ast::ExprStmt(ref expr) => self.resolve_expr(expr),
+			ast::ExprStmt(ref expr) => match expr.data {
+				ast::SysIdentExpr(ref ident) => self.resolve_sys_call(ident, &[], true),
+				ast::CallExpr(ref callee, ref args) => match callee.data {
+					ast::SysIdentExpr(ref ident) => {
+						self.resolve_call_args(args);
+						self.resolve_sys_call(ident, args, true);
+					}
+					_ => self.resolve_expr(expr),
+				},
+				_ => self.resolve_expr(expr),
+			},
 			ast::ReturnStmt(ref expr) => if let Some(ref e) = *expr { self.resolve_expr(e); },
 			ast::ImportStmt(ref decl) => self.resolve_import_decl(decl),
 			ast::AssertionStmt(ref assertion) => self.resolve_assertion(assertion),
@@ -603,6 +1733,13 @@ impl<'a> Resolver<'a> {
 				self.add_diag(DiagBuilder2::error("Don't know how to resolve name of disabled statement").span(stmt.span));
 			}
 			ast::VarDeclStmt(ref decl) => self.resolve_var_decl(decl, true),
+			ast::GenvarDeclStmt(ref decls) => for decl in decls {
+				assert_renumbered!(decl.span, decl.id);
+				if let Some(ref e) = decl.init {
+					self.resolve_expr(e);
+				}
+				self.define(decl.name, decl.name_span, DefId::Genvar(decl.id));
+			},
 
 			// Trivial cases
 			ast::NullStmt |
@@ -610,24 +1747,20 @@ impl<'a> Resolver<'a> {
 			ast::BreakStmt |
 			ast::WaitForkStmt |
 			ast::DisableForkStmt => (),
-
-			// Unimplemented cases
-			ast::GenvarDeclStmt(_) => self.add_diag(DiagBuilder2::error("resolve_stmt not implemented").span(stmt.span)),
 		}
 	}
 
 	pub fn resolve_expr(&mut self, expr: &ast::Expr) {
+		if self.resolve_hier_name(expr) {
+			return;
+		}
 		match expr.data {
+			ast::IdentExpr(ref ident) if is_class_handle(ident.name) => self.resolve_class_handle(ident),
 			ast::IdentExpr(ref ident) => {
 				assert_renumbered!(ident.span, ident.id);
 				self.resolve_ident(ident);
 			}
-			ast::SysIdentExpr(ref ident) => {
-				assert_renumbered!(ident.span, ident.id);
-				// TODO: Resolve the identifier against a table of builtin
-				// system tasks, functions, and names. No need to walk the scope
-				// hierarchy.
-			}
+			ast::SysIdentExpr(ref ident) => self.resolve_sys_call(ident, &[], false),
 			ast::IndexExpr{ref indexee, ref index, ..} => {
 				self.resolve_expr(indexee);
 				self.resolve_expr(index);
@@ -645,12 +1778,25 @@ impl<'a> Resolver<'a> {
 				self.resolve_expr(false_expr);
 			}
 			ast::CallExpr(ref expr, ref args) => {
-				self.resolve_expr(expr);
 				self.resolve_call_args(args);
+				match expr.data {
+					ast::SysIdentExpr(ref ident) => self.resolve_sys_call(ident, args, false),
+					_ => self.resolve_expr(expr),
+				}
+			}
+			ast::RandomizeWithExpr{ref call, ref names, ref items} => {
+				self.resolve_expr(call);
+				self.resolve_randomize_with(call, names, items);
 			}
 			ast::ConstructorCallExpr(ref args) => {
 				self.resolve_call_args(args);
 			}
+			ast::InsideExpr(ref expr, ref ranges) => {
+				self.resolve_expr(expr);
+				for range in ranges {
+					self.resolve_expr(range);
+				}
+			}
 			ast::ClassNewExpr(ref expr) => if let Some(ref e) = *expr { self.resolve_expr(e); },
 			ast::ArrayNewExpr(ref expr, ref other) => {
 				self.resolve_expr(expr);
@@ -684,7 +1830,57 @@ impl<'a> Resolver<'a> {
 				self.resolve_expr(typ);
 				self.resolve_expr(max);
 			}
-			ast::MemberExpr{ref expr, ..} => self.resolve_expr(expr),
+			ast::MemberExpr{ref expr, ref name} => {
+				self.resolve_expr(expr);
+
+				// Members of clocking blocks are clockvars, which we can bind
+				// right away.
+				let clocking = match expr.data {
+					ast::IdentExpr(ref ident) => match self.defs.get(&ident.id) {
+						Some(&DefId::Clocking(id)) => self.clocking_map.get(&id).map(|decl| (*decl, ident.name)),
+						_ => None,
+					},
+					_ => None,
+				};
+				if let Some((decl, cb)) = clocking {
+					match search_clocking_signals(decl, name.name) {
+						Some(def) => self.bind(name, def),
+						None => self.add_diag(DiagBuilder2::error(format!("`{}` is not a signal of clocking block `{}`", name.name, cb)).span(name.span)),
+					}
+				}
+
+				// Sequence instances only provide the `triggered` and
+				// `matched` methods.
+				let seq = match expr.data {
+					ast::IdentExpr(ref ident) => Some(ident),
+					ast::CallExpr(ref callee, _) => match callee.data {
+						ast::IdentExpr(ref ident) => Some(ident),
+						_ => None,
+					},
+					_ => None,
+				};
+				if let Some(seq) = seq {
+					if let Some(&DefId::Sequence(_)) = self.defs.get(&seq.id) {
+						let n = name.name.as_str();
+						if &*n != "triggered" && &*n != "matched" {
+							self.add_diag(DiagBuilder2::error(format!("Sequence `{}` has no method `{}`; only `triggered` and `matched` are available", seq.name, name.name)).span(name.span));
+						}
+					}
+				}
+
+				// Members of class objects are searched in the class and its
+				// base classes.
+				if let Some(class) = self.class_of_expr(expr) {
+					self.resolve_class_member(class, name, false);
+				}
+			}
+			ast::ScopeExpr(ref scope, ref name) => {
+				self.resolve_expr(scope);
+				// Nested scopes such as `pkg::cls::name` are not resolved yet.
+				if let ast::IdentExpr(ref scope) = scope.data {
+					self.resolve_scoped_name(scope, name);
+				}
+			}
 			ast::PatternExpr(ref fields) => for field in fields {
 				match field.data {
 					ast::PatternFieldData::Expr(ref expr) |
@@ -710,56 +1906,276 @@ impl<'a> Resolver<'a> {
 			ast::LiteralExpr(_) |
 			ast::EmptyQueueExpr => (),
 
-			// Unsupported cases
-			ast::DummyExpr => self.add_diag(DiagBuilder2::error("found dummy expression during resolution").span(expr.span)),
-			ast::TypeExpr(_) => self.add_diag(DiagBuilder2::error("found type expression during resolution").span(expr.span)),
+			// Unsupported cases
+			ast::DummyExpr => self.add_diag(DiagBuilder2::error("found dummy expression during resolution").span(expr.span)),
+			// Data types passed to system functions, as in `$bits(int)`.
+			ast::TypeExpr(ref ty) => self.resolve_type(ty),
+		}
+	}
+
+	/// Check whether a member expression is a hierarchical name, i.e. whether
+	/// it starts at a design element, an instance, or a port, or at a name that
+	/// is not visible lexically. Such names are recorded and resolved through
+	/// the instance tree after elaboration, which also reports names that
+	/// cannot be found.
+	/// IEEE 1800-2009 23.6
+	fn resolve_hier_name(&mut self, expr: &ast::Expr) -> bool {
+		let root = match expr.data {
+			ast::MemberExpr{..} => match hier_name_root(expr) {
+				Some(root) => root,
+				None => return false,
+			},
+			_ => return false,
+		};
+		if is_class_handle(root.name) {
+			return false;
+		}
+		// Names within classes refer to class members rather than to the
+		// design hierarchy.
+		let parent = self.scopes.iter().rev().filter_map(|scope| match scope.kind {
+			ScopeKind::Module(decl) => Some(Some(decl.id)),
+			ScopeKind::Interface(decl) => Some(Some(decl.id)),
+			ScopeKind::Checker(decl) => Some(Some(decl.name.id)),
+			ScopeKind::Class(_) => Some(None),
+			_ => None,
+		}).next();
+		let parent = match parent {
+			Some(Some(p)) => p,
+			_ => return false,
+		};
+		match self.lookup(root.name, None).map(|def| def.id) {
+			None |
+			Some(DefId::Module(_)) |
+			Some(DefId::Interface(_)) |
+			Some(DefId::Inst(_)) |
+			Some(DefId::Port(_)) => (),
+			Some(_) => return false,
+		}
+		assert_renumbered!(root.span, root.id);
+		if let Some(def) = self.lookup(root.name, Some(root.span)) {
+			self.bind(root, def);
+		}
+		self.resolve_hier_indices(expr);
+		self.hier_names.push(HierName {
+			parent: parent,
+			expr: expr.clone(),
+		});
+		true
+	}
+
+	/// Resolve the index expressions along a hierarchical name, e.g. the `i`
+	/// in `gen_blk[i].x`.
+	fn resolve_hier_indices(&mut self, expr: &ast::Expr) {
+		match expr.data {
+			ast::MemberExpr{ref expr, ..} => self.resolve_hier_indices(expr),
+			ast::IndexExpr{ref indexee, ref index} => {
+				self.resolve_hier_indices(indexee);
+				self.resolve_expr(index);
+			}
+			_ => (),
+		}
+	}
+
+	pub fn resolve_timing_control(&mut self, tc: &ast::TimingControl) {
+		match *tc {
+			ast::TimingControl::Delay(ref dc) => self.resolve_expr(&dc.expr),
+			ast::TimingControl::Event(ref ec) => self.resolve_event_control(ec),
+			ast::TimingControl::Cycle(ref cd) => self.resolve_cycle_delay(cd),
+		}
+	}
+
+	pub fn resolve_event_control(&mut self, ec: &ast::EventControl) {
+		match ec.data {
+			ast::EventControlData::Implicit => (),
+			ast::EventControlData::Expr(ref expr) => self.resolve_event_expr(expr),
+		}
+	}
+
+	pub fn resolve_cycle_delay(&mut self, cd: &ast::CycleDelay) {
+		self.resolve_expr(&cd.expr);
+
+		// Cycle delays count cycles of the default clocking, so one must be
+		// specified in the enclosing module or interface.
+		let found = self.scopes.iter().any(|scope| match scope.kind {
+			ScopeKind::Module(decl) => has_default_clocking(&decl.items),
+			ScopeKind::Interface(decl) => has_default_clocking(&decl.items),
+			ScopeKind::Generate(block) => has_default_clocking(&block.items),
+			_ => false,
+		});
+		if !found {
+			self.add_diag(DiagBuilder2::error("Cycle delay requires a default clocking").span(cd.span));
+		}
+	}
+
+	pub fn resolve_clocking_decl(&mut self, decl: &ast::ClockingDecl) {
+		self.resolve_event_control(&decl.event);
+		for item in &decl.items {
+			match *item {
+				ast::ClockingItem::DefaultSkew{ref input, ref output, ..} => {
+					for skew in input.iter().chain(output.iter()) {
+						self.resolve_clocking_skew(skew);
+					}
+				}
+				ast::ClockingItem::Signals{ref dir, ref signals, ..} => {
+					match *dir {
+						ast::ClockingDir::Input(ref skew) |
+						ast::ClockingDir::Output(ref skew) => if let Some(ref skew) = *skew {
+							self.resolve_clocking_skew(skew);
+						},
+						ast::ClockingDir::InputOutput(ref a, ref b) => {
+							for skew in a.iter().chain(b.iter()) {
+								self.resolve_clocking_skew(skew);
+							}
+						}
+						ast::ClockingDir::Inout => (),
+					}
+
+					// A clocking signal either refers to the signal of the same
+					// name in the enclosing scope, or to the given expression.
+					for signal in signals {
+						match signal.expr {
+							Some(ref e) => self.resolve_expr(e),
+							None => { self.resolve_ident(&signal.name); }
+						}
+					}
+				}
+			}
 		}
 	}
 
-	pub fn resolve_timing_control(&mut self, tc: &ast::TimingControl) {
-		match *tc {
-			ast::TimingControl::Delay(ref dc) => self.resolve_expr(&dc.expr),
-			ast::TimingControl::Event(ref ec) => self.resolve_event_control(ec),
-			ast::TimingControl::Cycle(ref cd) => self.resolve_cycle_delay(cd),
+	pub fn resolve_clocking_skew(&mut self, skew: &ast::ClockingSkew) {
+		if let Some(ref dc) = skew.delay {
+			self.resolve_expr(&dc.expr);
 		}
 	}
 
-	pub fn resolve_event_control(&mut self, ec: &ast::EventControl) {
-		match ec.data {
-			ast::EventControlData::Implicit => (),
-			ast::EventControlData::Expr(ref expr) => self.resolve_event_expr(expr),
+	/// Resolve an identifier that must refer to a clocking block, as in
+	/// `default clocking cb;` or `modport tb (clocking cb)`.
+	/// Resolve the ports of a modport. Simple ports without an expression and
+	/// subroutine ports refer to items of the enclosing interface.
+	/// IEEE 1800-2009 25.5
+	pub fn resolve_modport_port(&mut self, port: &ast::ModportPort) {
+		match *port {
+			ast::ModportPort::Simple { ref ports, .. } => for port in ports {
+				match port.expr {
+					Some(ref expr) => self.resolve_expr(expr),
+					None => match self.resolve_ident(&port.name) {
+						Some(Def { id: DefId::Var(_), .. }) |
+						Some(Def { id: DefId::Port(_), .. }) |
+						Some(Def { id: DefId::Clockvar(_), .. }) |
+						None => (),
+						Some(_) => self.add_diag(DiagBuilder2::error(format!("`{}` is not a variable or net", port.name.name)).span(port.name.span)),
+					},
+				}
+			},
+			ast::ModportPort::Tf { kind, ref ports, .. } => for port in ports {
+				let name = port.name();
+				// Exported subroutines are defined by the module the interface
+				// is connected to, so they need not exist in the interface.
+				if kind == ast::ModportTfKind::Export && self.lookup(name.name, None).is_none() {
+					continue;
+				}
+				match self.resolve_ident(name) {
+					Some(Def { id: DefId::Subroutine(_), .. }) | None => (),
+					Some(_) => self.add_diag(DiagBuilder2::error(format!("`{}` is not a function or task", name.name)).span(name.span)),
+				}
+			},
+			ast::ModportPort::Clocking(ref name) => self.resolve_clocking_ident(name),
 		}
 	}
 
-	pub fn resolve_cycle_delay(&mut self, cd: &ast::CycleDelay) {
+	pub fn resolve_clocking_ident(&mut self, name: &ast::Identifier) {
+		match self.resolve_ident(name) {
+			Some(Def { id: DefId::Clocking(_), .. }) | None => (),
+			Some(_) => self.add_diag(DiagBuilder2::error(format!("`{}` is not a clocking block", name.name)).span(name.span)),
+		}
 	}
 
+	/// Resolve an import declaration. Explicitly imported names are added to
+	/// the current scope, while wildcard imports only make the names of the
+	/// package visible as a fallback.
+	/// IEEE 1800-2009 26.3
 	pub fn resolve_import_decl(&mut self, decl: &ast::ImportDecl) {
 		for item in &decl.items {
-			let def = match self.resolve_ident(&item.pkg) {
+			let pkg = match self.resolve_package_ident(&item.pkg) {
+				Some(p) => p,
+				None => continue,
+			};
+			let name = match item.name {
+				Some(ref name) => name,
+				None => {
+					let scope = self.scopes.last_mut().unwrap();
+					if !scope.wildcard.iter().any(|p| p.id == pkg.id) {
+						scope.wildcard.push(pkg);
+					}
+					continue;
+				}
+			};
+			let def = match self.find_in_package(pkg, name.name) {
 				Some(d) => d,
+				None => {
+					self.add_diag(DiagBuilder2::error(format!("`{}` is not declared in package `{}`", name.name, pkg.name)).span(name.span));
+					continue;
+				}
+			};
+			self.bind(name, def);
+
+			// An explicit import conflicts with a declaration of the same name
+			// in the importing scope, but importing the same declaration twice
+			// is fine.
+			let scope = self.scopes.last_mut().unwrap();
+			match scope.find_def(name.name) {
+				Some(ex) if ex.id == def.id => (),
+				Some(ex) => self.add_diag(DiagBuilder2::error(format!("Import of `{}` conflicts with a declaration in this scope", name.name))
+					.span(name.span)
+					.add_note("previous declaration was here:")
+					.span(ex.span)),
+				None => {
+					scope.imports.insert(name.name, def);
+				}
+			}
+		}
+	}
+
+	/// Resolve an export declaration. These may only appear in packages.
+	/// IEEE 1800-2009 26.6
+	pub fn resolve_export_decl(&mut self, decl: &ast::ExportDecl) {
+		let in_package = match self.scopes.last().unwrap().kind {
+			ScopeKind::Package(_) => true,
+			_ => false,
+		};
+		if !in_package {
+			self.add_diag(DiagBuilder2::error("Export declarations may only appear in packages").span(decl.span));
+			return;
+		}
+		for item in &decl.items {
+			let pkg = match self.resolve_package_ident(&item.pkg) {
+				Some(p) => p,
 				None => continue,
 			};
 			if let Some(ref name) = item.name {
-				// TODO: We need to be able to resolve names in different
-				// contexts. Here it would be nice to resolve names within the
-				// package body.
-				// TODO: Add the name to the definitions of the current scope.
-				self.add_diag(DiagBuilder2::error("don't know how to handle named imports").span(decl.span));
-			} else {
-				match self.scopes.last_mut() {
-					Some(&mut Scope::Local{ref mut imported, ..}) => {
-						match def.id {
-							DefId::Package(id) => imported.push(Scope::Package(self.pkg_map[&id])),
-							_ => ()
-						}
-					}
-					_ => (),
+				match self.find_in_package(pkg, name.name) {
+					Some(def) => self.bind(name, def),
+					None => self.add_diag(DiagBuilder2::error(format!("`{}` is not declared in package `{}`", name.name, pkg.name)).span(name.span)),
 				}
 			}
 		}
 	}
 
+	/// Resolve the package named in an import or export item.
+	fn resolve_package_ident(&mut self, ident: &ast::Identifier) -> Option<&'a ast::PackageDecl> {
+		match self.find_package(ident.name) {
+			Some(pkg) => {
+				self.bind(ident, Def { span: pkg.name_span, id: DefId::Package(pkg.id) });
+				Some(pkg)
+			}
+			None => {
+				self.add_diag(DiagBuilder2::error(format!("`{}` is not a package", ident.name)).span(ident.span));
+				None
+			}
+		}
+	}
+
 	pub fn resolve_assertion(&mut self, assertion: &ast::Assertion) {
 		// TODO: Do something with the assertion label.
 		match assertion.data {
@@ -795,13 +2211,184 @@ impl<'a> Resolver<'a> {
 				self.resolve_propspec(ps);
 				self.resolve_stmt(stmt);
 			}
-			ast::ConcurrentAssertion::CoverSequence => (),
+			ast::ConcurrentAssertion::CoverSequence(ref ps, ref stmt) => {
+				self.resolve_propspec(ps);
+				self.resolve_stmt(stmt);
+			}
 			ast::ConcurrentAssertion::RestrictProperty(ref ps) => self.resolve_propspec(ps),
 		}
 	}
 
 	pub fn resolve_propspec(&mut self, ps: &ast::PropSpec) {
-		unimplemented!();
+		if let Some(ref clock) = ps.clock {
+			self.resolve_event_expr(clock);
+		}
+		if let Some(ref disable) = ps.disable {
+			self.resolve_expr(disable);
+		}
+		self.resolve_propexpr(&ps.prop);
+	}
+
+	pub fn resolve_propexpr(&mut self, expr: &ast::PropExpr) {
+		match expr.data {
+			ast::PropExprData::SeqOp(_, ref seq) => self.resolve_seqexpr(seq),
+			ast::PropExprData::SeqBinOp(_, _, ref seq, ref prop) => {
+				self.resolve_seqexpr(seq);
+				self.resolve_propexpr(prop);
+			}
+			ast::PropExprData::Not(ref prop) => self.resolve_propexpr(prop),
+			ast::PropExprData::BinOp(_, ref lhs, ref rhs) => {
+				self.resolve_propexpr(lhs);
+				self.resolve_propexpr(rhs);
+			}
+			ast::PropExprData::Clocked(ref ev, ref prop) => {
+				self.resolve_event_expr(ev);
+				self.resolve_propexpr(prop);
+			}
+			ast::PropExprData::If(ref cond, ref main, ref other) => {
+				self.resolve_expr(cond);
+				self.resolve_propexpr(main);
+				if let Some(ref other) = *other {
+					self.resolve_propexpr(other);
+				}
+			}
+			ast::PropExprData::Case(ref expr, ref items) => {
+				self.resolve_expr(expr);
+				for item in items {
+					for e in &item.exprs {
+						self.resolve_expr(e);
+					}
+					self.resolve_propexpr(&item.prop);
+				}
+			}
+			ast::PropExprData::Nexttime(_, ref index, ref prop) => {
+				if let Some(ref index) = *index {
+					self.resolve_expr(index);
+				}
+				self.resolve_propexpr(prop);
+			}
+			ast::PropExprData::Always(_, ref range, ref prop) |
+			ast::PropExprData::Eventually(_, ref range, ref prop) => {
+				if let Some(ref range) = *range {
+					self.resolve_seq_range(range);
+				}
+				self.resolve_propexpr(prop);
+			}
+			ast::PropExprData::Abort(_, ref cond, ref prop) => {
+				self.resolve_expr(cond);
+				self.resolve_propexpr(prop);
+			}
+		}
+	}
+
+	pub fn resolve_seqexpr(&mut self, expr: &ast::SeqExpr) {
+		match expr.data {
+			ast::SeqExprData::Expr(ref expr, ref rep) => {
+				self.resolve_expr(expr);
+				if let Some(ref rep) = *rep {
+					self.resolve_seq_rep(rep);
+				}
+			}
+			ast::SeqExprData::BinOp(_, ref lhs, ref rhs) => {
+				self.resolve_seqexpr(lhs);
+				self.resolve_seqexpr(rhs);
+			}
+			ast::SeqExprData::Throughout(ref expr, ref seq) => {
+				self.resolve_expr(expr);
+				self.resolve_seqexpr(seq);
+			}
+			ast::SeqExprData::Clocked(ref ev, ref seq) => {
+				self.resolve_event_expr(ev);
+				self.resolve_seqexpr(seq);
+			}
+			ast::SeqExprData::Delay(ref lhs, ref range, ref rhs) => {
+				if let Some(ref lhs) = *lhs {
+					self.resolve_seqexpr(lhs);
+				}
+				self.resolve_seq_range(range);
+				self.resolve_seqexpr(rhs);
+			}
+			ast::SeqExprData::Paren(ref seq, ref items, ref rep) => {
+				self.resolve_seqexpr(seq);
+				for item in items {
+					self.resolve_expr(item);
+				}
+				if let Some(ref rep) = *rep {
+					self.resolve_seq_rep(rep);
+				}
+			}
+			ast::SeqExprData::FirstMatch(ref seq, ref items) => {
+				self.resolve_seqexpr(seq);
+				for item in items {
+					self.resolve_expr(item);
+				}
+			}
+		}
+	}
+
+	pub fn resolve_seq_rep(&mut self, rep: &ast::SeqRep) {
+		match *rep {
+			ast::SeqRep::Consec(ref range) |
+			ast::SeqRep::Nonconsec(ref range) |
+			ast::SeqRep::Goto(ref range) => self.resolve_seq_range(range),
+		}
+	}
+
+	pub fn resolve_seq_range(&mut self, range: &ast::SeqRange) {
+		match *range {
+			ast::SeqRange::Single(ref expr) => self.resolve_expr(expr),
+			ast::SeqRange::Range(ref lo, ref hi) => {
+				self.resolve_expr(lo);
+				if let Some(ref hi) = *hi {
+					self.resolve_expr(hi);
+				}
+			}
+			ast::SeqRange::Star |
+			ast::SeqRange::Plus => (),
+		}
+	}
+
+	pub fn resolve_sequence_decl(&mut self, decl: &ast::SequenceDecl) {
+		// The formal arguments and local variables are only visible within
+		// the declaration.
+		self.with_scope(ScopeKind::Block, None, |r| {
+			r.resolve_assertion_ports(&decl.ports, false);
+			for var in &decl.vars {
+				r.resolve_var_decl(var, true);
+			}
+			r.resolve_seqexpr(&decl.seq);
+		});
+	}
+
+	pub fn resolve_property_decl(&mut self, decl: &ast::PropertyDecl) {
+		self.with_scope(ScopeKind::Block, None, |r| {
+			r.resolve_assertion_ports(&decl.ports, false);
+			for var in &decl.vars {
+				r.resolve_var_decl(var, true);
+			}
+			r.resolve_propspec(&decl.spec);
+		});
+	}
+
+	pub fn resolve_assertion_ports(&mut self, ports: &[ast::AssertionPort], checker: bool) {
+		for port in ports {
+			if let ast::AssertionPortType::Type(ref ty) = port.ty {
+				self.resolve_type(ty);
+			}
+			self.resolve_dims(&port.dims);
+			if let Some(ref default) = port.default {
+				self.resolve_expr(default);
+			}
+			assert_renumbered!(port.name.span, port.name.id);
+			let defid = if checker {
+				DefId::Port(port.name.id)
+			} else if port.local.is_some() {
+				DefId::Var(port.name.id)
+			} else {
+				DefId::AssertionPort(port.name.id)
+			};
+			self.define(port.name.name, port.name.span, defid);
+		}
 	}
 
 	pub fn resolve_assertion_action_block(&mut self, action: &ast::AssertionActionBlock) {
@@ -829,60 +2416,445 @@ impl<'a> Resolver<'a> {
 		}
 	}
 
-	pub fn resolve_call_args(&mut self, args: &[ast::CallArg]) {
-		for arg in args {
-			if let Some(ref e) = arg.expr {
-				self.resolve_expr(e);
+	/// Check a call to a system task or function, or a use of its name without
+	/// arguments, against the table of known ones. Tasks may only be called as
+	/// statements.
+	fn resolve_sys_call(&mut self, ident: &ast::Identifier, args: &[ast::CallArg], stmt: bool) {
+		assert_renumbered!(ident.span, ident.id);
+		let systfs = self.systfs;
+		let tf = match systfs.get(ident.name) {
+			Some(tf) => tf,
+			// `$root` and `$unit` name scopes rather than calls.
+			None if &*ident.name.as_str() == "root" || &*ident.name.as_str() == "unit" => return,
+			None => {
+				let mut d = DiagBuilder2::warning(format!("Unknown system task or function `${}`", ident.name)).span(ident.span);
+				if let Some(similar) = systfs.suggest(ident.name) {
+					d = d.add_note(format!("Did you mean `${}`?", similar));
+				}
+				self.add_diag(d);
+				return;
+			}
+		};
+		if tf.kind == SysTfKind::Task && !stmt {
+			self.add_diag(DiagBuilder2::error(format!("System task `${}` cannot be used in an expression", ident.name)).span(ident.span));
+		}
+		if self.in_const_expr && !tf.constant {
+			self.add_diag(DiagBuilder2::error(format!("`${}` cannot be used in a constant expression", ident.name)).span(ident.span));
+		}
+		if args.len() < tf.required || (tf.rest.is_none() && args.len() > tf.args.len()) {
+			self.add_diag(DiagBuilder2::error(format!("`${}` takes {}, but {} given", ident.name, tf.describe_arity(), args.len())).span(ident.span));
+			return;
+		}
+		for (i, arg) in args.iter().enumerate() {
+			if arg.name.is_some() {
+				self.add_diag(DiagBuilder2::error(format!("`${}` does not take named arguments", ident.name)).span(arg.name_span));
+				continue;
+			}
+			let expr = match arg.expr {
+				Some(ref expr) => expr,
+				None if i < tf.required => {
+					self.add_diag(DiagBuilder2::error(format!("Argument {} of `${}` cannot be omitted", i+1, ident.name)).span(arg.span));
+					continue;
+				}
+				None => continue,
+			};
+			match tf.arg(i) {
+				Some(SysArg::Value) if self.is_type_name(expr) => {
+					self.add_diag(DiagBuilder2::error(format!("Argument {} of `${}` must be a value, but `{}` is a type", i+1, ident.name, expr.span.extract())).span(expr.span));
+				}
+				Some(SysArg::Variable) if !is_variable_expr(expr) || self.is_type_name(expr) => {
+					self.add_diag(DiagBuilder2::error(format!("Argument {} of `${}` must be a variable", i+1, ident.name)).span(expr.span));
+				}
+				_ => (),
+			}
+		}
+	}
+
+	/// Check whether an expression is the name of a type, as in `$bits(t)`.
+	fn is_type_name(&self, expr: &ast::Expr) -> bool {
+		let ident = match expr.data {
+			ast::IdentExpr(ref ident) |
+			ast::ScopeExpr(_, ref ident) => ident,
+			ast::TypeExpr(_) => return true,
+			_ => return false,
+		};
+		match self.defs.get(&ident.id) {
+			Some(&DefId::Typedef(_)) |
+			Some(&DefId::Class(_)) |
+			Some(&DefId::Nettype(_)) => true,
+			_ => false,
+		}
+	}
+
+	pub fn resolve_call_args(&mut self, args: &[ast::CallArg]) {
+		for arg in args {
+			if let Some(ref e) = arg.expr {
+				self.resolve_expr(e);
+			}
+		}
+	}
+
+	pub fn resolve_var_decl(&mut self, decl: &ast::VarDecl, define: bool) {
+		self.resolve_type(&decl.ty);
+		// A declaration of the form `mynettype x;` declares a net, which is
+		// only possible outside of procedural code.
+		if self.is_nettype(&decl.ty) && (define || decl.konst || decl.var || decl.lifetime.is_some()) {
+			self.add_diag(DiagBuilder2::error("A nettype cannot be the type of a variable").span(decl.ty.span));
+		}
+		for name in &decl.names {
+			assert_renumbered!(name.span, name.id);
+			if define {
+				self.define(name.name, name.span, DefId::Var(name.id));
+			}
+			self.record_var_class(name.id, &decl.ty);
+			self.resolve_dims(&name.dims);
+			if let Some(ref i) = name.init {
+				self.resolve_expr(i);
+			}
+		}
+	}
+
+	pub fn resolve_net_decl(&mut self, decl: &ast::NetDecl) {
+		self.resolve_type(&decl.ty);
+		if self.is_nettype(&decl.ty) && decl.net_type != ast::NetType::User {
+			self.add_diag(DiagBuilder2::error("A nettype cannot be the data type of a built-in net").span(decl.ty.span));
+		}
+		if let Some(ref delay) = decl.delay {
+			self.resolve_expr(delay);
+		}
+		for name in &decl.names {
+			assert_renumbered!(name.span, name.id);
+			self.resolve_dims(&name.dims);
+			if let Some(ref i) = name.init {
+				self.resolve_expr(i);
+			}
+		}
+	}
+
+	/// Resolve a nettype declaration. The resolution function may be
+	/// qualified with a package name.
+	pub fn resolve_nettype_decl(&mut self, decl: &ast::NettypeDecl) {
+		self.resolve_type(&decl.ty);
+		let (scope, func) = match decl.resolve {
+			Some((ref scope, ref func)) => (scope, func),
+			None => return,
+		};
+		let def = match *scope {
+			Some(ref scope) => {
+				self.resolve_ident(scope);
+				self.resolve_scoped_name(scope, func);
+				match self.defs.get(&func.id) {
+					Some(&id) => Def { span: func.span, id: id },
+					None => return,
+				}
+			}
+			None => match self.resolve_ident(func) {
+				Some(def) => def,
+				None => return,
+			},
+		};
+		match def.id {
+			DefId::Subroutine(_) => (),
+			_ => self.add_diag(DiagBuilder2::error(format!("Resolution function `{}` of nettype `{}` is not a function", func.name, decl.name.name)).span(func.span)),
+		}
+	}
+
+	/// Check whether a type refers to a user-defined nettype.
+	fn is_nettype(&self, ty: &ast::Type) -> bool {
+		let name = match ty.data {
+			ast::NamedType(ref name) => name,
+			ast::ScopedType { ref name, member: false, .. } => name,
+			_ => return false,
+		};
+		match self.defs.get(&name.id) {
+			Some(&DefId::Nettype(_)) => true,
+			_ => false,
+		}
+	}
+
+	pub fn resolve_param_decl(&mut self, node: &ast::ParamDecl) {
+		match node.kind {
+			ast::ParamKind::Type(ref decls) => for decl in decls {
+				if let Some(ref ty) = decl.ty {
+					self.resolve_type(ty);
+				}
+			},
+			ast::ParamKind::Value(ref decls) => for decl in decls {
+				self.resolve_type(&decl.ty);
+				self.resolve_dims(&decl.dims);
+				if let Some(ref expr) = decl.expr {
+					let outer = std::mem::replace(&mut self.in_const_expr, true);
+					self.resolve_expr(expr);
+					self.in_const_expr = outer;
+				}
+			},
+		}
+	}
+
+	/// Resolve a function or task declaration. The arguments and the
+	/// declarations in the body are only visible within the subroutine.
+	pub fn resolve_subroutine_decl(&mut self, decl: &ast::SubroutineDecl) {
+		if let Some(ref ty) = decl.prototype.retty {
+			self.resolve_type(ty);
+		}
+		self.with_scope(ScopeKind::Subroutine, Some(decl.prototype.name.name), |r| {
+			r.resolve_subroutine_ports(&decl.prototype.args, true);
+			for item in &decl.items {
+				match *item {
+					ast::SubroutineItem::PortDecl(ref decl) => {
+						r.resolve_type(&decl.ty);
+						for name in &decl.names {
+							assert_renumbered!(name.span, name.id);
+							r.define(name.name, name.span, DefId::Var(name.id));
+							r.record_var_class(name.id, &decl.ty);
+							r.resolve_dims(&name.dims);
+							if let Some(ref e) = name.init {
+								r.resolve_expr(e);
+							}
+						}
+					}
+					ast::SubroutineItem::Stmt(ref stmt) => r.resolve_stmt(stmt),
+				}
+			}
+		});
+	}
+
+	pub fn resolve_dpi_decl(&mut self, decl: &ast::DpiDecl) {
+		match *decl {
+			ast::DpiDecl::Import{ref prototype, ..} => {
+				if let Some(ref ty) = prototype.retty {
+					self.resolve_type(ty);
+				}
+				self.resolve_subroutine_ports(&prototype.args, false);
+			}
+			ast::DpiDecl::Export{ref name, ..} => match self.resolve_ident(name) {
+				Some(Def { id: DefId::Subroutine(_), .. }) | None => (),
+				Some(_) => self.add_diag(DiagBuilder2::error(format!("`{}` is not a function or task; cannot export it through DPI", name.name)).span(name.span)),
+			},
+		}
+	}
+
+	pub fn resolve_specparam_decl(&mut self, decl: &ast::SpecparamDecl) {
+		self.resolve_dims(&decl.dims);
+		for assign in &decl.assigns {
+			self.resolve_expr(&assign.value);
+			if let Some(ref e) = assign.error {
+				self.resolve_expr(e);
+			}
+		}
+	}
+
+	pub fn resolve_specify_block(&mut self, blk: &ast::SpecifyBlock) {
+		// Specparams declared within the block are only visible there.
+		self.with_scope(ScopeKind::Block, None, |r| {
+			for item in &blk.items {
+				match *item {
+					ast::SpecifyItem::Specparam(ref decl) => {
+						r.resolve_specparam_decl(decl);
+						for assign in &decl.assigns {
+							assert_renumbered!(assign.name.span, assign.name.id);
+							r.define(assign.name.name, assign.name.span, DefId::Specparam(assign.name.id));
+						}
+					}
+					ast::SpecifyItem::Pulsestyle(_, _, ref outputs) |
+					ast::SpecifyItem::Showcancelled(_, _, ref outputs) => for e in outputs {
+						r.resolve_expr(e);
+					},
+					ast::SpecifyItem::Path(ref path) => {
+						if let Some(ast::PathCond::If(ref e)) = path.cond {
+							r.resolve_expr(e);
+						}
+						for e in path.inputs.iter().chain(path.outputs.iter()).chain(path.delays.iter()) {
+							r.resolve_expr(e);
+						}
+						if let Some((_, ref e)) = path.data_source {
+							r.resolve_expr(e);
+						}
+					}
+					ast::SpecifyItem::TimingCheck(ref tc) => {
+						for ev in &tc.events {
+							r.resolve_expr(&ev.terminal);
+							if let Some(ref e) = ev.cond {
+								r.resolve_expr(e);
+							}
+						}
+						for e in &tc.limits {
+							r.resolve_expr(e);
+						}
+						if let Some(ref n) = tc.notifier {
+							assert_renumbered!(n.span, n.id);
+							r.resolve_ident(n);
+						}
+						for e in tc.extra.iter().filter_map(|e| e.as_ref()) {
+							r.resolve_expr(e);
+						}
+					}
+				}
+			}
+		});
+	}
+
+	pub fn resolve_subroutine_ports(&mut self, ports: &[ast::SubroutinePort], define: bool) {
+		for port in ports {
+			self.resolve_type(&port.ty);
+			if let Some(ref name) = port.name {
+				self.resolve_dims(&name.dims);
+				if let Some(ref expr) = name.expr {
+					self.resolve_expr(expr);
+				}
+				if define {
+					self.define(name.name.name, name.name.span, DefId::Var(name.name.id));
+				}
+				self.record_var_class(name.name.id, &port.ty);
+			}
+		}
+	}
+
+	pub fn resolve_covergroup_decl(&mut self, decl: &ast::CovergroupDecl) {
+		// The ports and sample function arguments are visible throughout the
+		// covergroup.
+		self.with_scope(ScopeKind::Block, None, |r| {
+			r.resolve_subroutine_ports(&decl.ports, true);
+			match decl.event {
+				Some(ast::CoverageEvent::Clocking(ref ec)) => r.resolve_event_control(ec),
+				Some(ast::CoverageEvent::Sample(ref ports)) => r.resolve_subroutine_ports(ports, true),
+				None => (),
 			}
-		}
-	}
 
-	pub fn resolve_var_decl(&mut self, decl: &ast::VarDecl, define: bool) {
-		self.resolve_type(&decl.ty);
-		for name in &decl.names {
-			assert_renumbered!(name.span, name.id);
-			if define {
-				self.define(name.name, name.span, DefId::Var(name.id));
-			}
-			self.resolve_dims(&name.dims);
-			if let Some(ref i) = name.init {
-				self.resolve_expr(i);
+			// Resolve the coverpoint expressions and bins before the labels are
+			// declared, such that `a: coverpoint a` refers to the variable.
+			let mut coverpoints = HashMap::new();
+			for item in &decl.items {
+				match *item {
+					ast::CovergroupItem::Option(ref option) => r.resolve_expr(&option.expr),
+					ast::CovergroupItem::Coverpoint(ref cp) => {
+						assert_renumbered!(cp.span, cp.id);
+						r.resolve_expr(&cp.expr);
+						if let Some(ref e) = cp.iff {
+							r.resolve_expr(e);
+						}
+						r.resolve_bins(&cp.bins, Some(cp.id), &coverpoints);
+
+						// Coverpoints without a label are named after the variable
+						// they cover.
+						let name = match (cp.name, &cp.expr.data) {
+							(Some(ref name), _) => Some(name.name),
+							(None, &ast::IdentExpr(ref ident)) => Some(ident.name),
+							_ => None,
+						};
+						if let Some(name) = name {
+							coverpoints.insert(name, cp);
+						}
+					}
+					ast::CovergroupItem::Cross(_) => (),
+				}
 			}
-		}
+
+			// Resolve the crosses, which may refer to the coverpoints and other
+			// crosses by their labels.
+			r.with_scope(ScopeKind::Block, None, |r| {
+				for item in &decl.items {
+					match *item {
+						ast::CovergroupItem::Coverpoint(ast::Coverpoint{ id, name: Some(ref name), .. }) => {
+							r.define(name.name, name.span, DefId::Coverpoint(id));
+						}
+						ast::CovergroupItem::Cross(ast::Cross{ id, name: Some(ref name), .. }) => {
+							r.define(name.name, name.span, DefId::Cross(id));
+						}
+						_ => (),
+					}
+				}
+				for item in &decl.items {
+					if let ast::CovergroupItem::Cross(ref cross) = *item {
+						assert_renumbered!(cross.span, cross.id);
+						for item in &cross.items {
+							r.resolve_ident(item);
+						}
+						if let Some(ref e) = cross.iff {
+							r.resolve_expr(e);
+						}
+						r.resolve_bins(&cross.bins, None, &coverpoints);
+					}
+				}
+			});
+		});
 	}
 
-	pub fn resolve_param_decl(&mut self, node: &ast::ParamDecl) {
-		match node.kind {
-			ast::ParamKind::Type(ref decls) => for decl in decls {
-				if let Some(ref ty) = decl.ty {
-					self.resolve_type(ty);
+	/// Resolve the bins of a coverpoint or cross. Within the `with` filter of
+	/// a coverpoint's bins, `item` refers to the coverpoint.
+	fn resolve_bins(&mut self, bins: &[ast::BinsOrOption], coverpoint: Option<NodeId>, coverpoints: &HashMap<Name, &ast::Coverpoint>) {
+		for bin in bins {
+			let bin = match *bin {
+				ast::BinsOrOption::Option(ref option) => {
+					self.resolve_expr(&option.expr);
+					continue;
 				}
-			},
-			ast::ParamKind::Value(ref decls) => for decl in decls {
-				self.resolve_type(&decl.ty);
-				self.resolve_dims(&decl.dims);
-				if let Some(ref expr) = decl.expr {
+				ast::BinsOrOption::Bins(ref bin) => bin,
+			};
+			if let Some(Some(ref e)) = bin.array {
+				self.resolve_expr(e);
+			}
+			match bin.data {
+				ast::BinsData::Values(ref exprs) => for expr in exprs {
 					self.resolve_expr(expr);
-				}
-			},
+				},
+				ast::BinsData::Transitions(ref seqs) => for item in seqs.iter().flat_map(|s| s.iter()) {
+					for expr in &item.values {
+						self.resolve_expr(expr);
+					}
+					if let Some(ref repeat) = item.repeat {
+						self.resolve_expr(&repeat.min);
+						if let Some(ref e) = repeat.max {
+							self.resolve_expr(e);
+						}
+					}
+				},
+				ast::BinsData::Expr(ref expr) => self.resolve_expr(expr),
+				ast::BinsData::Select(ref sel) => self.resolve_select_expr(sel, coverpoints),
+				ast::BinsData::Default |
+				ast::BinsData::DefaultSequence => (),
+			}
+			if let Some(ref e) = bin.with {
+				self.with_scope(ScopeKind::Block, None, |r| {
+					if let Some(id) = coverpoint {
+						r.define(get_name_table().intern("item", true), bin.span, DefId::Coverpoint(id));
+					}
+					r.resolve_expr(e);
+				});
+			}
+			if let Some(ref e) = bin.iff {
+				self.resolve_expr(e);
+			}
 		}
 	}
 
-	pub fn resolve_subroutine_decl(&mut self, decl: &ast::SubroutineDecl) {
-		for arg in &decl.prototype.args {
-			self.resolve_type(&arg.ty);
-			if let Some(ref name) = arg.name {
-				self.resolve_dims(&name.dims);
-				if let Some(ref expr) = name.expr {
+	fn resolve_select_expr(&mut self, expr: &ast::SelectExpr, coverpoints: &HashMap<Name, &ast::Coverpoint>) {
+		match expr.data {
+			ast::SelectExprData::Binsof{ref target, ref bin, ref intersect} => {
+				if self.resolve_ident(target).is_some() {
+					if let Some(ref bin) = *bin {
+						let def = coverpoints.get(&target.name).and_then(|cp| search_bins(&cp.bins, bin.name));
+						match def {
+							Some(def) => self.bind(bin, def),
+							None => self.add_diag(DiagBuilder2::error(format!("`{}` is not a bin of coverpoint `{}`", bin.name, target.name)).span(bin.span)),
+						}
+					}
+				}
+				for expr in intersect {
 					self.resolve_expr(expr);
 				}
 			}
+			ast::SelectExprData::Not(ref expr) => self.resolve_select_expr(expr, coverpoints),
+			ast::SelectExprData::And(ref lhs, ref rhs) |
+			ast::SelectExprData::Or(ref lhs, ref rhs) => {
+				self.resolve_select_expr(lhs, coverpoints);
+				self.resolve_select_expr(rhs, coverpoints);
+			}
 		}
-		// TODO: Resolve items.
 	}
 
 	pub fn resolve_ident(&mut self, ident: &ast::Identifier) -> Option<Def> {
-		if let Some(def) = self.find_def(ident.name) {
+		if let Some(def) = self.lookup(ident.name, Some(ident.span)) {
 			self.bind(ident, def);
 			Some(def)
 		} else {
@@ -899,21 +2871,146 @@ impl<'a> Resolver<'a> {
 		}
 	}
 
-	pub fn find_def(&mut self, name: Name) -> Option<Def> {
-		for scope in self.scopes.iter_mut().rev() {
-			let def = scope.find_def(name);
-			if def.is_some() {
-				return def;
+	/// Find the definition a name refers to, searching the scopes from the
+	/// innermost outwards. Names made visible by a wildcard import are only
+	/// considered after the declarations of the scope the import appears in.
+	/// IEEE 1800-2009 26.3
+	fn lookup(&mut self, name: Name, span: Option<Span>) -> Option<Def> {
+		for i in (0..self.scopes.len()).rev() {
+			if let Some(def) = self.scopes[i].find_def(name) {
+				return Some(def);
+			}
+			// Classes also see the members inherited from their base classes.
+			if let ScopeKind::Class(decl) = self.scopes[i].kind {
+				let member = self.base_class(decl).and_then(|base| self.find_member(base, name));
+				if let Some(member) = member {
+					if let Some(span) = span {
+						self.check_member_visibility(&member, name, span);
+					}
+					if let Some(def) = member.def {
+						return Some(def);
+					}
+				}
+			}
+			if let Some(def) = self.find_wildcard_import(i, name, span) {
+				return Some(def);
+			}
+		}
+		None
+	}
+
+	/// Find a name among the packages imported with a wildcard import into a
+	/// scope. Reports an error if the name is visible through multiple packages
+	/// and a span is given.
+	fn find_wildcard_import(&mut self, scope: usize, name: Name, span: Option<Span>) -> Option<Def> {
+		if self.scopes[scope].wildcard.is_empty() {
+			return None;
+		}
+		let pkgs = self.scopes[scope].wildcard.clone();
+		let mut found: Vec<(&ast::PackageDecl, Def)> = Vec::new();
+		for pkg in pkgs {
+			if let Some(def) = self.find_in_package(pkg, name) {
+				if !found.iter().any(|&(_, d)| d.id == def.id) {
+					found.push((pkg, def));
+				}
+			}
+		}
+		if let (true, Some(span)) = (found.len() > 1, span) {
+			self.add_diag(DiagBuilder2::error(format!("`{}` is ambiguous; it is imported from packages `{}` and `{}`", name, found[0].0.name, found[1].0.name))
+				.span(span)
+				.add_note("declared here:")
+				.span(found[0].1.span)
+				.add_note("and here:")
+				.span(found[1].1.span)
+				.add_note("use an explicit import or a scoped name `pkg::name` to disambiguate"));
+		}
+		found.into_iter().next().map(|(_, def)| def)
+	}
+
+	/// Find the package with the given name.
+	fn find_package(&self, name: Name) -> Option<&'a ast::PackageDecl> {
+		match self.scopes[0].defs.get(&name) {
+			Some(&Def { id: DefId::Package(id), .. }) => self.pkg_map.get(&id).map(|&p| p),
+			_ => None,
+		}
+	}
+
+	/// Find a member of a package. This covers the declarations in the package
+	/// as well as the names it exports.
+	fn find_in_package(&self, pkg: &'a ast::PackageDecl, name: Name) -> Option<Def> {
+		self.find_in_package_inner(pkg, name, &mut Vec::new())
+	}
+
+	fn find_in_package_inner(&self, pkg: &'a ast::PackageDecl, name: Name, visited: &mut Vec<NodeId>) -> Option<Def> {
+		if let Some(def) = search_hierarchy_items(&pkg.items, name) {
+			return Some(def);
+		}
+		if visited.contains(&pkg.id) {
+			return None;
+		}
+		visited.push(pkg.id);
+
+		// Names imported into the package are only visible to others if they
+		// are exported. IEEE 1800-2009 26.6
+		for item in &pkg.items {
+			let decl = match *item {
+				ast::HierarchyItem::ExportDecl(ref decl) => decl,
+				_ => continue,
+			};
+			if decl.items.is_empty() {
+				// `export *::*` exports everything imported.
+				for item in &pkg.items {
+					if let ast::HierarchyItem::ImportDecl(ref import) = *item {
+						for import in &import.items {
+							if import.name.map(|n| n.name == name).unwrap_or(true) {
+								if let Some(def) = self.find_package(import.pkg.name).and_then(|p| self.find_in_package_inner(p, name, visited)) {
+									return Some(def);
+								}
+							}
+						}
+					}
+				}
+				continue;
+			}
+			for export in &decl.items {
+				if !export.name.map(|n| n.name == name).unwrap_or(true) {
+					continue;
+				}
+				// `export p::*` only exports names that are imported from `p`.
+				if export.name.is_none() && !imports_from(&pkg.items, export.pkg.name, name) {
+					continue;
+				}
+				if let Some(def) = self.find_package(export.pkg.name).and_then(|p| self.find_in_package_inner(p, name, visited)) {
+					return Some(def);
+				}
 			}
 		}
 		None
 	}
 
+	/// Resolve the name in a scoped reference `scope::name`, where `scope` has
+	/// already been resolved.
+	fn resolve_scoped_name(&mut self, scope: &ast::Identifier, name: &ast::Identifier) {
+		match self.defs.get(&scope.id).cloned() {
+			Some(DefId::Package(id)) => {
+				let pkg = self.pkg_map[&id];
+				match self.find_in_package(pkg, name.name) {
+					Some(def) => self.bind(name, def),
+					None => self.add_diag(DiagBuilder2::error(format!("`{}` is not declared in package `{}`", name.name, scope.name)).span(name.span)),
+				}
+			}
+			Some(DefId::Class(id)) => match self.class_map.get(&id).cloned() {
+				Some(class) => self.resolve_class_member(class, name, true),
+				None => (),
+			},
+			None => (),
+			Some(_) => self.add_diag(DiagBuilder2::error(format!("`{}` is not a package or class", scope.name)).span(scope.span)),
+		}
+	}
+
 	pub fn define(&mut self, name: Name, span: Span, defid: DefId) {
-		let prev = match self.scopes.last_mut() {
-			Some(&mut Scope::Local{ref mut defs, ..}) => defs.insert(name, Def { span: span, id: defid }),
-			x => panic!("tried to define {} as {:?} in incompatible scope {:?}", name, defid, x)
-		};
+		let scope = self.scopes.last_mut().unwrap();
+		let prev = scope.defs.insert(name, Def { span: span, id: defid }).or_else(|| scope.imports.get(&name).cloned());
 		if let Some(p) = prev {
 			self.add_diag(DiagBuilder2::error(format!("`{}` has already been declared", name)).span(span).add_note("previous declaration was here:").span(p.span));
 		}
@@ -923,29 +3020,114 @@ impl<'a> Resolver<'a> {
 
 
 impl<'a> Scope<'a> {
-	pub fn find_def(&self, name: Name) -> Option<Def> {
+	fn new(kind: ScopeKind<'a>, path: Option<Vec<Name>>) -> Scope<'a> {
+		Scope {
+			kind: kind,
+			path: path,
+			defs: HashMap::new(),
+			imports: HashMap::new(),
+			wildcard: Vec::new(),
+			cache: HashMap::new(),
+		}
+	}
+
+	/// Find a name declared in or explicitly imported into this scope.
+	pub fn find_def(&mut self, name: Name) -> Option<Def> {
+		if let Some(&def) = self.defs.get(&name).or(self.imports.get(&name)) {
+			return Some(def);
+		}
+		let kind = self.kind;
+		*self.cache.entry(name).or_insert_with(|| kind.find_def(name))
+	}
+}
+
+impl<'a> ScopeKind<'a> {
+	/// Find a name among the items of the construct the scope belongs to.
+	fn find_def(&self, name: Name) -> Option<Def> {
 		match *self {
-			Scope::Local{ref defs, ref imported} => {
-				if let Some(def) = defs.get(&name) {
-					return Some(*def);
-				}
-				for scope in imported.iter().rev() {
-					if let Some(def) = scope.find_def(name) {
-						return Some(def);
-					}
-				}
-				return None;
-			},
-			Scope::Module(decl) => search_param_ports(&decl.params, name)
+			ScopeKind::Module(decl) => search_param_ports(&decl.params, name)
 				.or_else(|| search_ports(&decl.ports, name))
 				.or_else(|| search_hierarchy_items(&decl.items, name)),
-			Scope::Interface(decl) => search_param_ports(&decl.params, name)
+			ScopeKind::Interface(decl) => search_param_ports(&decl.params, name)
 				.or_else(|| search_ports(&decl.ports, name))
 				.or_else(|| search_hierarchy_items(&decl.items, name)),
-			Scope::Package(decl) => search_hierarchy_items(&decl.items, name),
-			Scope::Global(ref defs) => defs.get(&name).map(|x| x.clone()),
+			ScopeKind::Package(decl) => search_hierarchy_items(&decl.items, name),
+			ScopeKind::Generate(block) => search_hierarchy_items(&block.items, name),
+			ScopeKind::Checker(decl) => search_hierarchy_items(&decl.items, name),
+			ScopeKind::Class(decl) => search_param_ports(&decl.params, name)
+				.or_else(|| search_class_items(&decl.items, name).map(|(def, _)| def)),
+			ScopeKind::Global |
+			ScopeKind::Subroutine |
+			ScopeKind::Block => None,
+		}
+	}
+}
+
+/// A member of a class, found in the class itself or one of its bases.
+struct Member<'a> {
+	def: Option<Def>,
+	class: &'a ast::ClassDecl,
+	qualifiers: &'a [(ast::ClassItemQualifier, Span)],
+}
+
+/// The maximum depth of a class hierarchy that is searched for members. Cyclic
+/// inheritance is reported by `check_class_cycle`; this merely keeps the
+/// searches from looping forever on such a hierarchy.
+const MAX_CLASS_DEPTH: usize = 256;
+
+fn has_qualifier(qualifiers: &[(ast::ClassItemQualifier, Span)], q: ast::ClassItemQualifier) -> bool {
+	qualifiers.iter().any(|&(x, _)| x == q)
+}
+
+/// Check whether a name is `this` or `super`.
+fn is_class_handle(name: Name) -> bool {
+	let name = name.as_str();
+	&*name == "this" || &*name == "super"
+}
+
+/// Check whether a name is one of the methods every class provides.
+/// IEEE 1800-2009 18.6, 18.8, 18.13
+fn is_builtin_class_method(name: Name) -> bool {
+	match &*name.as_str() {
+		"new" | "randomize" | "pre_randomize" | "post_randomize" | "srandom" |
+		"get_randstate" | "set_randstate" | "rand_mode" | "constraint_mode" => true,
+		_ => false,
+	}
+}
+
+/// Find a property, method, or covergroup among the items of a class. Also
+/// yields the qualifiers of the item.
+fn search_class_items(items: &[ast::ClassItem], name: Name) -> Option<(Def, &[(ast::ClassItemQualifier, Span)])> {
+	for item in items {
+		let def = match item.data {
+			ast::ClassItemData::Property { ref names, .. } => search_var_decl_names(names, name),
+			ast::ClassItemData::SubroutineDecl(ref decl) if decl.prototype.name.name == name => {
+				Some(Def { span: decl.prototype.name.span, id: DefId::Subroutine(decl.prototype.name.id) })
+			}
+			ast::ClassItemData::ExternSubroutine(ref proto) if proto.name.name == name => {
+				Some(Def { span: proto.name.span, id: DefId::Subroutine(proto.name.id) })
+			}
+			ast::ClassItemData::CovergroupDecl(ref decl) if decl.name.name == name => {
+				Some(Def { span: decl.name.span, id: DefId::Covergroup(decl.name.id) })
+			}
+			_ => None,
+		};
+		if let Some(def) = def {
+			return Some((def, &item.qualifiers));
 		}
 	}
+	None
+}
+
+/// Find the identifier a hierarchical name starts with, e.g. the `a` in
+/// `a[1].b.c`.
+fn hier_name_root(expr: &ast::Expr) -> Option<&ast::Identifier> {
+	match expr.data {
+		ast::IdentExpr(ref ident) => Some(ident),
+		ast::MemberExpr{ref expr, ..} => hier_name_root(expr),
+		ast::IndexExpr{ref indexee, ..} => hier_name_root(indexee),
+		_ => None,
+	}
 }
 
 fn search_param_ports(params: &[ast::ParamDecl], name: Name) -> Option<Def> {
@@ -972,6 +3154,52 @@ fn search_param_ports(params: &[ast::ParamDecl], name: Name) -> Option<Def> {
 	None
 }
 
+/// Check whether a list of items imports a name from a package, either
+/// explicitly or through a wildcard import.
+fn imports_from(items: &[ast::HierarchyItem], pkg: Name, name: Name) -> bool {
+	items.iter().any(|item| match *item {
+		ast::HierarchyItem::ImportDecl(ref decl) => decl.items.iter().any(|i| {
+			i.pkg.name == pkg && i.name.map(|n| n.name == name).unwrap_or(true)
+		}),
+		_ => false,
+	})
+}
+
+/// The names of the parameters in a parameter port list, in order.
+fn param_names(params: &[ast::ParamDecl]) -> Vec<Name> {
+	let mut names = Vec::new();
+	for param in params {
+		match param.kind {
+			ast::ParamKind::Type(ref decls) => names.extend(decls.iter().map(|d| d.name.name)),
+			ast::ParamKind::Value(ref decls) => names.extend(decls.iter().map(|d| d.name.name)),
+		}
+	}
+	names
+}
+
+/// Check whether an expression can be assigned to, e.g. `a`, `a[1].b`, or
+/// `{a, b}`.
+fn is_variable_expr(expr: &ast::Expr) -> bool {
+	match expr.data {
+		ast::IdentExpr(_) |
+		ast::ScopeExpr(..) => true,
+		ast::IndexExpr { ref indexee, .. } => is_variable_expr(indexee),
+		ast::MemberExpr { ref expr, .. } => is_variable_expr(expr),
+		ast::ConcatExpr { repeat: None, ref exprs } => exprs.iter().all(is_variable_expr),
+		_ => false,
+	}
+}
+
+/// The names and directions of the ports in a port list, in order.
+fn port_signature(ports: &[ast::Port]) -> Vec<(Option<Name>, Option<ast::PortDir>)> {
+	ports.iter().map(|port| match *port {
+		ast::Port::Intf{ ref name, .. } => (Some(name.name), None),
+		ast::Port::Explicit{ ref name, dir, .. } |
+		ast::Port::Named{ ref name, dir, .. } => (Some(name.name), dir),
+		ast::Port::Implicit(_) => (None, None),
+	}).collect()
+}
+
 fn search_ports(ports: &[ast::Port], query: Name) -> Option<Def> {
 	for port in ports {
 		match *port {
@@ -992,6 +3220,39 @@ fn search_ports(ports: &[ast::Port], query: Name) -> Option<Def> {
 	None
 }
 
+/// The identifiers in an item that implicitly declare a net if they have not
+/// been declared: the ones connected to the ports of an instance or the
+/// terminals of a gate, and the ones assigned by a continuous assignment.
+pub fn implicit_net_idents(item: &ast::HierarchyItem) -> Vec<&ast::Identifier> {
+	fn collect<'a>(expr: &'a ast::Expr, into: &mut Vec<&'a ast::Identifier>) {
+		match expr.data {
+			ast::IdentExpr(ref ident) => into.push(ident),
+			ast::ConcatExpr { repeat: None, ref exprs } => for e in exprs {
+				collect(e, into);
+			},
+			_ => (),
+		}
+	}
+	let mut idents = Vec::new();
+	match *item {
+		ast::HierarchyItem::Inst(ref inst) => for conn in inst.names.iter().flat_map(|n| n.conns.iter()) {
+			match conn.kind {
+				ast::PortConnKind::Positional(ref expr) |
+				ast::PortConnKind::Named(_, ast::PortConnMode::Connected(ref expr)) => collect(expr, &mut idents),
+				_ => (),
+			}
+		},
+		ast::HierarchyItem::GateInst(ref gate) => for expr in gate.names.iter().flat_map(|n| n.terminals.iter()) {
+			collect(expr, &mut idents);
+		},
+		ast::HierarchyItem::ContAssign(ref assign) => for &(ref lhs, _) in &assign.assignments {
+			collect(lhs, &mut idents);
+		},
+		_ => (),
+	}
+	idents
+}
+
 fn search_hierarchy_items(items: &[ast::HierarchyItem], name: Name) -> Option<Def> {
 	for item in items {
 		let def = search_hierarchy_item(item, name);
@@ -1046,6 +3307,14 @@ fn search_hierarchy_item(item: &ast::HierarchyItem, name: Name) -> Option<Def> {
 				});
 			}
 		}
+		ast::HierarchyItem::DpiDecl(ast::DpiDecl::Import{ref prototype, ..}) => {
+			if prototype.name.name == name {
+				return Some(Def {
+					span: prototype.name.span,
+					id: DefId::Subroutine(prototype.name.id),
+				});
+			}
+		}
 		ast::HierarchyItem::PortDecl(ref decl) => for decl_name in &decl.names {
 			if decl_name.name == name {
 				return Some(Def {
@@ -1055,6 +3324,11 @@ fn search_hierarchy_item(item: &ast::HierarchyItem, name: Name) -> Option<Def> {
 			}
 		},
 		ast::HierarchyItem::Typedef(ref td) => {
+			// Also search through the type, as it may contain an enum whose
+			// variant names we can bind against.
+			if let Some(def) = search_type(&td.ty, name) {
+				return Some(def);
+			}
 			if td.name.name == name {
 				return Some(Def {
 					span: td.name.span,
@@ -1062,6 +3336,30 @@ fn search_hierarchy_item(item: &ast::HierarchyItem, name: Name) -> Option<Def> {
 				});
 			}
 		}
+		ast::HierarchyItem::NettypeDecl(ref decl) => {
+			if decl.name.name == name {
+				return Some(Def {
+					span: decl.name.span,
+					id: DefId::Nettype(decl.name.id),
+				});
+			}
+		}
+		ast::HierarchyItem::ModuleDecl(ref decl) => {
+			if decl.name == name {
+				return Some(Def {
+					span: decl.name_span,
+					id: DefId::Module(decl.id),
+				});
+			}
+		}
+		ast::HierarchyItem::InterfaceDecl(ref decl) => {
+			if decl.name == name {
+				return Some(Def {
+					span: decl.name_span,
+					id: DefId::Interface(decl.id),
+				});
+			}
+		}
 		ast::HierarchyItem::ClassDecl(ref decl) => {
 			if decl.name.name == name {
 				return Some(Def {
@@ -1070,6 +3368,14 @@ fn search_hierarchy_item(item: &ast::HierarchyItem, name: Name) -> Option<Def> {
 				});
 			}
 		}
+		ast::HierarchyItem::CovergroupDecl(ref decl) => {
+			if decl.name.name == name {
+				return Some(Def {
+					span: decl.name.span,
+					id: DefId::Covergroup(decl.name.id),
+				});
+			}
+		}
 		ast::HierarchyItem::Inst(ref insts) => for inst in &insts.names {
 			if inst.name.name == name {
 				return Some(Def {
@@ -1078,11 +3384,105 @@ fn search_hierarchy_item(item: &ast::HierarchyItem, name: Name) -> Option<Def> {
 				});
 			}
 		},
+		ast::HierarchyItem::GateInst(ref insts) => for inst in &insts.names {
+			match inst.name {
+				Some(ref n) if n.name == name => {
+					return Some(Def {
+						span: n.span,
+						id: DefId::Inst(n.id),
+					});
+				}
+				_ => (),
+			}
+		},
+		ast::HierarchyItem::ClockingDecl(ref decl) => match decl.name {
+			Some(ref n) if n.name == name => {
+				return Some(Def {
+					span: n.span,
+					id: DefId::Clocking(n.id),
+				});
+			}
+			_ => (),
+		},
+		ast::HierarchyItem::SequenceDecl(ref decl) => if decl.name.name == name {
+			return Some(Def {
+				span: decl.name.span,
+				id: DefId::Sequence(decl.name.id),
+			});
+		},
+		ast::HierarchyItem::PropertyDecl(ref decl) => if decl.name.name == name {
+			return Some(Def {
+				span: decl.name.span,
+				id: DefId::Property(decl.name.id),
+			});
+		},
+		ast::HierarchyItem::SpecparamDecl(ref decl) => for assign in &decl.assigns {
+			if assign.name.name == name {
+				return Some(Def {
+					span: assign.name.span,
+					id: DefId::Specparam(assign.name.id),
+				});
+			}
+		},
+		ast::HierarchyItem::GenvarDecl(ref decls) => for decl in decls {
+			if decl.name == name {
+				assert_renumbered!(decl.name_span, decl.id);
+				return Some(Def {
+					span: decl.name_span,
+					id: DefId::Genvar(decl.id),
+				});
+			}
+		},
+		// Generate regions do not introduce a new scope, so their contents
+		// are visible in the surrounding scope.
+		ast::HierarchyItem::GenerateRegion(_, ref items) => return search_hierarchy_items(items, name),
 		_ => ()
 	}
 	None
 }
 
+/// Find the instance of the given name, and the instantiation it belongs to.
+fn find_inst(items: &[ast::HierarchyItem], name: Name) -> Option<(&ast::Inst, &ast::InstName)> {
+	for item in items {
+		match *item {
+			ast::HierarchyItem::Inst(ref inst) => if let Some(n) = inst.names.iter().find(|n| n.name.name == name) {
+				return Some((inst, n));
+			},
+			ast::HierarchyItem::GenerateRegion(_, ref items) => if let Some(x) = find_inst(items, name) {
+				return Some(x);
+			},
+			_ => (),
+		}
+	}
+	None
+}
+
+fn search_clocking_signals(decl: &ast::ClockingDecl, name: Name) -> Option<Def> {
+	for item in &decl.items {
+		if let ast::ClockingItem::Signals{ref signals, ..} = *item {
+			for signal in signals {
+				if signal.name.name == name {
+					return Some(Def {
+						span: signal.name.span,
+						id: DefId::Clockvar(signal.name.id),
+					});
+				}
+			}
+		}
+	}
+	None
+}
+
+/// Check whether a list of items specifies a default clocking.
+fn has_default_clocking(items: &[ast::HierarchyItem]) -> bool {
+	items.iter().any(|item| match *item {
+		ast::HierarchyItem::ClockingDecl(ref decl) => decl.kind == ast::ClockingKind::Default,
+		ast::HierarchyItem::DefaultClocking(..) => true,
+		ast::HierarchyItem::GenerateRegion(_, ref items) => has_default_clocking(items),
+		_ => false,
+	})
+}
+
 fn search_var_decl_names(decls: &[ast::VarDeclName], name: Name) -> Option<Def> {
 	for decl in decls {
 		if decl.name == name {
@@ -1114,14 +3514,30 @@ fn search_type(ty: &ast::Type, name: Name) -> Option<Def> {
 	}
 }
 
+fn search_bins(bins: &[ast::BinsOrOption], name: Name) -> Option<Def> {
+	for bin in bins {
+		if let ast::BinsOrOption::Bins(ref bin) = *bin {
+			if bin.name.name == name {
+				return Some(Def {
+					span: bin.name.span,
+					id: DefId::Bins(bin.name.id),
+				});
+			}
+		}
+	}
+	None
+}
+
 fn search_modport_decl(items: &[ast::HierarchyItem], name: Name) -> Option<Def> {
 	for item in items {
 		if let ast::HierarchyItem::ModportDecl(ref decl) = *item {
 			for item in &decl.items {
-				return Some(Def {
-					span: item.name.span,
-					id: DefId::Modport(item.name.id),
-				})
+				if item.name.name == name {
+					return Some(Def {
+						span: item.name.span,
+						id: DefId::Modport(item.name.id),
+					});
+				}
 			}
 		}
 	}
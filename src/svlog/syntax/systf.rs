@@ -0,0 +1,295 @@
+// Copyright (c) 2017 Fabian Schuiki
+
+//! This module provides the table of system tasks and functions, such as
+//! `$display` and `$clog2`, against which calls are checked during name
+//! resolution. Tools may register additional system tasks and functions, e.g.
+//! ones provided through PLI.
+//! IEEE 1800-2009 20, 21
+
+use std;
+use std::collections::HashMap;
+use moore_common::name::*;
+
+
+/// The table of known system tasks and functions, indexed by their name
+/// without the leading `$`.
+#[derive(Debug, Clone)]
+pub struct SysTfTable {
+	tfs: HashMap<Name, SysTf>,
+}
+
+/// A system task or function and its signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SysTf {
+	/// The name without the leading `$`.
+	pub name: Name,
+	pub kind: SysTfKind,
+	/// The kinds of the arguments. Those past `required` may be omitted.
+	pub args: Vec<SysArg>,
+	/// The number of arguments that must be given.
+	pub required: usize,
+	/// The kind of any number of further arguments, as taken by `$display`.
+	/// These may also be left empty, as in `$display(a,,b)`.
+	pub rest: Option<SysArg>,
+	/// Whether the function may be used in constant expressions.
+	pub constant: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SysTfKind {
+	Task,
+	Function,
+}
+
+/// The kind of an argument of a system task or function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SysArg {
+	/// An expression that yields a value.
+	Value,
+	/// A data type or an expression, as taken by `$bits`.
+	TypeOrValue,
+	/// A variable written by the call, as the destination of `$cast`.
+	Variable,
+}
+
+impl SysTf {
+	/// Create a system task that takes the given arguments.
+	pub fn task(name: &str, args: &[SysArg]) -> SysTf {
+		SysTf::new(name, SysTfKind::Task, args)
+	}
+
+	/// Create a system function that takes the given arguments.
+	pub fn function(name: &str, args: &[SysArg]) -> SysTf {
+		SysTf::new(name, SysTfKind::Function, args)
+	}
+
+	fn new(name: &str, kind: SysTfKind, args: &[SysArg]) -> SysTf {
+		SysTf {
+			name: get_name_table().intern(name, true),
+			kind: kind,
+			args: args.to_vec(),
+			required: args.len(),
+			rest: None,
+			constant: false,
+		}
+	}
+
+	/// Add arguments that may be omitted.
+	pub fn optional(mut self, args: &[SysArg]) -> SysTf {
+		self.args.extend(args.iter().cloned());
+		self
+	}
+
+	/// Accept any number of further arguments of the given kind.
+	pub fn rest(mut self, arg: SysArg) -> SysTf {
+		self.rest = Some(arg);
+		self
+	}
+
+	/// Mark the function as usable in constant expressions.
+	pub fn constant(mut self) -> SysTf {
+		self.constant = true;
+		self
+	}
+
+	/// The kind of the argument at a position, or `None` if the call takes no
+	/// argument there.
+	pub fn arg(&self, index: usize) -> Option<SysArg> {
+		self.args.get(index).cloned().or(self.rest)
+	}
+
+	/// Describe the number of arguments taken, e.g. "1 to 2 arguments".
+	pub fn describe_arity(&self) -> String {
+		let plural = |n| if n == 1 { "argument" } else { "arguments" };
+		if self.rest.is_some() {
+			format!("at least {} {}", self.required, plural(self.required))
+		} else if self.required == self.args.len() {
+			format!("{} {}", self.required, plural(self.required))
+		} else {
+			format!("{} to {} {}", self.required, self.args.len(), plural(self.args.len()))
+		}
+	}
+}
+
+impl SysTfTable {
+	/// Create a table of the system tasks and functions defined by the
+	/// standard.
+	pub fn new() -> SysTfTable {
+		let mut table = SysTfTable { tfs: HashMap::new() };
+		for tf in builtins() {
+			table.register(tf);
+		}
+		table
+	}
+
+	/// Add a system task or function to the table. Returns the entry it
+	/// replaces, if any.
+	pub fn register(&mut self, tf: SysTf) -> Option<SysTf> {
+		self.tfs.insert(tf.name, tf)
+	}
+
+	/// Find a system task or function by its name without the leading `$`.
+	pub fn get(&self, name: Name) -> Option<&SysTf> {
+		self.tfs.get(&name)
+	}
+
+	/// Find the known name closest to a misspelled one.
+	pub fn suggest(&self, name: Name) -> Option<Name> {
+		let name = name.as_str();
+		let limit = std::cmp::max(1, name.len() / 3);
+		self.tfs.keys()
+			.map(|&n| (edit_distance(&name, &n.as_str()), n))
+			.filter(|&(d, _)| d <= limit)
+			.min_by_key(|&(d, n)| (d, n.as_str()))
+			.map(|(_, n)| n)
+	}
+}
+
+impl Default for SysTfTable {
+	fn default() -> SysTfTable {
+		SysTfTable::new()
+	}
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut row: Vec<usize> = (0..b.len()+1).collect();
+	for (i, ca) in a.chars().enumerate() {
+		let mut prev = row[0];
+		row[0] = i + 1;
+		for j in 0..b.len() {
+			let cur = row[j+1];
+			row[j+1] = if ca == b[j] {
+				prev
+			} else {
+				1 + std::cmp::min(prev, std::cmp::min(row[j], cur))
+			};
+			prev = cur;
+		}
+	}
+	row[b.len()]
+}
+
+fn builtins() -> Vec<SysTf> {
+	use self::SysArg::*;
+	let mut v = Vec::new();
+
+	// Display and file output tasks (21.2, 21.3).
+	for &base in &["display", "write", "strobe", "monitor"] {
+		for &radix in &["", "b", "h", "o"] {
+			v.push(SysTf::task(&format!("{}{}", base, radix), &[]).rest(Value));
+			v.push(SysTf::task(&format!("f{}{}", base, radix), &[Value]).rest(Value));
+			if base == "write" {
+				v.push(SysTf::task(&format!("swrite{}", radix), &[Variable]).rest(Value));
+			}
+		}
+	}
+	v.push(SysTf::task("monitoron", &[]));
+	v.push(SysTf::task("monitoroff", &[]));
+	v.push(SysTf::task("sformat", &[Variable, Value]).rest(Value));
+	v.push(SysTf::function("sformatf", &[Value]).rest(Value));
+	v.push(SysTf::function("fopen", &[Value]).optional(&[Value]));
+	v.push(SysTf::task("fclose", &[Value]));
+	v.push(SysTf::task("fflush", &[]).optional(&[Value]));
+	v.push(SysTf::function("fgetc", &[Value]));
+	v.push(SysTf::function("ungetc", &[Value, Value]));
+	v.push(SysTf::function("fgets", &[Variable, Value]));
+	v.push(SysTf::function("fscanf", &[Value, Value]).rest(Variable));
+	v.push(SysTf::function("sscanf", &[Value, Value]).rest(Variable));
+	v.push(SysTf::function("fread", &[Variable, Value]).optional(&[Value, Value]));
+	v.push(SysTf::function("ftell", &[Value]));
+	v.push(SysTf::function("fseek", &[Value, Value, Value]));
+	v.push(SysTf::function("rewind", &[Value]));
+	v.push(SysTf::function("feof", &[Value]));
+	v.push(SysTf::function("ferror", &[Value, Variable]));
+	for &name in &["readmemb", "readmemh"] {
+		v.push(SysTf::task(name, &[Value, Variable]).optional(&[Value, Value]));
+	}
+	for &name in &["writememb", "writememh"] {
+		v.push(SysTf::task(name, &[Value, Value]).optional(&[Value, Value]));
+	}
+	v.push(SysTf::function("test$plusargs", &[Value]));
+	v.push(SysTf::function("value$plusargs", &[Value, Variable]));
+
+	// Simulation control and severity tasks (20.2, 20.10).
+	v.push(SysTf::task("finish", &[]).optional(&[Value]));
+	v.push(SysTf::task("stop", &[]).optional(&[Value]));
+	v.push(SysTf::task("exit", &[]));
+	v.push(SysTf::task("fatal", &[]).optional(&[Value]).rest(Value));
+	for &name in &["error", "warning", "info"] {
+		v.push(SysTf::task(name, &[]).rest(Value));
+	}
+	for &name in &["asserton", "assertoff", "assertkill"] {
+		v.push(SysTf::task(name, &[]).rest(Value));
+	}
+
+	// Time tasks and functions (20.3, 20.4).
+	for &name in &["time", "stime", "realtime"] {
+		v.push(SysTf::function(name, &[]));
+	}
+	v.push(SysTf::task("timeformat", &[]).optional(&[Value, Value, Value, Value]));
+	v.push(SysTf::task("printtimescale", &[]).optional(&[Value]));
+
+	// Conversion functions (20.5).
+	for &name in &["signed", "unsigned", "rtoi", "itor", "realtobits", "bitstoreal", "shortrealtobits", "bitstoshortreal"] {
+		v.push(SysTf::function(name, &[Value]).constant());
+	}
+	v.push(SysTf::function("cast", &[Variable, Value]));
+
+	// Data and array query functions (20.6, 20.7).
+	v.push(SysTf::function("bits", &[TypeOrValue]).constant());
+	v.push(SysTf::function("typename", &[TypeOrValue]));
+	v.push(SysTf::function("isunbounded", &[Value]).constant());
+	for &name in &["dimensions", "unpacked_dimensions"] {
+		v.push(SysTf::function(name, &[TypeOrValue]).constant());
+	}
+	for &name in &["left", "right", "low", "high", "increment", "size"] {
+		v.push(SysTf::function(name, &[TypeOrValue]).optional(&[Value]).constant());
+	}
+
+	// Math functions (20.8).
+	v.push(SysTf::function("clog2", &[Value]).constant());
+	for &name in &["ln", "log10", "exp", "sqrt", "floor", "ceil", "sin", "cos", "tan", "asin", "acos", "atan", "sinh", "cosh", "tanh", "asinh", "acosh", "atanh"] {
+		v.push(SysTf::function(name, &[Value]).constant());
+	}
+	for &name in &["pow", "atan2", "hypot"] {
+		v.push(SysTf::function(name, &[Value, Value]).constant());
+	}
+
+	// Bit vector functions (20.9).
+	v.push(SysTf::function("countbits", &[Value, Value]).rest(Value).constant());
+	for &name in &["countones", "onehot", "onehot0", "isunknown"] {
+		v.push(SysTf::function(name, &[Value]).constant());
+	}
+
+	// Sampled value functions (16.9.3).
+	v.push(SysTf::function("sampled", &[Value]));
+	for &name in &["rose", "fell", "stable", "changed"] {
+		v.push(SysTf::function(name, &[Value]).optional(&[Value]));
+	}
+	v.push(SysTf::function("past", &[Value]).optional(&[Value, Value, Value]));
+	for &name in &["global_clock", "inferred_clock", "inferred_disable"] {
+		v.push(SysTf::function(name, &[]));
+	}
+
+	// Random number functions (18.13, 20.15).
+	v.push(SysTf::function("random", &[]).optional(&[Variable]));
+	v.push(SysTf::function("urandom", &[]).optional(&[Value]));
+	v.push(SysTf::function("urandom_range", &[Value]).optional(&[Value]));
+	v.push(SysTf::function("dist_uniform", &[Variable, Value, Value]));
+	v.push(SysTf::function("dist_normal", &[Variable, Value, Value]));
+	v.push(SysTf::function("dist_exponential", &[Variable, Value]));
+	v.push(SysTf::function("dist_poisson", &[Variable, Value]));
+
+	// Value change dump tasks (21.7).
+	v.push(SysTf::task("dumpfile", &[]).optional(&[Value]));
+	v.push(SysTf::task("dumpvars", &[]).rest(Value));
+	for &name in &["dumpon", "dumpoff", "dumpall", "dumpflush"] {
+		v.push(SysTf::task(name, &[]));
+	}
+	v.push(SysTf::task("dumplimit", &[Value]));
+
+	v.push(SysTf::function("system", &[]).optional(&[Value]));
+	v
+}
@@ -516,6 +516,7 @@ declare_keywords! {
 	(Ifnone,               "ifnone")
 	(IgnoreBins,           "ignore_bins")
 	(IllegalBins,          "illegal_bins")
+	(Implements,           "implements")
 	(Implies,              "implies")
 	(Import,               "import")
 	(Incdir,               "incdir")
@@ -666,4 +667,9 @@ declare_keywords! {
 	(Wor,                  "wor")
 	(Xnor,                 "xnor")
 	(Xor,                  "xor")
+
+	// Keywords added in IEEE 1800-2012
+	(Interconnect,         "interconnect")
+	(Nettype,              "nettype")
+	(Soft,                 "soft")
 }
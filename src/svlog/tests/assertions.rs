@@ -0,0 +1,255 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::ast;
+use common::moore_svlog::ast::{PropExprData, SeqExprData, SeqRange, SeqRep};
+
+
+fn module_items(ast: &[ast::Root]) -> &[ast::HierarchyItem] {
+	match ast[0].items[0] {
+		ast::Item::Module(ref decl) => &decl.items,
+		_ => panic!("expected module"),
+	}
+}
+
+fn first_assertion_spec(ast: &[ast::Root]) -> &ast::PropSpec {
+	for item in module_items(ast) {
+		if let ast::HierarchyItem::Assertion(ref a) = *item {
+			if let ast::AssertionData::Concurrent(ref c) = a.data {
+				match *c {
+					ast::ConcurrentAssertion::AssertProperty(ref ps, _) |
+					ast::ConcurrentAssertion::CoverSequence(ref ps, _) => return ps,
+					_ => (),
+				}
+			}
+		}
+	}
+	panic!("no concurrent assertion found");
+}
+
+#[test]
+fn sequence_and_property_decls() {
+	let hir = compile_to_hir(parse("
+		module foo (input clk, rst, a, b, input [7:0] data);
+			sequence s_req(x, y, int n = 2);
+				int v;
+				(x, v = data) ##[1:n] y && data == v;
+			endsequence : s_req
+			property p_handshake(sequence s, local input logic l = 0);
+				@(posedge clk) disable iff (rst)
+				s |=> s_eventually b;
+			endproperty
+			assert property (p_handshake(s_req(a, b)));
+			cover sequence (@(posedge clk) s_req(a, b, 3)) $display(\"hit\");
+			always @(posedge clk) if (s_req(a, b).triggered) $display(\"trig\");
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.body.sequences.len(), 1);
+	assert_eq!(m.body.properties.len(), 1);
+	let seq = &m.body.sequences[0];
+	assert_eq!(seq.ports.len(), 3);
+	assert_eq!(seq.ports[0].ty, ast::AssertionPortType::Untyped);
+	assert!(seq.ports[2].default.is_some());
+	assert_eq!(seq.vars.len(), 1);
+	match seq.seq.data {
+		SeqExprData::Delay(Some(ref lhs), SeqRange::Range(_, Some(_)), _) => match lhs.data {
+			SeqExprData::Paren(_, ref items, None) => assert_eq!(items.len(), 1),
+			ref x => panic!("expected sequence with match items, got {:?}", x),
+		},
+		ref x => panic!("expected cycle delay, got {:?}", x),
+	}
+	let prop = &m.body.properties[0];
+	assert_eq!(prop.ports[0].ty, ast::AssertionPortType::Sequence);
+	assert_eq!(prop.ports[1].local, Some(ast::PortDir::Input));
+	assert!(prop.spec.clock.is_some());
+	assert!(prop.spec.disable.is_some());
+	match prop.spec.prop.data {
+		PropExprData::SeqBinOp(ast::PropSeqBinOp::ImplNonoverlap, _, _, ref rhs) => match rhs.data {
+			PropExprData::Eventually(true, None, _) => (),
+			ref x => panic!("expected s_eventually, got {:?}", x),
+		},
+		ref x => panic!("expected implication, got {:?}", x),
+	}
+}
+
+#[test]
+fn sequence_operators() {
+	let ast = parse("
+		module foo;
+			assert property (@(posedge clk) first_match(a ##1 b[*2:$], x = 1) intersect (c throughout d[->1]) within e[=2]);
+		endmodule
+	");
+	let spec = first_assertion_spec(&ast);
+	let seq = match spec.prop.data {
+		PropExprData::SeqOp(ast::PropSeqOp::None, ref seq) => seq,
+		ref x => panic!("expected a sequence, got {:?}", x),
+	};
+	match seq.data {
+		SeqExprData::BinOp(ast::SeqBinOp::Intersect, ref lhs, ref rhs) => {
+			match lhs.data {
+				SeqExprData::FirstMatch(_, ref items) => assert_eq!(items.len(), 1),
+				ref x => panic!("expected first_match, got {:?}", x),
+			}
+			match rhs.data {
+				SeqExprData::BinOp(ast::SeqBinOp::Within, ref lhs, ref rhs) => {
+					match lhs.data {
+						SeqExprData::Throughout(_, ref seq) => match seq.data {
+							SeqExprData::Expr(_, Some(SeqRep::Goto(_))) => (),
+							ref x => panic!("expected goto repetition, got {:?}", x),
+						},
+						ref x => panic!("expected throughout, got {:?}", x),
+					}
+					match rhs.data {
+						SeqExprData::Expr(_, Some(SeqRep::Nonconsec(_))) => (),
+						ref x => panic!("expected nonconsecutive repetition, got {:?}", x),
+					}
+				}
+				ref x => panic!("expected within, got {:?}", x),
+			}
+		}
+		ref x => panic!("expected intersect, got {:?}", x),
+	}
+}
+
+#[test]
+fn property_operators() {
+	let ast = parse("
+		module foo;
+			assert property (accept_on (stop) always [2:5] (a until_with b) or not s_nexttime [2] c);
+			assert property (if (mode) a |-> b else strong(c ##1 d));
+			assert property (case (sel) 0, 1: a implies b; default: weak(c); endcase);
+			assert property (a ##1 b and c ##1 d iff e);
+		endmodule
+	");
+	let specs: Vec<_> = module_items(&ast).iter().filter_map(|item| match *item {
+		ast::HierarchyItem::Assertion(ast::Assertion { data: ast::AssertionData::Concurrent(ast::ConcurrentAssertion::AssertProperty(ref ps, _)), .. }) => Some(ps),
+		_ => None,
+	}).collect();
+	assert_eq!(specs.len(), 4);
+	match specs[0].prop.data {
+		PropExprData::Abort(ast::PropAbortOp::AcceptOn, _, ref prop) => match prop.data {
+			PropExprData::Always(false, Some(SeqRange::Range(..)), ref prop) => match prop.data {
+				PropExprData::BinOp(ast::PropBinOp::Or, _, ref rhs) => match rhs.data {
+					PropExprData::Not(ref prop) => match prop.data {
+						PropExprData::Nexttime(true, Some(_), _) => (),
+						ref x => panic!("expected s_nexttime, got {:?}", x),
+					},
+					ref x => panic!("expected not, got {:?}", x),
+				},
+				ref x => panic!("expected or, got {:?}", x),
+			},
+			ref x => panic!("expected always, got {:?}", x),
+		},
+		ref x => panic!("expected accept_on, got {:?}", x),
+	}
+	match specs[1].prop.data {
+		PropExprData::If(_, _, Some(ref other)) => match other.data {
+			PropExprData::SeqOp(ast::PropSeqOp::Strong, _) => (),
+			ref x => panic!("expected strong sequence, got {:?}", x),
+		},
+		ref x => panic!("expected if/else, got {:?}", x),
+	}
+	match specs[2].prop.data {
+		PropExprData::Case(_, ref items) => {
+			assert_eq!(items.len(), 2);
+			assert_eq!(items[0].exprs.len(), 2);
+			assert!(items[1].exprs.is_empty());
+		}
+		ref x => panic!("expected case, got {:?}", x),
+	}
+	match specs[3].prop.data {
+		PropExprData::BinOp(ast::PropBinOp::Iff, ref lhs, _) => match lhs.data {
+			PropExprData::SeqOp(_, ast::SeqExpr { data: SeqExprData::BinOp(ast::SeqBinOp::And, _, _), .. }) => (),
+			ref x => panic!("expected sequence and, got {:?}", x),
+		},
+		ref x => panic!("expected iff, got {:?}", x),
+	}
+}
+
+#[test]
+fn sequence_matched() {
+	compile_to_hir(parse("
+		module foo (input clk, a, b);
+			sequence s;
+				a ##1 b;
+			endsequence
+			assert property (@(posedge clk) a |-> s.matched);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn sequence_invalid_method() {
+	compile_to_hir(parse("
+		module foo (input clk, a, b);
+			sequence s;
+				a ##1 b;
+			endsequence
+			always @(posedge clk) if (s.ended) $display(\"x\");
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn sequence_args_out_of_scope() {
+	compile_to_hir(parse("
+		module foo (input a);
+			sequence s(x);
+				x ##1 a;
+			endsequence
+			assert property (x);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn cover_sequence_with_property() {
+	parse("
+		module foo;
+			cover sequence (a |-> b);
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn sequence_name_mismatch() {
+	parse("
+		module foo;
+			sequence s;
+				a;
+			endsequence : t
+		endmodule
+	");
+}
+
+#[test]
+fn labeled_assertions() {
+	let ast = parse("
+		module foo (input clk, a, b);
+			a1: assert property (@(posedge clk) a |-> b);
+			a2: assume property (@(posedge clk) b);
+			c1: cover property (@(posedge clk) a ##1 b);
+			r1: restrict property (@(posedge clk) !a);
+			assert property (@(posedge clk) a);
+		endmodule
+	");
+	let labels: Vec<_> = module_items(&ast).iter().filter_map(|item| match *item {
+		ast::HierarchyItem::Assertion(ref a) => Some(a.label.as_ref().map(|&(n, _)| String::from(&*n.as_str()))),
+		_ => None,
+	}).collect();
+	assert_eq!(labels, vec![
+		Some(String::from("a1")),
+		Some(String::from("a2")),
+		Some(String::from("c1")),
+		Some(String::from("r1")),
+		None,
+	]);
+	compile_to_hir(ast);
+}
@@ -0,0 +1,150 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::ast;
+use common::moore_svlog::ast::HasAttrs;
+
+
+fn module(ast: &[ast::Root]) -> &ast::ModDecl {
+	for item in &ast[0].items {
+		if let ast::Item::Module(ref decl) = *item {
+			return decl;
+		}
+	}
+	panic!("expected module");
+}
+
+#[test]
+fn item_attributes() {
+	let ast = parse("
+		(* top, version = 2 *)
+		module foo (
+			(* keep *) input a,
+			(* mark = \"clk\" *) input clk
+		);
+			(* ram_style = \"block\" *) logic [7:0] mem [0:15];
+			(* keep *) (* keep = 0 *) wire w;
+			(* full_case, parallel_case *)
+			always @(*) begin
+				(* parallel_case *) case (a)
+					0: ;
+				endcase
+			end
+			(* dont_touch *) bar b ((* unused *) .x(a), .y(w));
+			assign w = a + (* carry *) clk;
+		endmodule
+	");
+	let m = module(&ast);
+	assert!(ast[0].items[0].has_attr("top"));
+	assert!(m.find_attr("version").unwrap().value.is_some());
+	assert!(m.ports[0].has_attr("keep"));
+	assert!(m.ports[1].find_attr("mark").unwrap().value.is_some());
+	assert!(!m.ports[1].has_attr("keep"));
+	assert!(m.items[0].has_attr("ram_style"));
+	assert_eq!(m.items[1].attrs().len(), 2);
+	assert!(m.items[1].find_attr("keep").unwrap().value.is_some());
+	match m.items[2] {
+		ast::HierarchyItem::Procedure(ref prc) => {
+			assert!(prc.has_attr("full_case"));
+			assert!(prc.has_attr("parallel_case"));
+			match prc.stmt.data {
+				ast::TimedStmt(_, ref stmt) => match stmt.data {
+					ast::SequentialBlock(ref stmts) => assert!(stmts[0].has_attr("parallel_case")),
+					ref x => panic!("expected sequential block, got {:?}", x),
+				},
+				ref x => panic!("expected timed statement, got {:?}", x),
+			}
+		}
+		ref x => panic!("expected procedure, got {:?}", x),
+	}
+	match m.items[3] {
+		ast::HierarchyItem::Inst(ref inst) => {
+			assert!(inst.has_attr("dont_touch"));
+			assert!(inst.names[0].conns[0].has_attr("unused"));
+			assert!(!inst.names[0].conns[1].has_attr("unused"));
+		}
+		ref x => panic!("expected instantiation, got {:?}", x),
+	}
+	match m.items[4] {
+		ast::HierarchyItem::ContAssign(ref assign) => assert!(assign.assignments[0].1.has_attr("carry")),
+		ref x => panic!("expected continuous assignment, got {:?}", x),
+	}
+}
+
+#[test]
+fn expr_attributes() {
+	let ast = parse("
+		module foo;
+			initial x = ~(* inv *) a ? f (* inline *) (b) : c;
+		endmodule
+	");
+	let m = module(&ast);
+	let stmt = match m.items[0] {
+		ast::HierarchyItem::Procedure(ref prc) => &prc.stmt,
+		ref x => panic!("expected procedure, got {:?}", x),
+	};
+	let rhs = match stmt.data {
+		ast::BlockingAssignStmt { ref rhs, .. } => rhs,
+		ref x => panic!("expected assignment, got {:?}", x),
+	};
+	match rhs.data {
+		ast::TernaryExpr { ref cond, ref true_expr, .. } => {
+			assert!(cond.has_attr("inv"));
+			assert!(true_expr.has_attr("inline"));
+		}
+		ref x => panic!("expected ternary expression, got {:?}", x),
+	}
+}
+
+#[test]
+fn conditional_attributes() {
+	let ast = parse("
+		module foo;
+			assign y = c ? (* mux, sel = 2 *) x : z;
+			assign w = c ? (* first *) (* second *) a + b : e;
+		endmodule
+	");
+	let m = module(&ast);
+	let rhs: Vec<&ast::Expr> = m.items.iter().map(|item| match *item {
+		ast::HierarchyItem::ContAssign(ref assign) => &assign.assignments[0].1,
+		ref x => panic!("expected continuous assignment, got {:?}", x),
+	}).collect();
+	match rhs[0].data {
+		ast::TernaryExpr { ref true_expr, .. } => {
+			assert!(rhs[0].has_attr("mux"));
+			assert!(rhs[0].has_attr("sel"));
+			assert!(!true_expr.has_attr("mux"));
+		}
+		ref x => panic!("expected ternary expression, got {:?}", x),
+	}
+	match rhs[1].data {
+		ast::TernaryExpr { ref true_expr, .. } => {
+			assert_eq!(rhs[1].attrs().len(), 2);
+			assert!(rhs[1].has_attr("second"));
+			assert!(true_expr.attrs().is_empty());
+		}
+		ref x => panic!("expected ternary expression, got {:?}", x),
+	}
+}
+
+#[test]
+fn event_star_is_not_attribute() {
+	compile_to_hir(parse("
+		module foo (input a, output logic b);
+			always @(*) b = a;
+			always @( * ) b = a;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn unterminated_attribute() {
+	parse("
+		(* foo = 1
+		module foo;
+		endmodule
+	");
+}
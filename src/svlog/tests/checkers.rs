@@ -0,0 +1,164 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::ast;
+
+
+#[test]
+fn checker_decl() {
+	let hir = compile_to_hir(parse("
+		checker req_ack(logic req, ack, event clk = $global_clock, output bit seen);
+			logic pending;
+			always_ff @clk pending <= req & ~ack;
+			assert property (@clk req |-> ##[1:4] ack);
+		endchecker : req_ack
+		module foo (input clk, req, ack);
+			bit s;
+			req_ack c (req, ack, clk, s);
+		endmodule
+	"));
+	let c = hir.checkers.values().next().unwrap();
+	assert_eq!(&*c.name.as_str(), "req_ack");
+	assert_eq!(c.ports.len(), 4);
+	assert_eq!(c.ports[1].local, Some(ast::PortDir::Input));
+	assert_eq!(c.ports[3].local, Some(ast::PortDir::Output));
+	assert_eq!(c.body.vars.len(), 1);
+	assert_eq!(c.body.asserts.len(), 1);
+	let inst = hir.design.scope.find_inst("c").unwrap();
+	assert_eq!(inst.target, c.id);
+	assert_eq!(inst.scope.body.asserts.len(), 1);
+}
+
+#[test]
+fn bind_into_module() {
+	let hir = compile_to_hir(parse("
+		module top;
+			cpu cpu0();
+			cpu cpu1();
+		endmodule
+		module cpu;
+			logic clk, valid, ready;
+		endmodule
+		module cpu_props (input clk, valid, ready);
+		endmodule
+		checker handshake(a, b);
+		endchecker
+		bind cpu cpu_props p (.clk(clk), .valid(valid), .ready(ready));
+		bind cpu : top.cpu1 handshake h (valid, ready);
+		bind top.cpu0 handshake h0 (ready, valid);
+	"));
+	let cpu0 = hir.design.scope.find_inst("cpu0").unwrap();
+	let cpu1 = hir.design.scope.find_inst("cpu1").unwrap();
+	assert!(cpu0.scope.find_inst("p").is_some());
+	assert!(cpu1.scope.find_inst("p").is_some());
+	assert!(cpu0.scope.find_inst("h").is_none());
+	assert!(cpu1.scope.find_inst("h").is_some());
+	assert!(cpu0.scope.find_inst("h0").is_some());
+	assert!(cpu1.scope.find_inst("h0").is_none());
+	assert_eq!(hir.binds.len(), 3);
+}
+
+#[test]
+#[should_panic]
+fn bind_port_outside_target() {
+	compile_to_hir(parse("
+		module top;
+			logic x;
+			cpu cpu0();
+		endmodule
+		module cpu;
+		endmodule
+		module mon (input a);
+		endmodule
+		bind cpu mon m (.a(x));
+	"));
+}
+
+#[test]
+#[should_panic]
+fn bind_unknown_instance() {
+	compile_to_hir(parse("
+		module top;
+			cpu cpu0();
+		endmodule
+		module cpu;
+		endmodule
+		module mon;
+		endmodule
+		bind cpu : top.cpu7 mon m ();
+	"));
+}
+
+#[test]
+#[should_panic]
+fn bind_unknown_nested_instance() {
+	compile_to_hir(parse("
+		module top;
+			cpu cpu0();
+		endmodule
+		module cpu;
+			alu alu0();
+		endmodule
+		module alu;
+		endmodule
+		module mon;
+		endmodule
+		bind alu : top.cpu0.alu7 mon m ();
+	"));
+}
+
+#[test]
+#[should_panic]
+fn checker_instantiates_module() {
+	compile_to_hir(parse("
+		module foo;
+		endmodule
+		checker c;
+			foo f();
+		endchecker
+		module top;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn checker_always_procedure() {
+	compile_to_hir(parse("
+		checker c(a);
+			always @(a) $display(a);
+		endchecker
+		module top;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn checker_net_decl() {
+	compile_to_hir(parse("
+		checker c;
+			wire w;
+		endchecker
+		module top;
+		endmodule
+	"));
+}
+
+#[test]
+fn checker_labeled_assertions() {
+	let hir = compile_to_hir(parse("
+		checker mutex(logic a, b, event clk = $global_clock);
+			a_excl: assert property (@clk !(a && b));
+			c_both: cover property (@clk a ##1 b);
+		endchecker
+		module foo (input clk, a, b);
+			mutex m (a, b, clk);
+		endmodule
+	"));
+	let c = hir.checkers.values().next().unwrap();
+	assert_eq!(c.body.asserts.len(), 2);
+	assert_eq!(c.body.asserts[0].label.as_ref().map(|&(n, _)| String::from(&*n.as_str())), Some(String::from("a_excl")));
+}
@@ -0,0 +1,219 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+
+
+#[test]
+fn inherited_members() {
+	compile_to_hir(parse("
+		module foo;
+			class Base;
+				int x;
+				protected int p;
+				static int count;
+				function new(int init);
+					x = init;
+				endfunction
+				virtual function int get();
+					return x;
+				endfunction
+			endclass
+			class Derived extends Base;
+				int y;
+				function new();
+					super.new(1);
+					this.y = x + p;
+				endfunction
+				virtual function int get();
+					return super.get() + this.y;
+				endfunction
+			endclass
+			Derived d;
+			int z;
+			initial begin
+				d = new();
+				d.x = 2;
+				z = d.get() + d.y;
+				z = Base::count;
+			end
+		endmodule
+	"));
+}
+
+#[test]
+fn specialized_class_members() {
+	compile_to_hir(parse("
+		module foo;
+			class Box #(type T = int);
+				T value;
+				function T get();
+					return value;
+				endfunction
+			endclass
+			Box #(logic [7:0]) b;
+			logic [7:0] v;
+			initial v = b.get() + b.value;
+		endmodule
+	"));
+}
+
+#[test]
+fn interface_class() {
+	compile_to_hir(parse("
+		module foo;
+			interface class Getter;
+				pure virtual function int get();
+			endclass
+			interface class Putter;
+				pure virtual function void put(int v);
+			endclass
+			virtual class Store implements Getter;
+				pure virtual function int get();
+			endclass
+			class Reg extends Store implements Putter;
+				int value;
+				virtual function int get();
+					return value;
+				endfunction
+				virtual function void put(int v);
+					value = v;
+				endfunction
+			endclass
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn local_member_from_subclass() {
+	compile_to_hir(parse("
+		module foo;
+			class Base;
+				local int secret;
+			endclass
+			class Derived extends Base;
+				function int peek();
+					return secret;
+				endfunction
+			endclass
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn protected_member_from_outside() {
+	compile_to_hir(parse("
+		module foo;
+			class C;
+				protected int p;
+			endclass
+			C c;
+			int z;
+			initial z = c.p;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn unknown_member() {
+	compile_to_hir(parse("
+		module foo;
+			class Base;
+				int x;
+			endclass
+			class Derived extends Base;
+			endclass
+			Derived d;
+			initial d.y = 1;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn non_static_property_through_scope() {
+	compile_to_hir(parse("
+		module foo;
+			class C;
+				int x;
+			endclass
+			int z;
+			initial z = C::x;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn missing_interface_method() {
+	compile_to_hir(parse("
+		module foo;
+			interface class Getter;
+				pure virtual function int get();
+			endclass
+			class C implements Getter;
+			endclass
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn override_mismatch() {
+	compile_to_hir(parse("
+		module foo;
+			class Base;
+				virtual function int f(int a);
+					return a;
+				endfunction
+			endclass
+			class Derived extends Base;
+				virtual function int f(int a, int b);
+					return a + b;
+				endfunction
+			endclass
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn extend_interface_class() {
+	compile_to_hir(parse("
+		module foo;
+			interface class I;
+			endclass
+			class C extends I;
+			endclass
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn self_inheritance() {
+	compile_to_hir(parse("
+		module foo;
+			class A extends A;
+			endclass
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn mutual_inheritance() {
+	compile_to_hir(parse("
+		module foo;
+			class A extends B;
+			endclass
+			class B extends A;
+			endclass
+			A a;
+		endmodule
+	"));
+}
@@ -0,0 +1,144 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::ast;
+use common::moore_svlog::ast::{ClockingDir, ClockingItem, ClockingKind};
+
+
+#[test]
+fn clocking_block() {
+	let hir = compile_to_hir(parse("
+		module foo (input clk, input [7:0] data, output ack, inout z);
+			logic [7:0] n;
+			clocking cb @(posedge clk);
+				default input #1step output negedge #2;
+				input data;
+				output #1ns ack;
+				input #0 output #1 d = data[0];
+				inout z;
+			endclocking : cb
+			default clocking cb;
+			initial begin
+				##2;
+				cb.ack <= ##1 cb.data[0];
+				##(n+1) cb.ack <= 0;
+			end
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.body.clockings.len(), 1);
+	assert_eq!(m.body.default_clockings.len(), 1);
+	let cb = &m.body.clockings[0];
+	assert_eq!(cb.kind, ClockingKind::Normal);
+	assert_eq!(cb.items.len(), 5);
+	match cb.items[0] {
+		ClockingItem::DefaultSkew{ref input, ref output, ..} => {
+			assert!(input.as_ref().map(|s| s.delay.is_some()) == Some(true));
+			assert!(output.as_ref().map(|s| s.edge) == Some(ast::EdgeIdent::Negedge));
+		}
+		ref x => panic!("expected default skew, got {:?}", x),
+	}
+	match cb.items[3] {
+		ClockingItem::Signals{dir: ClockingDir::InputOutput(Some(_), Some(_)), ref signals, ..} => {
+			assert!(signals[0].expr.is_some());
+		}
+		ref x => panic!("expected input/output signals, got {:?}", x),
+	}
+}
+
+#[test]
+fn default_and_global_clocking() {
+	let hir = compile_to_hir(parse("
+		module foo (input clk, output logic q);
+			default clocking @(posedge clk);
+				output q;
+			endclocking
+			global clocking sys @(clk); endclocking
+			initial ##1 q = 1;
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.body.clockings[0].kind, ClockingKind::Default);
+	assert!(m.body.clockings[0].name.is_none());
+	assert_eq!(m.body.clockings[1].kind, ClockingKind::Global);
+}
+
+#[test]
+fn modport_clocking() {
+	compile_to_hir(parse("
+		interface bus (input clk);
+			logic req, gnt;
+			clocking cb @(posedge clk);
+				output req;
+				input gnt;
+			endclocking
+			modport tb (clocking cb);
+		endinterface
+		module foo;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn cycle_delay_without_default_clocking() {
+	compile_to_hir(parse("
+		module foo (input clk);
+			clocking cb @(posedge clk); endclocking
+			initial ##1;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn unknown_clockvar() {
+	compile_to_hir(parse("
+		module foo (input clk, a, b);
+			clocking cb @(posedge clk);
+				input a;
+			endclocking
+			initial $display(cb.b);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn modport_clocking_non_clocking() {
+	compile_to_hir(parse("
+		interface bus;
+			logic req;
+			modport tb (clocking req);
+		endinterface
+		module foo;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn multiple_default_clockings() {
+	compile_to_hir(parse("
+		module foo (input clk);
+			clocking a @(posedge clk); endclocking
+			clocking b @(negedge clk); endclocking
+			default clocking a;
+			default clocking b;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn global_clocking_items() {
+	parse("
+		module foo (input clk, a);
+			global clocking @(posedge clk);
+				input a;
+			endclocking
+		endmodule
+	");
+}
@@ -4,6 +4,12 @@ pub extern crate moore_svlog;
 use self::moore_svlog::*;
 use self::moore_common::source::get_source_manager;
 use self::moore_common::Session;
+use self::moore_common::errors::DiagBuilder2;
+use std::cell::RefCell;
+
+// The spans of the nodes of the design most recently lowered on this thread,
+// used to find expressions by their source text.
+thread_local!(static SPANS: RefCell<renumber::NodeSpans> = RefCell::new(Default::default()));
 
 pub fn parse(input: &str) -> Vec<ast::Root> {
 	use std::cell::Cell;
@@ -23,9 +29,44 @@ pub fn parse(input: &str) -> Vec<ast::Root> {
 	}
 }
 
-pub fn compile_to_hir(mut asts: Vec<ast::Root>) -> hir::Root {
+pub fn compile_to_hir(asts: Vec<ast::Root>) -> hir::Root {
+	lower(asts, false)
+}
+
+/// Lower to HIR and assign types to all expressions.
+pub fn typecheck(asts: Vec<ast::Root>) -> hir::Root {
+	lower(asts, true)
+}
+
+/// Lower to HIR and assign types to all expressions, returning the
+/// diagnostics of the type checker.
+pub fn typecheck_diags(asts: Vec<ast::Root>) -> Vec<DiagBuilder2> {
+	with_hir(asts, false, |session, nameres, mut hir| hir::check_types(session, nameres, &mut hir))
+}
+
+/// Lower to HIR, type check, and run the driver analysis.
+pub fn lint(asts: Vec<ast::Root>) -> Vec<DiagBuilder2> {
+	with_hir(asts, true, |session, nameres, hir| hir::check_drivers(session, nameres, &hir))
+}
+
+/// Resolve names and generate a DPI header with include guard `FOO_H`.
+pub fn dpi_header(mut asts: Vec<ast::Root>) -> Result<String, ()> {
 	let session = Session::new();
 	renumber::renumber(&mut asts);
+	let nameres = resolve::resolve(&session, &asts)?;
+	dpi::generate_header(&asts, &nameres, "FOO_H")
+}
+
+fn lower(asts: Vec<ast::Root>, typeck: bool) -> hir::Root {
+	with_hir(asts, typeck, |_, _, hir| hir)
+}
+
+fn with_hir<F, R>(mut asts: Vec<ast::Root>, typeck: bool, f: F) -> R
+	where F: FnOnce(&Session, &resolve::NameResolution, hir::Root) -> R
+{
+	let session = Session::new();
+	let spans = renumber::renumber(&mut asts);
+	SPANS.with(|s| *s.borrow_mut() = spans);
 	let nameres = resolve::resolve(&session, &asts).expect("name resolution failed");
 	let top = (||{
 		for ast in &asts {
@@ -37,7 +78,30 @@ pub fn compile_to_hir(mut asts: Vec<ast::Root>) -> hir::Root {
 		}
 		panic!("no module found");
 	})();
-	hir::lower(&session, &nameres, top, asts).expect("lowering to hir failed")
+	let mut hir = hir::lower(&session, &nameres, top, asts).expect("lowering to hir failed");
+	if typeck {
+		hir::typeck(&session, &nameres, &mut hir).expect("type checking failed");
+	}
+	f(&session, &nameres, hir)
+}
+
+/// Find the type assigned to the expression with the given source text.
+pub fn type_id_of(scope: &hir::InstScope, expr: &str) -> hir::TyId {
+	let found = SPANS.with(|spans| {
+		let spans = spans.borrow();
+		scope.types.iter()
+			.find(|&(&id, _)| spans.get(id).map(|s| s.extract() == expr).unwrap_or(false))
+			.map(|(_, &ty)| ty)
+	});
+	match found {
+		Some(ty) => ty,
+		None => panic!("no type assigned to `{}`", expr),
+	}
+}
+
+/// Format the type assigned to the expression with the given source text.
+pub fn type_of(hir: &hir::Root, scope: &hir::InstScope, expr: &str) -> String {
+	format!("{}", hir.tys.display(type_id_of(scope, expr)))
 }
 
 pub fn unwrap_single_module(hir: &hir::Root) -> &hir::Module {
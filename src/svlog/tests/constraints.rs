@@ -0,0 +1,310 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::ast;
+use common::moore_svlog::ast::ConstraintItemData;
+
+
+/// Parse a class and return the items of its constraint blocks.
+fn constraints(input: &str) -> Vec<ast::ConstraintItem> {
+	let asts = parse(input);
+	let mut items = Vec::new();
+	for item in &asts[0].items {
+		if let ast::Item::Class(ref decl) = *item {
+			for ci in &decl.items {
+				if let ast::ClassItemData::Constraint(ref c) = ci.data {
+					items.extend(c.items.iter().cloned());
+				}
+			}
+		}
+	}
+	items
+}
+
+#[test]
+fn expressions() {
+	let items = constraints("
+		class foo;
+			rand bit [7:0] x, y;
+			constraint c {
+				x < 10;
+				soft y == 3;
+				x == 1 ? y > 2 : y < 2;
+			}
+		endclass
+	");
+	assert_eq!(items.len(), 3);
+	match items[1].data {
+		ConstraintItemData::Expr { soft, .. } => assert!(soft),
+		ref x => panic!("expected soft expression, got {:?}", x),
+	}
+	match items[2].data {
+		ConstraintItemData::Expr { expr: ast::Expr { data: ast::TernaryExpr { .. }, .. }, .. } => (),
+		ref x => panic!("expected conditional expression, got {:?}", x),
+	}
+}
+
+#[test]
+fn if_and_foreach() {
+	let items = constraints("
+		class foo;
+			rand bit [7:0] data [4];
+			rand bit mode;
+			constraint c {
+				if (mode) {
+					data[0] == 0;
+					data[1] == 1;
+				} else if (data[2] > 3)
+					data[3] == 0;
+				else
+					data[3] == 1;
+				foreach (data[i]) {
+					data[i] < 100;
+					if (i > 0) data[i] > data[i-1];
+				}
+			}
+		endclass
+	");
+	assert_eq!(items.len(), 2);
+	match items[0].data {
+		ConstraintItemData::If { ref main_items, else_items: Some(ref else_items), .. } => {
+			assert_eq!(main_items.len(), 2);
+			assert_eq!(else_items.len(), 1);
+			match else_items[0].data {
+				ConstraintItemData::If { else_items: Some(_), .. } => (),
+				ref x => panic!("expected nested if, got {:?}", x),
+			}
+		}
+		ref x => panic!("expected if, got {:?}", x),
+	}
+	match items[1].data {
+		ConstraintItemData::Foreach { ref vars, ref items, .. } => {
+			assert_eq!(vars.len(), 1);
+			assert_eq!(items.len(), 2);
+		}
+		ref x => panic!("expected foreach, got {:?}", x),
+	}
+}
+
+#[test]
+fn dist() {
+	let items = constraints("
+		class foo;
+			rand int x;
+			constraint c {
+				x dist { 0 := 1, [1:7] :/ 3, 8 };
+				soft x dist { [0:3] := 2 };
+			}
+		endclass
+	");
+	match items[0].data {
+		ConstraintItemData::Dist { soft: false, ref items, .. } => {
+			assert_eq!(items.len(), 3);
+			assert_eq!(items[0].weight.as_ref().map(|w| w.0), Some(ast::DistWeight::PerValue));
+			assert_eq!(items[1].weight.as_ref().map(|w| w.0), Some(ast::DistWeight::PerRange));
+			match items[1].value.data {
+				ast::RangeExpr { .. } => (),
+				ref x => panic!("expected range, got {:?}", x),
+			}
+			assert!(items[2].weight.is_none());
+		}
+		ref x => panic!("expected dist, got {:?}", x),
+	}
+	match items[1].data {
+		ConstraintItemData::Dist { soft: true, .. } => (),
+		ref x => panic!("expected soft dist, got {:?}", x),
+	}
+}
+
+#[test]
+fn ordering_and_uniqueness() {
+	let items = constraints("
+		class foo;
+			rand bit mode;
+			rand int a, b, c;
+			rand int arr [4];
+			constraint c {
+				solve mode before a, b;
+				unique { a, b, arr, [0:3] };
+				disable soft c;
+			}
+		endclass
+	");
+	match items[0].data {
+		ConstraintItemData::SolveBefore(ref first, ref then) => {
+			assert_eq!(first.len(), 1);
+			assert_eq!(then.len(), 2);
+		}
+		ref x => panic!("expected solve-before, got {:?}", x),
+	}
+	match items[1].data {
+		ConstraintItemData::Unique(ref exprs) => assert_eq!(exprs.len(), 4),
+		ref x => panic!("expected unique, got {:?}", x),
+	}
+	match items[2].data {
+		ConstraintItemData::DisableSoft(_) => (),
+		ref x => panic!("expected disable soft, got {:?}", x),
+	}
+}
+
+#[test]
+fn implication() {
+	let items = constraints("
+		class foo;
+			rand bit mode;
+			rand int x, y;
+			constraint c {
+				mode -> x < 4;
+				!mode -> {
+					x > 4;
+					y == 0 -> x == 5;
+				}
+			}
+		endclass
+	");
+	match items[0].data {
+		ConstraintItemData::Implication { ref items, .. } => assert_eq!(items.len(), 1),
+		ref x => panic!("expected implication, got {:?}", x),
+	}
+	match items[1].data {
+		ConstraintItemData::Implication { ref items, .. } => {
+			assert_eq!(items.len(), 2);
+			match items[1].data {
+				ConstraintItemData::Implication { .. } => (),
+				ref x => panic!("expected nested implication, got {:?}", x),
+			}
+		}
+		ref x => panic!("expected implication, got {:?}", x),
+	}
+}
+
+#[test]
+fn randomize_with() {
+	parse("
+		module foo;
+			initial begin
+				ok = pkt.randomize() with { len < 10; kind dist { 0 := 1, 1 := 3 }; };
+				if (!pkt.randomize with (len) { len > 2; }) $stop;
+				ok = randomize(x) with { x > 0; };
+			end
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn soft_implication() {
+	parse("
+		class foo;
+			rand int x;
+			constraint c { soft x > 0 -> x < 4; }
+		endclass
+	");
+}
+
+#[test]
+fn inside() {
+	let items = constraints("
+		class foo;
+			rand int x, y;
+			constraint c {
+				x inside {[0:3], 7, y};
+				soft y inside {[x:$]};
+				!(x inside {1, 2}) -> y == 0;
+			}
+		endclass
+	");
+	match items[0].data {
+		ConstraintItemData::Expr { expr: ast::Expr { data: ast::InsideExpr(_, ref ranges), .. }, soft: false } => {
+			assert_eq!(ranges.len(), 3);
+			match ranges[0].data {
+				ast::RangeExpr { .. } => (),
+				ref x => panic!("expected range, got {:?}", x),
+			}
+		}
+		ref x => panic!("expected inside expression, got {:?}", x),
+	}
+	match items[1].data {
+		ConstraintItemData::Expr { expr: ast::Expr { data: ast::InsideExpr(..), .. }, soft: true } => (),
+		ref x => panic!("expected soft inside expression, got {:?}", x),
+	}
+	match items[2].data {
+		ConstraintItemData::Implication { .. } => (),
+		ref x => panic!("expected implication, got {:?}", x),
+	}
+}
+
+#[test]
+fn out_of_class() {
+	compile_to_hir(parse("
+		module foo;
+			class C;
+				rand int x, y;
+				constraint c;
+				static constraint d;
+			endclass
+			constraint C::c { x < 4; y inside {[x:8]}; }
+			static constraint C::d { foreach (arr[i]) arr[i] > y; }
+			int arr [4];
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn out_of_class_without_prototype() {
+	compile_to_hir(parse("
+		module foo;
+			class C;
+				rand int x;
+			endclass
+			constraint C::c { x < 4; }
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn out_of_class_without_class() {
+	parse("
+		module foo;
+			constraint c { x < 4; }
+		endmodule
+	");
+}
+
+#[test]
+fn randomize_with_members() {
+	compile_to_hir(parse("
+		module foo;
+			class C;
+				rand int len;
+				rand bit [1:0] kind;
+			endclass
+			C pkt;
+			bit ok;
+			int max;
+			initial begin
+				ok = pkt.randomize() with { len < max; kind inside {0, 2}; };
+				ok = pkt.randomize with (len) { len > 2; };
+			end
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn randomize_with_unknown_member() {
+	compile_to_hir(parse("
+		module foo;
+			class C;
+				rand int len;
+			endclass
+			C pkt;
+			bit ok;
+			initial ok = pkt.randomize() with { size < 10; };
+		endmodule
+	"));
+}
@@ -0,0 +1,143 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::ast;
+
+
+#[test]
+fn module_covergroup() {
+	compile_to_hir(parse("
+		module foo (input logic clk);
+			logic [3:0] a, b;
+			logic en;
+			covergroup cg @(posedge clk);
+				option.per_instance = 1;
+				type_option.weight = 2;
+				cp_a: coverpoint a iff (en) {
+					bins low = { [0:3] };
+					bins high[] = { [12:15] };
+					bins odd[2] = { [4:11] } with (item % 2 == 1);
+					wildcard bins w = { 4'b1?? };
+					illegal_bins bad = { 7 };
+					ignore_bins skip = { 8, 9 };
+					bins rest = default;
+				}
+				coverpoint b {
+					option.auto_bin_max = 4;
+					bins up = (0 => 1 => 2), (3 => 4[*2] => 5);
+					bins goto = (6[-> 1:3] => 7), (8[= 2] => 9);
+					bins other = default sequence;
+				}
+				ab: cross cp_a, b iff (en) {
+					bins lo = binsof(cp_a.low) && binsof(b) intersect { [0:3] };
+					ignore_bins hi = !binsof(cp_a.high) || (binsof(b.up) && binsof(cp_a));
+				}
+			endgroup : cg
+			cg inst;
+		endmodule
+	"));
+}
+
+#[test]
+fn sample_function() {
+	compile_to_hir(parse("
+		module foo;
+			covergroup cg (ref logic [7:0] v, input int max) with function sample (logic [7:0] x);
+				coverpoint x {
+					bins lo = { [0:max] };
+				}
+				coverpoint v;
+			endgroup
+		endmodule
+	"));
+}
+
+#[test]
+fn class_covergroup() {
+	let asts = parse("
+		class foo;
+			rand bit [3:0] a;
+			covergroup cg;
+				coverpoint a;
+			endgroup
+		endclass
+	");
+	match asts[0].items[0] {
+		ast::Item::Class(ref decl) => match decl.items[1].data {
+			ast::ClassItemData::CovergroupDecl(ref cg) => assert_eq!(cg.items.len(), 1),
+			ref x => panic!("expected covergroup, got {:?}", x),
+		},
+		ref x => panic!("expected class, got {:?}", x),
+	}
+}
+
+#[test]
+fn covergroup_instantiation() {
+	compile_to_hir(parse("
+		module foo (input logic clk);
+			logic [7:0] a;
+			covergroup cg @(posedge clk);
+				coverpoint a;
+			endgroup
+			covergroup cg_max (input int max);
+				coverpoint a { bins lo = { [0:max] }; }
+			endgroup
+			cg c = new;
+			cg_max m = new(15);
+			class bar;
+				rand bit [3:0] b;
+				covergroup cov;
+					coverpoint b;
+				endgroup
+				function new();
+					cov = new;
+				endfunction
+			endclass
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn cross_unknown_coverpoint() {
+	compile_to_hir(parse("
+		module foo;
+			logic a, b;
+			covergroup cg;
+				cp_a: coverpoint a;
+				cross cp_a, cp_b;
+			endgroup
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn binsof_unknown_bin() {
+	compile_to_hir(parse("
+		module foo;
+			logic a, b;
+			covergroup cg;
+				cp_a: coverpoint a { bins zero = {0}; }
+				cross cp_a, b {
+					bins x = binsof(cp_a.one);
+				}
+			endgroup
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn mismatched_end_label() {
+	parse("
+		module foo;
+			logic a;
+			covergroup cg;
+				coverpoint a;
+			endgroup : other
+		endmodule
+	");
+}
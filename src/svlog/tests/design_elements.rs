@@ -0,0 +1,111 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+
+
+#[test]
+fn extern_module_wildcard_ports() {
+	let hir = compile_to_hir(parse("
+		extern module foo #(parameter N = 4) (input [N-1:0] a, output [N-1:0] b);
+		module foo (.*);
+			assign b = ~a;
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.params.len(), 1);
+	assert_eq!(m.ports.len(), 2);
+	assert_eq!(m.body.assigns.len(), 1);
+}
+
+#[test]
+fn extern_module_repeated_header() {
+	let hir = compile_to_hir(parse("
+		module foo (input a, output b);
+			assign b = a;
+		endmodule
+		extern module foo (input a, output b);
+	"));
+	assert_eq!(unwrap_single_module(&hir).ports.len(), 2);
+}
+
+#[test]
+fn extern_interface_wildcard_ports() {
+	let hir = compile_to_hir(parse("
+		module top;
+			logic clk;
+			bus b (clk);
+		endmodule
+		extern interface bus (input clk);
+		interface bus (.*);
+			logic valid;
+		endinterface
+	"));
+	let intf = hir.intfs.values().next().unwrap();
+	assert_eq!(intf.ports.len(), 1);
+}
+
+#[test]
+#[should_panic]
+fn extern_module_port_mismatch() {
+	compile_to_hir(parse("
+		extern module foo (input a, output b);
+		module foo (input a, output c);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn wildcard_ports_without_extern() {
+	compile_to_hir(parse("
+		module foo (.*);
+		endmodule
+	"));
+}
+
+#[test]
+fn nested_modules() {
+	let hir = compile_to_hir(parse("
+		module top;
+			timeunit 1us;
+			logic a, b;
+			inner i (.x(a), .y(b));
+			module inner (input x, output y);
+				assign y = x;
+			endmodule
+		endmodule
+	"));
+	assert_eq!(hir.mods.len(), 2);
+	let inner = hir.mods.values().find(|m| &*m.name.as_str() == "inner").unwrap();
+	assert_eq!(inner.timescale.unit, 1e-6);
+	assert_eq!(hir.design.scope.body.insts.len(), 1);
+}
+
+#[test]
+#[should_panic]
+fn nested_module_not_visible_outside() {
+	compile_to_hir(parse("
+		module top;
+			module inner;
+			endmodule
+		endmodule
+		module other;
+			inner i ();
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn module_in_package() {
+	compile_to_hir(parse("
+		package p;
+			module inner;
+			endmodule
+		endpackage
+		module top;
+		endmodule
+	"));
+}
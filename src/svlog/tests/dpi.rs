@@ -0,0 +1,209 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::ast;
+
+
+#[test]
+fn import_export() {
+	compile_to_hir(parse("
+		module foo;
+			import \"DPI-C\" pure function int c_add(input int a, input int b);
+			import \"DPI-C\" context c_log = task log_msg(string msg);
+			import \"DPI\" function void reset();
+			export \"DPI-C\" function sv_add;
+			export \"DPI-C\" c_tick = task tick;
+			int x;
+			function int sv_add(input int a, input int b);
+				return a + b;
+			endfunction
+			task tick;
+			endtask
+			initial begin
+				x = c_add(1, 2);
+				log_msg(\"hello\");
+				reset();
+			end
+		endmodule
+	"));
+}
+
+#[test]
+fn root_import() {
+	compile_to_hir(parse("
+		import \"DPI-C\" function int c_rand();
+		module foo;
+			int x;
+			initial x = c_rand();
+		endmodule
+	"));
+}
+
+#[test]
+fn qualifiers() {
+	let asts = parse("
+		package p;
+			import \"DPI-C\" context c_name = function void f();
+		endpackage
+	");
+	match asts[0].items[0] {
+		ast::Item::Package(ref decl) => match decl.items[0] {
+			ast::HierarchyItem::DpiDecl(ref dpi) => {
+				assert_eq!(&*dpi.c_name().as_str(), "c_name");
+				assert_eq!(&*dpi.sv_name().name.as_str(), "f");
+				match *dpi {
+					ast::DpiDecl::Import { property, .. } => assert_eq!(property, Some(ast::DpiProperty::Context)),
+					ref x => panic!("expected DPI import, got {:?}", x),
+				}
+			}
+			ref x => panic!("expected DPI declaration, got {:?}", x),
+		},
+		ref x => panic!("expected package, got {:?}", x),
+	}
+}
+
+#[test]
+fn header() {
+	let asts = parse("
+		package p;
+			import \"DPI-C\" function int c_add(input int a, int unsigned b);
+			import \"DPI-C\" context c_log = task log_msg(string msg, output bit [7:0] code);
+			import \"DPI-C\" function void fill(inout logic [31:0] data [], input byte n, output chandle h);
+			import \"DPI-C\" function real scale(shortreal x, longint y, bit b, logic l, int arr [4]);
+		endpackage
+		module foo;
+			export \"DPI-C\" function sv_cb;
+			function logic sv_cb(input int x, output logic y);
+			endfunction
+		endmodule
+	");
+	let header = dpi_header(asts).unwrap();
+	assert!(header.contains("#ifndef FOO_H\n"));
+	assert!(header.contains("#include \"svdpi.h\"\n"));
+	assert!(header.contains("extern int c_add(int a, unsigned int b);\n"));
+	assert!(header.contains("extern int c_log(const char* msg, svBitVecVal* code);\n"));
+	assert!(header.contains("extern void fill(svOpenArrayHandle data, char n, void** h);\n"));
+	assert!(header.contains("extern double scale(float x, long long y, svBit b, svLogic l, const int* arr);\n"));
+	assert!(header.contains("extern svLogic sv_cb(int x, svLogic* y);\n"));
+}
+
+#[test]
+fn header_typedef_args() {
+	let asts = parse("
+		package p;
+			typedef int unsigned word_t;
+		endpackage
+		module foo;
+			typedef int my_int_t;
+			typedef my_int_t alias_t;
+			typedef bit [7:0] byte_vec_t;
+			import \"DPI-C\" function void f(my_int_t x, alias_t y, p::word_t z, byte_vec_t v);
+		endmodule
+	");
+	let header = dpi_header(asts).unwrap();
+	assert!(header.contains("extern void f(int x, int y, unsigned int z, const svBitVecVal* v);\n"));
+}
+
+#[test]
+fn header_imported_typedef_args() {
+	let asts = parse("
+		package p;
+			typedef int word_t;
+		endpackage
+		package q;
+			typedef int unsigned word_t;
+		endpackage
+		module foo;
+			import q::*;
+			import \"DPI-C\" function void f(word_t x);
+		endmodule
+	");
+	let header = dpi_header(asts).unwrap();
+	assert!(header.contains("extern void f(unsigned int x);\n"));
+}
+
+#[test]
+fn header_rejects_ambiguous_typedef() {
+	let asts = parse("
+		package p;
+			typedef int word_t;
+		endpackage
+		package q;
+			typedef int unsigned word_t;
+		endpackage
+		module foo;
+			import p::*;
+			import q::*;
+			import \"DPI-C\" function void f(word_t x);
+		endmodule
+	");
+	assert!(dpi_header(asts).is_err());
+}
+
+#[test]
+fn header_rejects_invisible_typedef() {
+	let asts = parse("
+		package p;
+			typedef int word_t;
+		endpackage
+		module foo;
+			import \"DPI-C\" function void f(word_t x);
+		endmodule
+	");
+	assert!(dpi_header(asts).is_err());
+}
+
+#[test]
+fn header_rejects_class_args() {
+	let asts = parse("
+		module foo;
+			class C;
+			endclass
+			import \"DPI-C\" function void f(C c);
+		endmodule
+	");
+	assert!(dpi_header(asts).is_err());
+}
+
+#[test]
+fn header_rejects_packed_return() {
+	let asts = parse("
+		module foo;
+			import \"DPI-C\" function bit [7:0] f();
+		endmodule
+	");
+	assert!(dpi_header(asts).is_err());
+}
+
+#[test]
+#[should_panic]
+fn export_non_subroutine() {
+	compile_to_hir(parse("
+		module foo;
+			int x;
+			export \"DPI-C\" function x;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn pure_task() {
+	parse("
+		module foo;
+			import \"DPI-C\" pure task t();
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn unknown_spec() {
+	parse("
+		module foo;
+			import \"VHPI\" function void f();
+		endmodule
+	");
+}
@@ -0,0 +1,153 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_common::errors::{DiagBuilder2, DiagSegment};
+
+
+/// Format diagnostics as `severity: message`.
+fn messages(diags: &[DiagBuilder2]) -> Vec<String> {
+	diags.iter().map(|d| format!("{}: {}", d.get_severity().to_str(), d.get_message())).collect()
+}
+
+/// The source text of the spans attached to a diagnostic.
+fn span_texts(diag: &DiagBuilder2) -> Vec<String> {
+	diag.get_segments().iter().filter_map(|s| match *s {
+		DiagSegment::Span(sp) => Some(sp.extract()),
+		_ => None,
+	}).collect()
+}
+
+#[test]
+fn multiple_always_ff() {
+	let diags = lint(parse("
+		module top (input clk, input a, output logic q);
+			always_ff @(posedge clk) q <= a;
+			always_ff @(posedge clk) q <= ~a;
+		endmodule
+	"));
+	assert_eq!(messages(&diags), vec!["error: `q` is driven by more than one process"]);
+	assert_eq!(span_texts(&diags[0]), vec!["q", "q"]);
+}
+
+#[test]
+fn assign_and_always() {
+	let diags = lint(parse("
+		module top (input a, output logic y);
+			assign y = a;
+			always @* y = ~a;
+		endmodule
+	"));
+	assert_eq!(messages(&diags), vec!["error: `y` is driven by more than one process"]);
+}
+
+#[test]
+fn always_comb_written_elsewhere() {
+	let diags = lint(parse("
+		module top (input a, output logic [1:0] y);
+			always_comb y[0] = a;
+			initial y[0] = 0;
+			initial y[1] = 1;
+		endmodule
+	"));
+	assert_eq!(messages(&diags), vec!["error: `y` is driven by more than one process"]);
+	assert_eq!(span_texts(&diags[0]), vec!["y[0]", "y[0]"]);
+}
+
+#[test]
+fn output_port_and_assign() {
+	let diags = lint(parse("
+		module top (input a, output logic y);
+			assign y = a;
+			inv u_inv (.a(a), .y(y));
+		endmodule
+		module inv (input a, output y);
+			assign y = ~a;
+		endmodule
+	"));
+	assert_eq!(messages(&diags), vec!["error: `y` is driven by more than one process"]);
+}
+
+#[test]
+fn disjoint_selects() {
+	let diags = lint(parse("
+		module top (input a, output logic [3:0] y);
+			localparam int N = 2;
+			assign y[0] = a;
+			assign y[1] = ~a;
+			always_comb y[N+1:N] = {a, a};
+		endmodule
+	"));
+	assert_eq!(messages(&diags), Vec::<String>::new());
+}
+
+#[test]
+fn generate_loop_copies() {
+	let diags = lint(parse("
+		module top (input a, output logic [1:0] y);
+			for (genvar i = 0; i < 2; i++) begin : g
+				logic x;
+				assign x = a;
+				assign y[i] = x;
+			end
+		endmodule
+	"));
+	assert_eq!(messages(&diags), Vec::<String>::new());
+}
+
+#[test]
+fn multiple_always_allowed() {
+	let diags = lint(parse("
+		module top (input clk, input a, output logic q);
+			initial q = 0;
+			always @(posedge clk) q = a;
+		endmodule
+	"));
+	assert_eq!(messages(&diags), Vec::<String>::new());
+}
+
+#[test]
+fn undriven_output() {
+	let diags = lint(parse("
+		module top (input a, output logic y, output z);
+			assign z = a;
+		endmodule
+	"));
+	assert_eq!(messages(&diags), vec!["warning: output `y` of `top` is never driven"]);
+}
+
+#[test]
+fn unread_input() {
+	let diags = lint(parse("
+		module top (input a, input b, output y);
+			assign y = a;
+		endmodule
+	"));
+	assert_eq!(messages(&diags), vec!["warning: input `b` of `top` is never read"]);
+}
+
+#[test]
+fn ports_used_through_instances() {
+	let diags = lint(parse("
+		module top (input a, output y);
+			inv u_inv (.*);
+		endmodule
+		module inv (input a, output y);
+			assign y = ~a;
+		endmodule
+	"));
+	assert_eq!(messages(&diags), Vec::<String>::new());
+}
+
+#[test]
+fn unused_declaration() {
+	let diags = lint(parse("
+		module top (input a, output y);
+			logic x;
+			wire w = a;
+			assign y = w;
+		endmodule
+	"));
+	assert_eq!(messages(&diags), vec!["warning: `x` is declared but never used"]);
+}
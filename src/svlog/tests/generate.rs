@@ -0,0 +1,193 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+
+
+#[test]
+fn generate_for() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			genvar i;
+			for (i = 0; i < 4; i++) begin : gen_blk
+				logic x;
+			end
+		endmodule
+	"));
+	let names: Vec<_> = hir.design.scope.scopes.iter().map(|s| format!("{}", s.name)).collect();
+	assert_eq!(names, vec!["gen_blk[0]", "gen_blk[1]", "gen_blk[2]", "gen_blk[3]"]);
+	for s in &hir.design.scope.scopes {
+		assert_eq!(s.body.vars.len(), 1);
+	}
+}
+
+#[test]
+fn generate_for_inline_genvar() {
+	let hir = compile_to_hir(parse("
+		module foo #(parameter N = 3);
+			for (genvar i = N; i > 0; i = i - 1) begin : g
+			end
+		endmodule
+	"));
+	assert!(hir.design.scope.find_scope("g[3]").is_some());
+	assert!(hir.design.scope.find_scope("g[1]").is_some());
+	assert!(hir.design.scope.find_scope("g[0]").is_none());
+}
+
+#[test]
+fn generate_if() {
+	let hir = compile_to_hir(parse("
+		module foo #(parameter W = 8);
+			if (W > 4) begin : wide
+				logic [W-1:0] x;
+			end else begin : narrow
+				logic x;
+			end
+			if (W == 1) begin end
+			else if (W == 8) begin end
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert!(s.find_scope("wide").is_some());
+	assert!(s.find_scope("narrow").is_none());
+	assert!(s.find_scope("genblk2").is_some());
+}
+
+/// Shifting by the width of a constant or more shifts out all bits, and
+/// negative exponents only yield non-zero values for bases 1 and -1.
+#[test]
+fn generate_if_shift_and_pow() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			parameter N = 1 << 64;
+			parameter M = -8 >>> 70;
+			parameter B = -1;
+			parameter P = B ** -3;
+			if (N == 0) begin : a end
+			if (M == -1) begin : b end
+			if (P == -1) begin : c end
+			if (1 ** -2 == 1) begin : d end
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert!(s.find_scope("a").is_some());
+	assert!(s.find_scope("b").is_some());
+	assert!(s.find_scope("c").is_some());
+	assert!(s.find_scope("d").is_some());
+}
+
+#[test]
+fn generate_case() {
+	let hir = compile_to_hir(parse("
+		module top;
+			foo #(2) f2();
+			foo #(5) f5();
+		endmodule
+		module foo #(parameter N = 1);
+			case (N)
+				0, 1: begin : narrow
+					logic x;
+				end
+				2: begin : pair
+					logic [1:0] x;
+					bar b();
+				end
+				default: begin : wide
+					logic [N-1:0] x;
+				end
+			endcase
+			case (N)
+				2: if (N > 1) begin : nested end
+			endcase
+		endmodule
+		module bar;
+		endmodule
+	"));
+	let f2 = &hir.design.scope.find_inst("f2").unwrap().scope;
+	let names: Vec<_> = f2.scopes.iter().map(|s| format!("{}", s.name)).collect();
+	assert_eq!(names, vec!["pair", "nested"]);
+	assert!(f2.find_scope("pair").unwrap().find_inst("b").is_some());
+	let f5 = &hir.design.scope.find_inst("f5").unwrap().scope;
+	let names: Vec<_> = f5.scopes.iter().map(|s| format!("{}", s.name)).collect();
+	assert_eq!(names, vec!["wide"]);
+}
+
+#[test]
+fn generate_case_no_match() {
+	let hir = compile_to_hir(parse("
+		module foo #(parameter N = 3);
+			case (N)
+				1: begin logic x; end
+				2: begin logic y; end
+			endcase
+			case (N)
+				3: begin end
+			endcase
+		endmodule
+	"));
+	let names: Vec<_> = hir.design.scope.scopes.iter().map(|s| format!("{}", s.name)).collect();
+	assert_eq!(names, vec!["genblk2"]);
+}
+
+#[test]
+fn generate_nested_in_instance() {
+	let hir = compile_to_hir(parse("
+		module top;
+			bar #(.N(2)) b0();
+			bar #(3) b1();
+		endmodule
+		module bar #(parameter N = 1);
+			genvar i, j;
+			for (i = 0; i < N; i++) begin : outer
+				for (j = 0; j < i; j++) begin : inner
+				end
+			end
+		endmodule
+	"));
+	let b0 = hir.design.scope.find_inst("b0").unwrap();
+	let b1 = hir.design.scope.find_inst("b1").unwrap();
+	assert_eq!(b0.scope.scopes.len(), 2);
+	assert_eq!(b1.scope.scopes.len(), 3);
+	let outer = b1.scope.find_scope("outer[2]").unwrap();
+	assert_eq!(outer.scopes.len(), 2);
+	assert!(outer.find_scope("inner[1]").is_some());
+}
+
+/// The index of a generate loop must be a genvar.
+#[test]
+#[should_panic]
+fn generate_for_not_genvar() {
+	compile_to_hir(parse("
+		module foo;
+			int i;
+			for (i = 0; i < 4; i++) begin end
+		endmodule
+	"));
+}
+
+/// A genvar cannot be used by two nested loops at the same time.
+#[test]
+#[should_panic]
+fn generate_for_genvar_reused() {
+	compile_to_hir(parse("
+		module foo;
+			genvar i;
+			for (i = 0; i < 4; i++) begin
+				for (i = 0; i < 4; i++) begin end
+			end
+		endmodule
+	"));
+}
+
+/// A generate loop that does not terminate must be rejected.
+#[test]
+#[should_panic]
+fn generate_for_infinite() {
+	compile_to_hir(parse("
+		module foo;
+			genvar i;
+			for (i = 0; i < 4; i = i) begin end
+		endmodule
+	"));
+}
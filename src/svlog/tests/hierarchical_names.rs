@@ -0,0 +1,193 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::hir;
+
+
+/// The hierarchical paths of the references made within a scope, sorted.
+fn paths(scope: &hir::InstScope) -> Vec<String> {
+	let mut v: Vec<String> = scope.hier_refs.values().map(|r| {
+		let names: Vec<String> = r.path.iter().map(|n| format!("{}", n)).collect();
+		names.join(".")
+	}).collect();
+	v.sort();
+	v
+}
+
+#[test]
+fn downward_references() {
+	let hir = compile_to_hir(parse("
+		module top;
+			core u_core();
+			logic [7:0] a, b;
+			assign a = u_core.u_alu.result;
+			assign b = top.u_core.u_alu.result;
+		endmodule
+		module alu;
+			logic [7:0] result;
+		endmodule
+		module core;
+			alu u_alu();
+		endmodule
+	"));
+	assert_eq!(paths(&hir.design.scope), vec!["top.u_core.u_alu.result", "top.u_core.u_alu.result"]);
+}
+
+#[test]
+fn upward_references() {
+	let hir = compile_to_hir(parse("
+		module top;
+			a u_a();
+			b u_b();
+		endmodule
+		module a;
+			logic x;
+		endmodule
+		module b;
+			logic y;
+			assign y = u_a.x;
+		endmodule
+	"));
+	let u_b = &hir.design.scope.insts[1];
+	assert_eq!(paths(&u_b.scope), vec!["top.u_a.x"]);
+}
+
+#[test]
+fn generate_scopes() {
+	let hir = compile_to_hir(parse("
+		module top;
+			for (genvar i = 0; i < 2; i++) begin : g
+				logic x;
+			end
+			if (1) begin : c
+				logic y;
+			end
+			logic p, q;
+			assign p = g[1].x;
+			assign q = c.y;
+		endmodule
+	"));
+	assert_eq!(paths(&hir.design.scope), vec!["top.c.y", "top.g[1].x"]);
+}
+
+#[test]
+fn references_within_generate_loops() {
+	let hir = compile_to_hir(parse("
+		module top;
+			for (genvar i = 0; i < 2; i++) begin : g
+				leaf u();
+				logic y;
+				assign y = u.x;
+			end
+		endmodule
+		module leaf;
+			logic x;
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(paths(&s.scopes[0]), vec!["top.g[0].u.x"]);
+	assert_eq!(paths(&s.scopes[1]), vec!["top.g[1].u.x"]);
+}
+
+#[test]
+fn selects_from_declarations() {
+	let hir = compile_to_hir(parse("
+		module top;
+			for (genvar i = 0; i < 2; i++) begin : g
+				leaf u();
+			end
+			logic [7:0] y;
+			logic [1:0] k;
+			assign y = g[0].u.mem[k];
+		endmodule
+		module leaf;
+			logic [7:0] mem [4];
+		endmodule
+	"));
+	assert_eq!(paths(&hir.design.scope), vec!["top.g[0].u.mem"]);
+}
+
+#[test]
+fn interface_ports() {
+	let hir = compile_to_hir(parse("
+		module top;
+			bus u_bus();
+			sink u_sink(.b(u_bus));
+			logic w;
+			assign w = u_sink.b.valid;
+		endmodule
+		interface bus;
+			logic valid;
+		endinterface
+		module sink (bus b);
+			logic v;
+			assign v = b.valid;
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(paths(s), vec!["top.u_bus.valid"]);
+	assert_eq!(paths(&s.insts[1].scope), vec!["top.u_bus.valid"]);
+}
+
+#[test]
+fn named_blocks() {
+	let hir = compile_to_hir(parse("
+		module top;
+			leaf u();
+			logic y;
+			assign y = u.blk.x;
+		endmodule
+		module leaf;
+			initial begin : blk
+				logic x;
+			end
+		endmodule
+	"));
+	assert_eq!(paths(&hir.design.scope), vec!["top.u.blk.x"]);
+}
+
+#[test]
+#[should_panic]
+fn dangling_path() {
+	compile_to_hir(parse("
+		module top;
+			leaf u();
+			logic y;
+			assign y = u.z;
+		endmodule
+		module leaf;
+			logic x;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn undefined_root() {
+	compile_to_hir(parse("
+		module top;
+			logic y;
+			assign y = nowhere.x;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn ambiguous_path() {
+	compile_to_hir(parse("
+		module top;
+			leaf u();
+			initial begin : u
+				logic x;
+			end
+			logic y;
+			assign y = u.x;
+		endmodule
+		module leaf;
+			logic x;
+		endmodule
+	"));
+}
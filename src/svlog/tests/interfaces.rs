@@ -0,0 +1,166 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::{ast, hir};
+
+
+/// The interface with the given name.
+fn find_intf<'a>(hir: &'a hir::Root, name: &str) -> &'a hir::Interface {
+	hir.intfs.values().find(|i| &*i.name.as_str() == name).expect("interface not found")
+}
+
+/// The binding of the only interface port of an instance.
+fn single_binding(inst: &hir::Instance) -> &hir::IntfBinding {
+	assert_eq!(inst.intf_ports.len(), 1);
+	inst.intf_ports.values().next().unwrap()
+}
+
+fn path(binding: &hir::IntfBinding) -> String {
+	let names: Vec<String> = binding.path.iter().map(|n| format!("{}", n)).collect();
+	names.join(".")
+}
+
+#[test]
+fn modport_ports() {
+	let hir = compile_to_hir(parse("
+		module top;
+			bus u_bus();
+		endmodule
+		interface bus;
+			logic req, ack;
+			logic [7:0] data;
+			function void reset; endfunction
+			modport master (output req, input ack, .first(data[0]), import reset);
+		endinterface
+	"));
+	let mp = &find_intf(&hir, "bus").body.modports[0];
+	assert_eq!(&*mp.name.as_str(), "master");
+	let ports: Vec<String> = mp.ports.iter().map(|p| format!("{}", p.name)).collect();
+	assert_eq!(ports, vec!["req", "ack", "first", "reset"]);
+	match mp.ports[0].kind {
+		hir::ModportPortKind::Simple(ast::PortDir::Output, Some(ref e)) => match e.kind {
+			hir::ExprKind::Ident(ref i) => assert!(i.def.is_some()),
+			_ => panic!("expected an identifier"),
+		},
+		_ => panic!("expected an output port"),
+	}
+	match mp.ports[2].kind {
+		hir::ModportPortKind::Simple(ast::PortDir::Input, Some(ref e)) => assert_eq!(e.span.extract(), "data[0]"),
+		_ => panic!("expected an input port with an expression"),
+	}
+	match mp.ports[3].kind {
+		hir::ModportPortKind::Subroutine(ast::ModportTfKind::Import, Some(_)) => (),
+		_ => panic!("expected an imported subroutine"),
+	}
+}
+
+#[test]
+#[should_panic]
+fn modport_port_not_a_variable() {
+	compile_to_hir(parse("
+		module top;
+		endmodule
+		interface bus;
+			logic req;
+			function void reset; endfunction
+			modport master (output reset);
+		endinterface
+	"));
+}
+
+#[test]
+fn port_bound_through_modport() {
+	let hir = compile_to_hir(parse("
+		module top;
+			bus u_bus();
+			sink u_sink(.b(u_bus));
+		endmodule
+		interface bus;
+			logic valid;
+			modport master (output valid);
+			modport slave (input valid);
+		endinterface
+		module sink (bus.slave b);
+		endmodule
+	"));
+	let binding = single_binding(&hir.design.scope.insts[1]);
+	assert_eq!(path(binding), "top.u_bus");
+	let slave = &find_intf(&hir, "bus").body.modports[1];
+	assert_eq!(binding.intf, find_intf(&hir, "bus").id);
+	assert_eq!(binding.modport, Some(slave.id));
+}
+
+#[test]
+fn generic_port_bound_through_ports() {
+	let hir = compile_to_hir(parse("
+		module top;
+			bus u_bus();
+			mid u_mid(u_bus);
+		endmodule
+		interface bus;
+			logic valid;
+			modport slave (input valid);
+		endinterface
+		module mid (bus b);
+			sink u_sink(.c(b));
+		endmodule
+		module sink (interface.slave c);
+		endmodule
+	"));
+	let mid = &hir.design.scope.insts[1];
+	assert_eq!(path(single_binding(mid)), "top.u_bus");
+	let binding = single_binding(&mid.scope.insts[0]);
+	assert_eq!(path(binding), "top.u_bus");
+	assert!(binding.modport.is_some());
+}
+
+#[test]
+#[should_panic]
+fn port_of_other_interface() {
+	compile_to_hir(parse("
+		module top;
+			bus u_bus();
+			sink u_sink(.b(u_bus));
+		endmodule
+		interface bus;
+		endinterface
+		interface other;
+		endinterface
+		module sink (other b);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn generic_port_unknown_modport() {
+	compile_to_hir(parse("
+		module top;
+			bus u_bus();
+			sink u_sink(.b(u_bus));
+		endmodule
+		interface bus;
+			logic valid;
+			modport slave (input valid);
+		endinterface
+		module sink (interface.master b);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn port_connected_to_non_interface() {
+	compile_to_hir(parse("
+		module top;
+			logic x;
+			sink u_sink(.b(x));
+		endmodule
+		interface bus;
+		endinterface
+		module sink (bus b);
+		endmodule
+	"));
+}
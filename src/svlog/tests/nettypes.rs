@@ -0,0 +1,245 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::{ast, hir};
+
+
+#[test]
+fn user_defined_nettypes() {
+	let hir = compile_to_hir(parse("
+		package wreal_pkg;
+			function real res_avg(input real drivers);
+				return 0.0;
+			endfunction
+			nettype real wreal with res_avg;
+		endpackage
+		nettype logic [3:0] nibble_net;
+		nettype nibble_net other_net;
+		module foo;
+			import wreal_pkg::*;
+			wreal_pkg::wreal a;
+			wreal b, c;
+			other_net d;
+			logic [3:0] e;
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.body.nets.len(), 3);
+	assert_eq!(m.body.vars.len(), 1);
+	for net in &m.body.nets {
+		assert_eq!(net.net_type, ast::NetType::User);
+	}
+	let pkg = hir.pkgs.values().next().unwrap();
+	assert_eq!(pkg.body.nettypes.len(), 1);
+	assert!(pkg.body.nettypes[0].resolve.is_some());
+}
+
+#[test]
+fn interconnect_nets() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			interconnect bus;
+			interconnect [7:0] wide, arr [3:0];
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.body.nets.len(), 2);
+	assert_eq!(m.body.nets[1].names.len(), 2);
+	for net in &m.body.nets {
+		assert_eq!(net.net_type, ast::NetType::Interconnect);
+	}
+}
+
+#[test]
+fn alias_nets() {
+	let hir = compile_to_hir(parse("
+		module foo #(parameter N = 4) (inout wire [N-1:0] a, inout wire [1:0] x, y);
+			wire [2*N-1:0] b;
+			wire [N-1:0] c;
+			alias a = b[N-1:0] = c;
+			alias b[7 -: 4] = {x, y};
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.body.aliases.len(), 2);
+	assert_eq!(m.body.aliases[0].nets.len(), 3);
+	assert_eq!(hir.design.scope.body.aliases.len(), 2);
+}
+
+#[test]
+#[should_panic]
+fn alias_width_mismatch() {
+	compile_to_hir(parse("
+		module foo;
+			wire [3:0] a;
+			wire [4:0] b;
+			alias a = b;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn alias_to_itself() {
+	compile_to_hir(parse("
+		module foo;
+			wire [3:0] a;
+			alias a = a;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn alias_twice() {
+	compile_to_hir(parse("
+		module foo;
+			wire [3:0] a, b;
+			alias a = b;
+			alias b = a;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn alias_variable() {
+	compile_to_hir(parse("
+		module foo;
+			wire a;
+			logic b;
+			alias a = b;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn interconnect_with_type() {
+	parse("
+		module foo;
+			interconnect logic x;
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn nettype_as_variable() {
+	compile_to_hir(parse("
+		nettype logic [3:0] nibble_net;
+		module foo;
+			initial begin
+				nibble_net x;
+			end
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn nettype_resolution_not_function() {
+	compile_to_hir(parse("
+		nettype real wreal with foo;
+		module foo;
+		endmodule
+	"));
+}
+
+/// The names and types of the nets declared in a body.
+fn net_names(body: &hir::HierarchyBody) -> Vec<(String, ast::NetType)> {
+	body.nets.iter().flat_map(|n| n.names.iter().map(move |d| (format!("{}", d.name), n.net_type))).collect()
+}
+
+#[test]
+fn implicit_nets() {
+	let hir = typecheck(parse("
+		module top (input a, output y);
+			inv u_first (.a(a), .y(n));
+			inv u_second (n, m);
+			assign {w, y} = {m, m};
+			and g (x, a, w);
+		endmodule
+		module inv (input a, output y);
+			assign y = ~a;
+		endmodule
+	"));
+	let top = hir.mods.values().find(|m| &*m.name.as_str() == "top").unwrap();
+	assert_eq!(net_names(&top.body), vec![
+		("n".to_string(), ast::NetType::Wire),
+		("m".to_string(), ast::NetType::Wire),
+		("w".to_string(), ast::NetType::Wire),
+		("x".to_string(), ast::NetType::Wire),
+	]);
+}
+
+#[test]
+fn implicit_nets_in_generate_blocks() {
+	let hir = compile_to_hir(parse("
+		module top (input a);
+			if (1) begin : g
+				assign n = a;
+			end
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert!(m.body.nets.is_empty());
+	assert_eq!(net_names(&m.body.genifs[0].main_block.body), vec![("n".to_string(), ast::NetType::Wire)]);
+}
+
+#[test]
+fn default_nettype_directive() {
+	let hir = compile_to_hir(parse("
+		`default_nettype tri
+		module top;
+			assign a = 1;
+			foo f();
+		endmodule
+		`default_nettype wand
+		module foo;
+			assign b = 1;
+		endmodule
+	"));
+	let m = hir.mods.values().find(|m| &*m.name.as_str() == "top").unwrap();
+	assert_eq!(net_names(&m.body), vec![("a".to_string(), ast::NetType::Tri)]);
+	let m = hir.mods.values().find(|m| &*m.name.as_str() == "foo").unwrap();
+	assert_eq!(net_names(&m.body), vec![("b".to_string(), ast::NetType::WireAnd)]);
+}
+
+#[test]
+fn default_nettype_none_declared() {
+	let hir = compile_to_hir(parse("
+		`default_nettype none
+		module top (input wire a, output wire y);
+			wire n;
+			assign n = a;
+			assign y = n;
+		endmodule
+		`default_nettype wire
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(net_names(&m.body), vec![("n".to_string(), ast::NetType::Wire)]);
+}
+
+#[test]
+#[should_panic]
+fn default_nettype_none_undeclared() {
+	compile_to_hir(parse("
+		`default_nettype none
+		module top (input wire a);
+			assign n = a;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn default_nettype_invalid() {
+	parse("
+		`default_nettype supply0
+		module top;
+		endmodule
+	");
+}
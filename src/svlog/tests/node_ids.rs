@@ -0,0 +1,107 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::{ast, renumber};
+use std::collections::HashSet;
+
+
+fn module_items(asts: &[ast::Root]) -> &[ast::HierarchyItem] {
+	match asts[0].items[0] {
+		ast::Item::Module(ref decl) => &decl.items,
+		_ => panic!("expected a module"),
+	}
+}
+
+#[test]
+fn expressions_statements_and_types() {
+	let mut asts = parse("
+		module foo;
+			logic [7:0] a, b, c;
+			assign c = a + b;
+			initial begin
+				a = b;
+			end
+		endmodule
+	");
+	let spans = renumber::renumber(&mut asts);
+	let items = module_items(&asts);
+	let mut ids = HashSet::new();
+
+	let ty = match items[0] {
+		ast::HierarchyItem::VarDecl(ref decl) => &decl.ty,
+		_ => panic!("expected a variable declaration"),
+	};
+	assert_eq!(spans.get(ty.id).unwrap().extract(), "logic [7:0]");
+	assert!(ids.insert(ty.id));
+
+	let (lhs, rhs) = match items[1] {
+		ast::HierarchyItem::ContAssign(ref assign) => (&assign.assignments[0].0, &assign.assignments[0].1),
+		_ => panic!("expected a continuous assignment"),
+	};
+	assert_eq!(spans.get(lhs.id).unwrap().extract(), "c");
+	assert_eq!(spans.get(rhs.id).unwrap().extract(), "a + b");
+	assert!(ids.insert(lhs.id));
+	assert!(ids.insert(rhs.id));
+	if let ast::BinaryExpr { ref lhs, ref rhs, .. } = rhs.data {
+		assert!(ids.insert(lhs.id));
+		assert!(ids.insert(rhs.id));
+	} else {
+		panic!("expected a binary expression");
+	}
+
+	let stmt = match items[2] {
+		ast::HierarchyItem::Procedure(ref prok) => &prok.stmt,
+		_ => panic!("expected a procedure"),
+	};
+	assert!(ids.insert(stmt.id));
+	match stmt.data {
+		ast::SequentialBlock(ref stmts) => {
+			assert_eq!(spans.get(stmts[0].id).unwrap().extract(), "a = b;");
+			assert!(ids.insert(stmts[0].id));
+		}
+		_ => panic!("expected a sequential block"),
+	}
+	assert!(!ids.contains(&ast::DUMMY_NODE_ID));
+}
+
+#[test]
+fn innermost_node_at_span() {
+	let mut asts = parse("
+		module foo;
+			logic a, b;
+			assign a = ~b;
+		endmodule
+	");
+	let spans = renumber::renumber(&mut asts);
+	let operand = match module_items(&asts)[1] {
+		ast::HierarchyItem::ContAssign(ref assign) => match assign.assignments[0].1.data {
+			ast::UnaryExpr { ref expr, .. } => expr.clone(),
+			_ => panic!("expected a unary expression"),
+		},
+		_ => panic!("expected a continuous assignment"),
+	};
+	// The identifier `b` covers the same text as the expression it forms, and
+	// is the innermost node there.
+	let ident = match operand.data {
+		ast::IdentExpr(ref ident) => ident.id,
+		_ => panic!("expected an identifier"),
+	};
+	assert_eq!(spans.find(operand.span), Some(ident));
+	assert_eq!(spans.get(ident), Some(operand.span));
+}
+
+#[test]
+fn types_recorded_by_node_id() {
+	let hir = typecheck(parse("
+		module foo;
+			logic [7:0] a;
+			logic [3:0] b;
+			initial a = a + b;
+		endmodule
+	"));
+	let scope = &hir.design.scope;
+	assert_eq!(type_of(&hir, scope, "a + b"), "logic [7:0]");
+	assert_eq!(type_of(&hir, scope, "b"), "logic [7:0]");
+}
@@ -0,0 +1,169 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+
+
+#[test]
+fn named_import() {
+	let hir = typecheck(parse("
+		package p;
+			typedef logic [7:0] byte_t;
+		endpackage
+		module foo;
+			import p::byte_t;
+			byte_t a;
+			logic [7:0] b;
+			initial a = b;
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(type_id_of(s, "a"), type_id_of(s, "b"));
+}
+
+#[test]
+fn local_declaration_hides_wildcard_import() {
+	let hir = typecheck(parse("
+		package p;
+			typedef logic [3:0] t;
+		endpackage
+		module foo;
+			import p::*;
+			typedef logic [7:0] t;
+			t a;
+			logic [7:0] b;
+			initial a = b;
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(type_id_of(s, "a"), type_id_of(s, "b"));
+}
+
+#[test]
+fn export_chains() {
+	let hir = typecheck(parse("
+		package a;
+			typedef logic [2:0] t3;
+			typedef logic [4:0] t5;
+		endpackage
+		package b;
+			import a::t3;
+			export a::t3;
+		endpackage
+		package c;
+			import a::*;
+			import b::*;
+			export *::*;
+		endpackage
+		module foo;
+			import c::*;
+			t3 x;
+			b::t3 y;
+			logic [2:0] z;
+			initial begin
+				x = z;
+				y = z;
+			end
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(type_id_of(s, "x"), type_id_of(s, "z"));
+	assert_eq!(type_id_of(s, "y"), type_id_of(s, "z"));
+}
+
+#[test]
+fn scoped_names_in_expressions() {
+	let hir = typecheck(parse("
+		package p;
+			logic [5:0] v;
+		endpackage
+		module foo;
+			logic [5:0] a;
+			initial a = p::v;
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(type_id_of(s, "a"), type_id_of(s, "p::v"));
+}
+
+#[test]
+#[should_panic]
+fn ambiguous_wildcard_imports() {
+	compile_to_hir(parse("
+		package p1;
+			typedef logic t;
+		endpackage
+		package p2;
+			typedef bit t;
+		endpackage
+		module foo;
+			import p1::*;
+			import p2::*;
+			t x;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn import_conflicts_with_declaration() {
+	compile_to_hir(parse("
+		package p;
+			typedef logic t;
+		endpackage
+		module foo;
+			import p::t;
+			logic t;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn imports_are_not_exported_by_default() {
+	compile_to_hir(parse("
+		package a;
+			typedef logic t;
+		endpackage
+		package b;
+			import a::*;
+		endpackage
+		module foo;
+			import b::*;
+			t x;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn export_outside_package() {
+	compile_to_hir(parse("
+		package p;
+			typedef logic t;
+		endpackage
+		module foo;
+			export p::*;
+		endmodule
+	"));
+}
+
+#[test]
+fn parameters_in_elaboration() {
+	let hir = compile_to_hir(parse("
+		package p;
+			parameter int N = 3;
+		endpackage
+		package q;
+			parameter int M = p::N + 1;
+		endpackage
+		module foo;
+			import q::*;
+			for (genvar i = 0; i < M; i++) begin : g
+			end
+		endmodule
+	"));
+	assert!(hir.design.scope.find_scope("g[3]").is_some());
+	assert!(hir.design.scope.find_scope("g[4]").is_none());
+}
@@ -0,0 +1,189 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::ast;
+use common::moore_svlog::ast::UdpSymbol;
+
+
+#[test]
+fn gates() {
+	let hir = compile_to_hir(parse("
+		module foo (input a, b, en, output y, z);
+			wire [3:0] w, v;
+			nand g0 (y, a, b);
+			and (strong0, weak1) #(1, 2) g1 (z, a, b, en), g2 (w[0], a, b);
+			bufif0 #(1:2:3, 4, 5) (w[1], a, en);
+			not g3 [3:0] (v, w);
+			tran t0 (w[2], w[3]);
+			cmos (y, a, b, en);
+			pullup (pull1) (z);
+			pulldown (strong0, highz1) p0 (y);
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.body.gates.len(), 8);
+	assert_eq!(m.body.gates[1].strength, Some(ast::GateStrength::Drive(ast::DriveStrength::Strong0, ast::DriveStrength::Weak1)));
+	assert_eq!(m.body.gates[1].names.len(), 2);
+	assert_eq!(m.body.gates[2].delay.as_ref().map(|d| d.values.len()), Some(3));
+	assert_eq!(m.body.gates[3].names[0].dims.len(), 1);
+	assert_eq!(m.body.gates[6].strength, Some(ast::GateStrength::Pull(ast::DriveStrength::Pull1)));
+}
+
+#[test]
+fn combinational_udp() {
+	let hir = compile_to_hir(parse("
+		primitive mux (output y, input s, a, b);
+			table
+				// s a b : y
+				0 1 ? : 1;
+				0 0 ? : 0;
+				1 ? 1 : 1;
+				1 ? 0 : 0;
+				x 00  : 0;
+				x 11  : 1;
+			endtable
+		endprimitive : mux
+
+		module foo (input s, a, b, output y);
+			mux m0 (y, s, a, b);
+		endmodule
+	"));
+	let udp = hir.udps.values().next().unwrap();
+	assert!(!udp.sequential);
+	assert_eq!(udp.inputs.len(), 3);
+	assert_eq!(udp.entries.len(), 6);
+	assert_eq!(udp.entries[0].inputs, vec![UdpSymbol::Level('0'), UdpSymbol::Level('1'), UdpSymbol::Level('?')]);
+	assert_eq!(udp.entries[4].inputs, vec![UdpSymbol::Level('x'), UdpSymbol::Level('0'), UdpSymbol::Level('0')]);
+	assert!(hir.design.scope.insts.is_empty());
+	assert_eq!(hir.design.scope.body.insts.len(), 1);
+}
+
+#[test]
+fn sequential_udp() {
+	let asts = parse("
+		primitive dff (q, clk, d);
+			output q;
+			reg q;
+			input clk, d;
+			initial q = 1'b0;
+			table
+				(01) 0 : ? : 0;
+				(01) 1 : ? : 1;
+				(0?) 1 : 1 : 1;
+				(?0) ? : ? : -;
+				r    ? : 0 : 0;
+				*    b : ? : -;
+				?  (??) : ? : -;
+			endtable
+		endprimitive
+	");
+	match asts[0].items[0] {
+		ast::Item::Primitive(ref udp) => {
+			assert!(udp.sequential);
+			assert!(udp.init.is_some());
+			assert_eq!(udp.inputs.len(), 2);
+			assert_eq!(udp.entries[0].inputs[0], UdpSymbol::Edge('0', '1'));
+			assert_eq!(udp.entries[2].state, Some(UdpSymbol::Level('1')));
+			assert_eq!(udp.entries[3].output, UdpSymbol::NoChange);
+			assert_eq!(udp.entries[4].inputs[0], UdpSymbol::EdgeAbbrev('r'));
+			assert_eq!(udp.entries[5].inputs[0], UdpSymbol::EdgeAbbrev('*'));
+		}
+		ref x => panic!("expected primitive, got {:?}", x),
+	}
+}
+
+#[test]
+fn udp_unknown_edges() {
+	let hir = compile_to_hir(parse("
+		primitive latch (output reg q, input clk, d);
+			table
+				(01) 0 : ? : 0;
+				(0x) 1 : 1 : 1;
+				(1x) 0 : 0 : 0;
+				(x1) ? : ? : -;
+				?  (1x) : ? : -;
+			endtable
+		endprimitive
+
+		module foo (input clk, d, output q, r);
+			latch #1 l0 (q, clk, d), l1 (r, clk, d);
+		endmodule
+	"));
+	let udp = hir.udps.values().next().unwrap();
+	assert_eq!(udp.entries[1].inputs[0], UdpSymbol::Edge('0', 'x'));
+	assert_eq!(udp.entries[2].inputs[0], UdpSymbol::Edge('1', 'x'));
+	assert_eq!(udp.entries[3].inputs[0], UdpSymbol::Edge('x', '1'));
+	assert_eq!(udp.entries[4].inputs[1], UdpSymbol::Edge('1', 'x'));
+	let inst = &hir.design.scope.body.insts[0];
+	assert_eq!(inst.delay.as_ref().map(|d| d.values.len()), Some(1));
+	assert_eq!(inst.names.len(), 2);
+}
+
+#[test]
+#[should_panic]
+fn module_inst_delay() {
+	compile_to_hir(parse("
+		module foo (input a);
+			bar #1 b0 (a);
+		endmodule
+		module bar (input a);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn udp_wrong_input_count() {
+	parse("
+		primitive inv (output y, input a);
+			table
+				0 1 : 1;
+			endtable
+		endprimitive
+	");
+}
+
+#[test]
+#[should_panic]
+fn combinational_udp_edge() {
+	parse("
+		primitive inv (output y, input a);
+			table
+				(01) : 0;
+			endtable
+		endprimitive
+	");
+}
+
+#[test]
+#[should_panic]
+fn gate_terminal_count() {
+	parse("
+		module foo (input a, en, output y);
+			bufif1 (y, a);
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn tran_delay() {
+	parse("
+		module foo (inout a, b);
+			tran #5 (a, b);
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn gate_unknown_terminal() {
+	compile_to_hir(parse("
+		`default_nettype none
+		module foo (input a, output y);
+			not (y, c);
+		endmodule
+	"));
+}
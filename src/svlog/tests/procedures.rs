@@ -0,0 +1,144 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::{ast, hir, token};
+
+
+/// The names an `always` procedure with a leading event control waits for.
+fn sensitivity(prok: &hir::Procedure) -> Vec<String> {
+	match prok.stmt.kind {
+		hir::StmtKind::Timed(hir::Timing::Event(ref events), _) => events.iter().map(|e| match e.expr.kind {
+			hir::ExprKind::Ident(ref i) => format!("{:?} {}", e.edge, i.name),
+			_ => panic!("expected an identifier"),
+		}).collect(),
+		_ => panic!("expected an event control"),
+	}
+}
+
+#[test]
+fn always_comb_sensitivity() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			logic a, b, c, y;
+			always_comb begin
+				logic t;
+				t = a & b;
+				y = t;
+				if (c) y = ~y | a;
+			end
+		endmodule
+	"));
+	let prok = &unwrap_single_module(&hir).body.procs[0];
+	assert_eq!(prok.kind, hir::ProcedureKind::Always);
+	assert_eq!(prok.origin, ast::ProcedureKind::AlwaysComb);
+	assert_eq!(sensitivity(prok), vec!["Implicit a", "Implicit b", "Implicit c"]);
+}
+
+#[test]
+fn always_ff_desugared() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			logic clk, rst, d, q;
+			always_ff @(posedge clk or negedge rst) q <= d;
+		endmodule
+	"));
+	let prok = &unwrap_single_module(&hir).body.procs[0];
+	assert_eq!(prok.kind, hir::ProcedureKind::Always);
+	assert_eq!(sensitivity(prok), vec!["Posedge clk", "Negedge rst"]);
+}
+
+#[test]
+#[should_panic]
+fn always_ff_without_event_control() {
+	compile_to_hir(parse("
+		module foo;
+			logic d, q;
+			always_ff q <= d;
+		endmodule
+	"));
+}
+
+#[test]
+fn implicit_event_control() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			logic a, b, y;
+			always @* y = a | b | y;
+		endmodule
+	"));
+	let prok = &unwrap_single_module(&hir).body.procs[0];
+	assert_eq!(sensitivity(prok), vec!["Implicit a", "Implicit b", "Implicit y"]);
+}
+
+#[test]
+fn compound_assignment_expanded() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			int x;
+			initial x <<= 2;
+		endmodule
+	"));
+	let prok = &unwrap_single_module(&hir).body.procs[0];
+	match prok.stmt.kind {
+		hir::StmtKind::Assign { kind: hir::AssignKind::Blocking, ref lhs, ref rhs } => {
+			match lhs.kind {
+				hir::LValueKind::Ident(ref i) => assert_eq!(&*i.name.as_str(), "x"),
+				_ => panic!("expected an identifier"),
+			}
+			match rhs.kind {
+				hir::ExprKind::Binary(op, ref a, _) => {
+					assert_eq!(op, token::Op::LogicShL);
+					assert_eq!(a.span.extract(), "x");
+				}
+				_ => panic!("expected a binary operation"),
+			}
+		}
+		_ => panic!("expected a blocking assignment"),
+	}
+}
+
+#[test]
+fn cont_assign_split() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			wire a, b, c, d;
+			assign a = b, c = d;
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.body.assigns.len(), 2);
+	assert_eq!(m.body.assigns[1].lhs.span.extract(), "c");
+}
+
+#[test]
+#[should_panic]
+fn assign_to_replication() {
+	compile_to_hir(parse("
+		module foo;
+			logic a, b;
+			initial {2{a}} = b;
+		endmodule
+	"));
+}
+
+#[test]
+fn subroutine_args_inherited() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			function void f(logic [7:0] a, output int c, int d, input [3:0] e);
+			endfunction
+		endmodule
+	"));
+	let sub = &unwrap_single_module(&hir).body.subroutines[0];
+	let dirs: Vec<_> = sub.args.iter().map(|a| a.dir).collect();
+	assert_eq!(dirs, vec![
+		ast::SubroutinePortDir::Input,
+		ast::SubroutinePortDir::Output,
+		ast::SubroutinePortDir::Output,
+		ast::SubroutinePortDir::Input,
+	]);
+	assert_eq!(sub.args[2].ty.data, ast::IntType);
+	assert_eq!(sub.args[3].ty.span.extract(), "[3:0]");
+}
@@ -0,0 +1,113 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::{ast, renumber, resolve};
+use common::moore_svlog::resolve::NameResolution;
+use common::moore_common::Session;
+use common::moore_common::name::get_name_table;
+
+
+fn resolve_names(mut asts: Vec<ast::Root>) -> NameResolution {
+	let session = Session::new();
+	renumber::renumber(&mut asts);
+	resolve::resolve(&session, &asts).expect("name resolution failed")
+}
+
+fn path(names: &[&str]) -> Vec<common::moore_common::name::Name> {
+	names.iter().map(|n| get_name_table().intern(n, true)).collect()
+}
+
+#[test]
+fn block_variables() {
+	compile_to_hir(parse("
+		module foo;
+			logic a;
+			initial begin : outer
+				logic b;
+				begin
+					logic a;
+					a = b;
+				end
+				fork
+					logic c;
+					c = a;
+				join
+				b = a;
+			end
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn block_variables_do_not_leak() {
+	compile_to_hir(parse("
+		module foo;
+			initial begin
+				begin : blk
+					logic x;
+				end
+				x = 1;
+			end
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn loop_variables_do_not_leak() {
+	compile_to_hir(parse("
+		module foo;
+			logic [7:0] mem [4];
+			initial begin
+				for (int i = 0; i < 4; i++)
+					mem[i] = 0;
+				mem[i] = 1;
+			end
+		endmodule
+	"));
+}
+
+#[test]
+fn subroutine_bodies() {
+	compile_to_hir(parse("
+		module foo;
+			logic [7:0] q;
+			function logic [7:0] add(input logic [7:0] a, input logic [7:0] b);
+				logic [7:0] sum;
+				sum = a + b;
+				return sum;
+			endfunction
+			task clear;
+				output logic [7:0] x;
+				foreach (q[i])
+					x = q;
+			endtask
+		endmodule
+	"));
+}
+
+#[test]
+fn scope_hierarchical_names() {
+	let nameres = resolve_names(parse("
+		module top;
+			initial begin : outer
+				logic x;
+				for (int i = 0; i < 2; i++) begin : inner
+					logic y;
+				end
+			end
+			function void f(input int a);
+				int b;
+			endfunction
+		endmodule
+	"));
+	let outer = &nameres.scopes[&path(&["top", "outer"])];
+	assert!(outer.contains_key(&path(&["x"])[0]));
+	let inner = &nameres.scopes[&path(&["top", "outer", "inner"])];
+	assert!(inner.contains_key(&path(&["y"])[0]));
+	let f = &nameres.scopes[&path(&["top", "f"])];
+	assert_eq!(f.len(), 2);
+}
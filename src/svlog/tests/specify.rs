@@ -0,0 +1,209 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::ast;
+use common::moore_svlog::ast::{PathPolarity, SpecifyItem, TimingCheckKind};
+
+
+#[test]
+fn paths() {
+	let hir = compile_to_hir(parse("
+		module dff (input clk, d, en, rst, output q, qn);
+			specparam tSU = 2, tHOLD = 1;
+			specify
+				specparam tCQ = 1:2:3, PATHPULSE$ = (1, 2);
+				specparam PATHPULSE$clk$q = (1);
+				pulsestyle_ondetect q;
+				showcancelled q, qn;
+				(posedge clk => (q +: d)) = (tCQ, tCQ);
+				(clk *> q, qn) = 4;
+				(d -=> qn) = (1, 2, 3);
+				if (en) (clk => q) = 2;
+				ifnone (clk => q) = 3;
+				(en, rst -*> q, qn) = (1, 2, 3, 4, 5, 6);
+			endspecify
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.body.specparams.len(), 1);
+	assert_eq!(m.body.specify_blocks.len(), 1);
+	let items = &m.body.specify_blocks[0].items;
+	assert_eq!(items.len(), 10);
+	match items[0] {
+		SpecifyItem::Specparam(ref decl) => assert!(decl.assigns[1].error.is_some()),
+		ref x => panic!("expected specparam, got {:?}", x),
+	}
+	match items[4] {
+		SpecifyItem::Path(ref path) => {
+			assert_eq!(path.edge, ast::EdgeIdent::Posedge);
+			assert!(!path.full);
+			assert_eq!(path.data_source.as_ref().map(|&(pol, _)| pol), Some(Some(PathPolarity::Positive)));
+			assert_eq!(path.delays.len(), 2);
+		}
+		ref x => panic!("expected path declaration, got {:?}", x),
+	}
+	match items[5] {
+		SpecifyItem::Path(ref path) => {
+			assert!(path.full);
+			assert_eq!(path.outputs.len(), 2);
+		}
+		ref x => panic!("expected path declaration, got {:?}", x),
+	}
+	match items[6] {
+		SpecifyItem::Path(ref path) => assert_eq!(path.polarity, Some(PathPolarity::Negative)),
+		ref x => panic!("expected path declaration, got {:?}", x),
+	}
+	match items[8] {
+		SpecifyItem::Path(ref path) => assert_eq!(path.cond, Some(ast::PathCond::Ifnone)),
+		ref x => panic!("expected path declaration, got {:?}", x),
+	}
+	match items[9] {
+		SpecifyItem::Path(ref path) => {
+			assert_eq!(path.inputs.len(), 2);
+			assert_eq!(path.delays.len(), 6);
+		}
+		ref x => panic!("expected path declaration, got {:?}", x),
+	}
+}
+
+#[test]
+fn timing_checks() {
+	let hir = compile_to_hir(parse("
+		module dff (input clk, d, en, rst, output q);
+			reg notifier;
+			specparam tSU = 2, tHOLD = 1;
+			specify
+				$setup(d, posedge clk &&& en, tSU, notifier);
+				$hold(posedge clk, d, tHOLD, notifier);
+				$setuphold(posedge clk, negedge d, tSU, tHOLD, notifier, , , clk, d);
+				$width(negedge clk, 5, 0, notifier);
+				$period(edge [01, 10] clk, 10);
+				$recovery(posedge rst, posedge clk, 1);
+			endspecify
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	let checks: Vec<&ast::TimingCheck> = m.body.specify_blocks[0].items.iter().map(|item| match *item {
+		SpecifyItem::TimingCheck(ref tc) => tc,
+		ref x => panic!("expected timing check, got {:?}", x),
+	}).collect();
+	assert_eq!(checks[0].kind, TimingCheckKind::Setup);
+	assert!(checks[0].events[1].cond.is_some());
+	assert_eq!(checks[0].notifier.as_ref().map(|n| &*n.name.as_str() == "notifier"), Some(true));
+	assert_eq!(checks[2].limits.len(), 2);
+	assert_eq!(checks[2].extra.len(), 4);
+	assert!(checks[2].extra[0].is_none());
+	assert!(checks[2].extra[3].is_some());
+	assert_eq!(checks[3].limits.len(), 2);
+	assert!(checks[3].notifier.is_some());
+	assert_eq!(checks[4].events[0].descriptors, vec![('0', '1'), ('1', '0')]);
+	assert!(checks[5].notifier.is_none());
+}
+
+#[test]
+fn unknown_edge_descriptors() {
+	let hir = compile_to_hir(parse("
+		module dff (input clk, d, output q);
+			specify
+				$setup(d, edge[01, 0x] clk, 1);
+				$hold(edge[0x, x1, 1z, Z0] clk, d, 1);
+			endspecify
+		endmodule
+	"));
+	let m = unwrap_single_module(&hir);
+	let checks: Vec<&ast::TimingCheck> = m.body.specify_blocks[0].items.iter().map(|item| match *item {
+		SpecifyItem::TimingCheck(ref tc) => tc,
+		ref x => panic!("expected timing check, got {:?}", x),
+	}).collect();
+	assert_eq!(checks[0].events[1].descriptors, vec![('0', '1'), ('0', 'x')]);
+	assert_eq!(checks[1].events[0].descriptors, vec![('0', 'x'), ('x', '1'), ('1', 'z'), ('z', '0')]);
+}
+
+#[test]
+#[should_panic]
+fn invalid_edge_descriptor() {
+	parse("
+		module dff (input clk, d);
+			specify
+				$hold(edge[0x1] clk, d, 1);
+			endspecify
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn parallel_path_multiple_inputs() {
+	parse("
+		module foo (input a, b, output q);
+			specify
+				(a, b => q) = 1;
+			endspecify
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn path_delay_count() {
+	parse("
+		module foo (input a, output q);
+			specify
+				(a *> q) = (1, 2, 3, 4);
+			endspecify
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn ifnone_edge_path() {
+	parse("
+		module foo (input clk, output q);
+			specify
+				ifnone (posedge clk => q) = 1;
+			endspecify
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn too_many_timing_check_args() {
+	parse("
+		module foo (input clk, d);
+			reg n;
+			specify
+				$setup(d, posedge clk, 1, n, 2);
+			endspecify
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn undefined_notifier() {
+	compile_to_hir(parse("
+		module foo (input clk, d);
+			specify
+				$hold(posedge clk, d, 1, ntfr);
+			endspecify
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn specparam_scope() {
+	compile_to_hir(parse("
+		module foo (input a, output q);
+			specify
+				specparam tPD = 1;
+				(a => q) = tPD;
+			endspecify
+			assign q = tPD;
+		endmodule
+	"));
+}
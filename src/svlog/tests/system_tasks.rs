@@ -0,0 +1,160 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::{renumber, resolve};
+use common::moore_svlog::systf::{SysArg, SysTf, SysTfTable};
+use common::moore_common::Session;
+use common::moore_common::name::get_name_table;
+
+
+#[test]
+fn builtin_calls() {
+	compile_to_hir(parse("
+		module foo;
+			typedef logic [7:0] byte_t;
+			localparam W = $clog2(16);
+			logic [7:0] a, b;
+			int fd, n;
+			initial begin
+				fd = $fopen(\"out.txt\", \"w\");
+				$fdisplay(fd, \"a = %d\", a,, b);
+				$fclose(fd);
+				n = $countones(a) + $bits(byte_t) + $size(a) + $urandom_range(4);
+				if (!$cast(b, a))
+					$error(\"cast failed\");
+				$display;
+				$finish;
+			end
+		endmodule
+	"));
+}
+
+#[test]
+fn builtin_type_arguments() {
+	let hir = typecheck(parse("
+		module foo;
+			int a, b, c;
+			assign a = $bits(int);
+			assign b = $bits(logic [3:0]);
+			assign c = $size(bit [7:0]);
+		endmodule
+	"));
+	assert_eq!(type_of(&hir, &hir.design.scope, "$bits(logic [3:0])"), "bit signed [31:0]");
+}
+
+#[test]
+#[should_panic]
+fn type_as_value_argument() {
+	compile_to_hir(parse("
+		module foo;
+			int s;
+			initial s = $clog2(int);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn too_few_arguments() {
+	compile_to_hir(parse("
+		module foo;
+			logic [3:0] a;
+			initial a = $clog2();
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn too_many_arguments() {
+	compile_to_hir(parse("
+		module foo;
+			int a;
+			initial a = $countones(a, a);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn type_given_for_value() {
+	compile_to_hir(parse("
+		module foo;
+			typedef logic [7:0] byte_t;
+			int a;
+			initial a = $countones(byte_t);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn cast_into_expression() {
+	compile_to_hir(parse("
+		module foo;
+			int a, b;
+			initial $cast(a + 1, b);
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn task_in_expression() {
+	compile_to_hir(parse("
+		module foo;
+			int a;
+			initial a = $display(\"x\");
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn non_constant_function_in_parameter() {
+	compile_to_hir(parse("
+		module foo;
+			localparam P = $urandom;
+		endmodule
+	"));
+}
+
+#[test]
+fn unknown_names_are_suggested() {
+	let systfs = SysTfTable::new();
+	let name = |n| get_name_table().intern(n, true);
+	assert_eq!(systfs.suggest(name("dispaly")), Some(name("display")));
+	assert_eq!(systfs.suggest(name("clog")), Some(name("clog2")));
+	assert_eq!(systfs.suggest(name("frobnicate")), None);
+	compile_to_hir(parse("
+		module foo;
+			initial $dispaly(\"warning only\");
+		endmodule
+	"));
+}
+
+#[test]
+fn user_registered_tasks() {
+	let mut asts = parse("
+		module foo;
+			int a;
+			initial $my_probe(a, \"a\");
+		endmodule
+	");
+	let session = Session::new();
+	renumber::renumber(&mut asts);
+	let mut systfs = SysTfTable::new();
+	systfs.register(SysTf::task("my_probe", &[SysArg::Variable]).optional(&[SysArg::Value]));
+	assert!(resolve::resolve_with_systfs(&session, &asts, &systfs).is_ok());
+
+	// The same table rejects calls that do not match the registered signature.
+	let mut asts = parse("
+		module foo;
+			initial $my_probe(1);
+		endmodule
+	");
+	renumber::renumber(&mut asts);
+	assert!(resolve::resolve_with_systfs(&session, &asts, &systfs).is_err());
+}
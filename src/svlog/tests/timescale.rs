@@ -0,0 +1,110 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::hir;
+
+
+fn module_named<'a>(hir: &'a hir::Root, name: &str) -> &'a hir::Module {
+	hir.mods.values().find(|m| &*m.name.as_str() == name).expect("module not found")
+}
+
+#[test]
+fn timeunit_declarations() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			timeunit 10ns / 1ps;
+			logic a;
+			timeunit 10ns;
+			initial #1.5ns a = 1;
+		endmodule
+		package bar;
+			timeunit 1us;
+			timeprecision 100ps;
+		endpackage
+	"));
+	let m = unwrap_single_module(&hir);
+	assert_eq!(m.timescale, hir::Timescale { unit: 1e-8, precision: 1e-12 });
+	let p = hir.pkgs.values().next().unwrap();
+	assert_eq!(p.timescale, hir::Timescale { unit: 1e-6, precision: 1e-10 });
+}
+
+#[test]
+fn timescale_directive() {
+	let hir = compile_to_hir(parse("
+		module foo;
+			bar b();
+			baz z();
+		endmodule
+		`timescale 1ns / 10 ps
+		module bar;
+			timeprecision 1fs;
+		endmodule
+		`timescale 100us/1us
+		module baz;
+		endmodule
+	"));
+	assert_eq!(module_named(&hir, "foo").timescale, hir::Timescale { unit: 1e-9, precision: 1e-9 });
+	assert_eq!(module_named(&hir, "bar").timescale, hir::Timescale { unit: 1e-9, precision: 1e-15 });
+	assert_eq!(module_named(&hir, "baz").timescale, hir::Timescale { unit: 1e-4, precision: 1e-6 });
+}
+
+#[test]
+fn compilation_unit_timeunits() {
+	let hir = compile_to_hir(parse("
+		timeunit 1ps;
+		timeprecision 1fs;
+		module foo;
+			bar b();
+		endmodule
+		`timescale 1ns/1ns
+		module bar;
+		endmodule
+	"));
+	assert_eq!(module_named(&hir, "foo").timescale, hir::Timescale { unit: 1e-12, precision: 1e-15 });
+	assert_eq!(module_named(&hir, "bar").timescale, hir::Timescale { unit: 1e-9, precision: 1e-9 });
+}
+
+#[test]
+#[should_panic]
+fn timeunit_after_items() {
+	parse("
+		module foo;
+			logic a;
+			timeunit 1ns;
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn timeunit_mismatch() {
+	parse("
+		interface foo;
+			timeunit 1ns;
+			timeunit 10ns;
+		endinterface
+	");
+}
+
+#[test]
+#[should_panic]
+fn invalid_time_magnitude() {
+	parse("
+		`timescale 5ns/1ps
+		module foo;
+		endmodule
+	");
+}
+
+#[test]
+#[should_panic]
+fn coarse_precision() {
+	compile_to_hir(parse("
+		module foo;
+			timeunit 1ps;
+			timeprecision 1ns;
+		endmodule
+	"));
+}
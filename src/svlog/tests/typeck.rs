@@ -0,0 +1,433 @@
+// Copyright (c) 2017 Fabian Schuiki
+#![allow(unused_variables)]
+
+mod common;
+use common::*;
+use common::moore_svlog::ast;
+use common::moore_common::errors::Severity;
+
+
+#[test]
+fn declarations() {
+	let hir = typecheck(parse("
+		module foo #(parameter W = 4);
+			typedef logic [W-1:0] word_t;
+			typedef struct packed { logic a; bit [2:0] b; } pair_t;
+			word_t w;
+			pair_t p;
+			int i;
+			bit signed [7:0] s [0:3];
+			bit [2:0] v;
+			assign w = p;
+			assign i = s[1];
+			assign v = p.b;
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(type_of(&hir, s, "w"), "logic [3:0]");
+	assert_eq!(type_of(&hir, s, "p"), "struct packed { logic a; bit [2:0] b; }");
+	assert_eq!(type_of(&hir, s, "s"), "unpacked array [0:3] of bit signed [7:0]");
+	assert_eq!(type_of(&hir, s, "s[1]"), "bit signed [31:0]");
+	assert_eq!(type_of(&hir, s, "p.b"), "bit [2:0]");
+}
+
+/// The operands of arithmetic operators are extended to the width of the
+/// assignment target, while the operands of comparisons are only sized
+/// relative to each other.
+#[test]
+fn context_determined_width() {
+	let hir = typecheck(parse("
+		module foo;
+			logic [7:0] a, b;
+			logic [15:0] c;
+			logic d;
+			assign c = a + b;
+			assign d = a < c;
+			assign c = {a, b};
+			assign c = {2{a}};
+			assign c = a << 2;
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(type_of(&hir, s, "a + b"), "logic [15:0]");
+	assert_eq!(type_of(&hir, s, "a < c"), "logic");
+	assert_eq!(type_of(&hir, s, "{a, b}"), "logic [15:0]");
+	assert_eq!(type_of(&hir, s, "{2{a}}"), "logic [15:0]");
+	assert_eq!(type_of(&hir, s, "a << 2"), "logic [15:0]");
+}
+
+/// The warnings emitted by the type checker.
+fn warnings(asts: Vec<ast::Root>) -> Vec<String> {
+	typecheck_diags(asts).iter()
+		.filter(|d| d.get_severity() == Severity::Warning)
+		.map(|d| d.get_message().clone())
+		.collect()
+}
+
+/// Operands that are only widened by their context, such as `a` in `a + 1`,
+/// do not cause width warnings.
+#[test]
+fn width_warnings_absent() {
+	let w = warnings(parse("
+		module foo;
+			logic [7:0] a, b, y;
+			logic [15:0] z;
+			assign y = a + 1;
+			assign y = a + b;
+			assign y = ~a;
+			assign y = '1;
+			assign z = {a, b};
+		endmodule
+	"));
+	assert!(w.is_empty(), "unexpected warnings: {:?}", w);
+}
+
+#[test]
+fn width_warnings_present() {
+	let w = warnings(parse("
+		module foo;
+			logic [7:0] a, y;
+			logic [15:0] z;
+			assign y = z;
+			assign z = a + 8'd1;
+		endmodule
+	"));
+	assert_eq!(w, vec![
+		"16-bit value is truncated to 8 bits",
+		"8-bit value is extended to 16 bits",
+	]);
+}
+
+#[test]
+fn function_calls() {
+	let hir = typecheck(parse("
+		module foo;
+			function logic [3:0] f(input logic [3:0] x);
+				return x;
+			endfunction
+			logic [3:0] a, b;
+			assign b = f(a);
+		endmodule
+	"));
+	assert_eq!(type_of(&hir, &hir.design.scope, "f(a)"), "logic [3:0]");
+}
+
+#[test]
+fn signedness() {
+	let hir = typecheck(parse("
+		module foo;
+			logic signed [7:0] a;
+			logic [7:0] b;
+			logic signed [7:0] c;
+			logic [3:0] n;
+			int i;
+			assign i = a + 8'sd1;
+			assign c = $signed(b);
+			assign n = a[3:0];
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(type_of(&hir, s, "a + 8'sd1"), "logic signed [31:0]");
+	assert_eq!(type_of(&hir, s, "$signed(b)"), "logic signed [7:0]");
+	assert_eq!(type_of(&hir, s, "a[3:0]"), "logic [3:0]");
+}
+
+#[test]
+fn generate_scopes() {
+	let hir = typecheck(parse("
+		module foo;
+			for (genvar i = 1; i < 3; i++) begin : g
+				logic [i:0] x;
+				assign x = '0;
+			end
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(type_of(&hir, s.find_scope("g[1]").unwrap(), "x"), "logic [1:0]");
+	assert_eq!(type_of(&hir, s.find_scope("g[2]").unwrap(), "x"), "logic [2:0]");
+}
+
+#[test]
+fn enums() {
+	let hir = typecheck(parse("
+		module foo;
+			typedef enum logic [1:0] { IDLE, BUSY } state_t;
+			state_t q, d;
+			always_comb begin
+				d = q;
+				if (q == IDLE) d = BUSY;
+			end
+		endmodule
+	"));
+	assert_eq!(type_of(&hir, &hir.design.scope, "IDLE"), "enum logic [1:0]");
+}
+
+#[test]
+#[should_panic]
+fn assign_int_to_enum() {
+	typecheck(parse("
+		module foo;
+			typedef enum { A, B } e_t;
+			e_t x;
+			initial x = 1;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn assign_to_parameter() {
+	typecheck(parse("
+		module foo #(parameter N = 1);
+			initial N = 2;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn assign_to_non_lvalue() {
+	typecheck(parse("
+		module foo;
+			logic a, b;
+			initial (a & b) = 1;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn assign_unpacked_mismatch() {
+	typecheck(parse("
+		module foo;
+			logic [7:0] a [4];
+			logic [7:0] b [0:7];
+			initial a = b;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn unsized_in_concat() {
+	typecheck(parse("
+		module foo;
+			logic [7:0] a;
+			logic [39:0] b;
+			assign b = {a, 1};
+		endmodule
+	"));
+}
+
+#[test]
+fn zero_replication_in_concat() {
+	let hir = typecheck(parse("
+		module foo;
+			logic [7:0] a;
+			logic [7:0] b;
+			assign b = {a, {0{a}}};
+		endmodule
+	"));
+	assert_eq!(type_of(&hir, &hir.design.scope, "{a, {0{a}}}"), "logic [7:0]");
+}
+
+#[test]
+#[should_panic]
+fn zero_replication() {
+	typecheck(parse("
+		module foo;
+			logic [7:0] a, b;
+			assign b = {0{a}};
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn negative_replication() {
+	typecheck(parse("
+		module foo;
+			logic [7:0] a, b;
+			assign b = {-1{a}};
+		endmodule
+	"));
+}
+
+#[test]
+fn typedefs_are_resolved() {
+	let hir = typecheck(parse("
+		package pkg;
+			typedef logic [7:0] byte_t;
+		endpackage
+		module foo;
+			typedef pkg::byte_t word_t [2];
+			pkg::byte_t a;
+			logic [7:0] b;
+			word_t c;
+			logic [7:0] d [0:1];
+			initial begin
+				a = b;
+				c = d;
+			end
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(type_id_of(s, "a"), type_id_of(s, "b"));
+	assert_eq!(type_of(&hir, s, "c"), "unpacked array [0:1] of logic [7:0]");
+	assert!(hir.tys.is_equivalent(type_id_of(s, "c"), type_id_of(s, "d")));
+}
+
+#[test]
+fn bits_and_compatibility() {
+	let hir = typecheck(parse("
+		module foo;
+			typedef struct packed { logic [3:0] a; logic [11:0] b; } s_t;
+			typedef enum logic [15:0] { X, Y } e_t;
+			s_t s;
+			e_t e;
+			logic [15:0] v;
+			bit [15:0] w;
+			int i [4];
+			logic [127:0] x;
+			real r;
+			initial begin
+				v = s;
+				e = X;
+				w = v;
+				r = w;
+				i[0] = 0;
+				x = 0;
+			end
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	let tys = &hir.tys;
+	let (st, et, vt, wt, it, rt) = (
+		type_id_of(s, "s"),
+		type_id_of(s, "e"),
+		type_id_of(s, "v"),
+		type_id_of(s, "w"),
+		type_id_of(s, "i"),
+		type_id_of(s, "r"),
+	);
+	assert_eq!(tys.bits(st), Some(16));
+	assert_eq!(tys.bits(it), Some(128));
+	assert_eq!(tys.bits(rt), Some(64));
+	assert!(tys.is_equivalent(st, vt));
+	assert!(!tys.is_equivalent(vt, wt));
+	assert!(!tys.is_equivalent(et, vt));
+	assert!(tys.is_assignment_compatible(vt, et));
+	assert!(!tys.is_assignment_compatible(et, vt));
+	assert!(tys.is_cast_compatible(et, vt));
+	assert!(!tys.is_assignment_compatible(vt, it));
+	assert!(tys.is_cast_compatible(it, type_id_of(s, "x")));
+}
+
+#[test]
+fn class_specialization() {
+	let hir = typecheck(parse("
+		module foo;
+			class C #(parameter N = 1, type T = int);
+			endclass
+			C a;
+			C #(1) b;
+			C #(2) c;
+			C #(.T(bit)) d;
+			initial begin
+				a = b;
+				c = c;
+				d = d;
+			end
+		endmodule
+	"));
+	let s = &hir.design.scope;
+	assert_eq!(type_id_of(s, "a"), type_id_of(s, "b"));
+	assert_eq!(type_of(&hir, s, "c"), "C #(2, bit signed [31:0])");
+	assert_eq!(type_of(&hir, s, "d"), "C #(1, bit)");
+}
+
+#[test]
+#[should_panic]
+fn class_specialization_mismatch() {
+	typecheck(parse("
+		module foo;
+			class C #(parameter N = 1);
+			endclass
+			C #(1) a;
+			C #(2) b;
+			initial a = b;
+		endmodule
+	"));
+}
+
+#[test]
+fn derived_class_assignment() {
+	typecheck(parse("
+		module foo;
+			class A;
+			endclass
+			class B #(parameter N = 1) extends A;
+			endclass
+			class C extends B #(2);
+			endclass
+			A a;
+			C c;
+			initial a = c;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn base_class_assignment() {
+	typecheck(parse("
+		module foo;
+			class A;
+			endclass
+			class B extends A;
+			endclass
+			A a;
+			B b;
+			initial b = a;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn cyclic_inheritance() {
+	typecheck(parse("
+		module foo;
+			class A extends B;
+			endclass
+			class B extends A;
+			endclass
+			A a;
+		endmodule
+	"));
+}
+
+#[test]
+fn port_dims_match() {
+	let hir = typecheck(parse("
+		module foo (a, b);
+			parameter W = 8;
+			input [W-1:0] a;
+			output b;
+			wire [7:0] a;
+			logic [3:0] b;
+		endmodule
+	"));
+}
+
+#[test]
+#[should_panic]
+fn port_dims_mismatch() {
+	typecheck(parse("
+		module foo (a);
+			parameter W = 8;
+			input [W:0] a;
+			wire [7:0] a;
+		endmodule
+	"));
+}